@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    RequestError(#[from] reqwest::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error("JSON-RPC error {code}: {message}")]
+    RpcError { code: i64, message: String, data: Option<serde_json::Value> },
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+pub type ClientResult<T> = Result<T, Error>;
@@ -0,0 +1,411 @@
+//! Client of the `devnet_*` JSON-RPC methods of Starknet Devnet. The requests and responses are
+//! the types the server itself uses, defined in [`starknet_types::rpc::devnet`].
+//!
+//! ```ignore
+//! let client = DevnetClient::new(Url::parse("http://127.0.0.1:5050")?);
+//! let minted = client.mint(&MintTokensRequest { address, amount, unit: None }).await?;
+//! let block_hash = client.create_block().await?;
+//! ```
+
+mod error;
+
+pub use error::{ClientResult, Error};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use starknet_types::contract_address::ContractAddress;
+use starknet_types::felt::{BlockHash, TransactionHash};
+use starknet_types::rpc::block::BlockId;
+use starknet_types::rpc::devnet::{
+    AbortedBlocks, AbortingBlocks, AcceptedOnL1, AcceptingOnL1, AccountAddressInput,
+    AccountBalanceResponse, BalanceQuery, ClassCoverage, CoverageFormat, CoverageReport,
+    CoverageRequest, CreatedBlock, DropTransactionsRequest, DroppedTransactions, DumpPath,
+    ExportGenesisPath, FaultConfig, FaultsSet, FlushParameters, FlushedMessages, FoldedProfile,
+    IncreaseTime, IncreaseTimeResponse, LcovCoverage, LoadPath, MempoolTransaction, MessageHash,
+    MessagingLoadAddress, MintTokensRequest, MintTokensResponse, PostmanLoadL1MessagingContract,
+    PredeployedAccountsQuery, ProfileFormat, ProfileTransactionRequest, RestartParameters,
+    RevertingToSnapshot, SandboxCreationRequest, SandboxId, SandboxIdInput, SandboxInfo,
+    SerializableAccount, SetClassHashAtInput, SetNonceInput, SetStorageAtInput, SetTime,
+    SetTimeResponse, TakenSnapshot, TransactionProfile,
+};
+use starknet_types::rpc::gas_modification::{
+    DataAvailabilityModeModification, DataAvailabilityModeRequest, GasModification,
+    GasModificationRequest,
+};
+use starknet_types::rpc::messaging::{MessageToL1, MessageToL2};
+use url::Url;
+
+const RPC_PATH: &str = "/rpc";
+
+#[derive(Deserialize)]
+struct TransactionHashOutput {
+    transaction_hash: TransactionHash,
+}
+
+/// Returns `url` with `path` appended to its path, so that Devnet served under a base path (e.g.
+/// behind a proxy) is reachable
+fn join_path(url: &Url, path: &str) -> Url {
+    let mut joined = url.clone();
+    joined.set_path(&format!("{}{path}", url.path().trim_end_matches('/')));
+    joined
+}
+
+#[derive(Clone, Debug)]
+pub struct DevnetClient {
+    base_url: Url,
+    rpc_url: Url,
+    http_client: reqwest::Client,
+}
+
+impl DevnetClient {
+    /// `url` is the base URL of Devnet, e.g. `http://127.0.0.1:5050` or
+    /// `https://example.com/devnet`
+    pub fn new(url: Url) -> Self {
+        Self::with_http_client(url, reqwest::Client::new())
+    }
+
+    pub fn with_http_client(url: Url, http_client: reqwest::Client) -> Self {
+        let rpc_url = join_path(&url, RPC_PATH);
+        Self { base_url: url, rpc_url, http_client }
+    }
+
+    /// Returns a client of a sandbox hosted by the Devnet of this client
+    pub fn sandbox(&self, sandbox_id: SandboxId) -> Self {
+        let base_url = join_path(&self.base_url, &format!("/sandbox/{sandbox_id}"));
+        Self::with_http_client(base_url, self.http_client.clone())
+    }
+
+    /// Sends a JSON-RPC request and returns the deserialized result. Params are omitted if `None`.
+    pub async fn send<P: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Option<P>,
+    ) -> ClientResult<R> {
+        let mut body = json!({ "jsonrpc": "2.0", "id": 0, "method": method });
+        if let Some(params) = params {
+            body["params"] = serde_json::to_value(params)?;
+        }
+
+        let response: serde_json::Value =
+            self.http_client.post(self.rpc_url.clone()).json(&body).send().await?.json().await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(Error::RpcError {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+                data: error.get("data").cloned(),
+            });
+        }
+
+        match response.get("result") {
+            Some(result) => Ok(serde_json::from_value(result.clone())?),
+            None => Err(Error::InvalidResponse(format!("{method}: no result in {response}"))),
+        }
+    }
+
+    /// For methods which don't return anything meaningful
+    async fn send_ignoring_result<P: Serialize>(
+        &self,
+        method: &str,
+        params: Option<P>,
+    ) -> ClientResult<()> {
+        self.send::<_, serde_json::Value>(method, params).await.map(|_| ())
+    }
+
+    /// devnet_impersonateAccount
+    pub async fn impersonate_account(&self, account_address: ContractAddress) -> ClientResult<()> {
+        self.send_ignoring_result(
+            "devnet_impersonateAccount",
+            Some(AccountAddressInput { account_address }),
+        )
+        .await
+    }
+
+    /// devnet_stopImpersonateAccount
+    pub async fn stop_impersonate_account(
+        &self,
+        account_address: ContractAddress,
+    ) -> ClientResult<()> {
+        self.send_ignoring_result(
+            "devnet_stopImpersonateAccount",
+            Some(AccountAddressInput { account_address }),
+        )
+        .await
+    }
+
+    /// devnet_autoImpersonate
+    pub async fn auto_impersonate(&self) -> ClientResult<()> {
+        self.send_ignoring_result::<()>("devnet_autoImpersonate", None).await
+    }
+
+    /// devnet_stopAutoImpersonate
+    pub async fn stop_auto_impersonate(&self) -> ClientResult<()> {
+        self.send_ignoring_result::<()>("devnet_stopAutoImpersonate", None).await
+    }
+
+    /// devnet_setStorageAt
+    pub async fn set_storage_at(&self, request: &SetStorageAtInput) -> ClientResult<()> {
+        self.send_ignoring_result("devnet_setStorageAt", Some(request)).await
+    }
+
+    /// devnet_setNonce
+    pub async fn set_nonce(&self, request: &SetNonceInput) -> ClientResult<()> {
+        self.send_ignoring_result("devnet_setNonce", Some(request)).await
+    }
+
+    /// devnet_setClassHashAt
+    pub async fn set_class_hash_at(&self, request: &SetClassHashAtInput) -> ClientResult<()> {
+        self.send_ignoring_result("devnet_setClassHashAt", Some(request)).await
+    }
+
+    /// devnet_dump; if `path` is provided, the dump is written to it on the server's side and
+    /// `None` is returned
+    pub async fn dump(&self, path: Option<String>) -> ClientResult<Option<serde_json::Value>> {
+        self.send("devnet_dump", path.map(|path| DumpPath { path })).await
+    }
+
+    /// devnet_load
+    pub async fn load(&self, path: String) -> ClientResult<()> {
+        self.send_ignoring_result("devnet_load", Some(LoadPath { path })).await
+    }
+
+    /// devnet_exportGenesis; if `path` is provided, the genesis is written to it on the server's
+    /// side and `None` is returned
+    pub async fn export_genesis(
+        &self,
+        path: Option<String>,
+    ) -> ClientResult<Option<serde_json::Value>> {
+        self.send("devnet_exportGenesis", path.map(|path| ExportGenesisPath { path })).await
+    }
+
+    /// devnet_postmanLoad
+    pub async fn postman_load(
+        &self,
+        request: &PostmanLoadL1MessagingContract,
+    ) -> ClientResult<MessagingLoadAddress> {
+        self.send("devnet_postmanLoad", Some(request)).await
+    }
+
+    /// devnet_postmanFlush
+    pub async fn postman_flush(&self, dry_run: bool) -> ClientResult<FlushedMessages> {
+        self.send("devnet_postmanFlush", Some(FlushParameters { dry_run })).await
+    }
+
+    /// devnet_postmanSendMessageToL2; returns the hash of the L1 handler transaction
+    pub async fn postman_send_message_to_l2(
+        &self,
+        message: &MessageToL2,
+    ) -> ClientResult<TransactionHash> {
+        let output: TransactionHashOutput =
+            self.send("devnet_postmanSendMessageToL2", Some(message)).await?;
+        Ok(output.transaction_hash)
+    }
+
+    /// devnet_postmanConsumeMessageFromL2
+    pub async fn postman_consume_message_from_l2(
+        &self,
+        message: &MessageToL1,
+    ) -> ClientResult<MessageHash> {
+        self.send("devnet_postmanConsumeMessageFromL2", Some(message)).await
+    }
+
+    /// devnet_createBlock; returns the hash of the created block
+    pub async fn create_block(&self) -> ClientResult<BlockHash> {
+        let created: CreatedBlock = self.send::<(), _>("devnet_createBlock", None).await?;
+        Ok(created.block_hash)
+    }
+
+    /// devnet_abortBlocks; returns the hashes of the aborted blocks
+    pub async fn abort_blocks(&self, starting_block_id: BlockId) -> ClientResult<Vec<BlockHash>> {
+        let aborted: AbortedBlocks =
+            self.send("devnet_abortBlocks", Some(AbortingBlocks { starting_block_id })).await?;
+        Ok(aborted.aborted)
+    }
+
+    /// devnet_acceptOnL1; accepts the blocks up to the latest one if `block_id` is `None` and
+    /// returns the hashes of the newly accepted blocks
+    pub async fn accept_on_l1(&self, block_id: Option<BlockId>) -> ClientResult<Vec<BlockHash>> {
+        let accepted: AcceptedOnL1 =
+            self.send("devnet_acceptOnL1", Some(AcceptingOnL1 { block_id })).await?;
+        Ok(accepted.accepted)
+    }
+
+    /// devnet_snapshot; returns the ID of the taken snapshot
+    pub async fn take_snapshot(&self) -> ClientResult<u64> {
+        let taken: TakenSnapshot = self.send::<(), _>("devnet_snapshot", None).await?;
+        Ok(taken.snapshot_id)
+    }
+
+    /// devnet_revert
+    pub async fn revert_to_snapshot(&self, snapshot_id: u64) -> ClientResult<()> {
+        self.send_ignoring_result("devnet_revert", Some(RevertingToSnapshot { snapshot_id })).await
+    }
+
+    /// devnet_setGasPrice
+    pub async fn set_gas_price(
+        &self,
+        request: &GasModificationRequest,
+    ) -> ClientResult<GasModification> {
+        self.send("devnet_setGasPrice", Some(request)).await
+    }
+
+    /// devnet_setDataAvailabilityMode
+    pub async fn set_data_availability_mode(
+        &self,
+        request: &DataAvailabilityModeRequest,
+    ) -> ClientResult<DataAvailabilityModeModification> {
+        self.send("devnet_setDataAvailabilityMode", Some(request)).await
+    }
+
+    /// devnet_restart
+    pub async fn restart(&self, parameters: Option<RestartParameters>) -> ClientResult<()> {
+        self.send_ignoring_result("devnet_restart", parameters).await
+    }
+
+    /// devnet_setTime
+    pub async fn set_time(&self, request: &SetTime) -> ClientResult<SetTimeResponse> {
+        self.send("devnet_setTime", Some(request)).await
+    }
+
+    /// devnet_increaseTime
+    pub async fn increase_time(&self, time: u64) -> ClientResult<IncreaseTimeResponse> {
+        self.send("devnet_increaseTime", Some(IncreaseTime { time })).await
+    }
+
+    /// devnet_getPredeployedAccounts
+    pub async fn get_predeployed_accounts(
+        &self,
+        with_balance: bool,
+    ) -> ClientResult<Vec<SerializableAccount>> {
+        self.send(
+            "devnet_getPredeployedAccounts",
+            Some(PredeployedAccountsQuery { with_balance: Some(with_balance) }),
+        )
+        .await
+    }
+
+    /// devnet_getAccountBalance
+    pub async fn get_account_balance(
+        &self,
+        query: &BalanceQuery,
+    ) -> ClientResult<AccountBalanceResponse> {
+        self.send("devnet_getAccountBalance", Some(query)).await
+    }
+
+    /// devnet_mint
+    pub async fn mint(&self, request: &MintTokensRequest) -> ClientResult<MintTokensResponse> {
+        self.send("devnet_mint", Some(request)).await
+    }
+
+    /// devnet_getConfig
+    pub async fn get_config(&self) -> ClientResult<serde_json::Value> {
+        self.send::<(), _>("devnet_getConfig", None).await
+    }
+
+    /// devnet_createSandbox
+    pub async fn create_sandbox(
+        &self,
+        request: &SandboxCreationRequest,
+    ) -> ClientResult<SandboxInfo> {
+        self.send("devnet_createSandbox", Some(request)).await
+    }
+
+    /// devnet_getSandboxes
+    pub async fn get_sandboxes(&self) -> ClientResult<Vec<SandboxInfo>> {
+        self.send::<(), _>("devnet_getSandboxes", None).await
+    }
+
+    /// devnet_destroySandbox
+    pub async fn destroy_sandbox(&self, sandbox_id: SandboxId) -> ClientResult<()> {
+        self.send_ignoring_result("devnet_destroySandbox", Some(SandboxIdInput { sandbox_id }))
+            .await
+    }
+
+    /// devnet_getMempool
+    pub async fn get_mempool(&self) -> ClientResult<Vec<MempoolTransaction>> {
+        self.send::<(), _>("devnet_getMempool", None).await
+    }
+
+    /// devnet_dropMempoolTransactions; drops all transactions if `transaction_hashes` is `None`
+    pub async fn drop_mempool_transactions(
+        &self,
+        transaction_hashes: Option<Vec<TransactionHash>>,
+    ) -> ClientResult<Vec<TransactionHash>> {
+        let dropped: DroppedTransactions = self
+            .send(
+                "devnet_dropMempoolTransactions",
+                Some(DropTransactionsRequest { transaction_hashes }),
+            )
+            .await?;
+        Ok(dropped.dropped)
+    }
+
+    /// devnet_setFaults; replaces the injected faults and returns the seed deciding their
+    /// occurrence. Passing the default config removes all faults.
+    pub async fn set_faults(&self, config: FaultConfig) -> ClientResult<u64> {
+        let faults_set: FaultsSet = self.send("devnet_setFaults", Some(config)).await?;
+        Ok(faults_set.seed)
+    }
+
+    /// devnet_getCoverage; returns the PCs visited in each executed class
+    pub async fn get_coverage(&self) -> ClientResult<Vec<ClassCoverage>> {
+        let report: CoverageReport = self
+            .send("devnet_getCoverage", Some(CoverageRequest { format: CoverageFormat::Json }))
+            .await?;
+        Ok(report.classes)
+    }
+
+    /// devnet_getCoverage; returns the coverage of Cairo source lines as an LCOV tracefile
+    pub async fn get_coverage_lcov(&self) -> ClientResult<String> {
+        let coverage: LcovCoverage = self
+            .send("devnet_getCoverage", Some(CoverageRequest { format: CoverageFormat::Lcov }))
+            .await?;
+        Ok(coverage.lcov)
+    }
+
+    /// devnet_profileTransaction; the format of the request is ignored
+    pub async fn profile_transaction(
+        &self,
+        request: &ProfileTransactionRequest,
+    ) -> ClientResult<TransactionProfile> {
+        let request = ProfileTransactionRequest { format: ProfileFormat::Json, ..request.clone() };
+        self.send("devnet_profileTransaction", Some(request)).await
+    }
+
+    /// devnet_profileTransaction; returns folded stacks, weighted by the metric of the request
+    pub async fn profile_transaction_folded(
+        &self,
+        request: &ProfileTransactionRequest,
+    ) -> ClientResult<String> {
+        let request =
+            ProfileTransactionRequest { format: ProfileFormat::Folded, ..request.clone() };
+        let profile: FoldedProfile = self.send("devnet_profileTransaction", Some(request)).await?;
+        Ok(profile.folded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::DevnetClient;
+
+    #[test]
+    fn rpc_path_joined_onto_base_path() {
+        for (base, expected) in [
+            ("http://127.0.0.1:5050", "http://127.0.0.1:5050/rpc"),
+            ("http://127.0.0.1:5050/", "http://127.0.0.1:5050/rpc"),
+            ("https://example.com/devnet", "https://example.com/devnet/rpc"),
+            ("https://example.com/devnet/", "https://example.com/devnet/rpc"),
+        ] {
+            let client = DevnetClient::new(Url::parse(base).unwrap());
+            assert_eq!(client.rpc_url.as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn sandbox_path_joined_onto_base_path() {
+        let client = DevnetClient::new(Url::parse("https://example.com/devnet").unwrap());
+        assert_eq!(client.sandbox(3).rpc_url.as_str(), "https://example.com/devnet/sandbox/3/rpc");
+    }
+}
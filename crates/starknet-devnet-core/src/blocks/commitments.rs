@@ -0,0 +1,117 @@
+use starknet_rs_core::types::Felt;
+use starknet_types_core::hash::{Poseidon, StarkHash};
+
+use crate::patricia_trie::{calculate_root, BLOCK_TRIE_HEIGHT};
+use crate::transactions::StarknetTransaction;
+
+/// Calculates the commitment to `transactions`, which have to be in the order of execution. Each
+/// leaf commits to the hash and the signature of a transaction.
+pub(crate) fn calculate_transaction_commitment(transactions: &[&StarknetTransaction]) -> Felt {
+    let leaves = transactions.iter().enumerate().map(|(index, transaction)| {
+        let mut leaf_elements = vec![*transaction.inner.get_transaction_hash()];
+        let signature = transaction.inner.transaction.get_signature();
+        if signature.is_empty() {
+            leaf_elements.push(Felt::ZERO);
+        } else {
+            leaf_elements.extend_from_slice(signature);
+        }
+
+        (Felt::from(index), Poseidon::hash_array(&leaf_elements))
+    });
+
+    calculate_root::<Poseidon>(leaves, BLOCK_TRIE_HEIGHT)
+}
+
+/// Calculates the commitment to the events emitted by `transactions`, which have to be in the
+/// order of execution. Returns the commitment and the number of events.
+pub(crate) fn calculate_event_commitment(transactions: &[&StarknetTransaction]) -> (Felt, usize) {
+    let mut leaves = vec![];
+    for transaction in transactions {
+        let transaction_hash = *transaction.inner.get_transaction_hash();
+        for event in &transaction.events {
+            let mut leaf_elements = vec![event.from_address.into(), transaction_hash];
+            leaf_elements.push(Felt::from(event.keys.len()));
+            leaf_elements.extend_from_slice(&event.keys);
+            leaf_elements.push(Felt::from(event.data.len()));
+            leaf_elements.extend_from_slice(&event.data);
+
+            leaves.push((Felt::from(leaves.len()), Poseidon::hash_array(&leaf_elements)));
+        }
+    }
+
+    let n_events = leaves.len();
+    (calculate_root::<Poseidon>(leaves, BLOCK_TRIE_HEIGHT), n_events)
+}
+
+#[cfg(test)]
+mod tests {
+    use blockifier::transaction::objects::TransactionExecutionInfo;
+    use starknet_rs_core::types::Felt;
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::emitted_event::Event;
+    use starknet_types_core::hash::{Poseidon, StarkHash};
+
+    use super::{calculate_event_commitment, calculate_transaction_commitment};
+    use crate::patricia_trie::BLOCK_TRIE_HEIGHT;
+    use crate::starknet::transaction_trace::create_trace;
+    use crate::transactions::StarknetTransaction;
+    use crate::utils::test_utils::dummy_declare_transaction_v1;
+
+    fn dummy_starknet_transaction() -> StarknetTransaction {
+        let tx = dummy_declare_transaction_v1();
+        let trace = create_trace(
+            &mut Default::default(),
+            tx.get_type(),
+            &Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        StarknetTransaction::create_accepted(&tx, TransactionExecutionInfo::default(), trace)
+    }
+
+    /// Root of a trie whose only leaf is at index 0
+    fn root_of_first_leaf(leaf: Felt) -> Felt {
+        Poseidon::hash(&leaf, &Felt::ZERO) + Felt::from(BLOCK_TRIE_HEIGHT)
+    }
+
+    #[test]
+    fn commitments_of_empty_block_are_zero() {
+        assert_eq!(calculate_transaction_commitment(&[]), Felt::ZERO);
+        assert_eq!(calculate_event_commitment(&[]), (Felt::ZERO, 0));
+    }
+
+    #[test]
+    fn transaction_without_signature_is_committed_with_zero_signature() {
+        let transaction = dummy_starknet_transaction();
+        assert!(transaction.inner.transaction.get_signature().is_empty());
+
+        let transaction_hash = *transaction.inner.get_transaction_hash();
+        let expected = root_of_first_leaf(Poseidon::hash_array(&[transaction_hash, Felt::ZERO]));
+        assert_eq!(calculate_transaction_commitment(&[&transaction]), expected);
+    }
+
+    #[test]
+    fn event_is_committed_with_its_emitter_and_transaction() {
+        let mut transaction = dummy_starknet_transaction();
+        let from_address = ContractAddress::new(Felt::from(10)).unwrap();
+        transaction.events = vec![Event {
+            from_address,
+            keys: vec![Felt::from(11)],
+            data: vec![Felt::from(12), Felt::from(13)],
+        }];
+
+        let expected_leaf = Poseidon::hash_array(&[
+            from_address.into(),
+            *transaction.inner.get_transaction_hash(),
+            Felt::ONE,
+            Felt::from(11),
+            Felt::TWO,
+            Felt::from(12),
+            Felt::from(13),
+        ]);
+        assert_eq!(
+            calculate_event_commitment(&[&transaction]),
+            (root_of_first_leaf(expected_leaf), 1)
+        );
+    }
+}
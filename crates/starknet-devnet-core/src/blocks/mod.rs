@@ -1,7 +1,18 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 
 use indexmap::IndexMap;
-use starknet_api::block::{BlockHeader, BlockNumber, BlockStatus, BlockTimestamp};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{
+    BlockHash as ApiBlockHash, BlockHeader, BlockNumber, BlockStatus, BlockTimestamp,
+    GasPricePerToken, StarknetVersion,
+};
+use starknet_api::core::{
+    EventCommitment, GlobalRoot, ReceiptCommitment, SequencerContractAddress, StateDiffCommitment,
+    TransactionCommitment,
+};
 use starknet_api::data_availability::L1DataAvailabilityMode;
 use starknet_api::felt;
 use starknet_rs_core::types::{BlockId, BlockTag, Felt};
@@ -13,31 +24,45 @@ use starknet_types::rpc::block::{
 use starknet_types::traits::HashProducer;
 use starknet_types_core::hash::{Pedersen, StarkHash};
 
+use self::commitments::{calculate_event_commitment, calculate_transaction_commitment};
 use crate::constants::{DEVNET_DEFAULT_STARTING_BLOCK_NUMBER, STARKNET_VERSION};
+use crate::database::{Database, Table};
 use crate::error::{DevnetResult, Error};
 use crate::state::state_diff::StateDiff;
 use crate::state::StarknetState;
 use crate::traits::HashIdentified;
+use crate::transactions::StarknetTransaction;
+
+mod commitments;
 
+/// Blocks are kept in memory, unless a database is used, in which case they are only kept in the
+/// database and read from it on demand.
+#[derive(Serialize, Deserialize)]
 pub(crate) struct StarknetBlocks {
     pub(crate) num_to_hash: IndexMap<BlockNumber, BlockHash>,
     pub(crate) hash_to_block: HashMap<BlockHash, StarknetBlock>,
     pub(crate) pending_block: StarknetBlock,
     pub(crate) last_block_hash: Option<BlockHash>,
     pub(crate) hash_to_state_diff: HashMap<BlockHash, StateDiff>,
-    pub(crate) hash_to_state: HashMap<BlockHash, StarknetState>,
+    /// Archived states, ordered by their last use. Bounded when using a database, since evicted
+    /// states can be read from it again.
+    #[serde(skip, default = "LruCache::unbounded")]
+    pub(crate) hash_to_state: LruCache<BlockHash, StarknetState>,
     pub(crate) aborted_blocks: Vec<Felt>,
     pub(crate) starting_block_number: u64,
+    #[serde(skip)]
+    database: Option<Database>,
 }
 
 impl HashIdentified for StarknetBlocks {
     type Element = StarknetBlock;
     type Hash = BlockHash;
 
-    fn get_by_hash(&self, hash: Self::Hash) -> Option<&Self::Element> {
-        let block = self.hash_to_block.get(&hash)?;
-
-        Some(block)
+    fn get_by_hash(&self, hash: Self::Hash) -> DevnetResult<Option<Cow<'_, Self::Element>>> {
+        match &self.database {
+            Some(database) => Ok(database.get(Table::Blocks, &hash.to_bytes_be())?.map(Cow::Owned)),
+            None => Ok(self.hash_to_block.get(&hash).map(Cow::Borrowed)),
+        }
     }
 }
 
@@ -49,9 +74,33 @@ impl Default for StarknetBlocks {
             pending_block: StarknetBlock::create_pending_block(),
             last_block_hash: None,
             hash_to_state_diff: HashMap::new(),
-            hash_to_state: HashMap::new(),
+            hash_to_state: LruCache::unbounded(),
             aborted_blocks: Vec::new(),
             starting_block_number: DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
+            database: None,
+        }
+    }
+}
+
+impl Clone for StarknetBlocks {
+    fn clone(&self) -> Self {
+        // archived states are fully committed, so their historic copy is equal to them
+        let mut hash_to_state = LruCache::unbounded();
+        hash_to_state.resize(self.hash_to_state.cap());
+        for (block_hash, state) in self.hash_to_state.iter().rev() {
+            hash_to_state.put(*block_hash, state.clone_historic());
+        }
+
+        Self {
+            num_to_hash: self.num_to_hash.clone(),
+            hash_to_block: self.hash_to_block.clone(),
+            pending_block: self.pending_block.clone(),
+            last_block_hash: self.last_block_hash,
+            hash_to_state_diff: self.hash_to_state_diff.clone(),
+            hash_to_state,
+            aborted_blocks: self.aborted_blocks.clone(),
+            starting_block_number: self.starting_block_number,
+            database: self.database.clone(),
         }
     }
 }
@@ -63,9 +112,16 @@ impl StarknetBlocks {
         blocks
     }
 
+    /// Makes the collection store blocks in `database`. At most `states_capacity` archived states
+    /// are kept in memory.
+    pub(crate) fn use_database(&mut self, database: Database, states_capacity: NonZeroUsize) {
+        self.hash_to_state = LruCache::new(states_capacity);
+        self.database = Some(database);
+    }
+
     /// Inserts a block in the collection and modifies the block parent hash to match the last block
     /// hash
-    pub fn insert(&mut self, mut block: StarknetBlock, state_diff: StateDiff) {
+    pub fn insert(&mut self, mut block: StarknetBlock, state_diff: StateDiff) -> DevnetResult<()> {
         if let Some(last_block_hash) = self.last_block_hash {
             block.header.parent_hash = starknet_api::block::BlockHash(last_block_hash);
         }
@@ -73,43 +129,107 @@ impl StarknetBlocks {
         let hash = block.block_hash();
         let block_number = block.block_number();
 
-        self.num_to_hash.insert(block_number, hash);
-        self.hash_to_block.insert(hash, block);
-        self.hash_to_state_diff.insert(hash, state_diff);
+        match &self.database {
+            Some(database) => {
+                database.put(Table::BlockNumbers, &block_number.0.to_be_bytes(), &hash)?;
+                database.put(Table::Blocks, &hash.to_bytes_be(), &block)?;
+                database.put(Table::StateDiffs, &hash.to_bytes_be(), &state_diff)?;
+            }
+            None => {
+                self.num_to_hash.insert(block_number, hash);
+                self.hash_to_block.insert(hash, block);
+                self.hash_to_state_diff.insert(hash, state_diff);
+            }
+        }
         self.last_block_hash = Some(hash);
+
+        Ok(())
+    }
+
+    /// Applies `modify` to the block and stores the result.
+    pub(crate) fn update(
+        &mut self,
+        block_hash: &BlockHash,
+        modify: impl FnOnce(&mut StarknetBlock),
+    ) -> DevnetResult<()> {
+        match &self.database {
+            Some(database) => {
+                let key = block_hash.to_bytes_be();
+                let mut block: StarknetBlock =
+                    database.get(Table::Blocks, &key)?.ok_or(Error::NoBlock)?;
+                modify(&mut block);
+                database.put(Table::Blocks, &key, &block)
+            }
+            None => {
+                modify(self.hash_to_block.get_mut(block_hash).ok_or(Error::NoBlock)?);
+                Ok(())
+            }
+        }
     }
 
-    fn get_by_num(&self, num: &BlockNumber) -> Option<&StarknetBlock> {
-        let block_hash = self.num_to_hash.get(num)?;
-        let block = self.hash_to_block.get(block_hash)?;
+    /// Makes the number of the block no longer refer to it, e.g. once the block is aborted. The
+    /// block itself is kept.
+    pub(crate) fn remove_block_number(&mut self, block_number: BlockNumber) {
+        match &self.database {
+            Some(database) => database.delete(Table::BlockNumbers, &block_number.0.to_be_bytes()),
+            None => {
+                self.num_to_hash.shift_remove(&block_number);
+            }
+        }
+    }
 
-        Some(block)
+    /// Returns the state diff of the block.
+    pub(crate) fn get_state_diff(
+        &self,
+        block_hash: &BlockHash,
+    ) -> DevnetResult<Option<Cow<'_, StateDiff>>> {
+        match &self.database {
+            Some(database) => {
+                Ok(database.get(Table::StateDiffs, &block_hash.to_bytes_be())?.map(Cow::Owned))
+            }
+            None => Ok(self.hash_to_state_diff.get(block_hash).map(Cow::Borrowed)),
+        }
+    }
+
+    fn get_by_num(&self, num: &BlockNumber) -> DevnetResult<Option<Cow<'_, StarknetBlock>>> {
+        let block_hash = match &self.database {
+            Some(database) => database.get(Table::BlockNumbers, &num.0.to_be_bytes())?,
+            None => self.num_to_hash.get(num).copied(),
+        };
+
+        match block_hash {
+            Some(block_hash) => self.get_by_hash(block_hash),
+            None => Ok(None),
+        }
     }
 
     pub fn save_state_at(&mut self, block_hash: Felt, state: StarknetState) {
-        self.hash_to_state.insert(block_hash, state);
+        self.hash_to_state.put(block_hash, state);
     }
 
-    fn get_by_latest_hash(&self) -> Option<&StarknetBlock> {
-        if let Some(hash) = self.last_block_hash { self.get_by_hash(hash) } else { None }
+    fn get_by_latest_hash(&self) -> DevnetResult<Option<Cow<'_, StarknetBlock>>> {
+        if let Some(hash) = self.last_block_hash { self.get_by_hash(hash) } else { Ok(None) }
     }
 
-    pub fn get_by_block_id(&self, block_id: &BlockId) -> Option<&StarknetBlock> {
+    pub fn get_by_block_id(
+        &self,
+        block_id: &BlockId,
+    ) -> DevnetResult<Option<Cow<'_, StarknetBlock>>> {
         match block_id {
             BlockId::Hash(hash) => self.get_by_hash(*hash),
             BlockId::Number(block_number) => self.get_by_num(&BlockNumber(*block_number)),
-            BlockId::Tag(BlockTag::Pending) => Some(&self.pending_block),
+            BlockId::Tag(BlockTag::Pending) => Ok(Some(Cow::Borrowed(&self.pending_block))),
             BlockId::Tag(BlockTag::Latest) => self.get_by_latest_hash(),
         }
     }
 
     /// Returns the block number from a block id, by finding the block by the block id
-    fn block_number_from_block_id(&self, block_id: &BlockId) -> Option<BlockNumber> {
-        self.get_by_block_id(block_id).map(|block| block.block_number())
+    fn block_number_from_block_id(&self, block_id: &BlockId) -> DevnetResult<Option<BlockNumber>> {
+        Ok(self.get_by_block_id(block_id)?.map(|block| block.block_number()))
     }
 
-    /// Filter blocks based on from and to block ids and returns a collection of block's references
-    /// in ascending order
+    /// Filter blocks based on from and to block ids and returns a collection of blocks in ascending
+    /// order
     ///
     /// # Arguments
     /// * `from` - The block id from which to start the filtering
@@ -118,14 +238,12 @@ impl StarknetBlocks {
         &self,
         from: Option<BlockId>,
         to: Option<BlockId>,
-    ) -> DevnetResult<Vec<&StarknetBlock>> {
-        // used IndexMap to keep elements in the order of the keys
-        let mut filtered_blocks: IndexMap<Felt, &StarknetBlock> = IndexMap::new();
-
+    ) -> DevnetResult<Vec<Cow<'_, StarknetBlock>>> {
         let starting_block = if let Some(block_id) = from {
             // If the value for block number provided is not correct it will return None
             // So we have to return an error
-            let block_number = self.block_number_from_block_id(&block_id).ok_or(Error::NoBlock)?;
+            let block_number =
+                self.block_number_from_block_id(&block_id)?.ok_or(Error::NoBlock)?;
             Some(block_number)
         } else {
             None
@@ -134,29 +252,40 @@ impl StarknetBlocks {
         let ending_block = if let Some(block_id) = to {
             // if the value for block number provided is not correct it will return None
             // So we set the block number to the first possible block number which is 0
-            let block_number = self.block_number_from_block_id(&block_id).ok_or(Error::NoBlock)?;
+            let block_number =
+                self.block_number_from_block_id(&block_id)?.ok_or(Error::NoBlock)?;
             Some(block_number)
         } else {
             None
         };
 
-        // iterate over the blocks and apply the filter
-        // then insert the filtered blocks into the index map
-        self.num_to_hash
-            .iter()
-            .filter(|(current_block_number, _)| match (starting_block, ending_block) {
-                (None, None) => true,
-                (Some(start), None) => **current_block_number >= start,
-                (None, Some(end)) => **current_block_number <= end,
-                (Some(start), Some(end)) => {
-                    **current_block_number >= start && **current_block_number <= end
-                }
-            })
-            .for_each(|(_, block_hash)| {
-                filtered_blocks.insert(*block_hash, &self.hash_to_block[block_hash]);
-            });
-
-        Ok(filtered_blocks.into_values().collect())
+        let block_hashes: Vec<BlockHash> = match &self.database {
+            Some(database) => {
+                let from = starting_block.map_or(0, |number| number.0).to_be_bytes();
+                let to = ending_block.map_or(u64::MAX, |number| number.0).to_be_bytes();
+                let numbers_to_hashes = database.range(Table::BlockNumbers, &from, &to)?;
+                numbers_to_hashes.into_iter().map(|(_, block_hash)| block_hash).collect()
+            }
+            // iterate over the blocks and apply the filter
+            None => self
+                .num_to_hash
+                .iter()
+                .filter(|(current_block_number, _)| match (starting_block, ending_block) {
+                    (None, None) => true,
+                    (Some(start), None) => **current_block_number >= start,
+                    (None, Some(end)) => **current_block_number <= end,
+                    (Some(start), Some(end)) => {
+                        **current_block_number >= start && **current_block_number <= end
+                    }
+                })
+                .map(|(_, block_hash)| *block_hash)
+                .collect(),
+        };
+
+        block_hashes
+            .into_iter()
+            .map(|block_hash| self.get_by_hash(block_hash)?.ok_or(Error::NoBlock))
+            .collect()
     }
 
     pub fn next_block_number(&self) -> BlockNumber {
@@ -164,8 +293,33 @@ impl StarknetBlocks {
     }
 }
 
-#[derive(Clone, Eq, PartialEq)]
+/// Mirrors `BlockHeader`, whose own implementation doesn't serialize some fields, so it cannot be
+/// deserialized from its own output.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "BlockHeader")]
+struct SerializableBlockHeader {
+    block_hash: ApiBlockHash,
+    parent_hash: ApiBlockHash,
+    block_number: BlockNumber,
+    l1_gas_price: GasPricePerToken,
+    l1_data_gas_price: GasPricePerToken,
+    state_root: GlobalRoot,
+    sequencer: SequencerContractAddress,
+    timestamp: BlockTimestamp,
+    l1_da_mode: L1DataAvailabilityMode,
+    state_diff_commitment: Option<StateDiffCommitment>,
+    state_diff_length: Option<usize>,
+    transaction_commitment: Option<TransactionCommitment>,
+    event_commitment: Option<EventCommitment>,
+    n_transactions: usize,
+    n_events: usize,
+    receipt_commitment: Option<ReceiptCommitment>,
+    starknet_version: StarknetVersion,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct StarknetBlock {
+    #[serde(with = "SerializableBlockHeader")]
     pub(crate) header: BlockHeader,
     transaction_hashes: Vec<TransactionHash>,
     pub(crate) status: BlockStatus,
@@ -177,7 +331,7 @@ impl From<&StarknetBlock> for TypesPendingBlockHeader {
             parent_hash: value.parent_hash(),
             sequencer_address: value.sequencer_address(),
             timestamp: value.timestamp(),
-            starknet_version: STARKNET_VERSION.to_string(),
+            starknet_version: value.header.starknet_version.to_string(),
             l1_gas_price: ResourcePrice {
                 price_in_fri: value.header.l1_gas_price.price_in_fri.0.into(),
                 price_in_wei: value.header.l1_gas_price.price_in_wei.0.into(),
@@ -200,7 +354,7 @@ impl From<&StarknetBlock> for TypesBlockHeader {
             sequencer_address: value.sequencer_address(),
             new_root: value.new_root(),
             timestamp: value.timestamp(),
-            starknet_version: STARKNET_VERSION.to_string(),
+            starknet_version: value.header.starknet_version.to_string(),
             l1_gas_price: ResourcePrice {
                 price_in_fri: value.header.l1_gas_price.price_in_fri.0.into(),
                 price_in_wei: value.header.l1_gas_price.price_in_wei.0.into(),
@@ -259,6 +413,7 @@ impl StarknetBlock {
         Self {
             header: BlockHeader {
                 l1_da_mode: L1DataAvailabilityMode::Blob,
+                starknet_version: StarknetVersion(STARKNET_VERSION.to_string()),
                 ..BlockHeader::default()
             },
             status: BlockStatus::Pending,
@@ -273,6 +428,22 @@ impl StarknetBlock {
     pub(crate) fn set_timestamp(&mut self, timestamp: BlockTimestamp) {
         self.header.timestamp = timestamp;
     }
+
+    /// Sets the state root and the commitments to the transactions of the block and their events.
+    /// The `transactions` have to be in the order of execution.
+    pub(crate) fn set_commitments(
+        &mut self,
+        state_root: Felt,
+        transactions: &[&StarknetTransaction],
+    ) {
+        self.header.state_root = GlobalRoot(state_root);
+        self.header.transaction_commitment =
+            Some(TransactionCommitment(calculate_transaction_commitment(transactions)));
+
+        let (event_commitment, n_events) = calculate_event_commitment(transactions);
+        self.header.event_commitment = Some(EventCommitment(event_commitment));
+        self.header.n_events = n_events;
+    }
 }
 
 impl HashProducer for StarknetBlock {
@@ -287,9 +458,9 @@ impl HashProducer for StarknetBlock {
                                                 * is modified to enable replicability
                                                 * in re-execution on loading on dump */
             felt!(self.transaction_hashes.len() as u64), // transaction_count
-            Felt::ZERO,                                  // transaction_commitment
-            Felt::ZERO,                                  // event_count
-            Felt::ZERO,                                  // event_commitment
+            self.header.transaction_commitment.unwrap_or_default().0, // transaction_commitment
+            felt!(self.header.n_events as u64),          // event_count
+            self.header.event_commitment.unwrap_or_default().0, // event_commitment
             Felt::ZERO,                                  // protocol_version
             Felt::ZERO,                                  // extra_data
             self.header.parent_hash.0,                   // parent_block_hash
@@ -317,7 +488,7 @@ mod tests {
             block_to_insert.header.block_number = BlockNumber(block_number);
             block_to_insert.header.block_hash =
                 starknet_api::block::BlockHash(Felt::from(block_number as u128));
-            blocks.insert(block_to_insert, StateDiff::default());
+            blocks.insert(block_to_insert, StateDiff::default()).unwrap();
         }
 
         let expected_block_numbers = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
@@ -356,6 +527,7 @@ mod tests {
                 .block_number_from_block_id(&BlockId::Tag(
                     starknet_rs_core::types::BlockTag::Latest
                 ))
+                .unwrap()
                 .is_none()
         );
         // pending block returns some
@@ -364,6 +536,7 @@ mod tests {
                 .block_number_from_block_id(&BlockId::Tag(
                     starknet_rs_core::types::BlockTag::Pending
                 ))
+                .unwrap()
                 .is_some()
         );
 
@@ -371,18 +544,19 @@ mod tests {
         block_to_insert.header.block_number = BlockNumber(10);
         block_to_insert.header.block_hash = starknet_api::block::BlockHash(block_hash);
 
-        blocks.insert(block_to_insert, StateDiff::default());
+        blocks.insert(block_to_insert, StateDiff::default()).unwrap();
 
         // returns block number, even if the block number is not present in the collection
-        assert!(blocks.block_number_from_block_id(&BlockId::Number(11)).is_none());
-        assert!(blocks.block_number_from_block_id(&BlockId::Number(10)).is_some());
+        assert!(blocks.block_number_from_block_id(&BlockId::Number(11)).unwrap().is_none());
+        assert!(blocks.block_number_from_block_id(&BlockId::Number(10)).unwrap().is_some());
         // returns none because there is no block with the given hash
-        assert!(blocks.block_number_from_block_id(&BlockId::Hash(Felt::ONE)).is_none());
+        assert!(blocks.block_number_from_block_id(&BlockId::Hash(Felt::ONE)).unwrap().is_none());
         assert!(
             blocks
                 .block_number_from_block_id(&BlockId::Tag(
                     starknet_rs_core::types::BlockTag::Latest
                 ))
+                .unwrap()
                 .is_some()
         );
         assert!(
@@ -390,9 +564,10 @@ mod tests {
                 .block_number_from_block_id(&BlockId::Tag(
                     starknet_rs_core::types::BlockTag::Pending
                 ))
+                .unwrap()
                 .is_some()
         );
-        assert!(blocks.block_number_from_block_id(&BlockId::Hash(block_hash)).is_some());
+        assert!(blocks.block_number_from_block_id(&BlockId::Hash(block_hash)).unwrap().is_some());
     }
 
     #[test]
@@ -405,7 +580,7 @@ mod tests {
             block_to_insert.header.block_number = BlockNumber(block_number);
             block_to_insert.header.block_hash =
                 starknet_api::block::BlockHash(Felt::from(block_number as u128));
-            blocks.insert(block_to_insert.clone(), StateDiff::default());
+            blocks.insert(block_to_insert.clone(), StateDiff::default()).unwrap();
 
             // last block will be a pending block
             if block_number == last_block_number {
@@ -663,26 +838,28 @@ mod tests {
         block_to_insert.header.block_number = BlockNumber(10);
         blocks.pending_block = block_to_insert.clone();
 
-        blocks.insert(block_to_insert.clone(), StateDiff::default());
+        blocks.insert(block_to_insert.clone(), StateDiff::default()).unwrap();
 
-        let extracted_block = blocks.get_by_block_id(&BlockId::Number(10)).unwrap();
-        assert!(block_to_insert == extracted_block.clone());
+        let extracted_block = blocks.get_by_block_id(&BlockId::Number(10)).unwrap().unwrap();
+        assert!(block_to_insert == *extracted_block);
 
         let extracted_block =
-            blocks.get_by_block_id(&BlockId::Hash(block_to_insert.block_hash())).unwrap();
-        assert!(block_to_insert == extracted_block.clone());
+            blocks.get_by_block_id(&BlockId::Hash(block_to_insert.block_hash())).unwrap().unwrap();
+        assert!(block_to_insert == *extracted_block);
 
         let extracted_block = blocks
             .get_by_block_id(&BlockId::Tag(starknet_rs_core::types::BlockTag::Latest))
+            .unwrap()
             .unwrap();
-        assert!(block_to_insert == extracted_block.clone());
+        assert!(block_to_insert == *extracted_block);
 
         let extracted_block = blocks
             .get_by_block_id(&BlockId::Tag(starknet_rs_core::types::BlockTag::Pending))
+            .unwrap()
             .unwrap();
-        assert!(block_to_insert == extracted_block.clone());
+        assert!(block_to_insert == *extracted_block);
 
-        match blocks.get_by_block_id(&BlockId::Number(11)) {
+        match blocks.get_by_block_id(&BlockId::Number(11)).unwrap() {
             None => (),
             _ => panic!("Expected none"),
         }
@@ -699,23 +876,24 @@ mod tests {
             block.header.block_number = BlockNumber(block_number);
             block.set_block_hash(block.generate_hash().unwrap());
 
-            blocks.insert(block, StateDiff::default());
+            blocks.insert(block, StateDiff::default()).unwrap();
         }
 
         assert!(
-            blocks.get_by_num(&BlockNumber(0)).unwrap().header.parent_hash == BlockHash::default()
+            blocks.get_by_num(&BlockNumber(0)).unwrap().unwrap().header.parent_hash
+                == BlockHash::default()
         );
         assert!(
-            blocks.get_by_num(&BlockNumber(0)).unwrap().header.block_hash
-                == blocks.get_by_num(&BlockNumber(1)).unwrap().header.parent_hash
+            blocks.get_by_num(&BlockNumber(0)).unwrap().unwrap().header.block_hash
+                == blocks.get_by_num(&BlockNumber(1)).unwrap().unwrap().header.parent_hash
         );
         assert!(
-            blocks.get_by_num(&BlockNumber(1)).unwrap().header.block_hash
-                == blocks.get_by_num(&BlockNumber(2)).unwrap().header.parent_hash
+            blocks.get_by_num(&BlockNumber(1)).unwrap().unwrap().header.block_hash
+                == blocks.get_by_num(&BlockNumber(2)).unwrap().unwrap().header.parent_hash
         );
         assert!(
-            blocks.get_by_num(&BlockNumber(1)).unwrap().header.parent_hash
-                != blocks.get_by_num(&BlockNumber(2)).unwrap().header.parent_hash
+            blocks.get_by_num(&BlockNumber(1)).unwrap().unwrap().header.parent_hash
+                != blocks.get_by_num(&BlockNumber(2)).unwrap().unwrap().header.parent_hash
         )
     }
 
@@ -727,10 +905,10 @@ mod tests {
             starknet_api::block::BlockHash(block_to_insert.generate_hash().unwrap());
         block_to_insert.header.block_number = BlockNumber(1);
 
-        blocks.insert(block_to_insert.clone(), StateDiff::default());
+        blocks.insert(block_to_insert.clone(), StateDiff::default()).unwrap();
 
-        let extracted_block = blocks.get_by_hash(block_to_insert.block_hash()).unwrap();
-        assert!(block_to_insert == extracted_block.clone());
+        let extracted_block = blocks.get_by_hash(block_to_insert.block_hash()).unwrap().unwrap();
+        assert!(block_to_insert == *extracted_block);
     }
 
     #[test]
@@ -742,6 +920,9 @@ mod tests {
             block.header,
             BlockHeader {
                 l1_da_mode: starknet_api::data_availability::L1DataAvailabilityMode::Blob,
+                starknet_version: starknet_api::block::StarknetVersion(
+                    crate::constants::STARKNET_VERSION.to_string()
+                ),
                 ..Default::default()
             }
         );
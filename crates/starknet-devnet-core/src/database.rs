@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use redb::TableDefinition;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{DevnetResult, Error};
+
+/// Name of the database file in the directory specified by the user.
+const DATABASE_FILE_NAME: &str = "devnet.redb";
+
+/// Key of the only record of `Table::Devnet`.
+const DEVNET_RECORD_KEY: &[u8] = b"devnet";
+
+/// Groups of records. Keys are big-endian encoded, so that records are ordered by their keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Table {
+    /// Accepted and aborted blocks, by block hash
+    Blocks,
+    /// Hashes of accepted blocks, by block number
+    BlockNumbers,
+    /// State diffs of blocks, by block hash
+    StateDiffs,
+    Transactions,
+    Classes,
+    /// Values changed by accepted blocks, by state entry and block number
+    StateHistory,
+    /// The parts of Devnet which are not stored in other tables
+    Devnet,
+}
+
+impl Table {
+    const ALL: [Table; 7] = [
+        Table::Blocks,
+        Table::BlockNumbers,
+        Table::StateDiffs,
+        Table::Transactions,
+        Table::Classes,
+        Table::StateHistory,
+        Table::Devnet,
+    ];
+
+    fn definition(&self) -> TableDefinition<'static, &'static [u8], &'static [u8]> {
+        TableDefinition::new(match self {
+            Table::Blocks => "blocks",
+            Table::BlockNumbers => "block_numbers",
+            Table::StateDiffs => "state_diffs",
+            Table::Transactions => "transactions",
+            Table::Classes => "classes",
+            Table::StateHistory => "state_history",
+            Table::Devnet => "devnet",
+        })
+    }
+}
+
+/// Changes made since the last commit, by table and key; `None` marks a removed record.
+type StagedChanges = BTreeMap<(Table, Vec<u8>), Option<Vec<u8>>>;
+
+/// Embedded key-value store in a file on disk. Changes are staged in memory and written at once on
+/// `commit`, so that each modification of Devnet is written in a single transaction and an
+/// interrupted run doesn't leave partially written data. Reads see the staged changes.
+#[derive(Debug, Clone)]
+pub(crate) struct Database {
+    inner: Arc<redb::Database>,
+    staged: Arc<Mutex<StagedChanges>>,
+}
+
+impl Database {
+    /// Opens the database in the directory at `path`, creating the directory and the database if
+    /// missing.
+    pub(crate) fn open(path: &Path) -> DevnetResult<Self> {
+        fs::create_dir_all(path)?;
+        let inner =
+            redb::Database::create(path.join(DATABASE_FILE_NAME)).map_err(database_error)?;
+
+        // tables need to exist to be read from
+        let write = inner.begin_write().map_err(database_error)?;
+        for table in Table::ALL {
+            write.open_table(table.definition()).map_err(database_error)?;
+        }
+        write.commit().map_err(database_error)?;
+
+        Ok(Self { inner: Arc::new(inner), staged: Default::default() })
+    }
+
+    pub(crate) fn put_devnet_record<T: Serialize>(&self, record: &T) -> DevnetResult<()> {
+        self.put(Table::Devnet, DEVNET_RECORD_KEY, record)
+    }
+
+    pub(crate) fn get_devnet_record<T: DeserializeOwned>(&self) -> DevnetResult<Option<T>> {
+        self.get(Table::Devnet, DEVNET_RECORD_KEY)
+    }
+
+    pub(crate) fn put<T: Serialize>(
+        &self,
+        table: Table,
+        key: &[u8],
+        value: &T,
+    ) -> DevnetResult<()> {
+        let value = serde_json::to_vec(value)
+            .map_err(|e| Error::SerializationError { origin: e.to_string() })?;
+        self.staged.lock().insert((table, key.to_vec()), Some(value));
+        Ok(())
+    }
+
+    pub(crate) fn get<T: DeserializeOwned>(
+        &self,
+        table: Table,
+        key: &[u8],
+    ) -> DevnetResult<Option<T>> {
+        let staged = self.staged.lock().get(&(table, key.to_vec())).cloned();
+        let value = match staged {
+            Some(value) => value,
+            None => {
+                let read = self.inner.begin_read().map_err(database_error)?;
+                let records = read.open_table(table.definition()).map_err(database_error)?;
+                let value = records.get(key).map_err(database_error)?;
+                value.map(|value| value.value().to_vec())
+            }
+        };
+
+        value.map(|value| deserialize(&value)).transpose()
+    }
+
+    /// Removes the record if present.
+    pub(crate) fn delete(&self, table: Table, key: &[u8]) {
+        self.staged.lock().insert((table, key.to_vec()), None);
+    }
+
+    /// Returns the records of `table` with keys between `from` and `to` (inclusive), ordered by
+    /// their keys.
+    pub(crate) fn range<T: DeserializeOwned>(
+        &self,
+        table: Table,
+        from: &[u8],
+        to: &[u8],
+    ) -> DevnetResult<Vec<(Vec<u8>, T)>> {
+        if from > to {
+            return Ok(vec![]);
+        }
+
+        let mut records = BTreeMap::new();
+
+        let read = self.inner.begin_read().map_err(database_error)?;
+        let stored = read.open_table(table.definition()).map_err(database_error)?;
+        for record in stored.range(from..=to).map_err(database_error)? {
+            let (key, value) = record.map_err(database_error)?;
+            records.insert(key.value().to_vec(), Some(value.value().to_vec()));
+        }
+        records.extend(self.staged_range(table, from, to));
+
+        records
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .map(|(key, value)| Ok((key, deserialize(&value)?)))
+            .collect()
+    }
+
+    /// Returns the record of `table` with the greatest key between `from` and `to` (inclusive).
+    pub(crate) fn last_in_range<T: DeserializeOwned>(
+        &self,
+        table: Table,
+        from: &[u8],
+        to: &[u8],
+    ) -> DevnetResult<Option<(Vec<u8>, T)>> {
+        if from > to {
+            return Ok(None);
+        }
+
+        let staged: Vec<_> = self.staged_range(table, from, to).collect();
+
+        let read = self.inner.begin_read().map_err(database_error)?;
+        let stored = read.open_table(table.definition()).map_err(database_error)?;
+        // records removed since the last commit are skipped
+        let mut stored_last = None;
+        for record in stored.range(from..=to).map_err(database_error)?.rev() {
+            let (key, value) = record.map_err(database_error)?;
+            let key = key.value().to_vec();
+            if !staged.iter().any(|(staged_key, _)| staged_key == &key) {
+                stored_last = Some((key, value.value().to_vec()));
+                break;
+            }
+        }
+
+        let staged_last = staged.into_iter().rev().find_map(|(key, value)| Some((key, value?)));
+        let last = match (stored_last, staged_last) {
+            (Some(stored), Some(staged)) => {
+                Some(std::cmp::max_by(stored, staged, |a, b| a.0.cmp(&b.0)))
+            }
+            (stored, staged) => stored.or(staged),
+        };
+
+        last.map(|(key, value)| Ok((key, deserialize(&value)?))).transpose()
+    }
+
+    /// Writes the changes made since the last commit.
+    pub(crate) fn commit(&self) -> DevnetResult<()> {
+        let mut staged = self.staged.lock();
+        if staged.is_empty() {
+            return Ok(());
+        }
+
+        let write = self.inner.begin_write().map_err(database_error)?;
+        for table in Table::ALL {
+            let mut records = write.open_table(table.definition()).map_err(database_error)?;
+            let changes = staged.range((table, vec![])..).take_while(|((t, _), _)| *t == table);
+            for ((_, key), value) in changes {
+                match value {
+                    Some(value) => records.insert(key.as_slice(), value.as_slice()).map(drop),
+                    None => records.remove(key.as_slice()).map(drop),
+                }
+                .map_err(database_error)?;
+            }
+        }
+        write.commit().map_err(database_error)?;
+
+        staged.clear();
+        Ok(())
+    }
+
+    /// Removes all records, including those not yet committed.
+    pub(crate) fn clear(&self) -> DevnetResult<()> {
+        let mut staged = self.staged.lock();
+        staged.clear();
+
+        let write = self.inner.begin_write().map_err(database_error)?;
+        for table in Table::ALL {
+            write.delete_table(table.definition()).map_err(database_error)?;
+            write.open_table(table.definition()).map_err(database_error)?;
+        }
+        write.commit().map_err(database_error)
+    }
+
+    fn staged_range(
+        &self,
+        table: Table,
+        from: &[u8],
+        to: &[u8],
+    ) -> impl DoubleEndedIterator<Item = (Vec<u8>, Option<Vec<u8>>)> {
+        let bounds =
+            (Bound::Included((table, from.to_vec())), Bound::Included((table, to.to_vec())));
+        let staged = self.staged.lock();
+        let changes: Vec<_> =
+            staged.range(bounds).map(|((_, key), value)| (key.clone(), value.clone())).collect();
+        changes.into_iter()
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(value: &[u8]) -> DevnetResult<T> {
+    serde_json::from_slice(value).map_err(|e| Error::DeserializationError { origin: e.to_string() })
+}
+
+fn database_error(err: impl Into<redb::Error>) -> Error {
+    Error::DatabaseError(err.into())
+}
+
+pub(crate) fn write_json<T: Serialize>(path: &Path, value: &T) -> DevnetResult<()> {
+    let temporary_path = path.with_extension("tmp");
+
+    let mut writer = BufWriter::new(File::create(&temporary_path)?);
+    serde_json::to_writer(&mut writer, value)
+        .map_err(|e| Error::SerializationError { origin: e.to_string() })?;
+    writer.flush()?;
+
+    Ok(fs::rename(temporary_path, path)?)
+}
+
+pub(crate) fn read_json<T: DeserializeOwned>(path: &Path) -> DevnetResult<Option<T>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    serde_json::from_reader(BufReader::new(file))
+        .map(Some)
+        .map_err(|e| Error::DeserializationError { origin: e.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Database, Table};
+
+    #[test]
+    fn records_are_read_before_and_after_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = Database::open(dir.path()).unwrap();
+        let key = [0xab, 0xc];
+
+        assert_eq!(database.get::<u64>(Table::Blocks, &key).unwrap(), None);
+
+        database.put(Table::Blocks, &key, &1_u64).unwrap();
+        database.put(Table::Blocks, &key, &2_u64).unwrap();
+        assert_eq!(database.get::<u64>(Table::Blocks, &key).unwrap(), Some(2));
+        assert_eq!(database.get::<u64>(Table::StateDiffs, &key).unwrap(), None);
+
+        database.commit().unwrap();
+        assert_eq!(database.get::<u64>(Table::Blocks, &key).unwrap(), Some(2));
+
+        database.delete(Table::Blocks, &key);
+        assert_eq!(database.get::<u64>(Table::Blocks, &key).unwrap(), None);
+        database.commit().unwrap();
+        assert_eq!(database.get::<u64>(Table::Blocks, &key).unwrap(), None);
+    }
+
+    #[test]
+    fn uncommitted_records_are_not_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = Database::open(dir.path()).unwrap();
+        database.put(Table::Transactions, &[1], &"committed").unwrap();
+        database.commit().unwrap();
+        database.put(Table::Transactions, &[2], &"uncommitted").unwrap();
+        drop(database);
+
+        let reopened = Database::open(dir.path()).unwrap();
+        assert_eq!(
+            reopened.get::<String>(Table::Transactions, &[1]).unwrap().unwrap(),
+            "committed"
+        );
+        assert_eq!(reopened.get::<String>(Table::Transactions, &[2]).unwrap(), None);
+    }
+
+    #[test]
+    fn ranges_include_uncommitted_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = Database::open(dir.path()).unwrap();
+        for key in [1_u8, 2, 3, 5] {
+            database.put(Table::BlockNumbers, &[key], &key).unwrap();
+        }
+        database.commit().unwrap();
+
+        database.put(Table::BlockNumbers, &[4], &4_u8).unwrap();
+        database.delete(Table::BlockNumbers, &[3]);
+        let range = database.range::<u8>(Table::BlockNumbers, &[2], &[4]).unwrap();
+        assert_eq!(range, vec![(vec![2], 2), (vec![4], 4)]);
+
+        let last = database.last_in_range::<u8>(Table::BlockNumbers, &[0], &[3]).unwrap();
+        assert_eq!(last, Some((vec![2], 2)));
+        let last = database.last_in_range::<u8>(Table::BlockNumbers, &[0], &[4]).unwrap();
+        assert_eq!(last, Some((vec![4], 4)));
+        assert_eq!(database.last_in_range::<u8>(Table::BlockNumbers, &[0], &[0]).unwrap(), None);
+    }
+
+    #[test]
+    fn clearing_removes_all_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = Database::open(dir.path()).unwrap();
+        database.put(Table::Transactions, &[1], &"tx").unwrap();
+        database.commit().unwrap();
+        database.put_devnet_record(&"devnet").unwrap();
+
+        database.clear().unwrap();
+        assert_eq!(database.get_devnet_record::<String>().unwrap(), None);
+        assert_eq!(database.get::<String>(Table::Transactions, &[1]).unwrap(), None);
+
+        // the cleared database stays usable
+        database.put(Table::Transactions, &[1], &"tx").unwrap();
+        database.commit().unwrap();
+        assert_eq!(database.get::<String>(Table::Transactions, &[1]).unwrap().unwrap(), "tx");
+    }
+}
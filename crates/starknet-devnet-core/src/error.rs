@@ -22,6 +22,8 @@ pub enum Error {
     TypesError(#[from] starknet_types::error::Error),
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] redb::Error),
     #[error("Error when reading file {path}")]
     ReadFileError { source: std::io::Error, path: String },
     #[error("The file does not exist")]
@@ -36,6 +38,8 @@ pub enum Error {
     NoBlock,
     #[error("No state at block {block_id:?}; consider running with --state-archive-capacity full")]
     NoStateAtBlock { block_id: BlockId },
+    #[error("No snapshot with id {snapshot_id}")]
+    NoSnapshot { snapshot_id: u64 },
     #[error("Format error")]
     FormatError,
     #[error("No transaction found")]
@@ -70,6 +74,10 @@ pub enum Error {
     NoTransactionTrace,
     #[error("the compiled class hash did not match the one supplied in the transaction")]
     CompiledClassHashMismatch,
+    #[error("Invalid genesis: {msg}")]
+    InvalidGenesis { msg: String },
+    #[error("The pending block has no room left for the transaction")]
+    BlockFull,
 }
 
 impl From<starknet_types_core::felt::FromStrError> for Error {
@@ -2,8 +2,10 @@ pub mod account;
 mod blocks;
 pub mod constants;
 pub mod contract_class_choice;
+mod database;
 pub mod error;
 pub mod messaging;
+mod patricia_trie;
 mod predeployed_accounts;
 pub mod raw_execution;
 pub mod starknet;
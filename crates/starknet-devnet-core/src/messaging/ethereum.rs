@@ -7,7 +7,7 @@ use ethers::prelude::*;
 use ethers::providers::{Http, Provider, ProviderError};
 use ethers::types::{Address, BlockNumber, Log};
 use k256::ecdsa::SigningKey;
-use starknet_rs_core::types::Felt;
+use starknet_rs_core::types::{Felt, Hash256};
 use starknet_types::felt::felt_from_prefixed_hex;
 use starknet_types::rpc::contract_address::ContractAddress;
 use starknet_types::rpc::messaging::{MessageToL1, MessageToL2};
@@ -302,6 +302,7 @@ impl EthereumMessaging {
 ///
 /// * `log` - The log to be converted.
 pub fn message_to_l2_from_log(log: Log) -> DevnetResult<MessageToL2> {
+    let l1_transaction_hash = log.transaction_hash.map(|hash| Hash256::from_bytes(hash.0));
     let parsed_log = <LogMessageToL2 as EthLogDecode>::decode_log(&log.into()).map_err(|e| {
         Error::MessagingError(MessagingError::EthersError(format!("Log parsing failed {}", e)))
     })?;
@@ -324,6 +325,7 @@ pub fn message_to_l2_from_log(log: Log) -> DevnetResult<MessageToL2> {
         payload,
         paid_fee_on_l1,
         nonce,
+        l1_transaction_hash,
     })
 }
 
@@ -415,6 +417,7 @@ mod tests {
             payload,
             nonce: nonce.into(),
             paid_fee_on_l1: fee.into(),
+            l1_transaction_hash: None,
         };
 
         let message = message_to_l2_from_log(log).unwrap();
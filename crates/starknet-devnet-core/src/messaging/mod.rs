@@ -32,20 +32,22 @@
 //! contract (`mockSendMessageFromL2` entrypoint).
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
 use starknet_rs_core::types::{BlockId, ExecutionResult, Hash256};
+use starknet_types::felt::TransactionHash;
 use starknet_types::rpc::messaging::{MessageToL1, MessageToL2};
 
 use crate::error::{DevnetResult, Error, MessagingError};
 use crate::starknet::Starknet;
-use crate::traits::HashIdentified;
 use crate::StarknetBlock;
 
 pub mod ethereum;
 pub use ethereum::EthereumMessaging;
 
-#[derive(Default)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct MessagingBroker {
     /// The ethereum broker to send transaction / call contracts using ethers.
+    #[serde(skip)]
     pub(crate) ethereum: Option<EthereumMessaging>,
     /// The last local (starknet) block for which messages have been collected
     /// and sent.
@@ -61,6 +63,9 @@ pub struct MessagingBroker {
     pub l2_to_l1_messages_hashes: HashMap<String, u64>,
     /// This list of messages that will be sent to L1 node at the next `postman/flush`.
     pub l2_to_l1_messages_to_flush: Vec<MessageToL1>,
+    /// The L1 handler transactions executed for messages sent to L2, keyed by the hash of the L1
+    /// transaction which sent the messages. Using `String` keys for the same reason as above.
+    pub l1_to_l2_transaction_hashes: HashMap<String, Vec<TransactionHash>>,
 }
 
 impl MessagingBroker {
@@ -137,7 +142,7 @@ impl Starknet {
 
                 let mut last_processed_block: u64 = 0;
                 for block in blocks {
-                    messages.extend(self.get_block_messages(block)?);
+                    messages.extend(self.get_block_messages(&block)?);
                     last_processed_block = block.header.block_number.0;
                 }
 
@@ -199,6 +204,37 @@ impl Starknet {
         }
     }
 
+    /// Registers the L1 handler transaction executed for a message sent by the L1 transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `l1_transaction_hash` - The hash of the L1 transaction which sent the message.
+    /// * `transaction_hash` - The hash of the L1 handler transaction.
+    pub fn register_l1_handler_transaction(
+        &mut self,
+        l1_transaction_hash: Hash256,
+        transaction_hash: TransactionHash,
+    ) {
+        self.messaging
+            .l1_to_l2_transaction_hashes
+            .entry(format!("{}", l1_transaction_hash))
+            .or_default()
+            .push(transaction_hash);
+    }
+
+    /// Returns the hashes of the L1 handler transactions executed for the messages sent by the L1
+    /// transaction, or `None` if no messages of the L1 transaction were received.
+    ///
+    /// # Arguments
+    ///
+    /// * `l1_transaction_hash` - The hash of the L1 transaction which sent the messages.
+    pub fn get_l1_handler_transactions(
+        &self,
+        l1_transaction_hash: Hash256,
+    ) -> Option<&Vec<TransactionHash>> {
+        self.messaging.l1_to_l2_transaction_hashes.get(&format!("{}", l1_transaction_hash))
+    }
+
     /// Fetches all messages from L1 and converts the ethereum log into `MessageToL2`.
     pub async fn fetch_messages_to_l2(&mut self) -> DevnetResult<Vec<MessageToL2>> {
         let ethereum = self.messaging.ethereum_mut()?;
@@ -214,17 +250,15 @@ impl Starknet {
     fn get_block_messages(&self, block: &StarknetBlock) -> DevnetResult<Vec<MessageToL1>> {
         let mut messages = vec![];
 
-        block.get_transactions().iter().for_each(|transaction_hash| {
-            if let Ok(transaction) =
-                self.transactions.get_by_hash(*transaction_hash).ok_or(Error::NoTransaction)
-            {
+        for transaction_hash in block.get_transactions() {
+            if let Some(transaction) = self.transactions.get(transaction_hash)? {
                 // As we will send the messages to L1 node, we don't want to include
                 // the messages of reverted transactions.
                 if let ExecutionResult::Succeeded = transaction.execution_result {
                     messages.extend(transaction.get_l2_to_l1_messages())
                 }
             }
-        });
+        }
 
         Ok(messages)
     }
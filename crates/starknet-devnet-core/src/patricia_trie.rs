@@ -0,0 +1,385 @@
+//! Binary Merkle-Patricia tries, as used by Starknet for the state and block commitments.
+
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+
+use starknet_rs_core::types::Felt;
+use starknet_types::rpc::storage_proof::{
+    BinaryNode, EdgeNode, MerkleNode, NodeHashToNode, NodeHashToNodeMapping,
+};
+use starknet_types_core::hash::StarkHash;
+
+/// Height of the contract, storage and class tries
+pub(crate) const STATE_TRIE_HEIGHT: usize = 251;
+/// Height of the transaction and event tries
+pub(crate) const BLOCK_TRIE_HEIGHT: usize = 64;
+
+/// Index of the first bit of a key of a trie of `height`, in the big-endian bit representation of
+/// a felt
+fn first_key_bit(height: usize) -> usize {
+    256 - height
+}
+
+struct Leaf {
+    key_bits: [bool; 256],
+    value: Felt,
+}
+
+/// Calculates the root of a trie of `height`, whose nodes are hashed with `H`. Leaves with a zero
+/// value are considered empty. The root of an empty trie is zero.
+pub(crate) fn calculate_root<H: StarkHash>(
+    leaves: impl IntoIterator<Item = (Felt, Felt)>,
+    height: usize,
+) -> Felt {
+    calculate_root_with_proof::<H>(leaves, height, &[]).0
+}
+
+/// Like `calculate_root`, but also returns the nodes proving membership or non-membership of each
+/// of `proven_keys` in the trie. Every node is included only once.
+pub(crate) fn calculate_root_with_proof<H: StarkHash>(
+    leaves: impl IntoIterator<Item = (Felt, Felt)>,
+    height: usize,
+    proven_keys: &[Felt],
+) -> (Felt, NodeHashToNodeMapping) {
+    let mut leaves: Vec<(Felt, Felt)> =
+        leaves.into_iter().filter(|(_, value)| *value != Felt::ZERO).collect();
+    if leaves.is_empty() {
+        return (Felt::ZERO, vec![]);
+    }
+
+    leaves.sort_unstable_by_key(|(key, _)| *key);
+    let leaves: Vec<Leaf> =
+        leaves.into_iter().map(|(key, value)| Leaf { key_bits: key.to_bits_be(), value }).collect();
+
+    // keys which don't fit into the trie cannot be proven
+    let first_key_bit = first_key_bit(height);
+    let proven_keys: Vec<[bool; 256]> = proven_keys
+        .iter()
+        .map(|key| key.to_bits_be())
+        .filter(|key_bits| key_bits[..first_key_bit].iter().all(|bit| !bit))
+        .collect();
+    let proven_keys: Vec<&[bool; 256]> = proven_keys.iter().collect();
+
+    let mut proof = vec![];
+    let root = subtrie_hash::<H>(&leaves, first_key_bit, &proven_keys, &mut proof);
+    (root, proof)
+}
+
+/// Returns the hash of the subtrie containing `leaves`, which is rooted at bit `depth` of their
+/// keys. The leaves have to be sorted by key and share all key bits before `depth`. The nodes on
+/// the paths to `proven_keys`, which share the same bits, are added to `proof`.
+fn subtrie_hash<H: StarkHash>(
+    leaves: &[Leaf],
+    depth: usize,
+    proven_keys: &[&[bool; 256]],
+    proof: &mut NodeHashToNodeMapping,
+) -> Felt {
+    // since the leaves are sorted, their common path is shared by the first and the last one
+    let first = &leaves[0].key_bits;
+    let last = &leaves[leaves.len() - 1].key_bits;
+    let split_bit = (depth..256).find(|&bit| first[bit] != last[bit]);
+    let edge_end = split_bit.unwrap_or(256);
+
+    let node_hash = match split_bit {
+        // a single leaf
+        None => leaves[0].value,
+        Some(split_bit) => {
+            let (left_keys, right_keys): (Vec<&[bool; 256]>, Vec<&[bool; 256]>) = proven_keys
+                .iter()
+                .filter(|key| key[depth..split_bit] == first[depth..split_bit])
+                .partition(|key| !key[split_bit]);
+
+            let right_start = leaves.partition_point(|leaf| !leaf.key_bits[split_bit]);
+            let left = subtrie_hash::<H>(&leaves[..right_start], split_bit + 1, &left_keys, proof);
+            let right =
+                subtrie_hash::<H>(&leaves[right_start..], split_bit + 1, &right_keys, proof);
+
+            let node_hash = H::hash(&left, &right);
+            if !left_keys.is_empty() || !right_keys.is_empty() {
+                let node = MerkleNode::Binary(BinaryNode { left, right });
+                proof.push(NodeHashToNode { node_hash, node });
+            }
+            node_hash
+        }
+    };
+
+    if edge_end == depth {
+        return node_hash;
+    }
+
+    let path = &first[depth..edge_end];
+    let (edge_hash, path_felt) = edge_hash::<H>(node_hash, path);
+    if !proven_keys.is_empty() {
+        let node =
+            MerkleNode::Edge(EdgeNode { path: path_felt, length: path.len(), child: node_hash });
+        proof.push(NodeHashToNode { node_hash: edge_hash, node });
+    }
+    edge_hash
+}
+
+/// Returns the hash of the edge along `path` to the node with `child_hash`, and the path as a felt
+fn edge_hash<H: StarkHash>(child_hash: Felt, path: &[bool]) -> (Felt, Felt) {
+    let path_felt = path.iter().fold(Felt::ZERO, |acc, bit| acc.double() + Felt::from(*bit));
+    (H::hash(&child_hash, &path_felt) + Felt::from(path.len()), path_felt)
+}
+
+/// A trie of `STATE_TRIE_HEIGHT` which keeps the hashes of its subtries between updates, so that
+/// only the subtries on the paths to changed leaves are hashed again when calculating the root.
+pub(crate) struct IncrementalTrie<H: StarkHash> {
+    leaves: BTreeMap<Felt, Felt>,
+    /// Hashes of the subtries rooted at a bit of the keys, by the bit and the key bits before it
+    subtrie_hashes: HashMap<(usize, [u8; 32]), Felt>,
+    hasher: PhantomData<H>,
+}
+
+// not derived, since the hasher doesn't implement `Default`
+impl<H: StarkHash> Default for IncrementalTrie<H> {
+    fn default() -> Self {
+        Self { leaves: BTreeMap::new(), subtrie_hashes: HashMap::new(), hasher: PhantomData }
+    }
+}
+
+impl<H: StarkHash> IncrementalTrie<H> {
+    /// Sets the leaf at `key`. A zero value removes the leaf.
+    pub(crate) fn set(&mut self, key: Felt, value: Felt) {
+        let unchanged = match self.leaves.get(&key) {
+            Some(old_value) => *old_value == value,
+            None => value == Felt::ZERO,
+        };
+        if unchanged {
+            return;
+        }
+
+        if value == Felt::ZERO {
+            self.leaves.remove(&key);
+        } else {
+            self.leaves.insert(key, value);
+        }
+        let key_bytes = key.to_bytes_be();
+        for depth in first_key_bit(STATE_TRIE_HEIGHT)..=256 {
+            self.subtrie_hashes.remove(&(depth, key_prefix(&key_bytes, depth)));
+        }
+    }
+
+    /// Returns the root of the trie, which is zero if the trie is empty
+    pub(crate) fn root(&mut self) -> Felt {
+        if self.leaves.is_empty() {
+            return Felt::ZERO;
+        }
+        self.subtrie_hash(first_key_bit(STATE_TRIE_HEIGHT), [0; 32])
+    }
+
+    /// Returns the hash of the subtrie rooted at bit `depth`, containing the leaves whose keys
+    /// start with `prefix`. The hash of an empty subtrie is zero.
+    fn subtrie_hash(&mut self, depth: usize, prefix: [u8; 32]) -> Felt {
+        if let Some(hash) = self.subtrie_hashes.get(&(depth, prefix)) {
+            return *hash;
+        }
+
+        let mut last_key_bytes = prefix;
+        for bit in depth..256 {
+            last_key_bytes[bit / 8] |= 0x80 >> (bit % 8);
+        }
+        let mut range =
+            self.leaves.range(Felt::from_bytes_be(&prefix)..=Felt::from_bytes_be(&last_key_bytes));
+        let Some((&first_key, &first_value)) = range.next() else {
+            return Felt::ZERO;
+        };
+        let last_key = range.next_back().map_or(first_key, |(key, _)| *key);
+        let (first, last) = (first_key.to_bits_be(), last_key.to_bits_be());
+
+        let split_bit = (depth..256).find(|&bit| first[bit] != last[bit]);
+        let node_hash = match split_bit {
+            // a single leaf
+            None => first_value,
+            Some(split_bit) => {
+                let left = self.subtrie_hash(
+                    split_bit + 1,
+                    key_prefix(&first_key.to_bytes_be(), split_bit + 1),
+                );
+                let right = self.subtrie_hash(
+                    split_bit + 1,
+                    key_prefix(&last_key.to_bytes_be(), split_bit + 1),
+                );
+                H::hash(&left, &right)
+            }
+        };
+
+        let edge_end = split_bit.unwrap_or(256);
+        let hash = if edge_end == depth {
+            node_hash
+        } else {
+            edge_hash::<H>(node_hash, &first[depth..edge_end]).0
+        };
+        self.subtrie_hashes.insert((depth, prefix), hash);
+        hash
+    }
+}
+
+/// Returns the big-endian bytes of the key with all bits from `depth` on cleared
+fn key_prefix(key_bytes: &[u8; 32], depth: usize) -> [u8; 32] {
+    let mut prefix = *key_bytes;
+    for bit in depth..256 {
+        prefix[bit / 8] &= !(0x80 >> (bit % 8));
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_rs_core::types::Felt;
+    use starknet_types::rpc::storage_proof::{MerkleNode, NodeHashToNodeMapping};
+    use starknet_types_core::hash::{Pedersen, Poseidon, StarkHash};
+
+    use super::{
+        calculate_root, calculate_root_with_proof, first_key_bit, IncrementalTrie,
+        BLOCK_TRIE_HEIGHT, STATE_TRIE_HEIGHT,
+    };
+
+    /// Follows the path to `key` from `root` through the nodes of `proof`. Returns the value of the
+    /// leaf at `key`, or zero if the proof shows there is no such leaf.
+    fn verify_proof<H: StarkHash>(
+        root: Felt,
+        key: Felt,
+        height: usize,
+        proof: &NodeHashToNodeMapping,
+    ) -> Felt {
+        let key_bits = key.to_bits_be();
+        let mut node_hash = root;
+        let mut depth = first_key_bit(height);
+        while depth < 256 {
+            let node = &proof.iter().find(|item| item.node_hash == node_hash).unwrap().node;
+            match node {
+                MerkleNode::Binary(binary) => {
+                    assert_eq!(H::hash(&binary.left, &binary.right), node_hash);
+                    node_hash = if key_bits[depth] { binary.right } else { binary.left };
+                    depth += 1;
+                }
+                MerkleNode::Edge(edge) => {
+                    assert_eq!(
+                        H::hash(&edge.child, &edge.path) + Felt::from(edge.length),
+                        node_hash
+                    );
+                    let path_bits = edge.path.to_bits_be();
+                    if key_bits[depth..depth + edge.length] != path_bits[256 - edge.length..] {
+                        return Felt::ZERO;
+                    }
+                    node_hash = edge.child;
+                    depth += edge.length;
+                }
+            }
+        }
+
+        node_hash
+    }
+
+    #[test]
+    fn root_of_empty_trie_is_zero() {
+        assert_eq!(calculate_root::<Pedersen>([], STATE_TRIE_HEIGHT), Felt::ZERO);
+        assert_eq!(
+            calculate_root::<Pedersen>([(Felt::ONE, Felt::ZERO)], STATE_TRIE_HEIGHT),
+            Felt::ZERO
+        );
+    }
+
+    #[test]
+    fn root_of_single_leaf_trie_is_edge_to_leaf() {
+        let (key, value) = (Felt::from(5), Felt::from(42));
+        let expected = Pedersen::hash(&value, &key) + Felt::from(STATE_TRIE_HEIGHT);
+        assert_eq!(calculate_root::<Pedersen>([(key, value)], STATE_TRIE_HEIGHT), expected);
+    }
+
+    #[test]
+    fn root_of_trie_with_sibling_leaves() {
+        // keys 0b100 and 0b101 share all bits except the last one
+        let (left_value, right_value) = (Felt::from(7), Felt::from(8));
+        let leaves = [(Felt::from(0b101), right_value), (Felt::from(0b100), left_value)];
+
+        let binary_hash = Poseidon::hash(&left_value, &right_value);
+        let expected =
+            Poseidon::hash(&binary_hash, &Felt::from(0b10)) + Felt::from(BLOCK_TRIE_HEIGHT - 1);
+        assert_eq!(calculate_root::<Poseidon>(leaves, BLOCK_TRIE_HEIGHT), expected);
+    }
+
+    #[test]
+    fn root_of_trie_with_split_at_top() {
+        let low_key = Felt::ONE;
+        let high_key = Felt::ONE + Felt::TWO.pow(STATE_TRIE_HEIGHT as u128 - 1);
+        let (low_value, high_value) = (Felt::from(3), Felt::from(4));
+
+        let remaining_length = Felt::from(STATE_TRIE_HEIGHT - 1);
+        let left_hash = Pedersen::hash(&low_value, &Felt::ONE) + remaining_length;
+        let right_hash = Pedersen::hash(&high_value, &Felt::ONE) + remaining_length;
+        assert_eq!(
+            calculate_root::<Pedersen>(
+                [(low_key, low_value), (high_key, high_value)],
+                STATE_TRIE_HEIGHT
+            ),
+            Pedersen::hash(&left_hash, &right_hash)
+        );
+    }
+
+    #[test]
+    fn proof_shows_membership_and_non_membership() {
+        let leaves: Vec<(Felt, Felt)> = [1_u64, 2, 3, 8, 1 << 40, u64::MAX]
+            .into_iter()
+            .map(|key| (Felt::from(key), Felt::from(key) + Felt::ONE))
+            .collect();
+        let absent_keys = [Felt::ZERO, Felt::from(4), Felt::from(9), Felt::from(u64::MAX - 1)];
+        let proven_keys: Vec<Felt> =
+            leaves.iter().map(|(key, _)| *key).chain(absent_keys).collect();
+
+        let (root, proof) =
+            calculate_root_with_proof::<Pedersen>(leaves.clone(), STATE_TRIE_HEIGHT, &proven_keys);
+        assert_eq!(root, calculate_root::<Pedersen>(leaves.clone(), STATE_TRIE_HEIGHT));
+
+        for (key, value) in leaves {
+            assert_eq!(verify_proof::<Pedersen>(root, key, STATE_TRIE_HEIGHT, &proof), value);
+        }
+        for key in absent_keys {
+            assert_eq!(verify_proof::<Pedersen>(root, key, STATE_TRIE_HEIGHT, &proof), Felt::ZERO);
+        }
+    }
+
+    #[test]
+    fn proof_contains_only_nodes_on_paths_to_proven_keys() {
+        let leaves = [(Felt::from(0b100), Felt::from(7)), (Felt::from(0b111), Felt::from(8))];
+
+        let (_, proof) = calculate_root_with_proof::<Poseidon>(leaves, BLOCK_TRIE_HEIGHT, &[]);
+        assert!(proof.is_empty());
+
+        // the root edge, the binary node and the edge to the leaf
+        let (_, proof) =
+            calculate_root_with_proof::<Poseidon>(leaves, BLOCK_TRIE_HEIGHT, &[Felt::from(0b100)]);
+        assert_eq!(proof.len(), 3);
+    }
+
+    #[test]
+    fn incremental_trie_has_root_of_its_leaves_after_updates() {
+        let mut trie = IncrementalTrie::<Pedersen>::default();
+        assert_eq!(trie.root(), Felt::ZERO);
+
+        let mut leaves: Vec<(Felt, Felt)> = [1_u64, 2, 3, 8, 1 << 40, u64::MAX]
+            .into_iter()
+            .map(|key| (Felt::from(key), Felt::from(key) + Felt::ONE))
+            .collect();
+        for (key, value) in &leaves {
+            trie.set(*key, *value);
+        }
+        assert_eq!(trie.root(), calculate_root::<Pedersen>(leaves.clone(), STATE_TRIE_HEIGHT));
+
+        // changing, removing and adding leaves
+        leaves[0].1 = Felt::from(100);
+        leaves[3].1 = Felt::ZERO;
+        leaves.push((Felt::from(9), Felt::from(10)));
+        for (key, value) in &leaves {
+            trie.set(*key, *value);
+        }
+        assert_eq!(trie.root(), calculate_root::<Pedersen>(leaves.clone(), STATE_TRIE_HEIGHT));
+
+        for (key, _) in &leaves {
+            trie.set(*key, Felt::ZERO);
+        }
+        assert_eq!(trie.root(), Felt::ZERO);
+    }
+}
@@ -79,25 +79,28 @@ pub fn add_declare_transaction(
     )?);
 
     let transaction = TransactionWithHash::new(transaction_hash, declare_transaction);
-    let blockifier_execution_result =
+    let blockifier_declare_transaction =
         blockifier::transaction::account_transaction::AccountTransaction::Declare(
             blockifier_declare_transaction,
-        )
-        .execute(
+        );
+
+    // do the steps required in all transactions
+    starknet.execute_transaction(&transaction, |starknet| {
+        let blockifier_execution_result = blockifier_declare_transaction.execute(
             &mut starknet.pending_state.state,
             &starknet.block_context,
             true,
             validate,
         );
 
-    // if tx successful, store the class
-    if blockifier_execution_result.as_ref().is_ok_and(|res| !res.is_reverted()) {
-        let state = starknet.get_state();
-        state.declare_contract_class(class_hash, casm_hash, contract_class)?;
-    }
+        // if tx successful, store the class
+        if blockifier_execution_result.as_ref().is_ok_and(|res| !res.is_reverted()) {
+            let state = starknet.get_state();
+            state.declare_contract_class(class_hash, casm_hash, contract_class.clone())?;
+        }
 
-    // do the steps required in all transactions
-    starknet.handle_transaction_result(transaction, blockifier_execution_result)?;
+        Ok(blockifier_execution_result)
+    })?;
 
     Ok((transaction_hash, class_hash))
 }
@@ -152,7 +155,7 @@ mod tests {
     use crate::starknet::tests::setup_starknet_with_no_signature_check_account;
     use crate::starknet::Starknet;
     use crate::state::{BlockNumberOrPending, CustomStateReader};
-    use crate::traits::{HashIdentified, HashIdentifiedMut};
+    use crate::traits::HashIdentified;
     use crate::utils::exported_test_utils::dummy_cairo_0_contract_class;
     use crate::utils::test_utils::{
         convert_broadcasted_declare_v2_to_v3, dummy_broadcasted_declare_transaction_v2,
@@ -293,7 +296,7 @@ mod tests {
             )))
             .unwrap();
 
-        let tx = starknet.transactions.get_by_hash_mut(&tx_hash).unwrap();
+        let tx = starknet.transactions.get(&tx_hash).unwrap().unwrap();
 
         // check if generated class hash is expected one
         assert_eq!(
@@ -321,7 +324,7 @@ mod tests {
             )))
             .unwrap();
 
-        let tx = starknet.transactions.get_by_hash_mut(&tx_hash).unwrap();
+        let tx = starknet.transactions.get(&tx_hash).unwrap().unwrap();
 
         // check if generated class hash is expected one
         let generated_hash =
@@ -360,7 +363,7 @@ mod tests {
             .add_declare_transaction(BroadcastedDeclareTransaction::V2(Box::new(declare_txn)))
             .unwrap();
 
-        let retrieved_txn = starknet.transactions.get_by_hash_mut(&tx_hash).unwrap();
+        let retrieved_txn = starknet.transactions.get(&tx_hash).unwrap().unwrap();
 
         // check if generated class hash is expected one
         assert_eq!(retrieved_class_hash, expected_class_hash);
@@ -453,7 +456,7 @@ mod tests {
         let declare_txn = broadcasted_declare_transaction_v1(sender.account_address);
         let (tx_hash, class_hash) = starknet.add_declare_transaction(declare_txn.clone()).unwrap();
 
-        let tx = starknet.transactions.get_by_hash_mut(&tx_hash).unwrap();
+        let tx = starknet.transactions.get(&tx_hash).unwrap().unwrap();
         match declare_txn {
             BroadcastedDeclareTransaction::V1(ref v1) => {
                 // check if generated class hash is expected one
@@ -476,6 +479,7 @@ mod tests {
                 .blocks
                 .get_by_hash(starknet.blocks.last_block_hash.unwrap())
                 .unwrap()
+                .unwrap()
                 .get_transactions()
                 .first()
                 .unwrap(),
@@ -499,7 +503,7 @@ mod tests {
 
         let (tx_hash, class_hash) = starknet.add_declare_transaction(declare_txn).unwrap();
 
-        let tx = starknet.transactions.get_by_hash_mut(&tx_hash).unwrap();
+        let tx = starknet.transactions.get(&tx_hash).unwrap().unwrap();
 
         // check if txn is with status accepted
         assert_eq!(tx.finality_status, TransactionFinalityStatus::AcceptedOnL2);
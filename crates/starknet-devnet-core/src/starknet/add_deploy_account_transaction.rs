@@ -57,13 +57,19 @@ pub fn add_deploy_account_transaction(
     let transaction_hash = blockifier_deploy_account_transaction.tx_hash.0;
     let transaction = TransactionWithHash::new(transaction_hash, deploy_account_transaction);
 
-    let blockifier_execution_result =
+    let blockifier_deploy_account_transaction =
         blockifier::transaction::account_transaction::AccountTransaction::DeployAccount(
             blockifier_deploy_account_transaction,
-        )
-        .execute(&mut starknet.pending_state.state, &starknet.block_context, true, true);
+        );
 
-    starknet.handle_transaction_result(transaction, blockifier_execution_result)?;
+    starknet.execute_transaction(&transaction, |starknet| {
+        Ok(blockifier_deploy_account_transaction.execute(
+            &mut starknet.pending_state.state,
+            &starknet.block_context,
+            true,
+            true,
+        ))
+    })?;
 
     Ok((transaction_hash, address))
 }
@@ -71,6 +77,7 @@ pub fn add_deploy_account_transaction(
 mod tests {
 
     use blockifier::state::state_api::{State, StateReader};
+    use blockifier::versioned_constants::VersionedConstants;
     use nonzero_ext::nonzero;
     use starknet_api::transaction::{Fee, Tip};
     use starknet_rs_core::types::{
@@ -93,7 +100,7 @@ mod tests {
     use crate::error::Error;
     use crate::starknet::{predeployed, Starknet};
     use crate::state::CustomState;
-    use crate::traits::{Deployed, HashIdentifiedMut};
+    use crate::traits::Deployed;
     use crate::utils::get_storage_var_address;
     use crate::utils::test_utils::cairo_0_account_without_validations;
 
@@ -319,7 +326,7 @@ mod tests {
         let (txn_hash, _) = starknet
             .add_deploy_account_transaction(BroadcastedDeployAccountTransaction::V3(transaction))
             .unwrap();
-        let txn = starknet.transactions.get_by_hash_mut(&txn_hash).unwrap();
+        let txn = starknet.transactions.get(&txn_hash).unwrap().unwrap();
 
         assert_eq!(txn.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(txn.execution_result.status(), TransactionExecutionStatus::Succeeded);
@@ -375,7 +382,7 @@ mod tests {
         let (txn_hash, _) = starknet
             .add_deploy_account_transaction(BroadcastedDeployAccountTransaction::V1(transaction))
             .unwrap();
-        let txn = starknet.transactions.get_by_hash_mut(&txn_hash).unwrap();
+        let txn = starknet.transactions.get(&txn_hash).unwrap().unwrap();
 
         assert_eq!(txn.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(txn.execution_result.status(), TransactionExecutionStatus::Succeeded);
@@ -414,6 +421,7 @@ mod tests {
             constants::STRK_ERC20_CONTRACT_ADDRESS,
             DEVNET_DEFAULT_CHAIN_ID,
             DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
+            VersionedConstants::create_for_testing(),
         );
 
         starknet.restart_pending_block().unwrap();
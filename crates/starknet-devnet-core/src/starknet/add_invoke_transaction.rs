@@ -1,4 +1,21 @@
+use std::sync::Arc;
+
+use blockifier::context::{BlockContext, TransactionContext};
+use blockifier::execution::common_hints::ExecutionMode;
+use blockifier::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use blockifier::state::cached_state::CachedState;
+use blockifier::state::state_api::{State, StateReader};
+use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::errors::TransactionExecutionError;
+use blockifier::transaction::objects::{
+    DeprecatedTransactionInfo, HasRelatedFeeType, TransactionExecutionInfo, TransactionInfo,
+};
 use blockifier::transaction::transactions::ExecutableTransaction;
+use starknet_api::core::EntryPointSelector;
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::transaction::Calldata;
+use starknet_rs_core::types::Felt;
+use starknet_rs_core::utils::get_selector_from_name;
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::felt::TransactionHash;
 use starknet_types::rpc::transactions::invoke_transaction_v1::InvokeTransactionV1;
@@ -10,6 +27,9 @@ use starknet_types::rpc::transactions::{
 use super::Starknet;
 use crate::error::{DevnetResult, Error};
 
+/// The revert reason of transactions reverted via [`Starknet::set_transactions_to_revert`]
+pub const FORCED_REVERT_REASON: &str = "Reverted by fault injection";
+
 pub fn add_invoke_transaction(
     starknet: &mut Starknet,
     broadcasted_invoke_transaction: BroadcastedInvokeTransaction,
@@ -44,28 +64,105 @@ pub fn add_invoke_transaction(
         &ContractAddress::from(blockifier_invoke_transaction.sender_address()),
     )?);
 
-    let block_context = starknet.block_context.clone();
-
-    let state = &mut starknet.get_state().state;
-
-    let blockifier_execution_result =
-        blockifier::transaction::account_transaction::AccountTransaction::Invoke(
-            blockifier_invoke_transaction,
-        )
-        .execute(state, &block_context, true, validate);
+    let force_revert = starknet.transactions_to_revert > 0;
 
+    let sender_address = blockifier_invoke_transaction.sender_address();
+    let blockifier_invoke_transaction = AccountTransaction::Invoke(blockifier_invoke_transaction);
     let transaction = TransactionWithHash::new(transaction_hash, invoke_transaction);
 
-    starknet.handle_transaction_result(transaction, blockifier_execution_result)?;
+    starknet.execute_transaction(&transaction, |starknet| {
+        let block_context = starknet.block_context.clone();
+        let state = &mut starknet.get_state().state;
+        if force_revert {
+            execute_with_forced_revert(
+                state,
+                &block_context,
+                &blockifier_invoke_transaction,
+                sender_address,
+                validate,
+            )
+        } else {
+            Ok(blockifier_invoke_transaction.execute(state, &block_context, true, validate))
+        }
+    })?;
+
+    // only accepted transactions count as reverted by force
+    if force_revert {
+        starknet.transactions_to_revert -= 1;
+    }
 
     Ok(transaction_hash)
 }
+
+/// Executes the transaction, but keeps only the effects a reverted transaction would have: the
+/// nonce of the sender is incremented and the fee is transferred to the sequencer. The inner
+/// result is an error if the transaction fails validation, in which case it's rejected as usual.
+fn execute_with_forced_revert<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    transaction: &AccountTransaction,
+    sender_address: starknet_api::core::ContractAddress,
+    validate: bool,
+) -> DevnetResult<Result<TransactionExecutionInfo, TransactionExecutionError>> {
+    let mut transactional_state = CachedState::create_transactional(state);
+    let execution_result =
+        transaction.execute(&mut transactional_state, block_context, true, validate);
+    // the executed code counts towards coverage, even though its effects are discarded
+    let visited_pcs = std::mem::take(&mut transactional_state.visited_pcs);
+    transactional_state.abort();
+    for (class_hash, pcs) in &visited_pcs {
+        state.add_visited_pcs(*class_hash, pcs);
+    }
+    let mut execution_info = match execution_result {
+        Ok(execution_info) => execution_info,
+        Err(err) => return Ok(Err(err)),
+    };
+
+    state.increment_nonce(sender_address)?;
+
+    let fee = execution_info.transaction_receipt.fee;
+    let fee_transfer_call = CallEntryPoint {
+        entry_point_type: EntryPointType::External,
+        entry_point_selector: EntryPointSelector(
+            get_selector_from_name("transfer")
+                .map_err(|err| Error::UnexpectedInternalError { msg: err.to_string() })?,
+        ),
+        calldata: Calldata(Arc::new(vec![
+            *block_context.block_info().sequencer_address.0.key(),
+            Felt::from(fee.0),
+            Felt::ZERO,
+        ])),
+        storage_address: block_context.chain_info().fee_token_address(&transaction.fee_type()),
+        caller_address: sender_address,
+        initial_gas: block_context.versioned_constants().tx_initial_gas(),
+        ..Default::default()
+    };
+    let mut execution_context = EntryPointExecutionContext::new(
+        Arc::new(TransactionContext {
+            block_context: block_context.clone(),
+            tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+        }),
+        ExecutionMode::Execute,
+        true,
+    )?;
+    let fee_transfer_call_info =
+        fee_transfer_call.execute(state, &mut Default::default(), &mut execution_context)?;
+
+    execution_info.execute_call_info = None;
+    execution_info.fee_transfer_call_info = Some(fee_transfer_call_info);
+    execution_info.revert_error = Some(FORCED_REVERT_REASON.to_string());
+    Ok(Ok(execution_info))
+}
+
 #[cfg(test)]
 mod tests {
 
     use core::panic;
 
+    use blockifier::bouncer::BouncerWeights;
     use blockifier::state::state_api::StateReader;
+    use blockifier::transaction::errors::TransactionExecutionError;
+    use blockifier::versioned_constants::VersionedConstants;
     use nonzero_ext::nonzero;
     use starknet_api::core::Nonce;
     use starknet_api::transaction::{Fee, Tip};
@@ -84,19 +181,22 @@ mod tests {
     };
     use starknet_types::traits::HashProducer;
 
+    use super::FORCED_REVERT_REASON;
     use crate::account::{Account, FeeToken};
     use crate::constants::{
         self, DEVNET_DEFAULT_CHAIN_ID, DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
         ETH_ERC20_CONTRACT_ADDRESS,
     };
+    use crate::error::Error;
+    use crate::starknet::starknet_config::{BlockGenerationOn, FullBlockPolicy};
     use crate::starknet::{predeployed, Starknet};
     use crate::state::CustomState;
-    use crate::traits::{Accounted, Deployed, HashIdentifiedMut};
+    use crate::traits::{Accounted, Deployed};
     use crate::utils::exported_test_utils::dummy_cairo_0_contract_class;
-    use crate::utils::get_storage_var_address;
     use crate::utils::test_utils::{
         cairo_0_account_without_validations, dummy_contract_address, dummy_felt,
     };
+    use crate::utils::{custom_bouncer_config, get_storage_var_address};
 
     fn test_invoke_transaction_v1(
         account_address: ContractAddress,
@@ -242,7 +342,7 @@ mod tests {
 
         let transaction_hash = starknet.add_invoke_transaction(invoke_transaction).unwrap();
 
-        let transaction = starknet.transactions.get_by_hash_mut(&transaction_hash).unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
 
         assert_eq!(transaction.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Succeeded);
@@ -252,6 +352,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn invoke_transaction_v3_with_insufficient_l1_data_gas_bounds_rejected() {
+        let (mut starknet, account, contract_address, increase_balance_selector, _) = setup();
+        let account_address = account.get_address();
+        // commit the deployments of the setup, so that only the rejected transaction is discarded
+        starknet.commit_diff().unwrap();
+        let l1_gas = account
+            .get_balance(&mut starknet.pending_state, FeeToken::STRK)
+            .unwrap()
+            .to_string()
+            .parse::<u64>()
+            .unwrap();
+
+        let with_l1_data_gas_price = |max_price_per_unit: &str| {
+            let mut invoke_transaction = test_invoke_transaction_v3(
+                account_address,
+                contract_address,
+                increase_balance_selector,
+                Felt::from(10),
+                0,
+                l1_gas,
+                0,
+            );
+            if let BroadcastedInvokeTransaction::V3(ref mut tx) = invoke_transaction {
+                tx.common.resource_bounds = serde_json::from_value(serde_json::json!({
+                    "l1_gas": { "max_amount": format!("{l1_gas:#x}"), "max_price_per_unit": "0x1" },
+                    "l2_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+                    "l1_data_gas": { "max_amount": "0xffff", "max_price_per_unit": max_price_per_unit },
+                }))
+                .unwrap();
+            }
+            invoke_transaction
+        };
+
+        match starknet.add_invoke_transaction(with_l1_data_gas_price("0x0")) {
+            Err(Error::TransactionValidationError(
+                crate::error::TransactionValidationError::InsufficientMaxFee,
+            )) => (),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+        let nonce = starknet.pending_state.get_nonce_at(account_address.try_into().unwrap());
+        assert_eq!(nonce.unwrap(), Nonce(Felt::ZERO));
+
+        let transaction_hash =
+            starknet.add_invoke_transaction(with_l1_data_gas_price("0xffffffffffffffff")).unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
+        assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Succeeded);
+    }
+
     #[test]
     fn invoke_transaction_v3_positive_l2_gas_should_fail() {
         let (mut starknet, account, contract_address, increase_balance_selector, _) = setup();
@@ -312,7 +461,7 @@ mod tests {
 
         let transaction_hash = starknet.add_invoke_transaction(invoke_transaction).unwrap();
 
-        let transaction = starknet.transactions.get_by_hash_mut(&transaction_hash).unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
 
         assert_eq!(transaction.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Succeeded);
@@ -343,7 +492,7 @@ mod tests {
 
         // invoke transaction
         let transaction_hash = starknet.add_invoke_transaction(invoke_transaction).unwrap();
-        let transaction = starknet.transactions.get_by_hash_mut(&transaction_hash).unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
         assert_eq!(transaction.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Succeeded);
 
@@ -363,7 +512,7 @@ mod tests {
 
         // invoke transaction again
         let transaction_hash = starknet.add_invoke_transaction(invoke_transaction).unwrap();
-        let transaction = starknet.transactions.get_by_hash_mut(&transaction_hash).unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
 
         assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Succeeded);
         assert_eq!(transaction.finality_status, TransactionFinalityStatus::AcceptedOnL2);
@@ -416,7 +565,7 @@ mod tests {
         );
 
         let transaction_hash = starknet.add_invoke_transaction(invoke_transaction.clone()).unwrap();
-        let transaction = starknet.transactions.get_by_hash_mut(&transaction_hash).unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
         assert_eq!(transaction.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Succeeded);
 
@@ -430,6 +579,246 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reason_of_rejected_invoke_transaction_is_stored() {
+        let (mut starknet, account, contract_address, increase_balance_selector, _) = setup();
+
+        let account_address = account.get_address();
+        starknet
+            .add_invoke_transaction(test_invoke_transaction_v1(
+                account_address,
+                contract_address,
+                increase_balance_selector,
+                Felt::from(10),
+                0,
+            ))
+            .unwrap();
+
+        // a different transaction reusing the nonce
+        let rejected_transaction = test_invoke_transaction_v1(
+            account_address,
+            contract_address,
+            increase_balance_selector,
+            Felt::from(20),
+            0,
+        );
+        let rejected_hash = rejected_transaction
+            .create_blockifier_invoke_transaction(&starknet.chain_id().to_felt(), false)
+            .unwrap()
+            .tx_hash
+            .0;
+        let err = starknet.add_invoke_transaction(rejected_transaction).unwrap_err();
+
+        assert!(starknet.transactions.get(&rejected_hash).unwrap().is_none());
+        assert_eq!(
+            starknet.get_transaction_rejection_reason(&rejected_hash),
+            Some(err.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn forced_revert_discards_storage_changes_but_charges_fee() {
+        let (
+            mut starknet,
+            account,
+            contract_address,
+            increase_balance_selector,
+            balance_var_storage_address,
+        ) = setup();
+        let blockifier_address: starknet_api::core::ContractAddress =
+            contract_address.try_into().unwrap();
+        let storage_key = (*balance_var_storage_address.get_storage_key()).try_into().unwrap();
+        let account_address = account.get_address();
+
+        starknet.set_transactions_to_revert(1);
+        let transaction_hash = starknet
+            .add_invoke_transaction(test_invoke_transaction_v1(
+                account_address,
+                contract_address,
+                increase_balance_selector,
+                Felt::from(10),
+                0,
+            ))
+            .unwrap();
+
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
+        assert_eq!(transaction.execution_result.revert_reason(), Some(FORCED_REVERT_REASON));
+        assert!(transaction.actual_fee.0 > 0);
+        assert_eq!(
+            starknet.pending_state.get_storage_at(blockifier_address, storage_key).unwrap(),
+            Felt::ZERO
+        );
+        assert_eq!(
+            starknet.pending_state.get_nonce_at(account_address.try_into().unwrap()).unwrap(),
+            Nonce(Felt::ONE)
+        );
+        assert_eq!(starknet.transactions_to_revert(), 0);
+
+        // only the specified number of transactions is reverted
+        let transaction_hash = starknet
+            .add_invoke_transaction(test_invoke_transaction_v1(
+                account_address,
+                contract_address,
+                increase_balance_selector,
+                Felt::from(10),
+                1,
+            ))
+            .unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
+        assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Succeeded);
+        assert_eq!(
+            starknet.pending_state.get_storage_at(blockifier_address, storage_key).unwrap(),
+            Felt::from(10)
+        );
+    }
+
+    /// Executes a transaction in the pending block and limits the steps of a block, so that the
+    /// pending block has no room for another transaction like it. Returns the transaction hash.
+    fn fill_pending_block(
+        starknet: &mut Starknet,
+        account_address: ContractAddress,
+        contract_address: ContractAddress,
+        increase_balance_selector: Felt,
+    ) -> Felt {
+        starknet.config.block_generation_on = BlockGenerationOn::Demand;
+        // transactions are only weighed if the block capacity is limited
+        starknet.config.block_capacity = Some(custom_bouncer_config().block_max_capacity);
+        let transaction_hash = starknet
+            .add_invoke_transaction(test_invoke_transaction_v1(
+                account_address,
+                contract_address,
+                increase_balance_selector,
+                Felt::from(10),
+                0,
+            ))
+            .unwrap();
+
+        let transaction_steps = starknet.pending_block_weights.n_steps;
+        assert!(transaction_steps > 0);
+        starknet.config.block_capacity = Some(BouncerWeights {
+            n_steps: transaction_steps * 3 / 2,
+            ..custom_bouncer_config().block_max_capacity
+        });
+
+        transaction_hash
+    }
+
+    #[test]
+    fn transaction_not_fitting_into_pending_block_executed_in_next_block() {
+        let (mut starknet, account, contract_address, increase_balance_selector, _) = setup();
+        let account_address = account.get_address();
+        let first_hash = fill_pending_block(
+            &mut starknet,
+            account_address,
+            contract_address,
+            increase_balance_selector,
+        );
+
+        let second_hash = starknet
+            .add_invoke_transaction(test_invoke_transaction_v1(
+                account_address,
+                contract_address,
+                increase_balance_selector,
+                Felt::from(20),
+                1,
+            ))
+            .unwrap();
+
+        let latest_block = starknet.get_latest_block().unwrap();
+        assert_eq!(latest_block.get_transactions(), &vec![first_hash]);
+        assert_eq!(starknet.pending_block().get_transactions(), &vec![second_hash]);
+
+        let second_transaction = starknet.transactions.get(&second_hash).unwrap().unwrap();
+        assert_eq!(
+            second_transaction.execution_result.status(),
+            TransactionExecutionStatus::Succeeded
+        );
+    }
+
+    #[test]
+    fn transaction_not_fitting_into_pending_block_rejected() {
+        let (
+            mut starknet,
+            account,
+            contract_address,
+            increase_balance_selector,
+            balance_var_storage_address,
+        ) = setup();
+        let blockifier_address: starknet_api::core::ContractAddress =
+            contract_address.try_into().unwrap();
+        let storage_key = (*balance_var_storage_address.get_storage_key()).try_into().unwrap();
+        let account_address = account.get_address();
+        starknet.config.full_block_policy = FullBlockPolicy::Reject;
+        fill_pending_block(
+            &mut starknet,
+            account_address,
+            contract_address,
+            increase_balance_selector,
+        );
+
+        let rejected_transaction = test_invoke_transaction_v1(
+            account_address,
+            contract_address,
+            increase_balance_selector,
+            Felt::from(20),
+            1,
+        );
+        let rejected_hash = rejected_transaction
+            .create_blockifier_invoke_transaction(&starknet.chain_id().to_felt(), false)
+            .unwrap()
+            .tx_hash
+            .0;
+        match starknet.add_invoke_transaction(rejected_transaction) {
+            Err(Error::BlockFull) => (),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+
+        assert!(starknet.transactions.get(&rejected_hash).unwrap().is_none());
+        assert_eq!(
+            starknet.get_transaction_rejection_reason(&rejected_hash),
+            Some(Error::BlockFull.to_string().as_str())
+        );
+        // the changes of the rejected transaction are discarded
+        assert_eq!(
+            starknet.pending_state.get_storage_at(blockifier_address, storage_key).unwrap(),
+            Felt::from(10)
+        );
+        assert_eq!(
+            starknet.pending_state.get_nonce_at(account_address.try_into().unwrap()).unwrap(),
+            Nonce(Felt::ONE)
+        );
+    }
+
+    #[test]
+    fn transaction_exceeding_block_capacity_rejected() {
+        let (mut starknet, account, contract_address, increase_balance_selector, _) = setup();
+        let account_address = account.get_address();
+        // commit the deployments of the setup, so that only the transaction can be discarded
+        starknet.commit_diff().unwrap();
+        starknet.config.block_capacity =
+            Some(BouncerWeights { n_steps: 1, ..custom_bouncer_config().block_max_capacity });
+
+        let result = starknet.add_invoke_transaction(test_invoke_transaction_v1(
+            account_address,
+            contract_address,
+            increase_balance_selector,
+            Felt::from(10),
+            0,
+        ));
+        match result {
+            Err(Error::BlockifierTransactionError(
+                TransactionExecutionError::TransactionTooLarge,
+            )) => {}
+            other => panic!("Unexpected result: {other:?}"),
+        }
+
+        assert!(starknet.pending_block().get_transactions().is_empty());
+        assert_eq!(
+            starknet.pending_state.get_nonce_at(account_address.try_into().unwrap()).unwrap(),
+            Nonce(Felt::ZERO)
+        );
+    }
+
     #[test]
     fn nonce_should_be_incremented_if_invoke_reverted() {
         let (mut starknet, account, contract_address, increase_balance_selector, _) = setup();
@@ -459,7 +848,7 @@ mod tests {
         let transaction_hash = starknet
             .add_invoke_transaction(BroadcastedInvokeTransaction::V1(invoke_transaction))
             .unwrap();
-        let transaction = starknet.transactions.get_by_hash_mut(&transaction_hash).unwrap();
+        let transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
         assert_eq!(transaction.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(transaction.execution_result.status(), TransactionExecutionStatus::Reverted);
 
@@ -544,6 +933,7 @@ mod tests {
             constants::STRK_ERC20_CONTRACT_ADDRESS,
             DEVNET_DEFAULT_CHAIN_ID,
             DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
+            VersionedConstants::create_for_testing(),
         );
         starknet.next_block_gas = GasModification {
             gas_price_wei: nonzero!(1u128),
@@ -22,16 +22,16 @@ pub fn add_l1_handler_transaction(
     let charge_fee = false;
     let validate = true;
 
-    let blockifier_execution_result = blockifier_transaction.execute(
-        &mut starknet.pending_state.state,
-        &starknet.block_context,
-        charge_fee,
-        validate,
-    );
-
-    starknet.handle_transaction_result(
-        TransactionWithHash::new(transaction_hash, Transaction::L1Handler(transaction.clone())),
-        blockifier_execution_result,
+    starknet.execute_transaction(
+        &TransactionWithHash::new(transaction_hash, Transaction::L1Handler(transaction.clone())),
+        |starknet| {
+            Ok(blockifier_transaction.execute(
+                &mut starknet.pending_state.state,
+                &starknet.block_context,
+                charge_fee,
+                validate,
+            ))
+        },
     )?;
 
     Ok(transaction_hash)
@@ -44,6 +44,7 @@ mod tests {
 
     use blockifier::execution::errors::{EntryPointExecutionError, PreExecutionError};
     use blockifier::transaction::errors::TransactionExecutionError::ExecutionError;
+    use blockifier::versioned_constants::VersionedConstants;
     use nonzero_ext::nonzero;
     use starknet_rs_core::types::{Felt, TransactionExecutionStatus, TransactionFinalityStatus};
     use starknet_rs_core::utils::get_selector_from_name;
@@ -62,7 +63,7 @@ mod tests {
     };
     use crate::starknet::{predeployed, Starknet};
     use crate::state::CustomState;
-    use crate::traits::{Deployed, HashIdentifiedMut};
+    use crate::traits::Deployed;
     use crate::utils::exported_test_utils::dummy_cairo_l1l2_contract;
     use crate::utils::test_utils::{cairo_0_account_without_validations, dummy_felt};
 
@@ -110,7 +111,7 @@ mod tests {
 
         let transaction_hash = starknet.add_l1_handler_transaction(transaction).unwrap();
 
-        let state_transaction = starknet.transactions.get_by_hash_mut(&transaction_hash).unwrap();
+        let state_transaction = starknet.transactions.get(&transaction_hash).unwrap().unwrap();
 
         assert_eq!(state_transaction.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(
@@ -256,6 +257,7 @@ mod tests {
             constants::STRK_ERC20_CONTRACT_ADDRESS,
             DEVNET_DEFAULT_CHAIN_ID,
             DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
+            VersionedConstants::create_for_testing(),
         );
 
         starknet.restart_pending_block().unwrap();
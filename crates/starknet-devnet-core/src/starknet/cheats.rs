@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
+use serde::{Deserialize, Serialize};
 use starknet_types::contract_address::ContractAddress;
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub(crate) struct Cheats {
     impersonated_accounts: HashSet<ContractAddress>,
     auto_impersonate: bool,
@@ -0,0 +1,246 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use starknet_types::contract_class::{sierra_statement_offsets, ContractClass};
+use starknet_types::felt::ClassHash;
+use starknet_types::rpc::devnet::ClassCoverage;
+
+use super::Starknet;
+use crate::error::DevnetResult;
+use crate::state::BlockNumberOrPending;
+
+/// Key of the debug info annotations in which Scarb stores the source locations of statements
+const COVERAGE_ANNOTATIONS_KEY: &str = "github.com/software-mansion/cairo-coverage";
+
+/// Program counters visited during the execution of transactions, calls and simulations
+#[derive(Clone, Default)]
+pub(crate) struct Coverage {
+    visited_pcs: HashMap<ClassHash, BTreeSet<usize>>,
+    /// Offsets of the Sierra statements of classes in their bytecode, computed on the first
+    /// request, since it requires compiling the class
+    statement_offsets: HashMap<ClassHash, Vec<usize>>,
+}
+
+impl Coverage {
+    pub(crate) fn collect(
+        &mut self,
+        visited_pcs: HashMap<starknet_api::core::ClassHash, HashSet<usize>>,
+    ) {
+        for (class_hash, pcs) in visited_pcs {
+            self.visited_pcs.entry(class_hash.0).or_default().extend(pcs);
+        }
+    }
+}
+
+impl Starknet {
+    /// Returns the visited PCs of all executed classes, ordered by class hash
+    pub fn get_coverage(&self) -> Vec<ClassCoverage> {
+        let mut classes: Vec<ClassCoverage> = self
+            .coverage
+            .visited_pcs
+            .iter()
+            .map(|(class_hash, pcs)| ClassCoverage {
+                class_hash: *class_hash,
+                visited_pcs: pcs.iter().copied().collect(),
+            })
+            .collect();
+        classes.sort_by_key(|class| class.class_hash);
+        classes
+    }
+
+    /// Returns the coverage of Cairo source lines in the LCOV format. Only executed Cairo 1 classes
+    /// declared with source locations in their debug info are covered.
+    pub fn get_coverage_lcov(&mut self) -> DevnetResult<String> {
+        // per file, whether each of its lines with code was executed
+        let mut files: BTreeMap<String, BTreeMap<usize, bool>> = BTreeMap::new();
+
+        let Coverage { visited_pcs, statement_offsets } = &mut self.coverage;
+        for (class_hash, pcs) in visited_pcs.iter() {
+            let class = self
+                .rpc_contract_classes
+                .read()
+                .get_class(class_hash, &BlockNumberOrPending::Pending);
+            let Some(ContractClass::Cairo1(sierra_class)) = class else {
+                continue;
+            };
+            let Some(statement_locations) = statement_locations(&sierra_class) else {
+                continue;
+            };
+            let statement_offsets = match statement_offsets.entry(*class_hash) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(sierra_statement_offsets(&sierra_class)?),
+            };
+
+            let mut visited_statements = HashSet::new();
+            for pc in pcs {
+                // the statement whose code contains pc, together with the preceding statements
+                // which compile to no code at all
+                let statements_before = statement_offsets.partition_point(|offset| offset <= pc);
+                let Some(last_idx) = statements_before.checked_sub(1) else {
+                    continue;
+                };
+                let start_offset = statement_offsets[last_idx];
+                let first_idx = statement_offsets.partition_point(|offset| *offset < start_offset);
+                visited_statements.extend(first_idx..=last_idx);
+            }
+
+            for (statement_idx, locations) in statement_locations {
+                let visited = visited_statements.contains(&statement_idx);
+                for (file, line) in locations {
+                    let hit = files.entry(file).or_default().entry(line).or_default();
+                    *hit |= visited;
+                }
+            }
+        }
+
+        let mut lcov = String::new();
+        for (file, lines) in files {
+            writeln!(lcov, "TN:").unwrap();
+            writeln!(lcov, "SF:{file}").unwrap();
+            for (line, hit) in &lines {
+                writeln!(lcov, "DA:{line},{}", u8::from(*hit)).unwrap();
+            }
+            writeln!(lcov, "LF:{}", lines.len()).unwrap();
+            writeln!(lcov, "LH:{}", lines.values().filter(|hit| **hit).count()).unwrap();
+            writeln!(lcov, "end_of_record").unwrap();
+        }
+
+        Ok(lcov)
+    }
+}
+
+/// Returns the source files and 1-based lines of each Sierra statement, if present in the debug
+/// info of the class
+fn statement_locations(
+    sierra_class: &SierraContractClass,
+) -> Option<HashMap<usize, Vec<(String, usize)>>> {
+    let debug_info = serde_json::to_value(sierra_class.sierra_program_debug_info.as_ref()?).ok()?;
+    let locations = debug_info
+        .get("annotations")?
+        .get(COVERAGE_ANNOTATIONS_KEY)?
+        .get("statements_code_locations")?
+        .as_object()?;
+
+    let statement_locations = locations
+        .iter()
+        .filter_map(|(statement_idx, statement_locations)| {
+            let lines = statement_locations
+                .as_array()?
+                .iter()
+                .filter_map(|location| {
+                    let file = location.get(0)?.as_str()?;
+                    let line = location.get(1)?.get("start")?.get("line")?.as_u64()?;
+                    Some((file.to_string(), line as usize + 1))
+                })
+                .collect();
+            Some((statement_idx.parse().ok()?, lines))
+        })
+        .collect();
+
+    Some(statement_locations)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_types::contract_class::{sierra_statement_offsets, ContractClass};
+    use starknet_types::rpc::devnet::ClassCoverage;
+
+    use super::COVERAGE_ANNOTATIONS_KEY;
+    use crate::constants::{CAIRO_1_ERC20_CONTRACT_CLASS_HASH, ETH_ERC20_CONTRACT_ADDRESS};
+    use crate::starknet::starknet_config::StarknetConfig;
+    use crate::starknet::Starknet;
+    use crate::state::BlockNumberOrPending;
+
+    #[test]
+    fn call_collects_visited_pcs() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        assert!(starknet.get_coverage().is_empty());
+
+        starknet
+            .call(
+                &BlockId::Tag(BlockTag::Latest),
+                ETH_ERC20_CONTRACT_ADDRESS,
+                get_selector_from_name("balanceOf").unwrap(),
+                vec![Felt::ONE],
+            )
+            .unwrap();
+
+        match starknet.get_coverage().as_slice() {
+            [ClassCoverage { class_hash, visited_pcs }] => {
+                assert_eq!(class_hash, &CAIRO_1_ERC20_CONTRACT_CLASS_HASH);
+                assert!(!visited_pcs.is_empty());
+            }
+            other => panic!("Unexpected coverage: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn lcov_reports_lines_of_statements_visited_by_call() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        starknet
+            .call(
+                &BlockId::Tag(BlockTag::Latest),
+                ETH_ERC20_CONTRACT_ADDRESS,
+                get_selector_from_name("balanceOf").unwrap(),
+                vec![Felt::ONE],
+            )
+            .unwrap();
+        let visited_pcs = match starknet.get_coverage().as_slice() {
+            [ClassCoverage { visited_pcs, .. }] => visited_pcs.clone(),
+            other => panic!("Unexpected coverage: {other:?}"),
+        };
+
+        let class_hash = CAIRO_1_ERC20_CONTRACT_CLASS_HASH;
+        let Some(ContractClass::Cairo1(sierra_class)) = starknet
+            .rpc_contract_classes
+            .read()
+            .get_class(&class_hash, &BlockNumberOrPending::Pending)
+        else {
+            panic!("ERC20 class not found");
+        };
+        let offsets = sierra_statement_offsets(&sierra_class).unwrap();
+        // statements compiled to some code, the first one starting at a visited PC and the second
+        // one with none of its PCs visited
+        let statement_pcs =
+            |idx: usize| offsets[idx]..offsets.get(idx + 1).copied().unwrap_or(usize::MAX);
+        let code_statements: Vec<_> =
+            (0..offsets.len()).filter(|idx| !statement_pcs(*idx).is_empty()).collect();
+        let visited_idx =
+            *code_statements.iter().find(|idx| visited_pcs.contains(&offsets[**idx])).unwrap();
+        let unvisited_idx = *code_statements
+            .iter()
+            .find(|idx| !visited_pcs.iter().any(|pc| statement_pcs(**idx).contains(pc)))
+            .unwrap();
+
+        let location = |line: usize| {
+            serde_json::json!([
+                "src/lib.cairo",
+                { "start": { "line": line, "col": 4 }, "end": { "line": line, "col": 8 } }
+            ])
+        };
+        let mut class_json = serde_json::to_value(&sierra_class).unwrap();
+        class_json["sierra_program_debug_info"]["annotations"] = serde_json::json!({
+            COVERAGE_ANNOTATIONS_KEY: {
+                "statements_code_locations": {
+                    visited_idx.to_string(): [location(2)],
+                    unvisited_idx.to_string(): [location(6)],
+                }
+            }
+        });
+        // the class with source locations replaces the executed one, which is otherwise the same
+        let mut class_storage = starknet.rpc_contract_classes.write();
+        class_storage
+            .insert(class_hash, ContractClass::Cairo1(serde_json::from_value(class_json).unwrap()));
+        class_storage.commit(0);
+        drop(class_storage);
+
+        assert_eq!(
+            starknet.get_coverage_lcov().unwrap(),
+            "TN:\nSF:src/lib.cairo\nDA:3,1\nDA:7,0\nLF:2\nLH:1\nend_of_record\n"
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use std::io::Read;
+use std::num::NonZeroU128;
+use std::path::Path;
 
 use blockifier::execution::contract_class::ContractClass;
 use blockifier::state::errors::StateError;
@@ -7,8 +9,11 @@ use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
 use starknet_api::state::StorageKey;
 use starknet_rs_core::types::Felt;
 use starknet_types::contract_class::convert_codegen_to_blockifier_compiled_class;
-use tracing::debug;
+use starknet_types::felt::try_felt_to_num;
+use starknet_types::rpc::gas_modification::GasModification;
+use tracing::{debug, warn};
 
+use super::fork_cache::ForkCache;
 use super::starknet_config::ForkConfig;
 
 #[derive(thiserror::Error, Debug)]
@@ -23,27 +28,59 @@ enum OriginError {
 
 /// Used for interacting with the origin in forking mode. The calls are blocking. Only handles the
 /// basic state reading necessary for contract interaction. For other RPC methods, see
-/// `OriginForwarder`
+/// `OriginForwarder`. If a cache is specified, responses are read from it when possible and stored
+/// in it otherwise.
 #[derive(Debug, Clone)]
 struct BlockingOriginReader {
     url: url::Url,
     block_number: u64,
     client: reqwest::blocking::Client,
+    chain_id: Felt,
+    cache: Option<ForkCache>,
 }
 
 impl BlockingOriginReader {
-    fn new(url: url::Url, block_number: u64) -> Self {
-        Self { url, block_number, client: reqwest::blocking::Client::new() }
+    fn new(url: url::Url, block_number: u64, chain_id: Felt, cache: Option<ForkCache>) -> Self {
+        Self { url, block_number, client: reqwest::blocking::Client::new(), chain_id, cache }
     }
 
     fn send_body(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, OriginError> {
+        let Some(cache) = &self.cache else {
+            return self.send_body_to_origin(method, params);
+        };
+
+        match cache.get(self.chain_id, self.block_number, method, &params) {
+            Ok(Some(cached)) => return Ok(cached),
+            Ok(None) => (),
+            Err(e) => warn!("Failed to read from fork cache: {e}"),
+        }
+
+        // only results are cached; a missing result may be due to an error of the origin
+        let result = self.send_body_to_origin(method, params.clone());
+        if let Ok(value) = &result {
+            if let Err(e) = cache.put(self.chain_id, self.block_number, method, &params, value) {
+                warn!("Failed to write to fork cache: {e}");
+            }
+        }
+
+        result
+    }
+
+    fn send_body_to_origin(
         &self,
         method: &str,
         mut params: serde_json::Value,
     ) -> Result<serde_json::Value, OriginError> {
-        params["block_id"] = serde_json::json!({
-            "block_number": self.block_number
-        });
+        // state is read at the forking point, unless a request is about a specific block
+        if params.get("block_id").is_none() {
+            params["block_id"] = serde_json::json!({
+                "block_number": self.block_number
+            });
+        }
         let body = serde_json::json!({
             "jsonrpc": "2.0",
             "method": method,
@@ -95,10 +132,11 @@ pub struct StarknetDefaulter {
 }
 
 impl StarknetDefaulter {
-    pub fn new(fork_config: ForkConfig) -> Self {
+    pub fn new(fork_config: ForkConfig, chain_id: Felt) -> Self {
         let origin_reader =
             if let (Some(fork_url), Some(block)) = (fork_config.url, fork_config.block_number) {
-                Some(BlockingOriginReader::new(fork_url, block))
+                let cache = fork_config.cache_dir.map(|dir| ForkCache::new(Path::new(&dir)));
+                Some(BlockingOriginReader::new(fork_url, block, chain_id, cache))
             } else {
                 None
             };
@@ -140,6 +178,25 @@ impl StarknetDefaulter {
             Ok(Default::default())
         }
     }
+
+    /// Returns the hash of an origin block; None if not forking
+    pub fn get_block_hash(&self, block_number: u64) -> StateResult<Option<Felt>> {
+        if let Some(origin) = &self.origin_reader {
+            origin.get_block_hash(block_number).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the gas prices of an origin block; None if not forking or if the origin doesn't
+    /// contain the block
+    pub fn get_block_gas_prices(&self, block_number: u64) -> StateResult<Option<GasModification>> {
+        if let Some(origin) = &self.origin_reader {
+            origin.get_block_gas_prices(block_number)
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 fn convert_json_value_to_felt(json_value: serde_json::Value) -> StateResult<Felt> {
@@ -199,6 +256,47 @@ impl BlockingOriginReader {
         Ok(class_hash)
     }
 
+    fn get_block_hash(&self, block_number: u64) -> StateResult<Felt> {
+        match self.send_body(
+            "starknet_getBlockWithTxHashes",
+            serde_json::json!({
+                "block_id": { "block_number": block_number },
+            }),
+        ) {
+            Err(other_error) => Err(StateError::StateReadError(other_error.to_string())),
+            Ok(mut block) => convert_json_value_to_felt(block["block_hash"].take()),
+        }
+    }
+
+    fn get_block_gas_prices(&self, block_number: u64) -> StateResult<Option<GasModification>> {
+        let block = match self.send_body(
+            "starknet_getBlockWithTxHashes",
+            serde_json::json!({
+                "block_id": { "block_number": block_number },
+            }),
+        ) {
+            Err(OriginError::NoResult) => return Ok(None),
+            Err(other_error) => return Err(StateError::StateReadError(other_error.to_string())),
+            Ok(block) => block,
+        };
+
+        let get_price = |resource: &str, unit: &str| -> StateResult<NonZeroU128> {
+            let price = convert_json_value_to_felt(block[resource][unit].clone())?;
+            try_felt_to_num(price).ok().and_then(NonZeroU128::new).ok_or_else(|| {
+                StateError::StateReadError(format!(
+                    "Invalid {resource} {unit} of origin block {block_number}: {price}"
+                ))
+            })
+        };
+
+        Ok(Some(GasModification {
+            gas_price_wei: get_price("l1_gas_price", "price_in_wei")?,
+            data_gas_price_wei: get_price("l1_data_gas_price", "price_in_wei")?,
+            gas_price_fri: get_price("l1_gas_price", "price_in_fri")?,
+            data_gas_price_fri: get_price("l1_data_gas_price", "price_in_fri")?,
+        }))
+    }
+
     fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
         match self.send_body(
             "starknet_getClass",
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use blockifier::bouncer::BouncerWeights;
+use blockifier::context::BlockContext;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use serde::{Deserialize, Serialize};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_types::contract_class::{
+    Cairo0ContractClass, Cairo0Json, ContractClass, DeprecatedContractClass,
+};
+use starknet_types::felt::{BlockHash, ClassHash};
+use starknet_types::rpc::gas_modification::GasModification;
+
+use super::cheats::Cheats;
+use super::Starknet;
+use crate::blocks::StarknetBlocks;
+use crate::error::{DevnetResult, Error};
+use crate::messaging::MessagingBroker;
+use crate::state::state_diff::StateDiff;
+use crate::state::{CommittedClassStorage, SerializableState, StarknetState};
+use crate::transactions::StarknetTransactions;
+use crate::utils::custom_bouncer_config;
+
+/// Classes are tagged with their kind, since their serialized forms cannot always be told apart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SerializableContractClass {
+    Cairo0Json(Cairo0Json),
+    Cairo0(DeprecatedContractClass),
+    Cairo1(SierraContractClass),
+}
+
+impl From<ContractClass> for SerializableContractClass {
+    fn from(value: ContractClass) -> Self {
+        match value {
+            ContractClass::Cairo0(Cairo0ContractClass::RawJson(class)) => Self::Cairo0Json(class),
+            ContractClass::Cairo0(Cairo0ContractClass::Rpc(class)) => Self::Cairo0(class),
+            ContractClass::Cairo1(class) => Self::Cairo1(class),
+        }
+    }
+}
+
+impl From<SerializableContractClass> for ContractClass {
+    fn from(value: SerializableContractClass) -> Self {
+        match value {
+            SerializableContractClass::Cairo0Json(class) => class.into(),
+            SerializableContractClass::Cairo0(class) => class.into(),
+            SerializableContractClass::Cairo1(class) => class.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct CommittedClass {
+    pub(super) class_hash: ClassHash,
+    pub(super) block_number: u64,
+    pub(super) contract_class: SerializableContractClass,
+}
+
+/// Serializable copy of all parts of `Starknet` which change after startup. Loading it requires
+/// Devnet to be configured the same way as when dumping.
+#[derive(Serialize, Deserialize)]
+pub struct StarknetDump {
+    pub(super) classes: Vec<CommittedClass>,
+    pub(super) latest_state: SerializableState,
+    pub(super) pending_state: SerializableState,
+    pub(super) pending_state_diff: StateDiff,
+    pub(super) pending_block_weights: BouncerWeights,
+    /// Only present with full state archive capacity
+    pub(super) block_states: HashMap<BlockHash, SerializableState>,
+    pub(super) blocks: StarknetBlocks,
+    pub(super) transactions: StarknetTransactions,
+    pub(super) block_context_gas: GasModification,
+    pub(super) pending_block_timestamp_shift: i64,
+    pub(super) next_block_timestamp: Option<u64>,
+    pub(super) next_block_gas: GasModification,
+    pub(super) next_block_l1_da_mode: L1DataAvailabilityMode,
+    pub(super) messaging: MessagingBroker,
+    pub(super) cheats: Cheats,
+}
+
+impl Starknet {
+    /// Returns a serializable copy of the current state of Devnet.
+    pub fn dump_state(&self) -> StarknetDump {
+        let classes = self
+            .rpc_contract_classes
+            .read()
+            .iter_committed()
+            .map(|(class_hash, (contract_class, block_number))| CommittedClass {
+                class_hash: *class_hash,
+                block_number: *block_number,
+                contract_class: contract_class.clone().into(),
+            })
+            .collect();
+
+        StarknetDump {
+            classes,
+            // states are committed after each transaction, so their content is complete
+            latest_state: self.latest_state.to_serializable(),
+            pending_state: self.pending_state.to_serializable(),
+            pending_state_diff: self.pending_state_diff.clone(),
+            pending_block_weights: self.pending_block_weights,
+            block_states: self
+                .blocks
+                .hash_to_state
+                .iter()
+                .map(|(block_hash, state)| (*block_hash, state.to_serializable()))
+                .collect(),
+            blocks: self.blocks.clone(),
+            transactions: self.transactions.clone(),
+            block_context_gas: self.block_context_gas(),
+            pending_block_timestamp_shift: self.pending_block_timestamp_shift,
+            next_block_timestamp: self.next_block_timestamp,
+            next_block_gas: self.next_block_gas.clone(),
+            next_block_l1_da_mode: self.next_block_l1_da_mode,
+            messaging: self.messaging.clone(),
+            cheats: self.cheats.clone(),
+        }
+    }
+
+    /// Returns the gas prices of the pending block.
+    pub(super) fn block_context_gas(&self) -> GasModification {
+        let gas_prices = &self.block_context.block_info().gas_prices;
+        GasModification {
+            gas_price_wei: gas_prices.eth_l1_gas_price,
+            data_gas_price_wei: gas_prices.eth_l1_data_gas_price,
+            gas_price_fri: gas_prices.strk_l1_gas_price,
+            data_gas_price_fri: gas_prices.strk_l1_data_gas_price,
+        }
+    }
+
+    /// Replaces the current state of Devnet with the one in `dump`, without re-executing any
+    /// transactions. Snapshots and the connection to L1 are discarded.
+    pub fn load_state(&mut self, dump: StarknetDump) -> DevnetResult<()> {
+        if self.database.is_some() {
+            return Err(Error::UnsupportedAction {
+                msg: "Loading a dump is not supported when using a database".into(),
+            });
+        }
+
+        let mut class_storage = CommittedClassStorage::default();
+        let mut compiled_classes = HashMap::new();
+        for CommittedClass { class_hash, block_number, contract_class } in dump.classes {
+            let contract_class = ContractClass::from(contract_class);
+            compiled_classes.insert(class_hash, contract_class.clone().try_into()?);
+
+            class_storage.insert(class_hash, contract_class);
+            class_storage.commit(block_number);
+        }
+        *self.rpc_contract_classes.write() = class_storage;
+
+        let load = |serializable| {
+            StarknetState::from_serializable(
+                serializable,
+                self.defaulter.clone(),
+                self.rpc_contract_classes.clone(),
+                &compiled_classes,
+            )
+        };
+
+        self.latest_state = load(dump.latest_state)?;
+        self.pending_state = load(dump.pending_state)?;
+        let mut blocks = dump.blocks;
+        for (block_hash, serializable) in dump.block_states {
+            blocks.save_state_at(block_hash, load(serializable)?);
+        }
+        self.blocks = blocks;
+
+        self.pending_state_diff = dump.pending_state_diff;
+        // the tries are rebuilt from the restored state on the next block
+        self.state_tries = None;
+        self.pending_block_weights = dump.pending_block_weights;
+        self.transactions = dump.transactions;
+        self.pending_block_timestamp_shift = dump.pending_block_timestamp_shift;
+        self.next_block_timestamp = dump.next_block_timestamp;
+        self.next_block_gas = dump.next_block_gas;
+        self.next_block_l1_da_mode = dump.next_block_l1_da_mode;
+        self.messaging = dump.messaging;
+        self.cheats = dump.cheats;
+        self.snapshots = Default::default();
+
+        // the block context always describes the pending block
+        let mut block_info = self.block_context.block_info().clone();
+        block_info.block_number = self.blocks.pending_block.block_number();
+        block_info.block_timestamp = self.blocks.pending_block.timestamp();
+        self.block_context = BlockContext::new(
+            block_info,
+            self.block_context.chain_info().clone(),
+            self.block_context.versioned_constants().clone(),
+            custom_bouncer_config(),
+        );
+        Self::set_block_context_gas(&mut self.block_context, &dump.block_context_gas);
+        Self::set_block_context_l1_da_mode(
+            &mut self.block_context,
+            self.blocks.pending_block.header.l1_da_mode,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_types::contract_address::ContractAddress;
+
+    use crate::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use crate::starknet::starknet_config::{
+        BlockGenerationOn, StarknetConfig, StateArchiveCapacity,
+    };
+    use crate::starknet::Starknet;
+
+    fn dump_and_load(starknet: &Starknet) -> Starknet {
+        let dump = serde_json::to_string(&starknet.dump_state()).unwrap();
+
+        let mut loaded = Starknet::new(&starknet.config).unwrap();
+        loaded.load_state(serde_json::from_str(&dump).unwrap()).unwrap();
+        loaded
+    }
+
+    #[test]
+    fn loaded_blocks_and_states_are_equal_to_dumped() {
+        let config =
+            StarknetConfig { state_archive: StateArchiveCapacity::Full, ..Default::default() };
+        let mut starknet = Starknet::new(&config).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(1)).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(2)).unwrap();
+
+        let mut loaded = dump_and_load(&starknet);
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(
+            loaded.get_latest_block().unwrap().block_hash(),
+            starknet.get_latest_block().unwrap().block_hash()
+        );
+        for (block_id, expected_nonce) in
+            [(BlockId::Number(1), 1), (BlockId::Number(2), 2), (latest, 2)]
+        {
+            assert_eq!(
+                loaded.contract_nonce_at_block(&block_id, contract_address).unwrap(),
+                Felt::from(expected_nonce)
+            );
+        }
+
+        // loaded Devnet continues from the dumped state
+        loaded.set_nonce(contract_address, Felt::from(3)).unwrap();
+        assert_eq!(loaded.get_latest_block().unwrap().block_number().0, 3);
+        assert_eq!(
+            loaded.contract_nonce_at_block(&BlockId::Number(2), contract_address).unwrap(),
+            Felt::from(2)
+        );
+    }
+
+    #[test]
+    fn pending_state_diff_is_preserved() {
+        let config =
+            StarknetConfig { block_generation_on: BlockGenerationOn::Demand, ..Default::default() };
+        let mut starknet = Starknet::new(&config).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(1)).unwrap();
+
+        let mut loaded = dump_and_load(&starknet);
+        assert_eq!(loaded.pending_state_diff, starknet.pending_state_diff);
+
+        loaded.create_block().unwrap();
+        let state_update = loaded.block_state_update(&BlockId::Tag(BlockTag::Latest)).unwrap();
+        assert_eq!(state_update.get_state_diff().nonces.len(), 1);
+    }
+}
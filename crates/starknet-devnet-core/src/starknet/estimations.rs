@@ -11,10 +11,8 @@ use starknet_types::rpc::estimate_message_fee::{
 };
 use starknet_types::rpc::transactions::BroadcastedTransaction;
 
-use crate::constants::USE_KZG_DA;
 use crate::error::{DevnetResult, Error};
 use crate::starknet::Starknet;
-use crate::utils::get_versioned_constants;
 
 pub fn estimate_fee(
     starknet: &mut Starknet,
@@ -44,7 +42,7 @@ pub fn estimate_fee(
 
     let mut transactional_state = CachedState::create_transactional(&mut state.state);
 
-    transactions
+    let estimations = transactions
         .into_iter()
         .map(|(transaction, skip_validate_due_to_impersonation)| {
             estimate_transaction_fee(
@@ -61,7 +59,11 @@ pub fn estimate_fee(
                                                               * Otherwise use the validate parameter that is passed to the estimateFee request */
             )
         })
-        .collect()
+        .collect();
+
+    let visited_pcs = transactional_state.visited_pcs;
+    starknet.coverage.collect(visited_pcs);
+    estimations
 }
 
 pub fn estimate_message_fee(
@@ -81,7 +83,7 @@ pub fn estimate_message_fee(
 
     let l1_transaction = estimate_message_fee.create_blockifier_l1_transaction()?;
 
-    estimate_transaction_fee(
+    let estimation = estimate_transaction_fee(
         &mut transactional_state,
         &block_context,
         blockifier::transaction::transaction_execution::Transaction::L1HandlerTransaction(
@@ -89,7 +91,11 @@ pub fn estimate_message_fee(
         ),
         None,
         None,
-    )
+    );
+
+    let visited_pcs = transactional_state.visited_pcs;
+    starknet.coverage.collect(visited_pcs);
+    estimation
 }
 
 fn estimate_transaction_fee<S: StateReader>(
@@ -119,10 +125,10 @@ fn estimate_transaction_fee<S: StateReader>(
         return Err(Error::ExecutionError { revert_error });
     }
 
-    let gas_vector = transaction_execution_info
-        .transaction_receipt
-        .resources
-        .to_gas_vector(&get_versioned_constants(), USE_KZG_DA)?;
+    let gas_vector = transaction_execution_info.transaction_receipt.resources.to_gas_vector(
+        block_context.versioned_constants(),
+        block_context.block_info().use_kzg_da,
+    )?;
     let total_fee =
         fee_utils::get_fee_by_gas_vector(block_context.block_info(), gas_vector, &fee_type);
 
@@ -4,7 +4,6 @@ use starknet_types::emitted_event::{EmittedEvent, Event};
 
 use super::Starknet;
 use crate::error::{DevnetResult, Error};
-use crate::traits::HashIdentified;
 
 /// The method returns transaction events, based on query and if there are more results to be
 /// fetched in the form of a tuple (events, has_more).
@@ -35,7 +34,7 @@ pub(crate) fn get_events(
     for block in blocks {
         for transaction_hash in block.get_transactions() {
             let transaction =
-                starknet.transactions.get_by_hash(*transaction_hash).ok_or(Error::NoTransaction)?;
+                starknet.transactions.get(transaction_hash)?.ok_or(Error::NoTransaction)?;
 
             // filter the events from the transaction
             let filtered_transaction_events = transaction
@@ -133,7 +132,6 @@ mod tests {
     use crate::starknet::events::check_if_filter_applies_for_event_keys;
     use crate::starknet::starknet_config::StarknetConfig;
     use crate::starknet::Starknet;
-    use crate::traits::HashIdentified;
     use crate::utils::test_utils::{dummy_contract_address, dummy_declare_transaction_v1};
 
     #[test]
@@ -407,7 +405,7 @@ mod tests {
 
         assert_eq!(starknet.blocks.get_blocks(None, None).unwrap().len(), 6);
         for idx in 0..5 {
-            starknet.transactions.get_by_hash(Felt::from(idx as u128 + 100)).unwrap();
+            starknet.transactions.get(&Felt::from(idx as u128 + 100)).unwrap().unwrap();
         }
 
         starknet
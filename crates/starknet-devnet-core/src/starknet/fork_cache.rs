@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use starknet_rs_core::types::Felt;
+use starknet_rs_core::utils::starknet_keccak;
+
+use crate::database::{read_json, write_json};
+use crate::error::DevnetResult;
+
+/// Name of the directory holding records of origins, used on startup.
+const ORIGINS_DIRECTORY_NAME: &str = "origins";
+
+/// What Devnet learns about the origin on startup in forking mode. Stored so that a forked Devnet
+/// can be started without contacting the origin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkOriginRecord {
+    pub chain_id: Felt,
+    pub block_hash: Felt,
+    pub eth_erc20_class_hash: Felt,
+    pub eth_erc20_contract_class: String,
+    pub strk_erc20_class_hash: Felt,
+    pub strk_erc20_contract_class: String,
+}
+
+/// Persistent cache of responses received from the origin in forking mode. Responses are stored
+/// as JSON files under `<chain_id>/<block_number>/<method>/`, named by the hash of the request
+/// params. Since the state of the origin at a fixed block doesn't change, entries never expire.
+/// Only results are stored: a missing result may be an error of the origin, which is not final.
+#[derive(Debug, Clone)]
+pub struct ForkCache {
+    path: PathBuf,
+}
+
+impl ForkCache {
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf() }
+    }
+
+    /// Returns the cached result of `method` called with `params`, if any
+    pub fn get(
+        &self,
+        chain_id: Felt,
+        block_number: u64,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> DevnetResult<Option<serde_json::Value>> {
+        let cached: Option<serde_json::Value> =
+            read_json(&self.entry_path(chain_id, block_number, method, params))?;
+        // misses were stored as null by earlier versions
+        Ok(cached.filter(|result| !result.is_null()))
+    }
+
+    /// Stores `result` of `method` called with `params`. Null, i.e. a missing result, is not
+    /// stored.
+    pub fn put(
+        &self,
+        chain_id: Felt,
+        block_number: u64,
+        method: &str,
+        params: &serde_json::Value,
+        result: &serde_json::Value,
+    ) -> DevnetResult<()> {
+        if result.is_null() {
+            return Ok(());
+        }
+
+        let entry_path = self.entry_path(chain_id, block_number, method, params);
+        if let Some(method_dir) = entry_path.parent() {
+            fs::create_dir_all(method_dir)?;
+        }
+
+        write_json(&entry_path, result)
+    }
+
+    /// Origins are identified by URL here, because the chain ID is not known before the origin is
+    /// contacted.
+    pub fn get_origin_record(
+        &self,
+        url: &url::Url,
+        block_number: u64,
+    ) -> DevnetResult<Option<ForkOriginRecord>> {
+        read_json(&self.origin_record_path(url, block_number))
+    }
+
+    pub fn put_origin_record(
+        &self,
+        url: &url::Url,
+        block_number: u64,
+        record: &ForkOriginRecord,
+    ) -> DevnetResult<()> {
+        let origins_dir = self.path.join(ORIGINS_DIRECTORY_NAME);
+        fs::create_dir_all(origins_dir)?;
+
+        write_json(&self.origin_record_path(url, block_number), record)
+    }
+
+    fn entry_path(
+        &self,
+        chain_id: Felt,
+        block_number: u64,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> PathBuf {
+        let params_hash = starknet_keccak(params.to_string().as_bytes());
+        self.path
+            .join(format!("{chain_id:#x}"))
+            .join(block_number.to_string())
+            .join(method)
+            .join(format!("{params_hash:#x}.json"))
+    }
+
+    fn origin_record_path(&self, url: &url::Url, block_number: u64) -> PathBuf {
+        let url_hash = starknet_keccak(url.as_str().as_bytes());
+        self.path.join(ORIGINS_DIRECTORY_NAME).join(format!("{url_hash:#x}_{block_number}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_rs_core::types::Felt;
+
+    use super::ForkCache;
+
+    #[test]
+    fn entries_are_separated_by_chain_block_method_and_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ForkCache::new(dir.path());
+        let chain_id = Felt::from(0x534e5f5345504f4c4941_u128);
+        let params = serde_json::json!({ "contract_address": "0x1", "key": "0x2" });
+
+        assert_eq!(cache.get(chain_id, 1, "starknet_getStorageAt", &params).unwrap(), None);
+
+        cache
+            .put(chain_id, 1, "starknet_getStorageAt", &params, &serde_json::json!("0x5"))
+            .unwrap();
+        assert_eq!(
+            cache.get(chain_id, 1, "starknet_getStorageAt", &params).unwrap(),
+            Some(serde_json::json!("0x5"))
+        );
+
+        let other_params = serde_json::json!({ "contract_address": "0x1", "key": "0x3" });
+        assert_eq!(cache.get(chain_id, 1, "starknet_getStorageAt", &other_params).unwrap(), None);
+        assert_eq!(cache.get(chain_id, 2, "starknet_getStorageAt", &params).unwrap(), None);
+        assert_eq!(cache.get(Felt::ONE, 1, "starknet_getStorageAt", &params).unwrap(), None);
+        assert_eq!(cache.get(chain_id, 1, "starknet_getNonce", &params).unwrap(), None);
+    }
+
+    #[test]
+    fn missing_origin_result_is_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ForkCache::new(dir.path());
+        let params = serde_json::json!({ "class_hash": "0x1" });
+
+        cache.put(Felt::ONE, 1, "starknet_getClass", &params, &serde_json::Value::Null).unwrap();
+        assert_eq!(cache.get(Felt::ONE, 1, "starknet_getClass", &params).unwrap(), None);
+    }
+}
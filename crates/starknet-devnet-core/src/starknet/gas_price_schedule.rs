@@ -0,0 +1,230 @@
+use std::f64::consts::PI;
+use std::num::{NonZeroU128, NonZeroU64};
+
+use rand::Rng;
+use rand_mt::Mt64;
+use serde::Deserialize;
+use starknet_types::rpc::gas_modification::{GasModification, GasModificationRequest};
+
+/// How the gas prices change from block to block. Prices of a block are derived from its offset
+/// from the genesis block and, except for oscillation, from the prices of the block before it.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum GasPriceSchedule {
+    /// Prices of consecutive blocks, starting with the first block after the genesis block.
+    /// Prices missing from an entry, and all prices after the last entry, stay unchanged.
+    Series(Vec<GasModificationRequest>),
+    /// Each price changes by a random percentage of at most `max_change_percent` per block,
+    /// staying within `min` and `max`. The percentages are determined by the seed and the block
+    /// offset, but they are applied to the prices of the previous block, which may have been set
+    /// by the user; the walk is only reproducible if no prices are set.
+    RandomWalk { seed: u64, max_change_percent: u32, min: GasModification, max: GasModification },
+    /// Each price rises from `min` to `max` and falls back to `min` within `period` blocks
+    Oscillation { period: NonZeroU64, min: GasModification, max: GasModification },
+}
+
+impl GasPriceSchedule {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match self {
+            GasPriceSchedule::Series(entries) => {
+                if entries.iter().any(|entry| entry.generate_block.is_some()) {
+                    return Err("generate_block is not allowed in a series".to_string());
+                }
+            }
+            GasPriceSchedule::RandomWalk { min, max, .. }
+            | GasPriceSchedule::Oscillation { min, max, .. } => {
+                for (name, min, max) in [
+                    ("gas_price_wei", min.gas_price_wei, max.gas_price_wei),
+                    ("data_gas_price_wei", min.data_gas_price_wei, max.data_gas_price_wei),
+                    ("gas_price_fri", min.gas_price_fri, max.gas_price_fri),
+                    ("data_gas_price_fri", min.data_gas_price_fri, max.data_gas_price_fri),
+                ] {
+                    if min > max {
+                        return Err(format!("min {name} is greater than max {name}"));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the prices of the block which is `offset` blocks after the genesis block, given the
+    /// prices of the block before it.
+    pub(crate) fn prices_of_block(
+        &self,
+        offset: u64,
+        previous: &GasModification,
+    ) -> GasModification {
+        match self {
+            GasPriceSchedule::Series(entries) => {
+                let mut prices = previous.clone();
+                let entry = offset.checked_sub(1).and_then(|index| entries.get(index as usize));
+                if let Some(entry) = entry {
+                    prices.update(entry.clone());
+                }
+                prices
+            }
+            GasPriceSchedule::RandomWalk { seed, max_change_percent, min, max } => {
+                // seeded by the offset as well, so that each block takes a different step
+                let mut rng = Mt64::new_with_key([*seed, offset]);
+                let max_change = f64::from(*max_change_percent) / 100.0;
+                let mut walk = |price: NonZeroU128, min: NonZeroU128, max: NonZeroU128| {
+                    let change = rng.gen_range(-max_change..=max_change);
+                    bounded_price(price.get() as f64 * (1.0 + change), min, max)
+                };
+
+                GasModification {
+                    gas_price_wei: walk(
+                        previous.gas_price_wei,
+                        min.gas_price_wei,
+                        max.gas_price_wei,
+                    ),
+                    data_gas_price_wei: walk(
+                        previous.data_gas_price_wei,
+                        min.data_gas_price_wei,
+                        max.data_gas_price_wei,
+                    ),
+                    gas_price_fri: walk(
+                        previous.gas_price_fri,
+                        min.gas_price_fri,
+                        max.gas_price_fri,
+                    ),
+                    data_gas_price_fri: walk(
+                        previous.data_gas_price_fri,
+                        min.data_gas_price_fri,
+                        max.data_gas_price_fri,
+                    ),
+                }
+            }
+            GasPriceSchedule::Oscillation { period, min, max } => {
+                let phase = 2.0 * PI * (offset % period.get()) as f64 / period.get() as f64;
+                let amplitude = (1.0 - phase.cos()) / 2.0;
+                let oscillate = |min: NonZeroU128, max: NonZeroU128| {
+                    let range = (max.get() - min.get()) as f64;
+                    bounded_price(min.get() as f64 + range * amplitude, min, max)
+                };
+
+                GasModification {
+                    gas_price_wei: oscillate(min.gas_price_wei, max.gas_price_wei),
+                    data_gas_price_wei: oscillate(min.data_gas_price_wei, max.data_gas_price_wei),
+                    gas_price_fri: oscillate(min.gas_price_fri, max.gas_price_fri),
+                    data_gas_price_fri: oscillate(min.data_gas_price_fri, max.data_gas_price_fri),
+                }
+            }
+        }
+    }
+}
+
+/// Rounds the price and keeps it within the bounds
+fn bounded_price(price: f64, min: NonZeroU128, max: NonZeroU128) -> NonZeroU128 {
+    // negative prices are converted to zero
+    NonZeroU128::new(price.round() as u128).unwrap_or(min).clamp(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU128;
+
+    use nonzero_ext::nonzero;
+    use starknet_types::rpc::gas_modification::{GasModification, GasModificationRequest};
+
+    use super::GasPriceSchedule;
+
+    fn uniform_prices(price: NonZeroU128) -> GasModification {
+        GasModification {
+            gas_price_wei: price,
+            data_gas_price_wei: price,
+            gas_price_fri: price,
+            data_gas_price_fri: price,
+        }
+    }
+
+    #[test]
+    fn series_applied_from_first_block_after_genesis() {
+        let schedule = GasPriceSchedule::Series(vec![
+            GasModificationRequest {
+                gas_price_wei: Some(nonzero!(10u128)),
+                data_gas_price_wei: None,
+                gas_price_fri: None,
+                data_gas_price_fri: None,
+                generate_block: None,
+            },
+            GasModificationRequest {
+                gas_price_wei: None,
+                data_gas_price_wei: None,
+                gas_price_fri: Some(nonzero!(20u128)),
+                data_gas_price_fri: None,
+                generate_block: None,
+            },
+        ]);
+        let genesis_prices = uniform_prices(nonzero!(1u128));
+
+        let first = schedule.prices_of_block(1, &genesis_prices);
+        assert_eq!(first.gas_price_wei, nonzero!(10u128));
+        assert_eq!(first.gas_price_fri, nonzero!(1u128));
+
+        let second = schedule.prices_of_block(2, &first);
+        assert_eq!(second.gas_price_wei, nonzero!(10u128));
+        assert_eq!(second.gas_price_fri, nonzero!(20u128));
+
+        // prices stay unchanged after the series
+        let third = schedule.prices_of_block(3, &second);
+        assert_eq!(third.gas_price_wei, second.gas_price_wei);
+        assert_eq!(third.gas_price_fri, second.gas_price_fri);
+    }
+
+    #[test]
+    fn random_walk_deterministic_and_bounded() {
+        let min = nonzero!(90u128);
+        let max = nonzero!(110u128);
+        let schedule = GasPriceSchedule::RandomWalk {
+            seed: 42,
+            max_change_percent: 50,
+            min: uniform_prices(min),
+            max: uniform_prices(max),
+        };
+
+        let walk = |schedule: &GasPriceSchedule| {
+            let mut prices = vec![uniform_prices(nonzero!(100u128))];
+            for offset in 1..=20 {
+                let next = schedule.prices_of_block(offset, prices.last().unwrap());
+                prices.push(next);
+            }
+            prices.into_iter().map(|prices| prices.gas_price_wei).collect::<Vec<_>>()
+        };
+
+        let prices = walk(&schedule);
+        assert!(prices.iter().all(|price| (min..=max).contains(price)));
+        assert!(prices.windows(2).any(|pair| pair[0] != pair[1]));
+        assert_eq!(prices, walk(&schedule));
+    }
+
+    #[test]
+    fn oscillation_between_bounds() {
+        let schedule = GasPriceSchedule::Oscillation {
+            period: nonzero!(4u64),
+            min: uniform_prices(nonzero!(100u128)),
+            max: uniform_prices(nonzero!(300u128)),
+        };
+        let previous = uniform_prices(nonzero!(1u128));
+
+        let prices: Vec<_> = (1..=4)
+            .map(|offset| schedule.prices_of_block(offset, &previous).data_gas_price_fri.get())
+            .collect();
+        assert_eq!(prices, [200, 300, 200, 100]);
+    }
+
+    #[test]
+    fn bounds_validated() {
+        let schedule = GasPriceSchedule::Oscillation {
+            period: nonzero!(4u64),
+            min: uniform_prices(nonzero!(300u128)),
+            max: uniform_prices(nonzero!(100u128)),
+        };
+        assert_eq!(
+            schedule.validate().unwrap_err(),
+            "min gas_price_wei is greater than max gas_price_wei"
+        );
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use blockifier::context::{BlockContext, TransactionContext};
+use blockifier::execution::entry_point::CallEntryPoint;
+use blockifier::state::cached_state::CachedState;
+use blockifier::state::state_api::State;
+use serde::{Deserialize, Serialize};
+use starknet_api::core::PatriciaKey;
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_rs_core::types::Felt;
+use starknet_rs_core::utils::get_selector_from_name;
+use starknet_types::contract_address::ContractAddress;
+use starknet_types::contract_class::{Cairo0Json, ContractClass};
+use starknet_types::felt::ClassHash;
+use starknet_types::num_bigint::BigUint;
+use starknet_types::rpc::transaction_receipt::FeeUnit;
+use starknet_types::serde_helpers::dec_string::{deserialize_biguint, serialize_biguint};
+use starknet_types::traits::HashProducer;
+
+use super::dump::SerializableContractClass;
+use super::predeployed::add_erc20_balance;
+use super::Starknet;
+use crate::constants::{ETH_ERC20_CONTRACT_ADDRESS, STRK_ERC20_CONTRACT_ADDRESS};
+use crate::database::write_json;
+use crate::error::{DevnetResult, Error};
+use crate::state::{CustomState, CustomStateReader, StarknetState};
+
+/// Content of a genesis file: what is present at Devnet's startup in addition to the default
+/// predeployed classes and contracts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisSpec {
+    #[serde(default)]
+    pub classes: Vec<GenesisClassSpec>,
+    #[serde(default)]
+    pub contracts: Vec<GenesisContract>,
+    #[serde(default)]
+    pub balances: Vec<GenesisBalance>,
+}
+
+impl GenesisSpec {
+    /// Writes the content as a JSON genesis file.
+    pub fn write(&self, path: &Path) -> DevnetResult<()> {
+        write_json(path, self)
+    }
+}
+
+/// Relative paths are resolved against the directory of the genesis file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum GenesisClassSpec {
+    /// Path to a Sierra artifact; the CASM is compiled from it
+    Sierra(PathBuf),
+    /// Path to a compiled Cairo 0 artifact
+    Cairo0(PathBuf),
+    /// The class itself, tagged with its kind; used in exported genesis files
+    Inline(SerializableContractClass),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisContract {
+    pub address: Felt,
+    /// If absent, no contract is deployed, only the nonce and storage are set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<Felt>,
+    /// If present, the constructor is executed with this calldata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constructor_calldata: Option<Vec<Felt>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Felt>,
+    /// Set after the constructor is executed, overwriting what it stored
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<Felt, Felt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisBalance {
+    pub address: Felt,
+    #[serde(serialize_with = "serialize_biguint", deserialize_with = "deserialize_biguint")]
+    pub amount: BigUint,
+    /// Defaults to WEI, i.e. the ETH token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<FeeUnit>,
+}
+
+/// Genesis file with its classes loaded.
+#[derive(Debug, Clone)]
+pub struct Genesis {
+    pub(crate) classes: Vec<(ClassHash, ContractClass)>,
+    pub(crate) contracts: Vec<GenesisContract>,
+    pub(crate) balances: Vec<GenesisBalance>,
+}
+
+impl Genesis {
+    /// Loads the genesis file at `path`; parsed as YAML if its extension is `.yaml` or `.yml`,
+    /// otherwise as JSON.
+    pub fn load(path: &Path) -> DevnetResult<Self> {
+        let content = read_file(path)?;
+        let spec: GenesisSpec = match path.extension().and_then(|extension| extension.to_str()) {
+            // enum variants are written as single-key maps, like in JSON, instead of YAML tags
+            Some("yaml" | "yml") => serde_yaml::with::singleton_map_recursive::deserialize(
+                serde_yaml::Deserializer::from_str(&content),
+            )
+            .map_err(|e| Error::DeserializationError { origin: e.to_string() })?,
+            _ => serde_json::from_str(&content)
+                .map_err(|e| Error::DeserializationError { origin: e.to_string() })?,
+        };
+
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        let mut classes = vec![];
+        for class_spec in spec.classes {
+            let contract_class: ContractClass = match class_spec {
+                GenesisClassSpec::Sierra(class_path) => {
+                    ContractClass::cairo_1_from_sierra_json_str(&read_file(
+                        &base_dir.join(class_path),
+                    )?)?
+                    .into()
+                }
+                GenesisClassSpec::Cairo0(class_path) => {
+                    Cairo0Json::raw_json_from_json_str(&read_file(&base_dir.join(class_path))?)?
+                        .into()
+                }
+                GenesisClassSpec::Inline(contract_class) => contract_class.into(),
+            };
+            classes.push((contract_class.generate_hash()?, contract_class));
+        }
+
+        Ok(Self { classes, contracts: spec.contracts, balances: spec.balances })
+    }
+
+    /// Declares the classes, deploys the contracts and sets the balances. Contracts are deployed in
+    /// the specified order, so a constructor may rely on contracts specified before it.
+    pub(crate) fn apply(
+        &self,
+        state: &mut StarknetState,
+        block_context: &BlockContext,
+    ) -> DevnetResult<()> {
+        for (class_hash, contract_class) in &self.classes {
+            if !state.is_contract_declared(*class_hash) {
+                state.predeclare_contract_class(*class_hash, contract_class.clone())?;
+            }
+        }
+
+        for contract in &self.contracts {
+            let address = ContractAddress::new(contract.address)?;
+
+            if let Some(class_hash) = contract.class_hash {
+                if !state.is_contract_declared(class_hash) {
+                    return Err(Error::InvalidGenesis {
+                        msg: format!(
+                            "Class {:#x} of contract {:#x} is not declared",
+                            class_hash, contract.address
+                        ),
+                    });
+                }
+
+                state.predeploy_contract(address, class_hash)?;
+            }
+
+            if let Some(calldata) = &contract.constructor_calldata {
+                if contract.class_hash.is_none() {
+                    return Err(Error::InvalidGenesis {
+                        msg: format!(
+                            "Constructor calldata of contract {:#x} specified without a class hash",
+                            contract.address
+                        ),
+                    });
+                }
+
+                execute_constructor(state, block_context, address, calldata.clone())?;
+            }
+
+            if let Some(nonce) = contract.nonce {
+                state.set_nonce(address.try_into()?, nonce)?;
+            }
+
+            for (key, value) in &contract.storage {
+                let key = starknet_api::state::StorageKey(PatriciaKey::try_from(*key)?);
+                state.set_storage_at(address.try_into()?, key, *value)?;
+            }
+        }
+
+        for balance in &self.balances {
+            let token_address = match balance.unit {
+                None | Some(FeeUnit::WEI) => ETH_ERC20_CONTRACT_ADDRESS,
+                Some(FeeUnit::FRI) => STRK_ERC20_CONTRACT_ADDRESS,
+            };
+            add_erc20_balance(
+                state,
+                ContractAddress::new(token_address)?,
+                ContractAddress::new(balance.address)?,
+                &balance.amount,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Starknet {
+    /// Returns the latest committed state as the content of a genesis file. Classes are included
+    /// inline, so the result is self-contained. Balances are not listed separately, since they are
+    /// a part of the storage of the fee tokens. In forking mode, only the state modified locally is
+    /// included.
+    pub fn export_genesis(&self) -> DevnetResult<GenesisSpec> {
+        let committed_state = self.latest_state.committed_state();
+
+        let mut classes: Vec<(ClassHash, ContractClass)> = self
+            .rpc_contract_classes
+            .read()
+            .iter_committed()
+            .filter(|(class_hash, _)| {
+                committed_state
+                    .class_hash_to_class
+                    .contains_key(&starknet_api::core::ClassHash(**class_hash))
+            })
+            .map(|(class_hash, (contract_class, _))| (*class_hash, contract_class.clone()))
+            .collect();
+        classes.sort_by_key(|(class_hash, _)| *class_hash);
+
+        let mut contracts: BTreeMap<Felt, GenesisContract> = BTreeMap::new();
+        for (address, class_hash) in &committed_state.address_to_class_hash {
+            contract_entry(&mut contracts, address).class_hash = Some(class_hash.0);
+        }
+        for (address, nonce) in &committed_state.address_to_nonce {
+            if nonce.0 != Felt::ZERO {
+                contract_entry(&mut contracts, address).nonce = Some(nonce.0);
+            }
+        }
+        for ((address, key), value) in &committed_state.storage_view {
+            if *value != Felt::ZERO {
+                contract_entry(&mut contracts, address).storage.insert(*key.0.key(), *value);
+            }
+        }
+
+        Ok(GenesisSpec {
+            classes: classes
+                .into_iter()
+                .map(|(_, contract_class)| GenesisClassSpec::Inline(contract_class.into()))
+                .collect(),
+            contracts: contracts.into_values().collect(),
+            balances: vec![],
+        })
+    }
+}
+
+fn contract_entry<'a>(
+    contracts: &'a mut BTreeMap<Felt, GenesisContract>,
+    address: &starknet_api::core::ContractAddress,
+) -> &'a mut GenesisContract {
+    let address = *address.0.key();
+    contracts.entry(address).or_insert_with(|| GenesisContract {
+        address,
+        class_hash: None,
+        constructor_calldata: None,
+        nonce: None,
+        storage: BTreeMap::new(),
+    })
+}
+
+fn read_file(path: &Path) -> DevnetResult<String> {
+    std::fs::read_to_string(path)
+        .map_err(|source| Error::ReadFileError { source, path: path.display().to_string() })
+}
+
+fn execute_constructor(
+    state: &mut StarknetState,
+    block_context: &BlockContext,
+    address: ContractAddress,
+    calldata: Vec<Felt>,
+) -> DevnetResult<()> {
+    let call = CallEntryPoint {
+        code_address: Some(address.try_into()?),
+        entry_point_type: EntryPointType::Constructor,
+        entry_point_selector: starknet_api::core::EntryPointSelector(
+            get_selector_from_name("constructor")
+                .map_err(|err| Error::UnexpectedInternalError { msg: err.to_string() })?,
+        ),
+        calldata: starknet_api::transaction::Calldata(Arc::new(calldata)),
+        storage_address: address.try_into()?,
+        initial_gas: block_context.versioned_constants().tx_initial_gas(),
+        ..Default::default()
+    };
+
+    let mut execution_context =
+        blockifier::execution::entry_point::EntryPointExecutionContext::new(
+            Arc::new(TransactionContext {
+                block_context: block_context.clone(),
+                tx_info: blockifier::transaction::objects::TransactionInfo::Deprecated(
+                    blockifier::transaction::objects::DeprecatedTransactionInfo::default(),
+                ),
+            }),
+            blockifier::execution::common_hints::ExecutionMode::Execute,
+            true,
+        )?;
+
+    let mut transactional_state = CachedState::create_transactional(&mut state.state);
+    let call_info =
+        call.execute(&mut transactional_state, &mut Default::default(), &mut execution_context)?;
+    if call_info.execution.failed {
+        return Err(Error::InvalidGenesis {
+            msg: format!(
+                "Constructor of contract {:#x} failed with {:?}",
+                Felt::from(address),
+                call_info.execution.retdata.0
+            ),
+        });
+    }
+
+    transactional_state.commit();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::patricia_key::PatriciaKey;
+    use starknet_types::rpc::transaction_receipt::FeeUnit;
+
+    use super::Genesis;
+    use crate::constants::{CAIRO_0_ERC20_CONTRACT_PATH, ETH_ERC20_CONTRACT_ADDRESS};
+    use crate::starknet::starknet_config::StarknetConfig;
+    use crate::starknet::Starknet;
+
+    #[test]
+    fn yaml_genesis_file_is_loaded_with_classes_relative_to_it() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::copy(CAIRO_0_ERC20_CONTRACT_PATH, dir.path().join("erc20.json")).unwrap();
+
+        let genesis_path = dir.path().join("genesis.yaml");
+        let mut genesis_file = std::fs::File::create(&genesis_path).unwrap();
+        writeln!(
+            genesis_file,
+            r#"
+classes:
+  - cairo0: erc20.json
+contracts:
+  - address: "0x123"
+    class_hash: "0x456"
+    storage:
+      "0x1": "0x2"
+balances:
+  - address: "0x123"
+    amount: 1000
+    unit: FRI
+"#
+        )
+        .unwrap();
+
+        let genesis = Genesis::load(&genesis_path).unwrap();
+        assert_eq!(genesis.classes.len(), 1);
+        assert_eq!(genesis.contracts[0].address, Felt::from(0x123));
+        assert_eq!(genesis.contracts[0].storage.get(&Felt::ONE), Some(&Felt::TWO));
+        assert_eq!(genesis.balances[0].amount, 1000_u32.into());
+        assert_eq!(genesis.balances[0].unit, Some(FeeUnit::FRI));
+    }
+
+    #[test]
+    fn unknown_genesis_fields_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis_path = dir.path().join("genesis.json");
+        std::fs::write(&genesis_path, r#"{ "accounts": [] }"#).unwrap();
+
+        assert!(Genesis::load(&genesis_path).is_err());
+    }
+
+    #[test]
+    fn exported_genesis_recreates_committed_state() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        let address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        let key = PatriciaKey::new(Felt::from(0x123)).unwrap();
+        starknet.set_storage_at(address, key, Felt::from(0x456)).unwrap();
+        starknet.set_nonce(address, Felt::from(3)).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let genesis_path = dir.path().join("genesis.json");
+        let exported = starknet.export_genesis().unwrap();
+        std::fs::write(&genesis_path, serde_json::to_string(&exported).unwrap()).unwrap();
+
+        let genesis = Genesis::load(&genesis_path).unwrap();
+        assert_eq!(genesis.classes.len(), exported.classes.len());
+        let mut restored =
+            Starknet::new(&StarknetConfig { genesis: Some(genesis), ..Default::default() })
+                .unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(restored.get_latest_block().unwrap().block_number().0, 0);
+        assert_eq!(
+            restored.contract_storage_at_block(&latest, address, key).unwrap(),
+            Felt::from(0x456)
+        );
+        assert_eq!(restored.contract_nonce_at_block(&latest, address).unwrap(), Felt::from(3));
+    }
+}
@@ -1,8 +1,9 @@
 use blockifier::state::state_api::StateReader;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use starknet_api::block::BlockStatus;
 use starknet_rs_core::types::BlockId;
 use starknet_types::contract_address::ContractAddress;
-use starknet_types::contract_class::ContractClass;
+use starknet_types::contract_class::{compile_sierra_contract, ContractClass};
 use starknet_types::felt::ClassHash;
 
 use crate::error::{DevnetResult, Error, StateError};
@@ -18,7 +19,11 @@ pub fn get_class_hash_at_impl(
     let core_address = contract_address.try_into()?;
 
     let class_hash = state.get_class_hash_at(core_address)?;
-    if class_hash == Default::default() { Err(Error::ContractNotFound) } else { Ok(class_hash.0) }
+    if class_hash == Default::default() {
+        Err(Error::ContractNotFound)
+    } else {
+        Ok(class_hash.0)
+    }
 }
 
 pub fn get_class_impl(
@@ -52,14 +57,39 @@ pub fn get_class_at_impl(
     starknet.get_class(block_id, class_hash)
 }
 
+/// Returns the CASM stored on declaration. Classes without stored CASM, e.g. the ones loaded from a
+/// dump, are compiled once and their CASM is stored. Cairo 0 classes have no CASM, so they are
+/// reported as missing.
+pub fn get_compiled_casm_impl(
+    starknet: &Starknet,
+    block_id: &BlockId,
+    class_hash: ClassHash,
+) -> DevnetResult<CasmContractClass> {
+    let sierra_contract_class = match get_class_impl(starknet, block_id, class_hash)? {
+        ContractClass::Cairo1(sierra_contract_class) => sierra_contract_class,
+        ContractClass::Cairo0(_) => {
+            return Err(Error::StateError(StateError::NoneCasmClass(class_hash)));
+        }
+    };
+
+    if let Some(casm) = starknet.rpc_contract_classes.read().get_casm(&class_hash) {
+        return Ok(casm.clone());
+    }
+
+    let casm = compile_sierra_contract(&sierra_contract_class)?;
+    starknet.rpc_contract_classes.write().insert_casm(class_hash, casm.clone());
+    Ok(casm)
+}
+
 #[cfg(test)]
 mod tests {
-    use starknet_rs_core::types::BlockId;
+    use starknet_rs_core::types::{BlockId, BlockTag};
     use starknet_types::contract_class::ContractClass;
 
-    use crate::error::Error;
+    use crate::error::{Error, StateError};
     use crate::starknet::starknet_config::StateArchiveCapacity;
     use crate::starknet::tests::setup_starknet_with_no_signature_check_account_and_state_capacity;
+    use crate::utils::calculate_casm_hash;
     use crate::utils::test_utils::dummy_broadcasted_declare_transaction_v2;
 
     #[test]
@@ -155,4 +185,45 @@ mod tests {
             other => panic!("Got unexpected resp: {other:?}"),
         }
     }
+
+    #[test]
+    fn get_compiled_casm_of_declared_sierra_class() {
+        let (mut starknet, account) =
+            setup_starknet_with_no_signature_check_account_and_state_capacity(
+                1e8 as u128,
+                StateArchiveCapacity::Full,
+            );
+
+        let declare_txn = dummy_broadcasted_declare_transaction_v2(&account.account_address);
+        let compiled_class_hash = declare_txn.compiled_class_hash;
+        let (_, class_hash) = starknet
+            .add_declare_transaction(
+                starknet_types::rpc::transactions::BroadcastedDeclareTransaction::V2(Box::new(
+                    declare_txn,
+                )),
+            )
+            .unwrap();
+
+        // stored on declaration, so that it isn't compiled on request
+        let stored_casm = starknet.rpc_contract_classes.read().get_casm(&class_hash).cloned();
+        let casm = starknet.get_compiled_casm(&BlockId::Tag(BlockTag::Latest), class_hash).unwrap();
+        assert_eq!(Some(&casm), stored_casm.as_ref());
+        let casm_hash = calculate_casm_hash(serde_json::to_value(casm).unwrap()).unwrap();
+        assert_eq!(casm_hash, compiled_class_hash);
+    }
+
+    #[test]
+    fn get_compiled_casm_of_cairo_0_class() {
+        let (starknet, account) = setup_starknet_with_no_signature_check_account_and_state_capacity(
+            1e8 as u128,
+            StateArchiveCapacity::Full,
+        );
+
+        match starknet.get_compiled_casm(&BlockId::Tag(BlockTag::Latest), account.class_hash) {
+            Err(Error::StateError(StateError::NoneCasmClass(class_hash))) => {
+                assert_eq!(class_hash, account.class_hash)
+            }
+            other => panic!("Got unexpected resp: {other:?}"),
+        }
+    }
 }
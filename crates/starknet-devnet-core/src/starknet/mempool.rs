@@ -0,0 +1,562 @@
+use std::collections::{BTreeMap, HashMap};
+
+use blockifier::context::BlockContext;
+use blockifier::fee::gas_usage::{
+    compute_discounted_gas_from_gas_vector, estimate_minimal_gas_vector,
+};
+use blockifier::state::state_api::StateReader;
+use blockifier::transaction::account_transaction::AccountTransaction;
+use serde::{Deserialize, Serialize};
+use starknet_rs_core::types::Felt;
+use starknet_types::contract_address::ContractAddress;
+use starknet_types::felt::{Nonce, TransactionHash};
+use starknet_types::rpc::devnet::MempoolTransaction;
+use starknet_types::rpc::transactions::{
+    BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
+    BroadcastedInvokeTransaction, BroadcastedTransaction,
+};
+use tracing::warn;
+
+use super::starknet_config::{BlockGenerationOn, FullBlockPolicy, MempoolOrdering};
+use super::{
+    add_declare_transaction, add_deploy_account_transaction, add_invoke_transaction, Starknet,
+};
+use crate::error::{DevnetResult, Error, TransactionValidationError};
+
+#[derive(Clone, Serialize, Deserialize)]
+struct MempoolEntry {
+    transaction_hash: TransactionHash,
+    nonce: Nonce,
+    fee_priority: f64,
+    /// Position in the order of receiving
+    arrival: u64,
+    transaction: BroadcastedTransaction,
+}
+
+impl MempoolEntry {
+    /// Returns true if `self` should be executed before `other` when both are ready
+    fn precedes(&self, other: &MempoolEntry, ordering: MempoolOrdering) -> bool {
+        match ordering {
+            MempoolOrdering::Fifo => self.arrival < other.arrival,
+            MempoolOrdering::FeePriority => {
+                (self.fee_priority, other.arrival) > (other.fee_priority, self.arrival)
+            }
+        }
+    }
+}
+
+/// Transactions received in mempool mode, held per sender and ordered by nonce
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Mempool {
+    queues: HashMap<ContractAddress, BTreeMap<Nonce, MempoolEntry>>,
+    next_arrival: u64,
+}
+
+impl Mempool {
+    fn contains(&self, transaction_hash: &TransactionHash) -> bool {
+        self.queues
+            .values()
+            .any(|queue| queue.values().any(|entry| &entry.transaction_hash == transaction_hash))
+    }
+
+    /// Removes the specified transactions, or all if `None`. Returns hashes of the removed ones.
+    fn drop_transactions(
+        &mut self,
+        transaction_hashes: Option<&[TransactionHash]>,
+    ) -> Vec<TransactionHash> {
+        let mut dropped = vec![];
+        for queue in self.queues.values_mut() {
+            queue.retain(|_, entry| {
+                let retained = transaction_hashes
+                    .is_some_and(|hashes| !hashes.contains(&entry.transaction_hash));
+                if !retained {
+                    dropped.push((entry.arrival, entry.transaction_hash));
+                }
+                retained
+            });
+        }
+        self.queues.retain(|_, queue| !queue.is_empty());
+
+        dropped.sort();
+        dropped.into_iter().map(|(_, transaction_hash)| transaction_hash).collect()
+    }
+}
+
+/// The tip per gas unit, in FRI, which the transaction offers on top of the gas price. The tip of
+/// v3 transactions is taken as is. Older transactions offer their max fee for the gas they are
+/// estimated to consume, so their tip is the max fee divided by the minimal gas estimate, minus
+/// the gas price, converted from WEI to FRI by the ratio of the gas prices.
+fn fee_priority(
+    block_context: &BlockContext,
+    transaction: &BroadcastedTransaction,
+    blockifier_transaction: &AccountTransaction,
+) -> DevnetResult<f64> {
+    let max_fee = match transaction {
+        BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(v1)) => v1.common.max_fee,
+        BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V1(v1)) => {
+            v1.common.max_fee
+        }
+        BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V2(v2)) => {
+            v2.common.max_fee
+        }
+        BroadcastedTransaction::DeployAccount(BroadcastedDeployAccountTransaction::V1(v1)) => {
+            v1.common.max_fee
+        }
+        BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V3(v3)) => {
+            return Ok(v3.common.tip.0 as f64);
+        }
+        BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V3(v3)) => {
+            return Ok(v3.common.tip.0 as f64);
+        }
+        BroadcastedTransaction::DeployAccount(BroadcastedDeployAccountTransaction::V3(v3)) => {
+            return Ok(v3.common.tip.0 as f64);
+        }
+    };
+
+    let gas_vector = estimate_minimal_gas_vector(block_context, blockifier_transaction)
+        .map_err(|err| Error::BlockifierTransactionError(err.into()))?;
+    let gas_estimate = compute_discounted_gas_from_gas_vector(
+        &gas_vector,
+        &block_context.to_tx_context(blockifier_transaction),
+    );
+    let gas_prices = &block_context.block_info().gas_prices;
+    let (price_wei, price_fri) =
+        (gas_prices.eth_l1_gas_price.get() as f64, gas_prices.strk_l1_gas_price.get() as f64);
+
+    let tip_wei = max_fee.0 as f64 / gas_estimate.max(1) as f64 - price_wei;
+    Ok(tip_wei.max(0.0) * price_fri / price_wei)
+}
+
+fn assert_is_executable(transaction: &BroadcastedTransaction) -> DevnetResult<()> {
+    let (is_max_fee_zero_value, tx_type) = match transaction {
+        BroadcastedTransaction::Invoke(tx) => (tx.is_max_fee_zero_value(), tx.to_string()),
+        BroadcastedTransaction::Declare(tx) => (tx.is_max_fee_zero_value(), tx.to_string()),
+        BroadcastedTransaction::DeployAccount(tx) => (tx.is_max_fee_zero_value(), tx.to_string()),
+    };
+    if is_max_fee_zero_value {
+        return Err(Error::MaxFeeZeroError { tx_type });
+    }
+
+    let is_only_query = match transaction {
+        BroadcastedTransaction::Invoke(tx) => tx.is_only_query(),
+        BroadcastedTransaction::Declare(tx) => tx.is_only_query(),
+        BroadcastedTransaction::DeployAccount(tx) => tx.is_only_query(),
+    };
+    if is_only_query {
+        return Err(Error::UnsupportedAction {
+            msg: "only-query transactions are not supported".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+impl Starknet {
+    fn current_nonce(&mut self, address: ContractAddress) -> DevnetResult<Nonce> {
+        Ok(self.pending_state.get_nonce_at(address.try_into()?)?.0)
+    }
+
+    /// Holds the transaction until a block is produced. Transactions with a nonce lower than the
+    /// current one of the sender, or equal to that of an already held transaction, are rejected.
+    /// Returns the blockifier counterpart of the transaction, from which the hash and other
+    /// derived properties can be read.
+    pub(crate) fn add_to_mempool(
+        &mut self,
+        transaction: BroadcastedTransaction,
+    ) -> DevnetResult<AccountTransaction> {
+        assert_is_executable(&transaction)?;
+
+        let blockifier_transaction =
+            transaction.to_blockifier_account_transaction(&self.chain_id().to_felt(), false)?;
+        let (transaction_hash, sender_address, nonce) = match &blockifier_transaction {
+            AccountTransaction::Declare(tx) => {
+                (tx.tx_hash().0, tx.tx.sender_address(), tx.tx.nonce())
+            }
+            AccountTransaction::DeployAccount(tx) => {
+                (tx.tx_hash.0, tx.contract_address, tx.nonce())
+            }
+            AccountTransaction::Invoke(tx) => (tx.tx_hash.0, tx.sender_address(), tx.tx.nonce()),
+        };
+        let (sender_address, nonce) = (ContractAddress::from(sender_address), nonce.0);
+        let fee_priority =
+            fee_priority(&self.block_context, &transaction, &blockifier_transaction)?;
+
+        let is_nonce_taken = self
+            .mempool
+            .queues
+            .get(&sender_address)
+            .is_some_and(|queue| queue.contains_key(&nonce));
+        if nonce < self.current_nonce(sender_address)? || is_nonce_taken {
+            return Err(TransactionValidationError::InvalidTransactionNonce.into());
+        }
+
+        let arrival = self.mempool.next_arrival;
+        self.mempool.next_arrival += 1;
+        self.mempool.queues.entry(sender_address).or_default().insert(
+            nonce,
+            MempoolEntry { transaction_hash, nonce, fee_priority, arrival, transaction },
+        );
+
+        if self.config.block_generation_on == BlockGenerationOn::Transaction {
+            self.execute_mempool()?;
+        }
+
+        Ok(blockifier_transaction)
+    }
+
+    /// Removes the first transaction to be executed, if any transaction is ready, and returns it
+    /// together with its sender. A transaction is ready if its nonce is the current nonce of its
+    /// sender.
+    fn pop_ready_transaction(
+        &mut self,
+        ordering: MempoolOrdering,
+    ) -> DevnetResult<Option<(ContractAddress, MempoolEntry)>> {
+        let mut next: Option<(ContractAddress, &MempoolEntry)> = None;
+        for (sender_address, queue) in &self.mempool.queues {
+            let Some(entry) = queue.values().next() else { continue };
+            let current_nonce = self.pending_state.get_nonce_at((*sender_address).try_into()?)?.0;
+            if entry.nonce != current_nonce {
+                continue;
+            }
+            match next {
+                Some((_, next_entry)) if !entry.precedes(next_entry, ordering) => {}
+                _ => next = Some((*sender_address, entry)),
+            }
+        }
+
+        let Some((sender_address, entry)) = next else { return Ok(None) };
+        let nonce = entry.nonce;
+        let queue = self.mempool.queues.entry(sender_address).or_default();
+        let entry = queue.remove(&nonce);
+        if queue.is_empty() {
+            self.mempool.queues.remove(&sender_address);
+        }
+
+        Ok(entry.map(|entry| (sender_address, entry)))
+    }
+
+    /// Executes ready transactions until none is left, or until a transaction doesn't fit into
+    /// the pending block if full blocks are queued. Executing a transaction may make the next
+    /// transaction of its sender ready. Transactions failing validation are dropped.
+    pub(crate) fn execute_mempool(&mut self) -> DevnetResult<()> {
+        let Some(ordering) = self.config.mempool else { return Ok(()) };
+
+        while let Some((sender_address, entry)) = self.pop_ready_transaction(ordering)? {
+            let execution_result = match entry.transaction.clone() {
+                BroadcastedTransaction::Invoke(tx) => {
+                    add_invoke_transaction::add_invoke_transaction(self, tx).map(|_| ())
+                }
+                BroadcastedTransaction::Declare(tx) => {
+                    add_declare_transaction::add_declare_transaction(self, tx).map(|_| ())
+                }
+                BroadcastedTransaction::DeployAccount(tx) => {
+                    add_deploy_account_transaction::add_deploy_account_transaction(self, tx)
+                        .map(|_| ())
+                }
+            };
+
+            let is_queued = self.config.full_block_policy == FullBlockPolicy::Queue;
+            if is_queued && matches!(execution_result, Err(Error::BlockFull)) {
+                // the transaction waits for the next block, together with the ones not executed
+                self.mempool.queues.entry(sender_address).or_default().insert(entry.nonce, entry);
+                break;
+            }
+            if let Err(err) = execution_result {
+                warn!("Transaction {:#x} dropped from mempool: {err}", entry.transaction_hash);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the transactions waiting in the mempool, in the order of receiving
+    pub fn get_mempool(&mut self) -> DevnetResult<Vec<MempoolTransaction>> {
+        let mut entries = vec![];
+        let senders: Vec<_> = self.mempool.queues.keys().copied().collect();
+        for sender_address in senders {
+            let mut expected_nonce = self.current_nonce(sender_address)?;
+            for entry in
+                self.mempool.queues.get(&sender_address).into_iter().flat_map(|q| q.values())
+            {
+                let ready = entry.nonce == expected_nonce;
+                if ready {
+                    expected_nonce += Felt::ONE;
+                }
+
+                entries.push((
+                    entry.arrival,
+                    MempoolTransaction {
+                        transaction_hash: entry.transaction_hash,
+                        transaction_type: entry.transaction.get_type(),
+                        sender_address,
+                        nonce: entry.nonce,
+                        ready,
+                    },
+                ));
+            }
+        }
+
+        entries.sort_by_key(|(arrival, _)| *arrival);
+        Ok(entries.into_iter().map(|(_, transaction)| transaction).collect())
+    }
+
+    /// Returns true if the transaction is waiting in the mempool
+    pub fn is_transaction_in_mempool(&self, transaction_hash: &TransactionHash) -> bool {
+        self.mempool.contains(transaction_hash)
+    }
+
+    /// Removes the specified transactions from the mempool, or all of them if `None`. Returns
+    /// hashes of the removed transactions.
+    pub fn drop_mempool_transactions(
+        &mut self,
+        transaction_hashes: Option<&[TransactionHash]>,
+    ) -> DevnetResult<Vec<TransactionHash>> {
+        if self.config.mempool.is_none() {
+            return Err(Error::UnsupportedAction {
+                msg: "Mempool is not enabled; run Devnet with --mempool".into(),
+            });
+        }
+
+        Ok(self.mempool.drop_transactions(transaction_hashes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blockifier::bouncer::BouncerWeights;
+    use starknet_api::data_availability::DataAvailabilityMode;
+    use starknet_api::transaction::{Fee, Tip};
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::rpc::state::Balance;
+    use starknet_types::rpc::transactions::broadcasted_invoke_transaction_v1::BroadcastedInvokeTransactionV1;
+    use starknet_types::rpc::transactions::broadcasted_invoke_transaction_v3::BroadcastedInvokeTransactionV3;
+    use starknet_types::rpc::transactions::{
+        BroadcastedInvokeTransaction, BroadcastedTransactionCommonV3, ResourceBoundsWrapper,
+    };
+    use starknet_types::traits::HashProducer;
+
+    use crate::account::Account;
+    use crate::constants::{ETH_ERC20_CONTRACT_ADDRESS, STRK_ERC20_CONTRACT_ADDRESS};
+    use crate::error::{Error, TransactionValidationError};
+    use crate::starknet::starknet_config::{BlockGenerationOn, FullBlockPolicy, MempoolOrdering};
+    use crate::starknet::tests::setup_starknet_with_no_signature_check_account;
+    use crate::starknet::Starknet;
+    use crate::traits::Deployed;
+    use crate::utils::custom_bouncer_config;
+    use crate::utils::test_utils::cairo_0_account_without_validations;
+
+    fn setup(ordering: MempoolOrdering) -> (Starknet, ContractAddress) {
+        let (mut starknet, account) = setup_starknet_with_no_signature_check_account(1_000_000_000);
+        starknet.config.mempool = Some(ordering);
+        starknet.config.block_generation_on = BlockGenerationOn::Demand;
+        (starknet, account.get_address())
+    }
+
+    fn dummy_invoke(
+        sender: ContractAddress,
+        nonce: u32,
+        max_fee: u128,
+    ) -> BroadcastedInvokeTransaction {
+        let calldata = vec![
+            ETH_ERC20_CONTRACT_ADDRESS,
+            get_selector_from_name("name").unwrap(),
+            Felt::ZERO, // calldata len
+        ];
+        BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1::new(
+            sender,
+            Fee(max_fee),
+            &vec![],
+            Felt::from(nonce),
+            &calldata,
+            Felt::ONE,
+        ))
+    }
+
+    fn dummy_invoke_v3(sender: ContractAddress, nonce: u32, tip: u64) -> BroadcastedInvokeTransaction {
+        let BroadcastedInvokeTransaction::V1(v1) = dummy_invoke(sender, nonce, 1) else {
+            unreachable!()
+        };
+        BroadcastedInvokeTransaction::V3(BroadcastedInvokeTransactionV3 {
+            common: BroadcastedTransactionCommonV3 {
+                version: Felt::THREE,
+                signature: vec![],
+                nonce: Felt::from(nonce),
+                resource_bounds: ResourceBoundsWrapper::new(100_000, 1, 0, 0),
+                tip: Tip(tip),
+                paymaster_data: vec![],
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+            },
+            sender_address: sender,
+            calldata: v1.calldata,
+            account_deployment_data: vec![],
+        })
+    }
+
+    fn latest_block_transactions(starknet: &Starknet) -> Vec<Felt> {
+        starknet.get_block(&BlockId::Tag(BlockTag::Latest)).unwrap().get_transactions().clone()
+    }
+
+    #[test]
+    fn transaction_with_nonce_gap_is_held_until_gap_is_filled() {
+        let (mut starknet, sender) = setup(MempoolOrdering::Fifo);
+
+        let second_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 1, 5000)).unwrap();
+        let mempool = starknet.get_mempool().unwrap();
+        assert_eq!(mempool.len(), 1);
+        assert!(!mempool[0].ready);
+
+        starknet.create_block().unwrap();
+        assert!(latest_block_transactions(&starknet).is_empty());
+        assert!(starknet.is_transaction_in_mempool(&second_hash));
+
+        let first_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 0, 5000)).unwrap();
+        assert!(starknet.get_mempool().unwrap().iter().all(|tx| tx.ready));
+        assert!(matches!(starknet.get_transaction_by_hash(first_hash), Err(Error::NoTransaction)));
+
+        starknet.create_block().unwrap();
+        assert_eq!(latest_block_transactions(&starknet), vec![first_hash, second_hash]);
+        assert!(starknet.get_mempool().unwrap().is_empty());
+    }
+
+    #[test]
+    fn ready_transactions_are_executed_by_fee_priority() {
+        let (mut starknet, sender) = setup(MempoolOrdering::FeePriority);
+        let account_class = cairo_0_account_without_validations();
+        let other_account = Account::new(
+            Balance::from(1_000_000_000_u128),
+            Felt::TWO,
+            Felt::TWO,
+            account_class.generate_hash().unwrap(),
+            account_class.into(),
+            ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap(),
+            ContractAddress::new(STRK_ERC20_CONTRACT_ADDRESS).unwrap(),
+        )
+        .unwrap();
+        other_account.deploy(&mut starknet.pending_state).unwrap();
+        starknet.commit_diff().unwrap();
+        let other_sender = other_account.get_address();
+
+        let cheap_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 0, 5000)).unwrap();
+        let expensive_hash =
+            starknet.add_invoke_transaction(dummy_invoke(other_sender, 0, 6000)).unwrap();
+
+        starknet.create_block().unwrap();
+        assert_eq!(latest_block_transactions(&starknet), vec![expensive_hash, cheap_hash]);
+    }
+
+    #[test]
+    fn v1_and_v3_transactions_are_ordered_by_tip_per_gas_unit() {
+        let (mut starknet, sender) = setup(MempoolOrdering::FeePriority);
+        let account_class = cairo_0_account_without_validations();
+        let other_account = Account::new(
+            Balance::from(1_000_000_000_u128),
+            Felt::TWO,
+            Felt::TWO,
+            account_class.generate_hash().unwrap(),
+            account_class.into(),
+            ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap(),
+            ContractAddress::new(STRK_ERC20_CONTRACT_ADDRESS).unwrap(),
+        )
+        .unwrap();
+        other_account.deploy(&mut starknet.pending_state).unwrap();
+        starknet.commit_diff().unwrap();
+        let other_sender = other_account.get_address();
+
+        // with the gas price of 1, the max fee of the v1 transaction is a tip of less than 1000
+        // per unit of the gas it is estimated to consume
+        let v1_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 0, 50_000)).unwrap();
+        let v3_hash =
+            starknet.add_invoke_transaction(dummy_invoke_v3(other_sender, 0, 1000)).unwrap();
+        let v1_next_hash =
+            starknet.add_invoke_transaction(dummy_invoke(sender, 1, 50_000_000)).unwrap();
+        let v3_next_hash =
+            starknet.add_invoke_transaction(dummy_invoke_v3(other_sender, 1, 0)).unwrap();
+
+        starknet.create_block().unwrap();
+        assert_eq!(
+            latest_block_transactions(&starknet),
+            vec![v3_hash, v1_hash, v1_next_hash, v3_next_hash]
+        );
+    }
+
+    #[test]
+    fn transaction_with_used_nonce_is_rejected() {
+        let (mut starknet, sender) = setup(MempoolOrdering::Fifo);
+
+        starknet.add_invoke_transaction(dummy_invoke(sender, 0, 5000)).unwrap();
+        for nonce in [0, 0] {
+            // first rejected as already held, then as already executed
+            match starknet.add_invoke_transaction(dummy_invoke(sender, nonce, 5001)) {
+                Err(Error::TransactionValidationError(
+                    TransactionValidationError::InvalidTransactionNonce,
+                )) => (),
+                other => panic!("Unexpected result: {other:?}"),
+            }
+            starknet.create_block().unwrap();
+        }
+    }
+
+    #[test]
+    fn dropped_transactions_are_not_executed() {
+        let (mut starknet, sender) = setup(MempoolOrdering::Fifo);
+
+        let first_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 0, 5000)).unwrap();
+        let second_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 1, 5000)).unwrap();
+
+        let dropped = starknet.drop_mempool_transactions(Some(&[second_hash])).unwrap();
+        assert_eq!(dropped, vec![second_hash]);
+
+        starknet.create_block().unwrap();
+        assert_eq!(latest_block_transactions(&starknet), vec![first_hash]);
+
+        starknet.add_invoke_transaction(dummy_invoke(sender, 2, 5000)).unwrap();
+        assert_eq!(starknet.drop_mempool_transactions(None).unwrap().len(), 1);
+        assert!(starknet.get_mempool().unwrap().is_empty());
+    }
+
+    #[test]
+    fn transactions_not_fitting_into_block_are_queued_for_next_block() {
+        let (mut starknet, sender) = setup(MempoolOrdering::Fifo);
+        starknet.config.full_block_policy = FullBlockPolicy::Queue;
+        // transactions are only weighed if the block capacity is limited
+        starknet.config.block_capacity = Some(custom_bouncer_config().block_max_capacity);
+
+        let first_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 0, 5000)).unwrap();
+        starknet.execute_mempool().unwrap();
+        // the pending block has no room for another transaction like the first one
+        starknet.config.block_capacity = Some(BouncerWeights {
+            n_steps: starknet.pending_block_weights.n_steps * 3 / 2,
+            ..custom_bouncer_config().block_max_capacity
+        });
+
+        let second_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 1, 5000)).unwrap();
+        let third_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 2, 5000)).unwrap();
+
+        starknet.create_block().unwrap();
+        assert_eq!(latest_block_transactions(&starknet), vec![first_hash]);
+        assert!(starknet.is_transaction_in_mempool(&second_hash));
+        assert!(starknet.is_transaction_in_mempool(&third_hash));
+
+        starknet.create_block().unwrap();
+        assert_eq!(latest_block_transactions(&starknet), vec![second_hash]);
+        assert_eq!(starknet.get_mempool().unwrap().len(), 1);
+        assert!(starknet.get_transaction_rejection_reason(&third_hash).is_none());
+    }
+
+    #[test]
+    fn transactions_are_executed_on_arrival_in_block_generation_on_transaction_mode() {
+        let (mut starknet, sender) = setup(MempoolOrdering::Fifo);
+        starknet.config.block_generation_on = BlockGenerationOn::Transaction;
+
+        let second_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 1, 5000)).unwrap();
+        assert!(starknet.is_transaction_in_mempool(&second_hash));
+
+        let first_hash = starknet.add_invoke_transaction(dummy_invoke(sender, 0, 5000)).unwrap();
+        assert!(starknet.get_mempool().unwrap().is_empty());
+        assert_eq!(latest_block_transactions(&starknet), vec![second_hash]);
+        assert!(starknet.get_transaction_execution_and_finality_status(first_hash).is_ok());
+    }
+}
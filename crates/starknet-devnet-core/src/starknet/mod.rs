@@ -1,18 +1,29 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::num::NonZeroU128;
+use std::path::Path;
 use std::sync::Arc;
 
+use blockifier::abi::constants::{BLOCK_HASH_CONTRACT_ADDRESS, STORED_BLOCK_HASH_BUFFER};
 use blockifier::blockifier::block::{BlockInfo, GasPrices};
+use blockifier::bouncer::{get_tx_weights, BouncerWeights};
 use blockifier::context::{BlockContext, ChainInfo, TransactionContext};
 use blockifier::execution::entry_point::CallEntryPoint;
 use blockifier::state::cached_state::CachedState;
-use blockifier::state::state_api::StateReader;
+use blockifier::state::state_api::{State, StateReader};
 use blockifier::transaction::account_transaction::AccountTransaction;
-use blockifier::transaction::errors::TransactionPreValidationError;
+use blockifier::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use blockifier::transaction::transactions::ExecutableTransaction;
+use blockifier::versioned_constants::VersionedConstants;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use parking_lot::RwLock;
-use starknet_api::block::{BlockNumber, BlockStatus, BlockTimestamp, GasPrice, GasPricePerToken};
+use starknet_api::block::{
+    BlockNumber, BlockStatus, BlockTimestamp, GasPrice, GasPricePerToken,
+    StarknetVersion as ApiStarknetVersion,
+};
 use starknet_api::core::SequencerContractAddress;
+use starknet_api::data_availability::L1DataAvailabilityMode;
 use starknet_api::felt;
 use starknet_api::transaction::Fee;
 use starknet_config::BlockGenerationOn;
@@ -35,10 +46,13 @@ use starknet_types::rpc::block::{
     Block, BlockHeader, BlockResult, PendingBlock, PendingBlockHeader,
 };
 use starknet_types::rpc::estimate_message_fee::FeeEstimateWrapper;
-use starknet_types::rpc::gas_modification::{GasModification, GasModificationRequest};
+use starknet_types::rpc::gas_modification::{
+    DataAvailabilityModeRequest, GasModification, GasModificationRequest,
+};
 use starknet_types::rpc::state::{
     PendingStateUpdate, StateUpdate, StateUpdateResult, ThinStateDiff,
 };
+use starknet_types::rpc::storage_proof::{ContractStorageKeys, StorageProof};
 use starknet_types::rpc::transaction_receipt::{
     DeployTransactionReceipt, L1HandlerTransactionReceipt, TransactionReceipt,
 };
@@ -54,9 +68,12 @@ use starknet_types::traits::HashProducer;
 use tracing::{error, info};
 
 use self::cheats::Cheats;
+use self::coverage::Coverage;
 use self::defaulter::StarknetDefaulter;
+use self::mempool::Mempool;
 use self::predeployed::initialize_erc20_at_address;
-use self::starknet_config::{StarknetConfig, StateArchiveCapacity};
+use self::snapshot::Snapshots;
+use self::starknet_config::{FullBlockPolicy, StarknetConfig, StateArchiveCapacity};
 use self::transaction_trace::create_trace;
 use crate::account::Account;
 use crate::blocks::{StarknetBlock, StarknetBlocks};
@@ -64,31 +81,43 @@ use crate::constants::{
     CHARGEABLE_ACCOUNT_ADDRESS, CHARGEABLE_ACCOUNT_PRIVATE_KEY, DEVNET_DEFAULT_CHAIN_ID,
     DEVNET_DEFAULT_DATA_GAS_PRICE, DEVNET_DEFAULT_GAS_PRICE, DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
     ETH_ERC20_CONTRACT_ADDRESS, ETH_ERC20_NAME, ETH_ERC20_SYMBOL, STRK_ERC20_CONTRACT_ADDRESS,
-    STRK_ERC20_NAME, STRK_ERC20_SYMBOL, USE_KZG_DA,
+    STRK_ERC20_NAME, STRK_ERC20_SYMBOL,
 };
 use crate::contract_class_choice::AccountContractClassChoice;
-use crate::error::{DevnetResult, Error, TransactionValidationError};
+use crate::database::Database;
+use crate::error::{DevnetResult, Error, StateError, TransactionValidationError};
 use crate::messaging::MessagingBroker;
 use crate::predeployed_accounts::PredeployedAccounts;
 use crate::raw_execution::RawExecutionV1;
+use crate::state::state_commitment::StateTries;
 use crate::state::state_diff::StateDiff;
 use crate::state::{CommittedClassStorage, CustomState, CustomStateReader, StarknetState};
-use crate::traits::{AccountGenerator, Deployed, HashIdentified, HashIdentifiedMut};
+use crate::traits::{AccountGenerator, Deployed, HashIdentified};
 use crate::transactions::{StarknetTransaction, StarknetTransactions};
-use crate::utils::{custom_bouncer_config, get_versioned_constants};
+use crate::utils::custom_bouncer_config;
 
 mod add_declare_transaction;
 mod add_deploy_account_transaction;
 mod add_invoke_transaction;
 mod add_l1_handler_transaction;
 mod cheats;
+mod coverage;
 pub(crate) mod defaulter;
+pub mod dump;
 mod estimations;
 mod events;
+pub mod fork_cache;
+pub mod gas_price_schedule;
+pub mod genesis;
 mod get_class_impls;
+mod mempool;
+mod persistence;
 mod predeployed;
+pub mod profiler;
+pub mod snapshot;
 pub mod starknet_config;
 mod state_update;
+mod storage_proof;
 pub(crate) mod transaction_trace;
 
 pub struct Starknet {
@@ -96,6 +125,11 @@ pub struct Starknet {
     pub pending_state: StarknetState,
     /// Contains the diff since the last block
     pending_state_diff: StateDiff,
+    /// Tries of the latest state, which are updated with the diff of each new block when
+    /// calculating commitments. Built from the whole state if not present.
+    state_tries: Option<StateTries>,
+    /// Sum of the weights of the transactions in the pending block
+    pending_block_weights: BouncerWeights,
     predeployed_accounts: PredeployedAccounts,
     pub(in crate::starknet) block_context: BlockContext,
     // To avoid repeating some logic related to blocks,
@@ -106,9 +140,25 @@ pub struct Starknet {
     pub pending_block_timestamp_shift: i64,
     pub next_block_timestamp: Option<u64>,
     pub next_block_gas: GasModification,
+    pub next_block_l1_da_mode: L1DataAvailabilityMode,
     pub(crate) messaging: MessagingBroker,
     rpc_contract_classes: Arc<RwLock<CommittedClassStorage>>,
     cheats: Cheats,
+    snapshots: Snapshots,
+    /// If present, blocks, transactions and archived states are stored in it
+    database: Option<Database>,
+    /// Transactions waiting for execution; used only if mempool is enabled in the config
+    mempool: Mempool,
+    /// Reasons of rejecting transactions which failed validation
+    rejected_transactions: HashMap<TransactionHash, String>,
+    /// Number of the next invoke transactions which are reverted regardless of their execution
+    pub(crate) transactions_to_revert: u64,
+    /// PCs visited since the start of Devnet
+    pub(crate) coverage: Coverage,
+    /// Reads from the forking origin, if any; shared with the states
+    defaulter: StarknetDefaulter,
+    /// Gas prices of the origin blocks replayed by the blocks following the genesis block
+    replayed_gas_prices: Vec<GasModification>,
 }
 
 impl Default for Starknet {
@@ -123,10 +173,13 @@ impl Default for Starknet {
                 STRK_ERC20_CONTRACT_ADDRESS,
                 DEVNET_DEFAULT_CHAIN_ID,
                 DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
+                VersionedConstants::latest_constants().clone(),
             ),
             latest_state: Default::default(),
             pending_state: Default::default(),
             pending_state_diff: Default::default(),
+            state_tries: None,
+            pending_block_weights: Default::default(),
             predeployed_accounts: Default::default(),
             blocks: Default::default(),
             transactions: Default::default(),
@@ -139,18 +192,37 @@ impl Default for Starknet {
                 gas_price_fri: DEVNET_DEFAULT_GAS_PRICE,
                 data_gas_price_fri: DEVNET_DEFAULT_DATA_GAS_PRICE,
             },
+            next_block_l1_da_mode: L1DataAvailabilityMode::Blob,
             messaging: Default::default(),
             rpc_contract_classes: Default::default(),
             cheats: Default::default(),
+            snapshots: Default::default(),
+            database: None,
+            mempool: Default::default(),
+            rejected_transactions: Default::default(),
+            transactions_to_revert: 0,
+            coverage: Default::default(),
+            defaulter: Default::default(),
+            replayed_gas_prices: Default::default(),
         }
     }
 }
 
 impl Starknet {
     pub fn new(config: &StarknetConfig) -> DevnetResult<Self> {
-        let defaulter = StarknetDefaulter::new(config.fork_config.clone());
+        let database = match &config.db_path {
+            Some(db_path) => Some(Database::open(Path::new(db_path))?),
+            None => None,
+        };
+        Self::create(config, database)
+    }
+
+    /// Creates Devnet storing its data in `database`, if provided. The database file cannot be
+    /// opened twice, so on restart the database already opened is passed here.
+    fn create(config: &StarknetConfig, database: Option<Database>) -> DevnetResult<Self> {
+        let defaulter = StarknetDefaulter::new(config.fork_config.clone(), config.chain_id.into());
         let rpc_contract_classes = Arc::new(RwLock::new(CommittedClassStorage::default()));
-        let mut state = StarknetState::new(defaulter, rpc_contract_classes.clone());
+        let mut state = StarknetState::new(defaulter.clone(), rpc_contract_classes.clone());
 
         // predeclare account classes
         for account_class_choice in
@@ -221,23 +293,33 @@ impl Starknet {
         let starting_block_number =
             config.fork_config.block_number.map_or(DEVNET_DEFAULT_STARTING_BLOCK_NUMBER, |n| n + 1);
 
+        let mut block_context = Self::init_block_context(
+            config.gas_price_wei,
+            config.gas_price_fri,
+            config.data_gas_price_wei,
+            config.data_gas_price_fri,
+            ETH_ERC20_CONTRACT_ADDRESS,
+            STRK_ERC20_CONTRACT_ADDRESS,
+            config.chain_id,
+            starting_block_number,
+            config.versioned_constants.clone(),
+        );
+        Self::set_block_context_l1_da_mode(&mut block_context, config.l1_da_mode.into());
+
+        if let Some(genesis) = &config.genesis {
+            genesis.apply(&mut state, &block_context)?;
+        }
+
         let pending_state_diff = state.commit_diff(starting_block_number)?;
 
         let mut this = Self {
             latest_state: Default::default(), // temporary - overwritten on genesis block creation
             pending_state: state,
             pending_state_diff,
+            state_tries: None,
+            pending_block_weights: Default::default(),
             predeployed_accounts,
-            block_context: Self::init_block_context(
-                config.gas_price_wei,
-                config.gas_price_fri,
-                config.data_gas_price_wei,
-                config.data_gas_price_fri,
-                ETH_ERC20_CONTRACT_ADDRESS,
-                STRK_ERC20_CONTRACT_ADDRESS,
-                config.chain_id,
-                starting_block_number,
-            ),
+            block_context,
             blocks: StarknetBlocks::new(starting_block_number),
             transactions: StarknetTransactions::default(),
             config: config.clone(),
@@ -249,18 +331,36 @@ impl Starknet {
                 gas_price_fri: config.gas_price_fri,
                 data_gas_price_fri: config.data_gas_price_fri,
             },
+            next_block_l1_da_mode: config.l1_da_mode.into(),
             messaging: Default::default(),
             rpc_contract_classes,
             cheats: Default::default(),
+            snapshots: Default::default(),
+            database: None,
+            mempool: Default::default(),
+            rejected_transactions: Default::default(),
+            transactions_to_revert: 0,
+            coverage: Default::default(),
+            replayed_gas_prices: Self::fetch_replayed_gas_prices(config, &defaulter)?,
+            defaulter,
         };
 
         this.restart_pending_block()?;
 
+        if let Some(database) = &database {
+            // a database with data of an earlier run already contains the genesis block
+            if this.use_database(database.clone())? {
+                return Ok(this);
+            }
+        }
+
         // Create an empty genesis block, set start_time before if it's set
         if let Some(start_time) = config.start_time {
             this.set_next_block_timestamp(start_time);
         };
+        this.schedule_next_block_gas();
         this.create_block()?;
+        this.save_to_database()?;
 
         Ok(this)
     }
@@ -273,7 +373,11 @@ impl Starknet {
         let new_messsaging_ethereum =
             if restart_l1_to_l2_messaging { None } else { self.messaging.ethereum.clone() };
 
-        *self = Starknet::new(&self.config)?;
+        let database = self.database.take();
+        if let Some(database) = &database {
+            database.clear()?;
+        }
+        *self = Starknet::create(&self.config, database)?;
         self.messaging.ethereum = new_messsaging_ethereum;
 
         info!("Starknet Devnet restarted");
@@ -290,6 +394,7 @@ impl Starknet {
         Self::advance_block_context_block_number(&mut self.block_context);
 
         Self::set_block_context_gas(&mut self.block_context, &self.next_block_gas);
+        Self::set_block_context_l1_da_mode(&mut self.block_context, self.next_block_l1_da_mode);
 
         // Pending block header gas data needs to be set
         self.blocks.pending_block.header.l1_gas_price.price_in_wei =
@@ -302,10 +407,52 @@ impl Starknet {
             GasPrice(u128::from(self.next_block_gas.data_gas_price_fri));
 
         self.restart_pending_block()?;
+        self.schedule_next_block_gas();
 
         Ok(())
     }
 
+    /// Fetches the gas prices of the origin blocks to be replayed, oldest first. Fetched on
+    /// startup, so that creating a block doesn't wait for the origin.
+    fn fetch_replayed_gas_prices(
+        config: &StarknetConfig,
+        defaulter: &StarknetDefaulter,
+    ) -> DevnetResult<Vec<GasModification>> {
+        let (Some(replayed_blocks), Some(fork_block_number)) =
+            (config.fork_config.replay_gas_prices, config.fork_config.block_number)
+        else {
+            return Ok(vec![]);
+        };
+
+        let first_block_number = (fork_block_number + 1).saturating_sub(replayed_blocks.get());
+        let mut replayed_gas_prices = vec![];
+        for block_number in first_block_number..=fork_block_number {
+            replayed_gas_prices.extend(defaulter.get_block_gas_prices(block_number)?);
+        }
+
+        Ok(replayed_gas_prices)
+    }
+
+    /// Sets the gas prices of the block after the pending block, if they are determined by the
+    /// gas price schedule or by the forking origin. Prices set by the user are thus used for a
+    /// single block, after which the schedule continues.
+    fn schedule_next_block_gas(&mut self) {
+        let next_block_number = self.block_context.block_info().block_number.0 + 1;
+        let genesis_offset = next_block_number - self.blocks.starting_block_number;
+
+        if self.config.fork_config.replay_gas_prices.is_some() {
+            // once all origin blocks are replayed, the latest prices are kept
+            let replayed = genesis_offset
+                .checked_sub(1)
+                .and_then(|index| self.replayed_gas_prices.get(index as usize));
+            if let Some(gas_prices) = replayed {
+                self.next_block_gas = gas_prices.clone();
+            }
+        } else if let Some(schedule) = &self.config.gas_price_schedule {
+            self.next_block_gas = schedule.prices_of_block(genesis_offset, &self.next_block_gas);
+        }
+    }
+
     fn next_block_timestamp(&mut self) -> BlockTimestamp {
         match self.next_block_timestamp {
             Some(timestamp) => {
@@ -324,6 +471,24 @@ impl Starknet {
     pub(crate) fn generate_new_block_and_state(&mut self) -> DevnetResult<Felt> {
         let mut new_block = self.pending_block().clone();
         let new_block_number = self.blocks.next_block_number();
+        let old_block_hash = self.old_block_hash(new_block_number.0 + 1)?;
+
+        if !self.config.lite_mode && self.config.calculate_commitments {
+            let mut transactions = vec![];
+            for tx_hash in new_block.get_transactions() {
+                transactions.extend(self.transactions.get(tx_hash)?);
+            }
+            let transactions: Vec<&StarknetTransaction> =
+                transactions.iter().map(|tx| tx.as_ref()).collect();
+            let state_tries = match &mut self.state_tries {
+                Some(state_tries) => {
+                    state_tries.apply_diff(&self.pending_state_diff);
+                    state_tries
+                }
+                None => self.state_tries.insert(self.pending_state.build_state_tries()),
+            };
+            new_block.set_commitments(state_tries.state_root(), &transactions);
+        }
 
         // set new block header
         new_block.set_block_hash(if self.config.lite_mode {
@@ -342,34 +507,79 @@ impl Starknet {
         let new_block_hash = new_block.header.block_hash.0;
 
         // update txs block hash block number for each transaction in the pending block
-        new_block.get_transactions().iter().for_each(|tx_hash| {
-            if let Some(tx) = self.transactions.get_by_hash_mut(tx_hash) {
+        for tx_hash in new_block.get_transactions() {
+            let updated = self.transactions.update(tx_hash, |tx| {
                 tx.block_hash = Some(new_block_hash);
                 tx.block_number = Some(new_block_number);
                 tx.finality_status = TransactionFinalityStatus::AcceptedOnL2;
-            } else {
-                error!("Transaction is not present in the transactions collection");
+            });
+            match updated {
+                Err(Error::NoTransaction) => {
+                    error!("Transaction is not present in the transactions collection")
+                }
+                other => other?,
             }
-        });
+        }
 
         // insert pending block in the blocks collection and connect it to the state diff
-        self.blocks.insert(new_block, self.pending_state_diff.clone());
-        self.pending_state_diff = StateDiff::default();
+        let state_diff = std::mem::take(&mut self.pending_state_diff);
+        self.store_block(new_block_number.0, &state_diff)?;
+        self.blocks.insert(new_block, state_diff)?;
 
         // save into blocks state archive
-        if self.config.state_archive == StateArchiveCapacity::Full {
-            let clone = self.pending_state.clone_historic();
-            self.blocks.save_state_at(new_block_hash, clone);
-        }
+        self.archive_state(new_block_hash);
 
         self.generate_pending_block()?;
 
         // for every new block we need to clone pending state into state
         self.latest_state = self.pending_state.clone_historic();
 
+        self.store_old_block_hash(old_block_hash)?;
+
         Ok(new_block_hash)
     }
 
+    /// Returns the number and hash of the block which is `STORED_BLOCK_HASH_BUFFER` blocks older
+    /// than the pending block numbered `pending_block_number`, if there is such a block. Blocks
+    /// preceding the forking point are fetched from the origin, so this fails if the origin does.
+    fn old_block_hash(&self, pending_block_number: u64) -> DevnetResult<Option<(u64, BlockHash)>> {
+        let Some(old_block_number) = pending_block_number.checked_sub(STORED_BLOCK_HASH_BUFFER)
+        else {
+            return Ok(None);
+        };
+
+        let old_block_hash =
+            match self.blocks.get_by_block_id(&BlockId::Number(old_block_number))? {
+                Some(block) => Some(block.block_hash()),
+                // blocks preceding the forking point are only available on the origin
+                None => self.defaulter.get_block_hash(old_block_number)?,
+            };
+        Ok(old_block_hash.map(|block_hash| (old_block_number, block_hash)))
+    }
+
+    /// Like the sequencer at the start of each block, stores the hash of the block which is
+    /// `STORED_BLOCK_HASH_BUFFER` blocks older than the pending block, as returned by
+    /// `old_block_hash`, in the block hash contract, keyed by its number. That's where the
+    /// `get_block_hash` syscall reads from. The hash is obtained before the chain is changed, so
+    /// that failing to obtain it leaves the chain intact.
+    fn store_old_block_hash(
+        &mut self,
+        old_block_hash: Option<(u64, BlockHash)>,
+    ) -> DevnetResult<()> {
+        let Some((old_block_number, old_block_hash)) = old_block_hash else {
+            return Ok(());
+        };
+
+        self.pending_state.set_storage_at(
+            ContractAddress::new(Felt::from(BLOCK_HASH_CONTRACT_ADDRESS))?.try_into()?,
+            PatriciaKey::new(Felt::from(old_block_number))?.try_into()?,
+            old_block_hash,
+        )?;
+        self.commit_diff()?;
+
+        Ok(())
+    }
+
     /// Commits the changes since the last commit. Use it to commit the changes generated by the
     /// last tx. Updates the `pending_state_diff` to accumulate the changes since the last block.
     /// Check `StarknetState::commit_diff` for more info.
@@ -381,17 +591,46 @@ impl Starknet {
         Ok(state_diff)
     }
 
+    /// Executes the transaction with `execute` and handles the result. If the pending block has no
+    /// room left for the transaction, the full block policy is applied: the block is sealed and
+    /// the transaction is executed again in the next block, or `Error::BlockFull` is returned.
+    pub(crate) fn execute_transaction(
+        &mut self,
+        transaction: &TransactionWithHash,
+        execute: impl Fn(
+            &mut Starknet,
+        )
+            -> DevnetResult<Result<TransactionExecutionInfo, TransactionExecutionError>>,
+    ) -> DevnetResult<()> {
+        let transaction_result = execute(self)?;
+        match self.handle_transaction_result(transaction, transaction_result) {
+            Err(Error::BlockFull) => match self.config.full_block_policy {
+                FullBlockPolicy::Seal => {
+                    self.generate_new_block_and_state()?;
+                    let transaction_result = execute(self)?;
+                    self.handle_transaction_result(transaction, transaction_result)
+                }
+                FullBlockPolicy::Reject => {
+                    self.rejected_transactions
+                        .insert(*transaction.get_transaction_hash(), Error::BlockFull.to_string());
+                    Err(Error::BlockFull)
+                }
+                // the transaction is kept by the mempool
+                FullBlockPolicy::Queue => Err(Error::BlockFull),
+            },
+            result => result,
+        }
+    }
+
     /// Handles transaction result either Ok or Error and updates the state accordingly.
     ///
     /// # Arguments
     ///
     /// * `transaction` - Transaction to be added in the collection of transactions.
-    /// * `contract_class` - Contract class to be added in the state cache. Only in declare
-    ///   transactions.
     /// * `transaction_result` - Result with transaction_execution_info
     pub(crate) fn handle_transaction_result(
         &mut self,
-        transaction: TransactionWithHash,
+        transaction: &TransactionWithHash,
         transaction_result: Result<
             TransactionExecutionInfo,
             blockifier::transaction::errors::TransactionExecutionError,
@@ -399,9 +638,18 @@ impl Starknet {
     ) -> DevnetResult<()> {
         let transaction_hash = *transaction.get_transaction_hash();
 
+        // regardless of the result, execution leaves the visited PCs in the pending state
+        let visited_pcs = std::mem::take(&mut self.pending_state.state.visited_pcs);
+        self.coverage.collect(visited_pcs);
+
         match transaction_result {
             Ok(tx_info) => {
-                self.handle_accepted_transaction(&transaction_hash, &transaction, tx_info)
+                if let Err(err) = self.check_l1_data_gas_bounds(transaction, &tx_info) {
+                    self.pending_state.discard_uncommitted();
+                    self.rejected_transactions.insert(transaction_hash, err.to_string());
+                    return Err(err);
+                }
+                self.handle_accepted_transaction(&transaction_hash, transaction, tx_info)
             }
             Err(tx_err) => {
                 /// utility to avoid duplication
@@ -421,8 +669,9 @@ impl Starknet {
                 }
 
                 // based on this https://community.starknet.io/t/efficient-utilization-of-sequencer-capacity-in-starknet-v0-12-1/95607#the-validation-phase-in-the-gateway-5
-                // we should not save transactions that failed with one of the following errors
-                match tx_err {
+                // we should not save transactions that failed with one of the following errors;
+                // only their rejection reason is kept
+                let result = match tx_err {
                     blockifier::transaction::errors::TransactionExecutionError::TransactionPreValidationError(
                         TransactionPreValidationError::InvalidNonce { .. }
                     ) => Err(TransactionValidationError::InvalidTransactionNonce.into()),
@@ -437,11 +686,37 @@ impl Starknet {
                         Err(TransactionValidationError::ValidationFailure { reason: tx_err.to_string() }.into())
                     }
                     _ => Err(tx_err.into())
+                };
+
+                if let Err(err) = &result {
+                    self.rejected_transactions.insert(transaction_hash, err.to_string());
                 }
+                result
             }
         }
     }
 
+    /// The L1 data gas bounds of V3 transactions, introduced in JSON-RPC 0.8, are unknown to
+    /// blockifier, so the consumed L1 data gas and its price are checked against them after
+    /// execution.
+    fn check_l1_data_gas_bounds(
+        &self,
+        transaction: &TransactionWithHash,
+        tx_info: &TransactionExecutionInfo,
+    ) -> DevnetResult<()> {
+        let Some(bounds) = transaction.transaction.get_l1_data_gas_bounds() else {
+            return Ok(());
+        };
+
+        let price = self.block_context.block_info().gas_prices.strk_l1_data_gas_price.get();
+        let consumed = tx_info.transaction_receipt.gas.l1_data_gas;
+        if bounds.max_price_per_unit < price || u128::from(bounds.max_amount) < consumed {
+            return Err(TransactionValidationError::InsufficientMaxFee.into());
+        }
+
+        Ok(())
+    }
+
     /// Handles succeeded and reverted transactions. The tx is stored and potentially dumped. A new
     /// block is generated in block-generation-on-transaction mode.
     pub(crate) fn handle_accepted_transaction(
@@ -450,6 +725,14 @@ impl Starknet {
         transaction: &TransactionWithHash,
         tx_info: TransactionExecutionInfo,
     ) -> DevnetResult<()> {
+        if let Err(err) = self.add_to_pending_block_weights(&tx_info) {
+            self.pending_state.discard_uncommitted();
+            if !matches!(err, Error::BlockFull) {
+                self.rejected_transactions.insert(*transaction_hash, err.to_string());
+            }
+            return Err(err);
+        }
+
         let state_diff = self.commit_diff()?;
 
         let trace = create_trace(
@@ -462,8 +745,9 @@ impl Starknet {
 
         // add accepted transaction to pending block
         self.blocks.pending_block.add_transaction(*transaction_hash);
+        self.rejected_transactions.remove(transaction_hash);
 
-        self.transactions.insert(transaction_hash, transaction_to_add);
+        self.transactions.insert(transaction_hash, transaction_to_add)?;
 
         // create new block from pending one, only in block-generation-on-transaction mode
         if self.config.block_generation_on == BlockGenerationOn::Transaction {
@@ -473,6 +757,40 @@ impl Starknet {
         Ok(())
     }
 
+    /// Adds the weights of the executed, not yet committed transaction to those of the pending
+    /// block, if they fit into the block capacity. Each transaction is weighed on its own, so
+    /// storage entries and classes used by several transactions of a block are counted for each.
+    /// Transactions are not weighed if the block capacity is not limited.
+    fn add_to_pending_block_weights(
+        &mut self,
+        tx_info: &TransactionExecutionInfo,
+    ) -> DevnetResult<()> {
+        let Some(block_capacity) = self.config.block_capacity else {
+            return Ok(());
+        };
+
+        let state = &mut self.pending_state.state;
+        let state_changes_keys = state.get_actual_state_changes()?.into_keys();
+        let execution_summary = tx_info.summarize();
+        let tx_weights = get_tx_weights(
+            state,
+            &execution_summary.executed_class_hashes,
+            execution_summary.visited_storage_entries.len(),
+            &tx_info.transaction_receipt.resources,
+            &state_changes_keys,
+        )?;
+
+        if !block_capacity.has_room(tx_weights) {
+            return Err(TransactionExecutionError::TransactionTooLarge.into());
+        }
+        if !block_capacity.has_room(self.pending_block_weights + tx_weights) {
+            return Err(Error::BlockFull);
+        }
+
+        self.pending_block_weights += tx_weights;
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn init_block_context(
         gas_price_wei: NonZeroU128,
@@ -483,6 +801,7 @@ impl Starknet {
         strk_fee_token_address: Felt,
         chain_id: ChainId,
         block_number: u64,
+        versioned_constants: VersionedConstants,
     ) -> BlockContext {
         use starknet_api::core::{ContractAddress, PatriciaKey};
         use starknet_api::{contract_address, patricia_key};
@@ -499,7 +818,8 @@ impl Starknet {
                 eth_l1_data_gas_price: data_gas_price_wei,
                 strk_l1_data_gas_price: data_gas_price_fri,
             },
-            use_kzg_da: USE_KZG_DA,
+            // blob DA unless changed with set_block_context_l1_da_mode
+            use_kzg_da: true,
         };
 
         let chain_info = ChainInfo {
@@ -514,12 +834,7 @@ impl Starknet {
             },
         };
 
-        BlockContext::new(
-            block_info,
-            chain_info,
-            get_versioned_constants(),
-            custom_bouncer_config(),
-        )
+        BlockContext::new(block_info, chain_info, versioned_constants, custom_bouncer_config())
     }
 
     /// Update block context block_number with the next one
@@ -532,7 +847,7 @@ impl Starknet {
         *block_context = BlockContext::new(
             block_info,
             block_context.chain_info().clone(),
-            get_versioned_constants(),
+            block_context.versioned_constants().clone(),
             custom_bouncer_config(),
         );
     }
@@ -550,7 +865,23 @@ impl Starknet {
         *block_context = BlockContext::new(
             block_info,
             block_context.chain_info().clone(),
-            get_versioned_constants(),
+            block_context.versioned_constants().clone(),
+            custom_bouncer_config(),
+        );
+    }
+
+    fn set_block_context_l1_da_mode(
+        block_context: &mut BlockContext,
+        l1_da_mode: L1DataAvailabilityMode,
+    ) {
+        let mut block_info = block_context.block_info().clone();
+        block_info.use_kzg_da = l1_da_mode == L1DataAvailabilityMode::Blob;
+
+        // TODO: update block_context via preferred method in the documentation
+        *block_context = BlockContext::new(
+            block_info,
+            block_context.chain_info().clone(),
+            block_context.versioned_constants().clone(),
             custom_bouncer_config(),
         );
     }
@@ -566,7 +897,7 @@ impl Starknet {
         *block_context = BlockContext::new(
             block_info,
             block_context.chain_info().clone(),
-            get_versioned_constants(),
+            block_context.versioned_constants().clone(),
             custom_bouncer_config(),
         );
     }
@@ -580,6 +911,8 @@ impl Starknet {
         let mut block = StarknetBlock::create_pending_block();
 
         block.header.block_number = self.block_context.block_info().block_number;
+        block.header.starknet_version =
+            ApiStarknetVersion(self.config.starknet_version.to_string());
         block.header.l1_gas_price = GasPricePerToken {
             price_in_fri: GasPrice(
                 self.block_context.block_info().gas_prices.strk_l1_gas_price.get(),
@@ -598,10 +931,16 @@ impl Starknet {
         };
         block.header.sequencer =
             SequencerContractAddress(self.block_context.block_info().sequencer_address);
+        block.header.l1_da_mode = if self.block_context.block_info().use_kzg_da {
+            L1DataAvailabilityMode::Blob
+        } else {
+            L1DataAvailabilityMode::Calldata
+        };
 
         block.set_timestamp(self.block_context.block_info().block_timestamp);
 
         self.blocks.pending_block = block;
+        self.pending_block_weights = BouncerWeights::default();
 
         Ok(())
     }
@@ -625,9 +964,7 @@ impl Starknet {
                 }
 
                 let state = self
-                    .blocks
-                    .hash_to_state
-                    .get_mut(&block_hash)
+                    .get_mut_archived_state(&block_hash)?
                     .ok_or(Error::NoStateAtBlock { block_id: *block_id })?;
                 Ok(state)
             }
@@ -650,6 +987,14 @@ impl Starknet {
         get_class_impls::get_class_impl(self, block_id, class_hash)
     }
 
+    pub fn get_compiled_casm(
+        &self,
+        block_id: &BlockId,
+        class_hash: ClassHash,
+    ) -> DevnetResult<CasmContractClass> {
+        get_class_impls::get_compiled_casm_impl(self, block_id, class_hash)
+    }
+
     pub fn get_class_at(
         &mut self,
         block_id: &BlockId,
@@ -691,13 +1036,12 @@ impl Starknet {
             )?;
 
         let mut transactional_state = CachedState::create_transactional(&mut state.state);
-        let res = call.execute(
-            &mut transactional_state,
-            &mut Default::default(),
-            &mut execution_context,
-        )?;
+        let res =
+            call.execute(&mut transactional_state, &mut Default::default(), &mut execution_context);
+        let visited_pcs = transactional_state.visited_pcs;
+        self.coverage.collect(visited_pcs);
 
-        Ok(res.execution.retdata.0)
+        Ok(res?.execution.retdata.0)
     }
 
     pub fn estimate_fee(
@@ -727,6 +1071,14 @@ impl Starknet {
         &mut self,
         declare_transaction: BroadcastedDeclareTransaction,
     ) -> DevnetResult<(TransactionHash, ClassHash)> {
+        if self.config.mempool.is_some() {
+            let transaction = BroadcastedTransaction::Declare(declare_transaction);
+            return match self.add_to_mempool(transaction)? {
+                AccountTransaction::Declare(tx) => Ok((tx.tx_hash().0, tx.class_hash().0)),
+                _ => Err(Error::UnsupportedTransactionType),
+            };
+        }
+
         add_declare_transaction::add_declare_transaction(self, declare_transaction)
     }
 
@@ -739,6 +1091,16 @@ impl Starknet {
         &mut self,
         deploy_account_transaction: BroadcastedDeployAccountTransaction,
     ) -> DevnetResult<(TransactionHash, ContractAddress)> {
+        if self.config.mempool.is_some() {
+            let transaction = BroadcastedTransaction::DeployAccount(deploy_account_transaction);
+            return match self.add_to_mempool(transaction)? {
+                AccountTransaction::DeployAccount(tx) => {
+                    Ok((tx.tx_hash.0, tx.contract_address.into()))
+                }
+                _ => Err(Error::UnsupportedTransactionType),
+            };
+        }
+
         add_deploy_account_transaction::add_deploy_account_transaction(
             self,
             deploy_account_transaction,
@@ -749,6 +1111,14 @@ impl Starknet {
         &mut self,
         invoke_transaction: BroadcastedInvokeTransaction,
     ) -> DevnetResult<TransactionHash> {
+        if self.config.mempool.is_some() {
+            let transaction = BroadcastedTransaction::Invoke(invoke_transaction);
+            return match self.add_to_mempool(transaction)? {
+                AccountTransaction::Invoke(tx) => Ok(tx.tx_hash.0),
+                _ => Err(Error::UnsupportedTransactionType),
+            };
+        }
+
         add_invoke_transaction::add_invoke_transaction(self, invoke_transaction)
     }
 
@@ -809,11 +1179,14 @@ impl Starknet {
             },
         };
 
-        // apply the invoke tx
-        add_invoke_transaction::add_invoke_transaction(
+        // apply the invoke tx; minting is not subject to forced reverts
+        let transactions_to_revert = std::mem::take(&mut self.transactions_to_revert);
+        let mint_result = add_invoke_transaction::add_invoke_transaction(
             self,
             BroadcastedInvokeTransaction::V1(invoke_tx),
-        )
+        );
+        self.transactions_to_revert = transactions_to_revert;
+        mint_result
     }
 
     pub fn block_state_update(&self, block_id: &BlockId) -> DevnetResult<StateUpdateResult> {
@@ -836,6 +1209,22 @@ impl Starknet {
         }
     }
 
+    pub fn get_storage_proof(
+        &self,
+        block_id: &BlockId,
+        class_hashes: &[Felt],
+        contract_addresses: &[ContractAddress],
+        contracts_storage_keys: &[ContractStorageKeys],
+    ) -> DevnetResult<StorageProof> {
+        storage_proof::storage_proof_by_block_id(
+            self,
+            block_id,
+            class_hashes,
+            contract_addresses,
+            contracts_storage_keys,
+        )
+    }
+
     pub fn set_next_block_gas(
         &mut self,
         gas_prices: GasModificationRequest,
@@ -851,6 +1240,21 @@ impl Starknet {
         Ok(self.next_block_gas.clone())
     }
 
+    /// Sets the data availability mode of the next block, and of the pending block if a new block
+    /// is generated.
+    pub fn set_next_block_l1_da_mode(
+        &mut self,
+        request: DataAvailabilityModeRequest,
+    ) -> DevnetResult<L1DataAvailabilityMode> {
+        self.next_block_l1_da_mode = request.l1_da_mode;
+
+        if let Some(true) = request.generate_block {
+            self.create_block()?
+        }
+
+        Ok(self.next_block_l1_da_mode)
+    }
+
     pub fn abort_blocks(&mut self, mut starting_block_id: BlockId) -> DevnetResult<Vec<Felt>> {
         if self.config.state_archive != StateArchiveCapacity::Full {
             let msg = "The abort blocks feature requires state-archive-capacity set to full.";
@@ -862,48 +1266,74 @@ impl Starknet {
             starting_block_id = BlockId::Tag(BlockTag::Latest);
         }
 
-        let starting_block_hash = match self.blocks.get_by_block_id(&starting_block_id) {
-            Some(block) => block.block_hash(),
-            None => return Err(Error::NoBlock),
-        };
+        let (starting_block_hash, starting_block_number, starting_block_status) =
+            match self.blocks.get_by_block_id(&starting_block_id)? {
+                Some(block) => (block.block_hash(), block.block_number().0, block.status),
+                None => return Err(Error::NoBlock),
+            };
 
         if self.blocks.aborted_blocks.contains(&starting_block_hash) {
             return Err(Error::UnsupportedAction { msg: "Block is already aborted".into() });
         }
 
+        if starting_block_status == BlockStatus::AcceptedOnL1 {
+            return Err(Error::UnsupportedAction {
+                msg: "Block is accepted on L1 and can't be aborted".into(),
+            });
+        }
+
         let genesis_block = self
             .blocks
-            .get_by_block_id(&BlockId::Number(self.blocks.starting_block_number))
+            .get_by_block_id(&BlockId::Number(self.blocks.starting_block_number))?
             .ok_or(Error::UnsupportedAction { msg: "Cannot abort - no genesis block".into() })?;
 
         if starting_block_hash == genesis_block.block_hash() {
             return Err(Error::UnsupportedAction { msg: "Genesis block can't be aborted".into() });
         }
 
+        // the aborted blocks are replaced by the pending block
+        let old_block_hash = self.old_block_hash(starting_block_number)?;
+
         let mut next_block_to_abort_hash = self
             .blocks
             .last_block_hash
             .ok_or(Error::UnsupportedAction { msg: "No blocks to abort".into() })?;
         let mut reached_starting_block = false;
         let mut aborted: Vec<Felt> = Vec::new();
+        // changes of the aborted blocks, from the latest; only needed when using a database
+        let mut aborted_diffs: Vec<StateDiff> = Vec::new();
 
         let mut rpc_contract_classes = self.rpc_contract_classes.write();
 
         // Abort blocks from latest to starting (iterating backwards) and revert transactions.
         while !reached_starting_block {
             reached_starting_block = next_block_to_abort_hash == starting_block_hash;
-            let block_to_abort = self.blocks.hash_to_block.get_mut(&next_block_to_abort_hash);
+            let block_to_abort = self.blocks.get_by_hash(next_block_to_abort_hash)?;
 
-            if let Some(block) = block_to_abort {
-                block.status = BlockStatus::Rejected;
-                self.blocks.num_to_hash.shift_remove(&block.block_number());
+            if let Some(block) = block_to_abort.map(Cow::into_owned) {
+                self.blocks.update(&block.block_hash(), |block| {
+                    block.status = BlockStatus::Rejected;
+                })?;
+                self.blocks.remove_block_number(block.block_number());
 
                 // Revert transactions
                 for tx_hash in block.get_transactions() {
-                    let tx =
-                        self.transactions.get_by_hash_mut(tx_hash).ok_or(Error::NoTransaction)?;
-                    tx.execution_result =
-                        ExecutionResult::Reverted { reason: "Block aborted manually".to_string() };
+                    self.transactions.update(tx_hash, |tx| {
+                        tx.execution_result = ExecutionResult::Reverted {
+                            reason: "Block aborted manually".to_string(),
+                        };
+                    })?;
+                }
+
+                if self.database.is_some() {
+                    let state_diff = self
+                        .blocks
+                        .get_state_diff(&block.block_hash())?
+                        .ok_or(Error::NoBlock)?
+                        .into_owned();
+                    self.remove_stored_block(block.block_number().0, &state_diff);
+                    self.blocks.hash_to_state.pop(&block.block_hash());
+                    aborted_diffs.push(state_diff);
                 }
 
                 rpc_contract_classes.remove_classes_at(block.block_number().0);
@@ -919,26 +1349,82 @@ impl Starknet {
         // starting block is reached in while loop.
         if reached_starting_block {
             let current_block =
-                self.blocks.hash_to_block.get(&last_reached_block_hash).ok_or(Error::NoBlock)?;
+                self.blocks.get_by_hash(last_reached_block_hash)?.ok_or(Error::NoBlock)?;
+            let current_block_number = current_block.block_number().0;
             self.blocks.last_block_hash = Some(current_block.block_hash());
 
-            let reverted_state = self.blocks.hash_to_state.get(&current_block.block_hash()).ok_or(
-                Error::NoStateAtBlock { block_id: BlockId::Number(current_block.block_number().0) },
-            )?;
+            let reverted_state = match &self.database {
+                // the pending changes are reverted as well
+                Some(database) => {
+                    aborted_diffs.insert(0, self.pending_state_diff.clone());
+                    self.revert_state_from_history(database, current_block_number, &aborted_diffs)?
+                }
+                None => self
+                    .blocks
+                    .hash_to_state
+                    .peek(&last_reached_block_hash)
+                    .ok_or(Error::NoStateAtBlock {
+                        block_id: BlockId::Number(current_block_number),
+                    })?
+                    .clone_historic(),
+            };
 
             // in the abort block scenario, we need to revert state and pending_state to be able to
             // use the calls properly
             self.latest_state = reverted_state.clone_historic();
-            self.pending_state = reverted_state.clone_historic();
+            self.pending_state = reverted_state;
+            self.state_tries = None;
         }
 
         self.pending_state_diff = StateDiff::default();
         rpc_contract_classes.empty_staging();
+        drop(rpc_contract_classes);
         self.blocks.aborted_blocks = aborted.clone();
 
+        // the pending state was reverted to the state of a block preceding the pending one
+        self.store_old_block_hash(old_block_hash)?;
+
         Ok(aborted)
     }
 
+    /// Marks the block identified by `block_id` and all blocks before it as accepted on L1,
+    /// together with their transactions. Returns the hashes of the newly accepted blocks, ordered
+    /// from the oldest to the newest.
+    pub fn accept_on_l1(&mut self, block_id: &BlockId) -> DevnetResult<Vec<BlockHash>> {
+        if block_id == &BlockId::Tag(BlockTag::Pending) {
+            return Err(Error::UnsupportedAction {
+                msg: "Pending block can't be accepted on L1".into(),
+            });
+        }
+
+        let mut next_block_hash =
+            self.blocks.get_by_block_id(block_id)?.ok_or(Error::NoBlock)?.block_hash();
+        let mut accepted = vec![];
+
+        // iterate backwards until reaching a block accepted on L1 or the first block
+        while let Some(block) = self.blocks.get_by_hash(next_block_hash)?.map(Cow::into_owned) {
+            if block.status != BlockStatus::AcceptedOnL2 {
+                break;
+            }
+
+            self.blocks.update(&block.block_hash(), |block| {
+                block.status = BlockStatus::AcceptedOnL1;
+            })?;
+            for tx_hash in block.get_transactions() {
+                self.transactions.update(tx_hash, |tx| {
+                    tx.finality_status = TransactionFinalityStatus::AcceptedOnL1;
+                })?;
+            }
+
+            accepted.push(block.block_hash());
+            next_block_hash = block.parent_hash();
+        }
+
+        accepted.reverse();
+
+        Ok(accepted)
+    }
+
     pub fn get_block_txs_count(&self, block_id: &BlockId) -> DevnetResult<u64> {
         let block = self.get_block(block_id)?;
         Ok(block.get_transactions().len() as u64)
@@ -966,8 +1452,8 @@ impl Starknet {
         Ok(state.get_storage_at(contract_address.try_into()?, storage_key.try_into()?)?)
     }
 
-    pub fn get_block(&self, block_id: &BlockId) -> DevnetResult<&StarknetBlock> {
-        self.blocks.get_by_block_id(block_id).ok_or(Error::NoBlock)
+    pub fn get_block(&self, block_id: &BlockId) -> DevnetResult<Cow<'_, StarknetBlock>> {
+        self.blocks.get_by_block_id(block_id)?.ok_or(Error::NoBlock)
     }
 
     pub fn get_block_with_transactions(&self, block_id: &BlockId) -> DevnetResult<BlockResult> {
@@ -977,7 +1463,7 @@ impl Starknet {
             .iter()
             .map(|transaction_hash| {
                 self.transactions
-                    .get_by_hash(*transaction_hash)
+                    .get(transaction_hash)?
                     .ok_or(Error::NoTransaction)
                     .map(|transaction| transaction.inner.clone())
             })
@@ -985,13 +1471,13 @@ impl Starknet {
 
         if block.status() == &BlockStatus::Pending {
             Ok(BlockResult::PendingBlock(PendingBlock {
-                header: PendingBlockHeader::from(block),
+                header: PendingBlockHeader::from(block.as_ref()),
                 transactions: Transactions::Full(transactions),
             }))
         } else {
             Ok(BlockResult::Block(Block {
                 status: *block.status(),
-                header: BlockHeader::from(block),
+                header: BlockHeader::from(block.as_ref()),
                 transactions: Transactions::Full(transactions),
             }))
         }
@@ -1003,7 +1489,7 @@ impl Starknet {
 
         for transaction_hash in block.get_transactions() {
             let sn_transaction =
-                self.transactions.get_by_hash(*transaction_hash).ok_or(Error::NoTransaction)?;
+                self.transactions.get(transaction_hash)?.ok_or(Error::NoTransaction)?;
 
             let transaction = sn_transaction.inner.clone();
             let mut receipt = sn_transaction.get_receipt()?;
@@ -1029,13 +1515,13 @@ impl Starknet {
 
         if block.status() == &BlockStatus::Pending {
             Ok(BlockResult::PendingBlock(PendingBlock {
-                header: PendingBlockHeader::from(block),
+                header: PendingBlockHeader::from(block.as_ref()),
                 transactions: Transactions::FullWithReceipts(transaction_receipts),
             }))
         } else {
             Ok(BlockResult::Block(Block {
                 status: *block.status(),
-                header: BlockHeader::from(block),
+                header: BlockHeader::from(block.as_ref()),
                 transactions: Transactions::FullWithReceipts(transaction_receipts),
             }))
         }
@@ -1045,7 +1531,7 @@ impl Starknet {
         &self,
         block_id: &BlockId,
         index: u64,
-    ) -> DevnetResult<&TransactionWithHash> {
+    ) -> DevnetResult<TransactionWithHash> {
         let block = self.get_block(block_id)?;
         let transaction_hash = block
             .get_transactions()
@@ -1055,22 +1541,28 @@ impl Starknet {
         self.get_transaction_by_hash(*transaction_hash)
     }
 
+    /// Returns the number of the first block of the chain, which follows the forking block in
+    /// forking mode
+    pub fn genesis_block_number(&self) -> u64 {
+        self.blocks.starting_block_number
+    }
+
     pub fn get_latest_block(&self) -> DevnetResult<StarknetBlock> {
         let block = self
             .blocks
-            .get_by_block_id(&BlockId::Tag(starknet_rs_core::types::BlockTag::Latest))
+            .get_by_block_id(&BlockId::Tag(starknet_rs_core::types::BlockTag::Latest))?
             .ok_or(crate::error::Error::NoBlock)?;
 
-        Ok(block.clone())
+        Ok(block.into_owned())
     }
 
     pub fn get_transaction_by_hash(
         &self,
         transaction_hash: Felt,
-    ) -> DevnetResult<&TransactionWithHash> {
+    ) -> DevnetResult<TransactionWithHash> {
         self.transactions
-            .get_by_hash(transaction_hash)
-            .map(|starknet_transaction| &starknet_transaction.inner)
+            .get(&transaction_hash)?
+            .map(|starknet_transaction| starknet_transaction.into_owned().inner)
             .ok_or(Error::NoTransaction)
     }
 
@@ -1091,7 +1583,7 @@ impl Starknet {
         transaction_hash: &TransactionHash,
     ) -> DevnetResult<TransactionReceipt> {
         let transaction_to_map =
-            self.transactions.get(transaction_hash).ok_or(Error::NoTransaction)?;
+            self.transactions.get(transaction_hash)?.ok_or(Error::NoTransaction)?;
 
         transaction_to_map.get_receipt()
     }
@@ -1100,7 +1592,7 @@ impl Starknet {
         &self,
         transaction_hash: TransactionHash,
     ) -> DevnetResult<TransactionTrace> {
-        let tx = self.transactions.get(&transaction_hash).ok_or(Error::NoTransaction)?;
+        let tx = self.transactions.get(&transaction_hash)?.ok_or(Error::NoTransaction)?;
         tx.get_trace().ok_or(Error::NoTransactionTrace)
     }
 
@@ -1132,11 +1624,40 @@ impl Starknet {
         &self,
         transaction_hash: TransactionHash,
     ) -> DevnetResult<(TransactionExecutionStatus, TransactionFinalityStatus)> {
-        let transaction = self.transactions.get(&transaction_hash).ok_or(Error::NoTransaction)?;
+        let transaction = self.transactions.get(&transaction_hash)?.ok_or(Error::NoTransaction)?;
 
         Ok((transaction.execution_result.status(), transaction.finality_status))
     }
 
+    /// Makes the next `count` invoke transactions revert after validation, as if their execution
+    /// failed. Their fee is charged and the nonce of their sender is incremented.
+    pub fn set_transactions_to_revert(&mut self, count: u64) {
+        self.transactions_to_revert = count;
+    }
+
+    /// Returns the number of the next invoke transactions which are going to be reverted
+    pub fn transactions_to_revert(&self) -> u64 {
+        self.transactions_to_revert
+    }
+
+    /// Returns the reason of rejecting the transaction, or `None` if it wasn't rejected
+    pub fn get_transaction_rejection_reason(
+        &self,
+        transaction_hash: &TransactionHash,
+    ) -> Option<&str> {
+        self.rejected_transactions.get(transaction_hash).map(String::as_str)
+    }
+
+    /// Returns the reason of reverting the transaction, or `None` if it succeeded
+    pub fn get_transaction_revert_reason(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> DevnetResult<Option<String>> {
+        let transaction = self.transactions.get(&transaction_hash)?.ok_or(Error::NoTransaction)?;
+
+        Ok(transaction.execution_result.revert_reason().map(String::from))
+    }
+
     pub fn simulate_transactions(
         &mut self,
         block_id: &BlockId,
@@ -1203,6 +1724,8 @@ impl Starknet {
             )?;
             transactions_traces.push(trace);
         }
+        let visited_pcs = transactional_state.visited_pcs;
+        self.coverage.collect(visited_pcs);
 
         let estimated = estimations::estimate_fee(
             self,
@@ -1236,8 +1759,9 @@ impl Starknet {
         Ok(simulation_results)
     }
 
-    /// create new block from pending one
+    /// create new block from pending one, after executing the ready transactions of the mempool
     pub fn create_block(&mut self) -> DevnetResult<(), Error> {
+        self.execute_mempool()?;
         self.generate_new_block_and_state()?;
         Ok(())
     }
@@ -1329,6 +1853,72 @@ impl Starknet {
         Ok(())
     }
 
+    /// Writes directly to the storage of a contract, without executing a transaction
+    ///
+    /// # Arguments
+    /// * `contract_address` - Address of the contract whose storage is modified
+    /// * `key` - Storage key
+    /// * `value` - New storage value
+    pub fn set_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: PatriciaKey,
+        value: Felt,
+    ) -> DevnetResult<()> {
+        self.pending_state.set_storage_at(contract_address.try_into()?, key.try_into()?, value)?;
+        self.commit_state_modification()
+    }
+
+    /// Sets the nonce of a contract, without executing a transaction
+    ///
+    /// # Arguments
+    /// * `contract_address` - Address of the contract whose nonce is set
+    /// * `nonce` - New nonce
+    pub fn set_nonce(
+        &mut self,
+        contract_address: ContractAddress,
+        nonce: Felt,
+    ) -> DevnetResult<()> {
+        self.pending_state.set_nonce(contract_address.try_into()?, nonce)?;
+        self.commit_state_modification()
+    }
+
+    /// Links an address to a declared class, without executing a transaction. If the address is
+    /// not occupied, this deploys a contract without running its constructor; otherwise, the class
+    /// of the existing contract is replaced.
+    ///
+    /// # Arguments
+    /// * `contract_address` - Address of the contract whose class hash is set
+    /// * `class_hash` - Hash of an already declared class
+    pub fn set_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> DevnetResult<()> {
+        if !self.pending_state.is_contract_declared(class_hash) {
+            return Err(StateError::NoneClassHash(class_hash).into());
+        }
+
+        let core_address = contract_address.try_into()?;
+        // the initial value has to be cached for the write to be included in the state diff
+        self.pending_state.get_class_hash_at(core_address)?;
+        self.pending_state
+            .set_class_hash_at(core_address, starknet_api::core::ClassHash(class_hash))?;
+        self.commit_state_modification()
+    }
+
+    /// Records a direct state modification in the pending block, as if it was done by a
+    /// transaction. A new block is generated in block-generation-on-transaction mode.
+    fn commit_state_modification(&mut self) -> DevnetResult<()> {
+        self.commit_diff()?;
+
+        if self.config.block_generation_on == BlockGenerationOn::Transaction {
+            self.generate_new_block_and_state()?;
+        }
+
+        Ok(())
+    }
+
     /// Returns true if the account is not part of the state and is impersonated
     ///
     /// # Arguments
@@ -1387,28 +1977,38 @@ mod tests {
     use std::thread;
     use std::time::Duration;
 
+    use blockifier::abi::constants::BLOCK_HASH_CONTRACT_ADDRESS;
     use blockifier::execution::errors::{EntryPointExecutionError, PreExecutionError};
     use blockifier::state::state_api::{State, StateReader};
+    use blockifier::versioned_constants::VersionedConstants;
     use nonzero_ext::nonzero;
     use starknet_api::block::{BlockHash, BlockNumber, BlockStatus, BlockTimestamp, GasPrice};
     use starknet_api::core::EntryPointSelector;
+    use starknet_api::data_availability::L1DataAvailabilityMode;
     use starknet_rs_core::types::{BlockId, BlockTag, Felt};
     use starknet_rs_core::utils::get_selector_from_name;
     use starknet_types::contract_address::ContractAddress;
     use starknet_types::felt::felt_from_prefixed_hex;
-    use starknet_types::rpc::state::Balance;
+    use starknet_types::patricia_key::PatriciaKey;
+    use starknet_types::rpc::gas_modification::{
+        DataAvailabilityModeRequest, GasModificationRequest,
+    };
+    use starknet_types::rpc::state::{Balance, ContractNonce, StorageDiff, StorageEntry};
     use starknet_types::traits::HashProducer;
 
+    use super::gas_price_schedule::GasPriceSchedule;
     use super::Starknet;
     use crate::account::{Account, FeeToken};
     use crate::blocks::StarknetBlock;
     use crate::constants::{
-        DEVNET_DEFAULT_CHAIN_ID, DEVNET_DEFAULT_INITIAL_BALANCE,
+        CAIRO_0_ACCOUNT_CONTRACT_HASH, DEVNET_DEFAULT_CHAIN_ID, DEVNET_DEFAULT_INITIAL_BALANCE,
         DEVNET_DEFAULT_STARTING_BLOCK_NUMBER, ETH_ERC20_CONTRACT_ADDRESS,
         STRK_ERC20_CONTRACT_ADDRESS,
     };
-    use crate::error::{DevnetResult, Error};
-    use crate::starknet::starknet_config::{StarknetConfig, StateArchiveCapacity};
+    use crate::error::{DevnetResult, Error, StateError};
+    use crate::starknet::starknet_config::{
+        L1DaMode, StarknetConfig, StarknetVersion, StateArchiveCapacity,
+    };
     use crate::traits::{Accounted, Deployed, HashIdentified};
     use crate::utils::test_utils::{
         cairo_0_account_without_validations, dummy_contract_address, dummy_declare_transaction_v1,
@@ -1492,6 +2092,7 @@ mod tests {
             STRK_ERC20_CONTRACT_ADDRESS,
             DEVNET_DEFAULT_CHAIN_ID,
             DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
+            VersionedConstants::create_for_testing(),
         );
         assert_eq!(block_ctx.block_info().block_number, BlockNumber(0));
         assert_eq!(block_ctx.block_info().block_timestamp, BlockTimestamp(0));
@@ -1536,12 +2137,88 @@ mod tests {
 
         // get latest block and check that the transactions in the block are correct
         let added_block =
-            starknet.blocks.get_by_hash(starknet.blocks.last_block_hash.unwrap()).unwrap();
+            starknet.blocks.get_by_hash(starknet.blocks.last_block_hash.unwrap()).unwrap().unwrap();
 
         assert!(added_block.get_transactions().len() == 1);
         assert_eq!(*added_block.get_transactions().first().unwrap(), *tx.get_transaction_hash());
     }
 
+    #[test]
+    fn blocks_executed_with_configured_starknet_version() {
+        let starknet_version = StarknetVersion::V0_13_0;
+        let config = StarknetConfig {
+            starknet_version,
+            versioned_constants: starknet_version.versioned_constants(),
+            ..Default::default()
+        };
+        let mut starknet = Starknet::new(&config).unwrap();
+        starknet.create_block().unwrap();
+
+        let latest_block = starknet.get_latest_block().unwrap();
+        assert_eq!(latest_block.header.starknet_version.0, "0.13.0");
+        assert_eq!(starknet.pending_block().header.starknet_version.0, "0.13.0");
+        assert_eq!(starknet.block_context.versioned_constants().invoke_tx_max_n_steps, 3_000_000);
+    }
+
+    #[test]
+    fn data_availability_mode_applied_from_next_block() {
+        let config = StarknetConfig { l1_da_mode: L1DaMode::Calldata, ..Default::default() };
+        let mut starknet = Starknet::new(&config).unwrap();
+        assert!(!starknet.block_context.block_info().use_kzg_da);
+        assert_eq!(starknet.pending_block().header.l1_da_mode, L1DataAvailabilityMode::Calldata);
+
+        let l1_da_mode = starknet
+            .set_next_block_l1_da_mode(DataAvailabilityModeRequest {
+                l1_da_mode: L1DataAvailabilityMode::Blob,
+                generate_block: Some(true),
+            })
+            .unwrap();
+        assert_eq!(l1_da_mode, L1DataAvailabilityMode::Blob);
+
+        let latest_block = starknet.get_latest_block().unwrap();
+        assert_eq!(latest_block.header.l1_da_mode, L1DataAvailabilityMode::Calldata);
+        assert_eq!(starknet.pending_block().header.l1_da_mode, L1DataAvailabilityMode::Blob);
+        assert!(starknet.block_context.block_info().use_kzg_da);
+    }
+
+    #[test]
+    fn gas_price_schedule_applied_after_genesis_block() {
+        let series_entry = |gas_price_wei| GasModificationRequest {
+            gas_price_wei: Some(gas_price_wei),
+            data_gas_price_wei: None,
+            gas_price_fri: None,
+            data_gas_price_fri: None,
+            generate_block: None,
+        };
+        let config = StarknetConfig {
+            gas_price_wei: nonzero!(1u128),
+            gas_price_schedule: Some(GasPriceSchedule::Series(vec![
+                series_entry(nonzero!(10u128)),
+                series_entry(nonzero!(20u128)),
+                series_entry(nonzero!(30u128)),
+            ])),
+            ..Default::default()
+        };
+        let mut starknet = Starknet::new(&config).unwrap();
+        let gas_price_wei_of = |block: &StarknetBlock| block.header.l1_gas_price.price_in_wei.0;
+
+        assert_eq!(gas_price_wei_of(&starknet.get_latest_block().unwrap()), 1);
+        assert_eq!(gas_price_wei_of(starknet.pending_block()), 10);
+
+        // the price set by the user replaces the scheduled price of a single block
+        starknet
+            .set_next_block_gas(GasModificationRequest {
+                generate_block: Some(true),
+                ..series_entry(nonzero!(5u128))
+            })
+            .unwrap();
+        assert_eq!(gas_price_wei_of(starknet.pending_block()), 5);
+
+        starknet.create_block().unwrap();
+        assert_eq!(gas_price_wei_of(starknet.pending_block()), 30);
+        assert_eq!(starknet.block_context.block_info().gas_prices.eth_l1_gas_price.get(), 30);
+    }
+
     #[test]
     fn successful_emptying_of_pending_block() {
         let config = StarknetConfig { start_time: Some(0), ..Default::default() };
@@ -1606,6 +2283,7 @@ mod tests {
             STRK_ERC20_CONTRACT_ADDRESS,
             DEVNET_DEFAULT_CHAIN_ID,
             DEVNET_DEFAULT_STARTING_BLOCK_NUMBER,
+            VersionedConstants::create_for_testing(),
         );
         let initial_block_number = block_ctx.block_info().block_number;
         Starknet::advance_block_context_block_number(&mut block_ctx);
@@ -1647,7 +2325,7 @@ mod tests {
         let mut starknet = Starknet::new(&config).unwrap();
         let genesis_block_hash = starknet.get_latest_block().unwrap();
         let block_hash = starknet.generate_new_block_and_state().unwrap();
-        starknet.blocks.hash_to_state.remove(&block_hash);
+        starknet.blocks.hash_to_state.pop(&block_hash);
         starknet.blocks.last_block_hash = Some(genesis_block_hash.block_hash());
 
         match starknet.get_mut_state_at(&BlockId::Number(1)) {
@@ -1753,7 +2431,7 @@ mod tests {
 
         // last added block number -> 0
         let added_block =
-            starknet.blocks.get_by_hash(starknet.blocks.last_block_hash.unwrap()).unwrap();
+            starknet.blocks.get_by_hash(starknet.blocks.last_block_hash.unwrap()).unwrap().unwrap();
         // number of the accepted block -> 1
         let block_number = starknet.get_latest_block().unwrap().block_number();
 
@@ -1762,7 +2440,7 @@ mod tests {
         starknet.generate_new_block_and_state().unwrap();
 
         let added_block2 =
-            starknet.blocks.get_by_hash(starknet.blocks.last_block_hash.unwrap()).unwrap();
+            starknet.blocks.get_by_hash(starknet.blocks.last_block_hash.unwrap()).unwrap().unwrap();
         let block_number2 = starknet.get_latest_block().unwrap().block_number();
 
         assert_eq!(block_number2.0, added_block2.header.block_number.0);
@@ -1924,4 +2602,165 @@ mod tests {
             unexpected => panic!("Got unexpected response: {unexpected:?}"),
         }
     }
+
+    #[test]
+    fn accepting_on_l1_marks_block_and_its_predecessors() {
+        let mut starknet = Starknet::new(&StarknetConfig {
+            state_archive: StateArchiveCapacity::Full,
+            ..Default::default()
+        })
+        .unwrap();
+        for _ in 0..3 {
+            starknet.create_block().unwrap();
+        }
+        let block_hash_at = |starknet: &Starknet, number: u64| {
+            starknet.get_block(&BlockId::Number(number)).unwrap().block_hash()
+        };
+
+        let accepted = starknet.accept_on_l1(&BlockId::Number(2)).unwrap();
+        assert_eq!(accepted, (0..=2).map(|n| block_hash_at(&starknet, n)).collect::<Vec<_>>());
+        assert_eq!(
+            starknet.get_block(&BlockId::Number(2)).unwrap().status,
+            BlockStatus::AcceptedOnL1
+        );
+        assert_eq!(
+            starknet.get_block(&BlockId::Number(3)).unwrap().status,
+            BlockStatus::AcceptedOnL2
+        );
+
+        match starknet.abort_blocks(BlockId::Number(2)) {
+            Err(Error::UnsupportedAction { msg }) => assert!(msg.contains("accepted on L1")),
+            unexpected => panic!("Got unexpected response: {unexpected:?}"),
+        }
+
+        // only the blocks which are not yet accepted are returned
+        let accepted = starknet.accept_on_l1(&BlockId::Tag(BlockTag::Latest)).unwrap();
+        assert_eq!(accepted, vec![block_hash_at(&starknet, 3)]);
+    }
+
+    #[test]
+    fn hashes_of_old_blocks_stored_in_block_hash_contract() {
+        let mut starknet = Starknet::new(&StarknetConfig {
+            state_archive: StateArchiveCapacity::Full,
+            ..Default::default()
+        })
+        .unwrap();
+        let block_hash_at = |starknet: &Starknet, number: u64| {
+            starknet.get_block(&BlockId::Number(number)).unwrap().block_hash()
+        };
+        // no class is deployed at the address of the block hash contract, so its storage is read
+        // directly from the state
+        let stored_hash_of = |starknet: &mut Starknet, number: u64| {
+            starknet
+                .pending_state
+                .get_storage_at(
+                    ContractAddress::new(Felt::from(BLOCK_HASH_CONTRACT_ADDRESS))
+                        .unwrap()
+                        .try_into()
+                        .unwrap(),
+                    PatriciaKey::new(Felt::from(number)).unwrap().try_into().unwrap(),
+                )
+                .unwrap()
+        };
+
+        // blocks 0 to 20 are mined, so the pending block is 21
+        for _ in 0..20 {
+            starknet.create_block().unwrap();
+        }
+        for number in 0..=11 {
+            assert_eq!(stored_hash_of(&mut starknet, number), block_hash_at(&starknet, number));
+        }
+        assert_eq!(stored_hash_of(&mut starknet, 12), Felt::ZERO);
+
+        // the pending block is 15 again
+        let old_hash_of_16 = block_hash_at(&starknet, 16);
+        starknet.abort_blocks(BlockId::Number(15)).unwrap();
+        assert_eq!(stored_hash_of(&mut starknet, 5), block_hash_at(&starknet, 5));
+        assert_eq!(stored_hash_of(&mut starknet, 6), Felt::ZERO);
+
+        // a shifted timestamp makes the new blocks differ from the aborted ones; the pending block
+        // is 27, so the new block 16 is stored
+        starknet.increase_time(1000).unwrap();
+        for _ in 0..11 {
+            starknet.create_block().unwrap();
+        }
+        let new_hash_of_16 = block_hash_at(&starknet, 16);
+        assert_ne!(new_hash_of_16, old_hash_of_16);
+        assert_eq!(stored_hash_of(&mut starknet, 16), new_hash_of_16);
+        assert_eq!(stored_hash_of(&mut starknet, 17), block_hash_at(&starknet, 17));
+        assert_eq!(stored_hash_of(&mut starknet, 18), Felt::ZERO);
+    }
+
+    #[test]
+    fn setting_storage_and_nonce_is_reflected_in_latest_state_and_state_update() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        let storage_key = PatriciaKey::new(Felt::from(42)).unwrap();
+        let storage_value = Felt::from(7);
+        let nonce = Felt::from(100);
+
+        starknet.set_storage_at(contract_address, storage_key, storage_value).unwrap();
+        starknet.set_nonce(contract_address, nonce).unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(
+            starknet.contract_storage_at_block(&latest, contract_address, storage_key).unwrap(),
+            storage_value
+        );
+        assert_eq!(starknet.contract_nonce_at_block(&latest, contract_address).unwrap(), nonce);
+
+        // each modification is in its own block in block-generation-on-transaction mode
+        let storage_diff =
+            starknet.block_state_update(&BlockId::Number(1)).unwrap().get_state_diff();
+        assert_eq!(
+            storage_diff.storage_diffs,
+            vec![StorageDiff {
+                address: contract_address,
+                storage_entries: vec![StorageEntry { key: storage_key, value: storage_value }]
+            }]
+        );
+        let nonce_diff = starknet.block_state_update(&BlockId::Number(2)).unwrap().get_state_diff();
+        assert_eq!(nonce_diff.nonces, vec![ContractNonce { contract_address, nonce }]);
+    }
+
+    #[test]
+    fn nonce_can_be_set_to_lower_value() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+
+        starknet.set_nonce(contract_address, Felt::from(5)).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(2)).unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(
+            starknet.contract_nonce_at_block(&latest, contract_address).unwrap(),
+            Felt::from(2)
+        );
+    }
+
+    #[test]
+    fn setting_class_hash_deploys_contract_of_declared_class() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        let contract_address = dummy_contract_address();
+        let class_hash = felt_from_prefixed_hex(CAIRO_0_ACCOUNT_CONTRACT_HASH).unwrap();
+
+        starknet.set_class_hash_at(contract_address, class_hash).unwrap();
+
+        assert_eq!(
+            starknet.get_class_hash_at(&BlockId::Tag(BlockTag::Latest), contract_address).unwrap(),
+            class_hash
+        );
+    }
+
+    #[test]
+    fn setting_undeclared_class_hash_fails() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+
+        match starknet.set_class_hash_at(dummy_contract_address(), dummy_felt()) {
+            Err(Error::StateError(StateError::NoneClassHash(class_hash))) => {
+                assert_eq!(class_hash, dummy_felt())
+            }
+            unexpected => panic!("Got unexpected response: {unexpected:?}"),
+        }
+    }
 }
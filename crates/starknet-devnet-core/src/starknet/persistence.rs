@@ -0,0 +1,565 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+
+use blockifier::bouncer::BouncerWeights;
+use blockifier::state::state_api::StateReader;
+use nonzero_ext::nonzero;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockStatus;
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_rs_core::types::Felt;
+use starknet_types::felt::{BlockHash, ClassHash, TransactionHash};
+use starknet_types::rpc::gas_modification::GasModification;
+
+use super::cheats::Cheats;
+use super::dump::{CommittedClass, StarknetDump};
+use super::mempool::Mempool;
+use super::starknet_config::{StarknetConfig, StateArchiveCapacity};
+use super::Starknet;
+use crate::blocks::{StarknetBlock, StarknetBlocks};
+use crate::database::{Database, Table};
+use crate::error::{DevnetResult, Error, StateError};
+use crate::messaging::MessagingBroker;
+use crate::state::state_diff::StateDiff;
+use crate::state::state_history::{self, StateHistoryReader};
+use crate::state::{BlockNumberOrPending, StarknetState};
+use crate::traits::HashIdentified;
+use crate::transactions::StarknetTransactions;
+
+/// How many archived states read from the database are kept in memory at once.
+const ARCHIVED_STATES_CACHE_CAPACITY: NonZeroUsize = nonzero!(16usize);
+
+/// Parts of `Starknet` which are not stored in tables. The record is rewritten after every change.
+#[derive(Serialize, Deserialize)]
+struct DevnetRecord {
+    starting_config: serde_json::Value,
+    pending_block: StarknetBlock,
+    pending_state_diff: StateDiff,
+    pending_block_weights: BouncerWeights,
+    last_block_hash: Option<BlockHash>,
+    aborted_blocks: Vec<Felt>,
+    block_context_gas: GasModification,
+    pending_block_timestamp_shift: i64,
+    next_block_timestamp: Option<u64>,
+    next_block_gas: GasModification,
+    next_block_l1_da_mode: L1DataAvailabilityMode,
+    messaging: MessagingBroker,
+    cheats: Cheats,
+    mempool: Mempool,
+    rejected_transactions: HashMap<TransactionHash, String>,
+}
+
+/// The part of the configuration which determines the state Devnet starts with and what is stored
+/// in the database. The stored diffs only reproduce the state if they are replayed on the same
+/// starting state, so the database can only be used with the configuration that created it.
+fn starting_config(config: &StarknetConfig) -> serde_json::Value {
+    serde_json::json!({
+        "seed": config.seed,
+        "total_accounts": config.total_accounts,
+        "account_contract_class_hash": config.account_contract_class_hash,
+        "predeployed_accounts_initial_balance":
+            config.predeployed_accounts_initial_balance.to_str_radix(10),
+        "chain_id": config.chain_id.to_string(),
+        "fork_url": config.fork_config.url.as_ref().map(|url| url.to_string()),
+        "fork_block_number": config.fork_config.block_number,
+        "eth_erc20_class_hash": config.eth_erc20_class_hash,
+        "strk_erc20_class_hash": config.strk_erc20_class_hash,
+        "genesis_path": config.genesis_path,
+        "state_archive": config.state_archive,
+    })
+}
+
+impl Starknet {
+    /// Makes Devnet store its data in `database`. If the database contains data of an earlier run,
+    /// Devnet continues from it and `true` is returned.
+    pub(super) fn use_database(&mut self, database: Database) -> DevnetResult<bool> {
+        let restored = match database.get_devnet_record()? {
+            Some(record) => {
+                self.restore_from_database(&database, record)?;
+                true
+            }
+            None => {
+                self.blocks.use_database(database.clone(), ARCHIVED_STATES_CACHE_CAPACITY);
+                // the diff of the genesis block doesn't contain all of the starting state, e.g. the
+                // balances of predeployed accounts
+                if self.config.state_archive == StateArchiveCapacity::Full {
+                    state_history::write_state(
+                        &database,
+                        self.blocks.starting_block_number,
+                        &self.pending_state.to_serializable(),
+                    )?;
+                }
+                false
+            }
+        };
+
+        self.transactions = StarknetTransactions::with_database(database.clone());
+        self.database = Some(database);
+        Ok(restored)
+    }
+
+    fn restore_from_database(
+        &mut self,
+        database: &Database,
+        record: DevnetRecord,
+    ) -> DevnetResult<()> {
+        if record.starting_config != starting_config(&self.config) {
+            return Err(Error::UnsupportedAction {
+                msg: format!(
+                    "The database was created with a different configuration; start Devnet with \
+                     the same configuration or with a different database path. Stored \
+                     configuration: {}",
+                    record.starting_config
+                ),
+            });
+        }
+
+        let classes = database
+            .range::<CommittedClass>(Table::Classes, &[0; 32], &[u8::MAX; 32])?
+            .into_iter()
+            .map(|(_, class)| class)
+            .collect();
+
+        let mut blocks = StarknetBlocks::new(self.blocks.starting_block_number);
+        blocks.use_database(database.clone(), ARCHIVED_STATES_CACHE_CAPACITY);
+        blocks.pending_block = record.pending_block;
+        blocks.last_block_hash = record.last_block_hash;
+        blocks.aborted_blocks = record.aborted_blocks;
+
+        // The latest state is not stored, but recreated by applying the diffs of accepted blocks to
+        // the state Devnet starts with. The diff of the genesis block partly repeats the starting
+        // state, which is harmless. Diffs are read one at a time, so they are not all in memory.
+        let mut latest_state = self.pending_state.to_serializable();
+        let accepted_blocks = database.range::<BlockHash>(
+            Table::BlockNumbers,
+            &0_u64.to_be_bytes(),
+            &u64::MAX.to_be_bytes(),
+        )?;
+        for (_, block_hash) in accepted_blocks {
+            let state_diff = blocks.get_state_diff(&block_hash)?.ok_or(Error::NoBlock)?;
+            latest_state.apply_diff(&state_diff);
+        }
+        let mut pending_state = latest_state.clone();
+        pending_state.apply_diff(&record.pending_state_diff);
+
+        self.load_state(StarknetDump {
+            classes,
+            latest_state,
+            pending_state,
+            pending_state_diff: record.pending_state_diff,
+            pending_block_weights: record.pending_block_weights,
+            // archived states are read from the state history on demand
+            block_states: HashMap::new(),
+            blocks,
+            transactions: StarknetTransactions::default(),
+            block_context_gas: record.block_context_gas,
+            pending_block_timestamp_shift: record.pending_block_timestamp_shift,
+            next_block_timestamp: record.next_block_timestamp,
+            next_block_gas: record.next_block_gas,
+            next_block_l1_da_mode: record.next_block_l1_da_mode,
+            messaging: record.messaging,
+            cheats: record.cheats,
+        })?;
+
+        self.mempool = record.mempool;
+        self.rejected_transactions = record.rejected_transactions;
+        Ok(())
+    }
+
+    /// Writes the record of everything that is not stored in tables and commits it together with
+    /// the rest of the changes made since the last save, in a single transaction. Meant to be
+    /// called once per modification of Devnet. Does nothing if no database is used.
+    pub fn save_to_database(&self) -> DevnetResult<()> {
+        let Some(database) = &self.database else {
+            return Ok(());
+        };
+
+        database.put_devnet_record(&DevnetRecord {
+            starting_config: starting_config(&self.config),
+            pending_block: self.blocks.pending_block.clone(),
+            pending_state_diff: self.pending_state_diff.clone(),
+            pending_block_weights: self.pending_block_weights,
+            last_block_hash: self.blocks.last_block_hash,
+            aborted_blocks: self.blocks.aborted_blocks.clone(),
+            block_context_gas: self.block_context_gas(),
+            pending_block_timestamp_shift: self.pending_block_timestamp_shift,
+            next_block_timestamp: self.next_block_timestamp,
+            next_block_gas: self.next_block_gas.clone(),
+            next_block_l1_da_mode: self.next_block_l1_da_mode,
+            messaging: self.messaging.clone(),
+            cheats: self.cheats.clone(),
+            mempool: self.mempool.clone(),
+            rejected_transactions: self.rejected_transactions.clone(),
+        })?;
+        database.commit()
+    }
+
+    /// Writes the classes declared in the block and, with full state archive capacity, the values
+    /// changed by it to the database, if used. Archived states are read from these values.
+    pub(super) fn store_block(
+        &self,
+        block_number: u64,
+        state_diff: &StateDiff,
+    ) -> DevnetResult<()> {
+        let Some(database) = &self.database else {
+            return Ok(());
+        };
+
+        let classes = self.rpc_contract_classes.read();
+        for class_hash in declared_classes(state_diff) {
+            let contract_class = classes
+                .get_class(class_hash, &BlockNumberOrPending::Number(block_number))
+                .ok_or(Error::StateError(StateError::NoneClassHash(*class_hash)))?;
+            let committed_class = CommittedClass {
+                class_hash: *class_hash,
+                block_number,
+                contract_class: contract_class.into(),
+            };
+            database.put(Table::Classes, &class_hash.to_bytes_be(), &committed_class)?;
+        }
+
+        if self.config.state_archive == StateArchiveCapacity::Full {
+            state_history::write_block_changes(database, block_number, state_diff)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the classes declared in the aborted block and the values changed by it from the
+    /// database, if used.
+    pub(super) fn remove_stored_block(&self, block_number: u64, state_diff: &StateDiff) {
+        if let Some(database) = &self.database {
+            for class_hash in declared_classes(state_diff) {
+                database.delete(Table::Classes, &class_hash.to_bytes_be());
+            }
+            state_history::remove_block_changes(database, block_number, state_diff);
+        }
+    }
+
+    /// With full state archive capacity, saves a copy of the committed pending state as the state
+    /// of the block. When using a database, archived states are read from it instead.
+    pub(super) fn archive_state(&mut self, block_hash: BlockHash) {
+        if self.config.state_archive == StateArchiveCapacity::Full && self.database.is_none() {
+            let clone = self.pending_state.clone_historic();
+            self.blocks.save_state_at(block_hash, clone);
+        }
+    }
+
+    /// Returns the archived state of the block. When using a database, the state is read from it
+    /// and kept in memory among a limited number of the most recently used states.
+    pub(super) fn get_mut_archived_state(
+        &mut self,
+        block_hash: &BlockHash,
+    ) -> DevnetResult<Option<&mut StarknetState>> {
+        if self.database.is_some() && !self.blocks.hash_to_state.contains(block_hash) {
+            let Some(state) = self.load_archived_state(block_hash)? else {
+                return Ok(None);
+            };
+            // evicts the least recently used state if the capacity is reached
+            self.blocks.save_state_at(*block_hash, state);
+        }
+
+        Ok(self.blocks.hash_to_state.get_mut(block_hash))
+    }
+
+    /// Creates the archived state of the block, which reads the values set up to the block from
+    /// the state history. Returns `None` if no database is used or if the block is not accepted.
+    fn load_archived_state(&self, block_hash: &BlockHash) -> DevnetResult<Option<StarknetState>> {
+        let Some(database) = &self.database else {
+            return Ok(None);
+        };
+        let block_number = match self.blocks.get_by_hash(*block_hash)? {
+            Some(block) if block.status != BlockStatus::Rejected => block.block_number().0,
+            _ => return Ok(None),
+        };
+
+        let class_hashes: Vec<ClassHash> = self
+            .rpc_contract_classes
+            .read()
+            .iter_committed()
+            .filter(|(_, (_, committed_at))| *committed_at <= block_number)
+            .map(|(class_hash, _)| *class_hash)
+            .collect();
+
+        let state = StarknetState::with_history(
+            StateHistoryReader::new(database.clone(), block_number),
+            self.defaulter.clone(),
+            self.rpc_contract_classes.clone(),
+            self.compiled_classes(&class_hashes)?,
+        )?;
+        Ok(Some(state))
+    }
+
+    /// Reverts the state to the one of the accepted block with `block_number`, undoing
+    /// `reverted_diffs` by reading the preceding values from the state history.
+    pub(super) fn revert_state_from_history(
+        &self,
+        database: &Database,
+        block_number: u64,
+        reverted_diffs: &[StateDiff],
+    ) -> DevnetResult<StarknetState> {
+        let history = StateHistoryReader::new(database.clone(), block_number);
+        let mut state = self.pending_state.to_serializable();
+        for state_diff in reverted_diffs {
+            state_history::revert_changes(&mut state, &history, state_diff)?;
+        }
+
+        let compiled_classes = self.compiled_classes(state.declared_classes())?;
+        StarknetState::from_serializable(
+            state,
+            self.defaulter.clone(),
+            self.rpc_contract_classes.clone(),
+            &compiled_classes,
+        )
+    }
+
+    /// Returns the compiled versions of the classes, taken from the pending state, which contains
+    /// all classes declared in accepted blocks.
+    fn compiled_classes(
+        &self,
+        class_hashes: &[ClassHash],
+    ) -> DevnetResult<HashMap<ClassHash, blockifier::execution::contract_class::ContractClass>>
+    {
+        let mut compiled_classes = HashMap::new();
+        for class_hash in class_hashes {
+            let compiled_class = self
+                .pending_state
+                .get_compiled_contract_class(starknet_api::core::ClassHash(*class_hash))?;
+            compiled_classes.insert(*class_hash, compiled_class);
+        }
+        Ok(compiled_classes)
+    }
+}
+
+fn declared_classes(state_diff: &StateDiff) -> impl Iterator<Item = &ClassHash> {
+    state_diff.declared_contracts.iter().chain(&state_diff.cairo_0_declared_contracts)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_api::block::BlockStatus;
+    use starknet_api::transaction::Fee;
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::rpc::transactions::broadcasted_invoke_transaction_v1::BroadcastedInvokeTransactionV1;
+    use starknet_types::rpc::transactions::BroadcastedInvokeTransaction;
+
+    use super::ARCHIVED_STATES_CACHE_CAPACITY;
+    use crate::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use crate::error::Error;
+    use crate::starknet::starknet_config::{
+        BlockGenerationOn, MempoolOrdering, StarknetConfig, StateArchiveCapacity,
+    };
+    use crate::starknet::Starknet;
+    use crate::traits::Deployed;
+
+    fn config_with_database(db_path: &std::path::Path) -> StarknetConfig {
+        StarknetConfig {
+            db_path: Some(db_path.to_str().unwrap().to_string()),
+            state_archive: StateArchiveCapacity::Full,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn blocks_and_states_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_database(dir.path());
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+
+        let mut starknet = Starknet::new(&config).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(1)).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(2)).unwrap();
+        let latest_block = starknet.get_latest_block().unwrap();
+        starknet.save_to_database().unwrap();
+        drop(starknet);
+
+        let mut restarted = Starknet::new(&config).unwrap();
+        assert_eq!(restarted.get_latest_block().unwrap().block_hash(), latest_block.block_hash());
+        for (block_id, expected_nonce) in
+            [(BlockId::Number(1), 1), (BlockId::Number(2), 2), (BlockId::Tag(BlockTag::Latest), 2)]
+        {
+            assert_eq!(
+                restarted.contract_nonce_at_block(&block_id, contract_address).unwrap(),
+                Felt::from(expected_nonce)
+            );
+        }
+
+        // the restarted Devnet continues from the stored state
+        restarted.set_nonce(contract_address, Felt::from(3)).unwrap();
+        assert_eq!(restarted.get_latest_block().unwrap().block_number().0, 3);
+        assert_eq!(
+            restarted.contract_nonce_at_block(&BlockId::Number(2), contract_address).unwrap(),
+            Felt::from(2)
+        );
+    }
+
+    #[test]
+    fn pending_changes_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = StarknetConfig {
+            block_generation_on: BlockGenerationOn::Demand,
+            ..config_with_database(dir.path())
+        };
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+
+        let mut starknet = Starknet::new(&config).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(1)).unwrap();
+        starknet.save_to_database().unwrap();
+        drop(starknet);
+
+        let mut restarted = Starknet::new(&config).unwrap();
+        let pending = BlockId::Tag(BlockTag::Pending);
+        assert_eq!(
+            restarted.contract_nonce_at_block(&pending, contract_address).unwrap(),
+            Felt::from(1)
+        );
+
+        restarted.create_block().unwrap();
+        let state_update = restarted.block_state_update(&BlockId::Tag(BlockTag::Latest)).unwrap();
+        assert_eq!(state_update.get_state_diff().nonces.len(), 1);
+    }
+
+    #[test]
+    fn mempool_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = StarknetConfig {
+            mempool: Some(MempoolOrdering::Fifo),
+            block_generation_on: BlockGenerationOn::Demand,
+            ..config_with_database(dir.path())
+        };
+
+        let mut starknet = Starknet::new(&config).unwrap();
+        let sender = starknet.get_predeployed_accounts()[0].get_address();
+        // the nonce gap keeps the transaction in the mempool
+        let transaction = BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1::new(
+            sender,
+            Fee(1_000_000),
+            &vec![],
+            Felt::ONE,
+            &vec![],
+            Felt::ONE,
+        ));
+        let transaction_hash = starknet.add_invoke_transaction(transaction).unwrap();
+        starknet.save_to_database().unwrap();
+        drop(starknet);
+
+        let restarted = Starknet::new(&config).unwrap();
+        assert!(restarted.is_transaction_in_mempool(&transaction_hash));
+    }
+
+    #[test]
+    fn database_is_not_used_with_different_starting_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_database(dir.path());
+
+        let starknet = Starknet::new(&config).unwrap();
+        starknet.save_to_database().unwrap();
+        drop(starknet);
+
+        let different_config = StarknetConfig { seed: config.seed + 1, ..config.clone() };
+        assert!(matches!(Starknet::new(&different_config), Err(Error::UnsupportedAction { .. })));
+        // configuration not affecting the stored data may change
+        let config = StarknetConfig { block_generation_on: BlockGenerationOn::Demand, ..config };
+        Starknet::new(&config).unwrap();
+    }
+
+    #[test]
+    fn aborted_blocks_stay_aborted_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_database(dir.path());
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+
+        let mut starknet = Starknet::new(&config).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(1)).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(2)).unwrap();
+        let aborted = starknet.abort_blocks(BlockId::Number(2)).unwrap();
+        starknet.save_to_database().unwrap();
+        drop(starknet);
+
+        let mut restarted = Starknet::new(&config).unwrap();
+        assert_eq!(restarted.get_latest_block().unwrap().block_number().0, 1);
+        assert_eq!(
+            restarted.get_block(&BlockId::Hash(aborted[0])).unwrap().status(),
+            &BlockStatus::Rejected
+        );
+        assert_eq!(
+            restarted
+                .contract_nonce_at_block(&BlockId::Tag(BlockTag::Latest), contract_address)
+                .unwrap(),
+            Felt::from(1)
+        );
+
+        // the number of the aborted block is reused
+        restarted.set_nonce(contract_address, Felt::from(3)).unwrap();
+        assert_eq!(restarted.get_latest_block().unwrap().block_number().0, 2);
+    }
+
+    #[test]
+    fn archived_states_are_loaded_on_demand_and_least_recently_used_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut starknet = Starknet::new(&config_with_database(dir.path())).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+
+        let capacity = ARCHIVED_STATES_CACHE_CAPACITY.get() as u64;
+        for nonce in 1..=capacity + 2 {
+            starknet.set_nonce(contract_address, Felt::from(nonce)).unwrap();
+        }
+        assert!(starknet.blocks.hash_to_state.is_empty());
+
+        let assert_nonce_at = |starknet: &mut Starknet, block_number: u64| {
+            let nonce = starknet
+                .contract_nonce_at_block(&BlockId::Number(block_number), contract_address)
+                .unwrap();
+            assert_eq!(nonce, Felt::from(block_number));
+        };
+        let is_cached = |starknet: &Starknet, block_number: u64| {
+            let block_hash =
+                starknet.get_block(&BlockId::Number(block_number)).unwrap().block_hash();
+            starknet.blocks.hash_to_state.contains(&block_hash)
+        };
+
+        for block_number in 1..=capacity {
+            assert_nonce_at(&mut starknet, block_number);
+        }
+        assert_eq!(starknet.blocks.hash_to_state.len(), capacity as usize);
+
+        // block 1 is used again, so block 2 is the least recently used one
+        assert_nonce_at(&mut starknet, 1);
+        assert_nonce_at(&mut starknet, capacity + 1);
+        assert_eq!(starknet.blocks.hash_to_state.len(), capacity as usize);
+        assert!(is_cached(&starknet, 1));
+        assert!(!is_cached(&starknet, 2));
+
+        // evicted states are read from the database again
+        assert_nonce_at(&mut starknet, 2);
+        assert!(is_cached(&starknet, 2));
+    }
+
+    #[test]
+    fn restarting_clears_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_database(dir.path());
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+
+        let mut starknet = Starknet::new(&config).unwrap();
+        starknet.set_nonce(contract_address, Felt::from(1)).unwrap();
+        starknet.restart(false).unwrap();
+        assert_eq!(starknet.get_latest_block().unwrap().block_number().0, 0);
+        drop(starknet);
+
+        let starknet = Starknet::new(&config).unwrap();
+        assert_eq!(starknet.get_latest_block().unwrap().block_number().0, 0);
+    }
+
+    #[test]
+    fn snapshots_and_loading_are_not_supported_with_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut starknet = Starknet::new(&config_with_database(dir.path())).unwrap();
+
+        assert!(matches!(starknet.take_snapshot(), Err(Error::UnsupportedAction { .. })));
+
+        let dump = Starknet::new(&StarknetConfig::default()).unwrap().dump_state();
+        assert!(matches!(starknet.load_state(dump), Err(Error::UnsupportedAction { .. })));
+    }
+}
@@ -1,8 +1,11 @@
+use blockifier::abi::sierra_types::next_storage_key;
 use blockifier::state::state_api::State;
+use starknet_api::state::StorageKey;
 use starknet_rs_core::types::Felt;
 use starknet_rs_core::utils::cairo_short_string_to_felt;
 use starknet_types::contract_address::ContractAddress;
-use starknet_types::felt::felt_from_prefixed_hex;
+use starknet_types::felt::{felt_from_prefixed_hex, join_felts, split_biguint};
+use starknet_types::num_bigint::BigUint;
 
 use crate::constants::{
     CHARGEABLE_ACCOUNT_ADDRESS, UDC_CONTRACT, UDC_CONTRACT_ADDRESS, UDC_CONTRACT_CLASS_HASH,
@@ -53,6 +56,34 @@ pub(crate) fn initialize_erc20_at_address(
     Ok(())
 }
 
+/// Increases the balance of `holder` and the total supply of the ERC20 token at `token_address`
+pub(crate) fn add_erc20_balance(
+    state: &mut impl State,
+    token_address: ContractAddress,
+    holder: ContractAddress,
+    amount: &BigUint,
+) -> DevnetResult<()> {
+    let token_address = token_address.try_into()?;
+
+    let balance_address_low: StorageKey =
+        get_storage_var_address("ERC20_balances", &[Felt::from(holder)])?.try_into()?;
+    let total_supply_address_low: StorageKey =
+        get_storage_var_address("ERC20_total_supply", &[])?.try_into()?;
+
+    for storage_address_low in [balance_address_low, total_supply_address_low] {
+        let storage_address_high = next_storage_key(&storage_address_low)?;
+
+        let low = state.get_storage_at(token_address, storage_address_low)?;
+        let high = state.get_storage_at(token_address, storage_address_high)?;
+        let (new_high, new_low) = split_biguint(join_felts(&high, &low) + amount);
+
+        state.set_storage_at(token_address, storage_address_low, new_low)?;
+        state.set_storage_at(token_address, storage_address_high, new_high)?;
+    }
+
+    Ok(())
+}
+
 pub(crate) fn create_udc() -> DevnetResult<SystemContract> {
     let udc_contract =
         SystemContract::new_cairo0(UDC_CONTRACT_CLASS_HASH, UDC_CONTRACT_ADDRESS, UDC_CONTRACT)?;
@@ -0,0 +1,414 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use blockifier::fee::fee_utils::calculate_l1_gas_by_vm_usage;
+use serde_json::Value;
+use starknet_rs_core::types::Felt;
+use starknet_rs_core::utils::get_selector_from_name;
+use starknet_types::contract_class::{Cairo0ContractClass, ContractClass};
+use starknet_types::felt::ClassHash;
+use starknet_types::rpc::devnet::{
+    CallProfile, InvocationPhase, InvocationProfile, ProfileMetric, ProfiledResources,
+    TransactionProfile,
+};
+use starknet_types::rpc::transaction_receipt::ComputationResources;
+use starknet_types::rpc::transactions::{
+    ExecutionInvocation, FunctionInvocation, TransactionTrace,
+};
+
+use super::Starknet;
+use crate::error::DevnetResult;
+use crate::state::BlockNumberOrPending;
+
+/// Names of a contract and its functions, as declared in the ABI of its class
+#[derive(Default)]
+struct ClassNames {
+    contract_name: Option<String>,
+    function_names: HashMap<Felt, String>,
+}
+
+impl Starknet {
+    /// Aggregates the resources used by a traced transaction per invoked entry point
+    pub fn profile_transaction_trace(
+        &self,
+        trace: &TransactionTrace,
+    ) -> DevnetResult<TransactionProfile> {
+        let (invocations, execution_resources) = match trace {
+            TransactionTrace::Invoke(trace) => {
+                let execute_invocation = match &trace.execute_invocation {
+                    ExecutionInvocation::Succeeded(invocation) => Some(invocation),
+                    ExecutionInvocation::Reverted(_) => None,
+                };
+                (
+                    vec![
+                        (InvocationPhase::Validate, trace.validate_invocation.as_ref()),
+                        (InvocationPhase::Execute, execute_invocation),
+                        (InvocationPhase::FeeTransfer, trace.fee_transfer_invocation.as_ref()),
+                    ],
+                    &trace.execution_resources,
+                )
+            }
+            TransactionTrace::Declare(trace) => (
+                vec![
+                    (InvocationPhase::Validate, trace.validate_invocation.as_ref()),
+                    (InvocationPhase::FeeTransfer, trace.fee_transfer_invocation.as_ref()),
+                ],
+                &trace.execution_resources,
+            ),
+            // the constructor of the account is executed before its validation
+            TransactionTrace::DeployAccount(trace) => (
+                vec![
+                    (InvocationPhase::Constructor, trace.constructor_invocation.as_ref()),
+                    (InvocationPhase::Validate, trace.validate_invocation.as_ref()),
+                    (InvocationPhase::FeeTransfer, trace.fee_transfer_invocation.as_ref()),
+                ],
+                &trace.execution_resources,
+            ),
+            TransactionTrace::L1Handler(trace) => (
+                vec![(InvocationPhase::L1Handler, Some(&trace.function_invocation))],
+                &trace.execution_resources,
+            ),
+        };
+
+        let mut class_names = HashMap::new();
+        let invocations = invocations
+            .into_iter()
+            .filter_map(|(phase, invocation)| Some((phase, invocation?)))
+            .map(|(phase, invocation)| {
+                Ok(InvocationProfile {
+                    phase,
+                    call: self.profile_call(invocation, &mut class_names)?,
+                })
+            })
+            .collect::<DevnetResult<_>>()?;
+
+        let total_resources = &execution_resources.computation_resources;
+        Ok(TransactionProfile {
+            invocations,
+            data_availability: execution_resources.data_availability.clone(),
+            total: ProfiledResources {
+                steps: total_resources.steps,
+                builtins: total_resources.builtin_applications(),
+                l1_gas: execution_resources.total_gas_consumed.l1_gas,
+                l1_data_gas: execution_resources.total_gas_consumed.l1_data_gas,
+            },
+        })
+    }
+
+    fn profile_call(
+        &self,
+        invocation: &FunctionInvocation,
+        class_names: &mut HashMap<ClassHash, ClassNames>,
+    ) -> DevnetResult<CallProfile> {
+        let calls = invocation
+            .calls()
+            .iter()
+            .map(|call| self.profile_call(call, class_names))
+            .collect::<DevnetResult<_>>()?;
+
+        let class_hash = invocation.class_hash();
+        let names = class_names.entry(class_hash).or_insert_with(|| self.class_names(&class_hash));
+        let entry_point_selector = invocation.entry_point_selector();
+
+        Ok(CallProfile {
+            contract_address: invocation.contract_address(),
+            class_hash,
+            contract_name: names.contract_name.clone(),
+            entry_point_selector,
+            function_name: names.function_names.get(&entry_point_selector).cloned(),
+            resources: self.profiled_resources(invocation.execution_resources())?,
+            self_resources: self.profiled_resources(&self_resources(invocation))?,
+            calls,
+        })
+    }
+
+    /// Computation resources are converted to L1 gas the same way as for the whole transaction
+    fn profiled_resources(
+        &self,
+        resources: &ComputationResources,
+    ) -> DevnetResult<ProfiledResources> {
+        let gas = calculate_l1_gas_by_vm_usage(
+            self.block_context.versioned_constants(),
+            &resources.into(),
+            0,
+        )?;
+
+        Ok(ProfiledResources {
+            steps: resources.steps,
+            builtins: resources.builtin_applications(),
+            l1_gas: gas.l1_gas,
+            l1_data_gas: gas.l1_data_gas,
+        })
+    }
+
+    fn class_names(&self, class_hash: &ClassHash) -> ClassNames {
+        let class =
+            self.rpc_contract_classes.read().get_class(class_hash, &BlockNumberOrPending::Pending);
+        // a raw Cairo 0 class is kept as the JSON it was loaded from
+        let class = class.and_then(|class| match class {
+            ContractClass::Cairo0(Cairo0ContractClass::RawJson(json)) => Some(json.inner),
+            class => serde_json::to_value(class).ok(),
+        });
+        // depending on the class version, the ABI is either a JSON array or its string encoding
+        let abi = class.and_then(|mut class| match class["abi"].take() {
+            Value::String(abi) => serde_json::from_str(&abi).ok(),
+            abi => Some(abi),
+        });
+        let Some(Value::Array(items)) = abi else {
+            return ClassNames::default();
+        };
+
+        let mut names = ClassNames::default();
+        let mut event_enums = vec![];
+        let mut nested_events = HashSet::new();
+        collect_names(&items, &mut names, &mut event_enums, &mut nested_events);
+
+        // the event enum of a Cairo 1 contract is declared in the module of the contract and
+        // contains the events of its components, but is not contained in any other event enum
+        let mut contract_events =
+            event_enums.into_iter().filter(|event| !nested_events.contains(event));
+        if let (Some(contract_event), None) = (contract_events.next(), contract_events.next()) {
+            names.contract_name = contract_event
+                .strip_suffix("::Event")
+                .and_then(|module| module.rsplit("::").next())
+                .map(String::from);
+        }
+
+        names
+    }
+}
+
+fn collect_names(
+    items: &[Value],
+    names: &mut ClassNames,
+    event_enums: &mut Vec<String>,
+    nested_events: &mut HashSet<String>,
+) {
+    for item in items {
+        let name = item.get("name").and_then(Value::as_str);
+        match (item.get("type").and_then(Value::as_str), name) {
+            (Some("function" | "constructor" | "l1_handler"), Some(name)) => {
+                if let Ok(selector) = get_selector_from_name(name) {
+                    names.function_names.insert(selector, name.to_string());
+                }
+            }
+            (Some("interface"), _) => {
+                if let Some(items) = item.get("items").and_then(Value::as_array) {
+                    collect_names(items, names, event_enums, nested_events);
+                }
+            }
+            (Some("event"), Some(name))
+                if item.get("kind").and_then(Value::as_str) == Some("enum") =>
+            {
+                event_enums.push(name.to_string());
+                let variants = item.get("variants").and_then(Value::as_array);
+                for variant in variants.into_iter().flatten() {
+                    if let Some(variant_type) = variant.get("type").and_then(Value::as_str) {
+                        nested_events.insert(variant_type.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Resources of the invocation, excluding those of its inner calls
+fn self_resources(invocation: &FunctionInvocation) -> ComputationResources {
+    let mut resources = invocation.execution_resources().clone();
+    for call in invocation.calls() {
+        let inner = call.execution_resources();
+        resources.steps = resources.steps.saturating_sub(inner.steps);
+        for (own, inner) in [
+            (&mut resources.memory_holes, inner.memory_holes),
+            (
+                &mut resources.range_check_builtin_applications,
+                inner.range_check_builtin_applications,
+            ),
+            (&mut resources.pedersen_builtin_applications, inner.pedersen_builtin_applications),
+            (&mut resources.poseidon_builtin_applications, inner.poseidon_builtin_applications),
+            (&mut resources.ec_op_builtin_applications, inner.ec_op_builtin_applications),
+            (&mut resources.ecdsa_builtin_applications, inner.ecdsa_builtin_applications),
+            (&mut resources.bitwise_builtin_applications, inner.bitwise_builtin_applications),
+            (&mut resources.keccak_builtin_applications, inner.keccak_builtin_applications),
+            (
+                &mut resources.segment_arena_builtin_applications,
+                inner.segment_arena_builtin_applications,
+            ),
+        ] {
+            *own = own
+                .map(|applications| applications.saturating_sub(inner.unwrap_or_default()))
+                .filter(|applications| *applications > 0);
+        }
+    }
+    resources
+}
+
+/// Renders the profile as folded stacks, in which each call contributes the `metric` of its own
+/// resources. Resources not attributed to any call are reported in separate stacks, so that the
+/// stacks sum up to the total of the transaction.
+pub fn folded_stacks(profile: &TransactionProfile, metric: ProfileMetric) -> String {
+    let weight = |resources: &ProfiledResources| match metric {
+        ProfileMetric::Steps => resources.steps as u128,
+        ProfileMetric::L1Gas => resources.l1_gas,
+        ProfileMetric::L1DataGas => resources.l1_data_gas,
+    };
+
+    let mut stacks = vec![];
+    for invocation in &profile.invocations {
+        let phase = match invocation.phase {
+            InvocationPhase::Validate => "validate",
+            InvocationPhase::Execute => "execute",
+            InvocationPhase::Constructor => "constructor",
+            InvocationPhase::L1Handler => "l1_handler",
+            InvocationPhase::FeeTransfer => "fee_transfer",
+        };
+        fold_call(&invocation.call, phase.to_string(), &weight, &mut stacks);
+    }
+
+    let data_availability = match metric {
+        ProfileMetric::Steps => 0,
+        ProfileMetric::L1Gas => profile.data_availability.l1_gas,
+        ProfileMetric::L1DataGas => profile.data_availability.l1_data_gas,
+    };
+    stacks.push(("data_availability".to_string(), data_availability));
+
+    let attributed: u128 = stacks.iter().map(|(_, weight)| weight).sum();
+    stacks.push(("overhead".to_string(), weight(&profile.total).saturating_sub(attributed)));
+
+    let mut folded = String::new();
+    for (stack, weight) in stacks.into_iter().filter(|(_, weight)| *weight > 0) {
+        writeln!(folded, "{stack} {weight}").unwrap();
+    }
+    folded
+}
+
+fn fold_call(
+    call: &CallProfile,
+    parent_stack: String,
+    weight: &impl Fn(&ProfiledResources) -> u128,
+    stacks: &mut Vec<(String, u128)>,
+) {
+    let contract = call.contract_name.clone().unwrap_or_else(|| format!("{:#x}", call.class_hash));
+    let function =
+        call.function_name.clone().unwrap_or_else(|| format!("{:#x}", call.entry_point_selector));
+    let stack = format!("{parent_stack};{contract}::{function}");
+
+    for inner_call in &call.calls {
+        fold_call(inner_call, stack.clone(), weight, stacks);
+    }
+    stacks.push((stack, weight(&call.self_resources)));
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_api::transaction::Fee;
+    use starknet_rs_core::types::Felt;
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::rpc::devnet::{
+        CallProfile, InvocationPhase, InvocationProfile, ProfileMetric, ProfiledResources,
+        TransactionProfile,
+    };
+    use starknet_types::rpc::transaction_receipt::DataAvailability;
+    use starknet_types::rpc::transactions::broadcasted_invoke_transaction_v1::BroadcastedInvokeTransactionV1;
+    use starknet_types::rpc::transactions::BroadcastedInvokeTransaction;
+
+    use super::folded_stacks;
+    use crate::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use crate::starknet::tests::setup_starknet_with_no_signature_check_account;
+    use crate::traits::Deployed;
+
+    fn call_profile(
+        contract_name: &str,
+        function_name: &str,
+        steps: usize,
+        self_steps: usize,
+        calls: Vec<CallProfile>,
+    ) -> CallProfile {
+        CallProfile {
+            contract_address: ContractAddress::new(Felt::ONE).unwrap(),
+            class_hash: Felt::ONE,
+            contract_name: Some(contract_name.to_string()),
+            entry_point_selector: get_selector_from_name(function_name).unwrap(),
+            function_name: Some(function_name.to_string()),
+            resources: ProfiledResources { steps, ..Default::default() },
+            self_resources: ProfiledResources { steps: self_steps, ..Default::default() },
+            calls,
+        }
+    }
+
+    #[test]
+    fn invoke_profiled_per_entry_point() {
+        let (mut starknet, account) = setup_starknet_with_no_signature_check_account(1_000_000_000);
+        let calldata = vec![
+            ETH_ERC20_CONTRACT_ADDRESS,
+            get_selector_from_name("name").unwrap(),
+            Felt::ZERO, // calldata len
+        ];
+        let transaction_hash = starknet
+            .add_invoke_transaction(BroadcastedInvokeTransaction::V1(
+                BroadcastedInvokeTransactionV1::new(
+                    account.get_address(),
+                    Fee(1_000_000),
+                    &vec![],
+                    Felt::ZERO,
+                    &calldata,
+                    Felt::ONE,
+                ),
+            ))
+            .unwrap();
+
+        let trace = starknet.get_transaction_trace_by_hash(transaction_hash).unwrap();
+        let profile = starknet.profile_transaction_trace(&trace).unwrap();
+
+        let execute = profile
+            .invocations
+            .iter()
+            .find(|invocation| invocation.phase == InvocationPhase::Execute)
+            .map(|invocation| &invocation.call)
+            .unwrap();
+        assert_eq!(execute.function_name.as_deref(), Some("__execute__"));
+        assert!(execute.self_resources.steps > 0);
+
+        match execute.calls.as_slice() {
+            [erc20_call] => {
+                assert_eq!(erc20_call.contract_name.as_deref(), Some("ERC20"));
+                assert_eq!(erc20_call.function_name.as_deref(), Some("name"));
+                assert_eq!(erc20_call.resources, erc20_call.self_resources);
+                assert!(erc20_call.resources.l1_gas > 0);
+            }
+            other => panic!("Unexpected inner calls: {other:?}"),
+        }
+
+        assert!(profile.total.steps > execute.resources.steps);
+    }
+
+    #[test]
+    fn folded_stacks_sum_up_to_total() {
+        let profile = TransactionProfile {
+            invocations: vec![InvocationProfile {
+                phase: InvocationPhase::Execute,
+                call: call_profile(
+                    "Account",
+                    "__execute__",
+                    30,
+                    10,
+                    vec![call_profile("ERC20", "transfer", 20, 20, vec![])],
+                ),
+            }],
+            data_availability: DataAvailability { l1_gas: 0, l1_data_gas: 128 },
+            total: ProfiledResources { steps: 50, l1_data_gas: 200, ..Default::default() },
+        };
+
+        assert_eq!(
+            folded_stacks(&profile, ProfileMetric::Steps),
+            "execute;Account::__execute__;ERC20::transfer 20\nexecute;Account::__execute__ \
+             10\noverhead 20\n"
+        );
+        assert_eq!(
+            folded_stacks(&profile, ProfileMetric::L1DataGas),
+            "data_availability 128\noverhead 72\n"
+        );
+    }
+}
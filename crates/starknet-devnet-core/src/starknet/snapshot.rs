@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashMap};
+
+use blockifier::bouncer::BouncerWeights;
+use blockifier::context::BlockContext;
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_types::felt::TransactionHash;
+use starknet_types::rpc::gas_modification::GasModification;
+
+use super::cheats::Cheats;
+use super::mempool::Mempool;
+use super::Starknet;
+use crate::blocks::StarknetBlocks;
+use crate::error::{DevnetResult, Error};
+use crate::messaging::MessagingBroker;
+use crate::state::state_diff::StateDiff;
+use crate::state::{CommittedClassStorage, StarknetState};
+use crate::transactions::StarknetTransactions;
+
+pub type SnapshotId = u64;
+
+/// Copy of all parts of `Starknet` which change after startup. Config and predeployed accounts
+/// are not included, since they are not modified while Devnet is running.
+struct StarknetSnapshot {
+    latest_state: StarknetState,
+    pending_state: StarknetState,
+    pending_state_diff: StateDiff,
+    pending_block_weights: BouncerWeights,
+    block_context: BlockContext,
+    blocks: StarknetBlocks,
+    transactions: StarknetTransactions,
+    pending_block_timestamp_shift: i64,
+    next_block_timestamp: Option<u64>,
+    next_block_gas: GasModification,
+    next_block_l1_da_mode: L1DataAvailabilityMode,
+    messaging: MessagingBroker,
+    rpc_contract_classes: CommittedClassStorage,
+    cheats: Cheats,
+    mempool: Mempool,
+    rejected_transactions: HashMap<TransactionHash, String>,
+}
+
+#[derive(Default)]
+pub(crate) struct Snapshots {
+    taken: BTreeMap<SnapshotId, StarknetSnapshot>,
+    next_id: SnapshotId,
+}
+
+impl Starknet {
+    /// Stores a copy of the current state of Devnet and returns the id under which it can be
+    /// reverted to. Not supported when using a database, since its content cannot be reverted.
+    pub fn take_snapshot(&mut self) -> DevnetResult<SnapshotId> {
+        if self.database.is_some() {
+            return Err(Error::UnsupportedAction {
+                msg: "Snapshots are not supported when using a database".into(),
+            });
+        }
+
+        let snapshot = StarknetSnapshot {
+            // states are committed after each transaction, so their historic copies are complete
+            latest_state: self.latest_state.clone_historic(),
+            pending_state: self.pending_state.clone_historic(),
+            pending_state_diff: self.pending_state_diff.clone(),
+            pending_block_weights: self.pending_block_weights,
+            block_context: self.block_context.clone(),
+            blocks: self.blocks.clone(),
+            transactions: self.transactions.clone(),
+            pending_block_timestamp_shift: self.pending_block_timestamp_shift,
+            next_block_timestamp: self.next_block_timestamp,
+            next_block_gas: self.next_block_gas.clone(),
+            next_block_l1_da_mode: self.next_block_l1_da_mode,
+            messaging: self.messaging.clone(),
+            rpc_contract_classes: self.rpc_contract_classes.read().clone(),
+            cheats: self.cheats.clone(),
+            mempool: self.mempool.clone(),
+            rejected_transactions: self.rejected_transactions.clone(),
+        };
+
+        let snapshot_id = self.snapshots.next_id;
+        self.snapshots.taken.insert(snapshot_id, snapshot);
+        self.snapshots.next_id += 1;
+
+        Ok(snapshot_id)
+    }
+
+    /// Returns the number of the first block which would be discarded by reverting to the snapshot
+    /// identified by `snapshot_id`, i.e. the block following the latest one when it was taken.
+    pub fn snapshot_next_block_number(&self, snapshot_id: SnapshotId) -> Option<u64> {
+        self.snapshots.taken.get(&snapshot_id).map(|snapshot| snapshot.blocks.next_block_number().0)
+    }
+
+    /// Restores Devnet to the state it was in when the snapshot identified by `snapshot_id` was
+    /// taken. The snapshot and all snapshots taken after it are discarded.
+    pub fn revert_to_snapshot(&mut self, snapshot_id: SnapshotId) -> DevnetResult<()> {
+        let snapshot =
+            self.snapshots.taken.remove(&snapshot_id).ok_or(Error::NoSnapshot { snapshot_id })?;
+        self.snapshots.taken.split_off(&snapshot_id);
+
+        self.latest_state = snapshot.latest_state;
+        self.pending_state = snapshot.pending_state;
+        self.pending_state_diff = snapshot.pending_state_diff;
+        self.state_tries = None;
+        self.pending_block_weights = snapshot.pending_block_weights;
+        self.block_context = snapshot.block_context;
+        self.blocks = snapshot.blocks;
+        self.transactions = snapshot.transactions;
+        self.pending_block_timestamp_shift = snapshot.pending_block_timestamp_shift;
+        self.next_block_timestamp = snapshot.next_block_timestamp;
+        self.next_block_gas = snapshot.next_block_gas;
+        self.next_block_l1_da_mode = snapshot.next_block_l1_da_mode;
+        self.messaging = snapshot.messaging;
+        self.cheats = snapshot.cheats;
+        self.mempool = snapshot.mempool;
+        self.rejected_transactions = snapshot.rejected_transactions;
+
+        // the class storage is shared by all states, so its content is replaced in place
+        *self.rpc_contract_classes.write() = snapshot.rpc_contract_classes;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_rs_core::types::BlockId;
+
+    use crate::error::Error;
+    use crate::starknet::starknet_config::StarknetConfig;
+    use crate::starknet::Starknet;
+
+    fn latest_block_number(starknet: &Starknet) -> u64 {
+        starknet.get_latest_block().unwrap().block_number().0
+    }
+
+    #[test]
+    fn revert_restores_blocks() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        let snapshot_id = starknet.take_snapshot().unwrap();
+
+        starknet.create_block().unwrap();
+        starknet.create_block().unwrap();
+        assert_eq!(latest_block_number(&starknet), 2);
+
+        starknet.revert_to_snapshot(snapshot_id).unwrap();
+        assert_eq!(latest_block_number(&starknet), 0);
+        assert!(starknet.get_block(&BlockId::Number(1)).is_err());
+
+        // block numbering continues from the restored latest block
+        starknet.create_block().unwrap();
+        assert_eq!(latest_block_number(&starknet), 1);
+    }
+
+    #[test]
+    fn revert_discards_reverted_and_later_snapshots() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+
+        let first_id = starknet.take_snapshot().unwrap();
+        starknet.create_block().unwrap();
+        let second_id = starknet.take_snapshot().unwrap();
+        starknet.create_block().unwrap();
+        let third_id = starknet.take_snapshot().unwrap();
+
+        starknet.revert_to_snapshot(second_id).unwrap();
+        assert_eq!(latest_block_number(&starknet), 1);
+
+        for discarded_id in [second_id, third_id] {
+            match starknet.revert_to_snapshot(discarded_id) {
+                Err(Error::NoSnapshot { snapshot_id }) => assert_eq!(snapshot_id, discarded_id),
+                other => panic!("Unexpected result: {other:?}"),
+            }
+        }
+
+        starknet.revert_to_snapshot(first_id).unwrap();
+        assert_eq!(latest_block_number(&starknet), 0);
+    }
+}
@@ -1,7 +1,13 @@
-use std::num::NonZeroU128;
+use std::num::{NonZeroU128, NonZeroU64};
+use std::path::Path;
 
+use blockifier::bouncer::BouncerWeights;
+use blockifier::versioned_constants::{
+    StarknetVersion as BlockifierStarknetVersion, VersionedConstants,
+};
 use clap::Error;
 use serde::{Serialize, Serializer};
+use starknet_api::data_availability::L1DataAvailabilityMode;
 use starknet_rs_core::types::Felt;
 use starknet_types::chain_id::ChainId;
 use starknet_types::contract_class::ContractClass;
@@ -9,11 +15,15 @@ use starknet_types::rpc::state::Balance;
 use starknet_types::traits::HashProducer;
 use url::Url;
 
+use super::gas_price_schedule::GasPriceSchedule;
+use super::genesis::Genesis;
 use crate::constants::{
     CAIRO_1_ACCOUNT_CONTRACT_SIERRA, CAIRO_1_ERC20_CONTRACT, CAIRO_1_ERC20_CONTRACT_CLASS_HASH,
     DEVNET_DEFAULT_CHAIN_ID, DEVNET_DEFAULT_DATA_GAS_PRICE, DEVNET_DEFAULT_GAS_PRICE,
     DEVNET_DEFAULT_INITIAL_BALANCE, DEVNET_DEFAULT_TEST_SEED, DEVNET_DEFAULT_TOTAL_ACCOUNTS,
 };
+use crate::error::{DevnetResult, Error as DevnetError};
+use crate::utils::custom_bouncer_config;
 
 #[derive(Copy, Clone, Debug, clap::ValueEnum, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -23,6 +33,16 @@ pub enum DumpOn {
     Request,
 }
 
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpFormat {
+    /// The list of received requests, which are replayed on loading
+    #[default]
+    Events,
+    /// The state of Devnet, which is restored without re-execution on loading
+    State,
+}
+
 #[derive(Default, Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum, Serialize)]
 #[serde(rename_all = "snake_case")]
 #[clap(rename_all = "snake_case")]
@@ -32,6 +52,173 @@ pub enum StateArchiveCapacity {
     Full,
 }
 
+/// The order in which ready transactions of the mempool are executed
+#[derive(Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
+pub enum MempoolOrdering {
+    /// In the order of receiving
+    Fifo,
+    /// By tip (v3) or max fee (older versions), highest first
+    FeePriority,
+}
+
+/// What happens to a transaction which doesn't fit into the pending block
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
+pub enum FullBlockPolicy {
+    /// The pending block is sealed and the transaction is executed in the next one
+    #[default]
+    Seal,
+    /// The transaction is rejected
+    Reject,
+    /// The transaction is left in the mempool until the next block is created
+    Queue,
+}
+
+/// Where the state diffs of blocks are published on L1, which determines how their data
+/// availability is charged
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
+pub enum L1DaMode {
+    /// Charged in L1 data gas
+    #[default]
+    Blob,
+    /// Charged in L1 gas
+    Calldata,
+}
+
+impl From<L1DaMode> for L1DataAvailabilityMode {
+    fn from(value: L1DaMode) -> Self {
+        match value {
+            L1DaMode::Blob => L1DataAvailabilityMode::Blob,
+            L1DaMode::Calldata => L1DataAvailabilityMode::Calldata,
+        }
+    }
+}
+
+/// Starknet versions whose versioned constants are bundled with Devnet
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum, Serialize)]
+pub enum StarknetVersion {
+    #[value(name = "0.13.0")]
+    #[serde(rename = "0.13.0")]
+    V0_13_0,
+    #[value(name = "0.13.1")]
+    #[serde(rename = "0.13.1")]
+    V0_13_1,
+    #[value(name = "0.13.1.1")]
+    #[serde(rename = "0.13.1.1")]
+    V0_13_1_1,
+    #[value(name = "0.13.2")]
+    #[serde(rename = "0.13.2")]
+    V0_13_2,
+    #[default]
+    #[value(name = "0.13.2.1")]
+    #[serde(rename = "0.13.2.1")]
+    V0_13_2_1,
+}
+
+impl StarknetVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StarknetVersion::V0_13_0 => "0.13.0",
+            StarknetVersion::V0_13_1 => "0.13.1",
+            StarknetVersion::V0_13_1_1 => "0.13.1.1",
+            StarknetVersion::V0_13_2 => "0.13.2",
+            StarknetVersion::V0_13_2_1 => "0.13.2.1",
+        }
+    }
+
+    /// The constants (e.g. fee costs and step limits) with which transactions are executed in this
+    /// version, as shipped with blockifier. The latest version uses the latest constants.
+    pub fn versioned_constants(&self) -> VersionedConstants {
+        let blockifier_version = match self {
+            StarknetVersion::V0_13_0 => BlockifierStarknetVersion::V0_13_0,
+            StarknetVersion::V0_13_1 => BlockifierStarknetVersion::V0_13_1,
+            StarknetVersion::V0_13_1_1 => BlockifierStarknetVersion::V0_13_1_1,
+            StarknetVersion::V0_13_2 => BlockifierStarknetVersion::V0_13_2,
+            StarknetVersion::V0_13_2_1 => BlockifierStarknetVersion::Latest,
+        };
+
+        VersionedConstants::get(blockifier_version).clone()
+    }
+}
+
+impl std::fmt::Display for StarknetVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Loads custom versioned constants from the JSON file at `path`, which has the format of the
+/// versioned constants of blockifier.
+pub fn load_versioned_constants(path: &Path) -> DevnetResult<VersionedConstants> {
+    VersionedConstants::try_from(path).map_err(|err| DevnetError::DeserializationError {
+        origin: format!("Invalid versioned constants file {}: {err}", path.display()),
+    })
+}
+
+/// Loads the maximum weights of a block from a JSON file. Weights missing from the file keep
+/// their default values.
+pub fn load_block_capacity(path: &Path) -> DevnetResult<BouncerWeights> {
+    let invalid_file = |reason: String| DevnetError::DeserializationError {
+        origin: format!("Invalid block capacity file {}: {reason}", path.display()),
+    };
+
+    let content = std::fs::read_to_string(path).map_err(|source| DevnetError::ReadFileError {
+        source,
+        path: path.display().to_string(),
+    })?;
+    let weights: serde_json::Value =
+        serde_json::from_str(&content).map_err(|err| invalid_file(err.to_string()))?;
+
+    let mut capacity = serde_json::to_value(custom_bouncer_config().block_max_capacity)
+        .map_err(|err| DevnetError::SerializationError { origin: err.to_string() })?;
+    merge_weights(&mut capacity, weights).map_err(invalid_file)?;
+
+    serde_json::from_value(capacity).map_err(|err| invalid_file(err.to_string()))
+}
+
+/// Loads a gas price schedule from a JSON file.
+pub fn load_gas_price_schedule(path: &Path) -> DevnetResult<GasPriceSchedule> {
+    let invalid_file = |reason: String| DevnetError::DeserializationError {
+        origin: format!("Invalid gas price schedule file {}: {reason}", path.display()),
+    };
+
+    let content = std::fs::read_to_string(path).map_err(|source| DevnetError::ReadFileError {
+        source,
+        path: path.display().to_string(),
+    })?;
+    let schedule: GasPriceSchedule =
+        serde_json::from_str(&content).map_err(|err| invalid_file(err.to_string()))?;
+    schedule.validate().map_err(invalid_file)?;
+
+    Ok(schedule)
+}
+
+/// Overwrites the weights in `target` with those in `source`, which may be a subset of them
+fn merge_weights(target: &mut serde_json::Value, source: serde_json::Value) -> Result<(), String> {
+    let (serde_json::Value::Object(target), serde_json::Value::Object(source)) = (target, source)
+    else {
+        return Err("expected an object of weights".to_string());
+    };
+
+    for (name, value) in source {
+        let Some(target_value) = target.get_mut(&name) else {
+            return Err(format!("unknown weight {name}"));
+        };
+        if target_value.is_object() {
+            merge_weights(target_value, value)?;
+        } else {
+            *target_value = value;
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BlockGenerationOn {
@@ -67,6 +254,10 @@ pub struct ForkConfig {
     #[serde(serialize_with = "serialize_config_url")]
     pub url: Option<Url>,
     pub block_number: Option<u64>,
+    pub cache_dir: Option<String>,
+    /// If set, new blocks take the gas prices of this many origin blocks up to and including the
+    /// forking block, one block after another
+    pub replay_gas_prices: Option<NonZeroU64>,
 }
 
 pub fn serialize_config_url<S>(url: &Option<Url>, serializer: S) -> Result<S::Ok, S::Error>
@@ -107,12 +298,29 @@ pub struct StarknetConfig {
     pub gas_price_fri: NonZeroU128,
     pub data_gas_price_wei: NonZeroU128,
     pub data_gas_price_fri: NonZeroU128,
+    pub l1_da_mode: L1DaMode,
+    pub gas_price_schedule_path: Option<String>,
+    /// If set, determines the gas prices of the blocks after the genesis block
+    #[serde(skip_serializing)]
+    pub gas_price_schedule: Option<GasPriceSchedule>,
     #[serde(serialize_with = "serialize_chain_id")]
     pub chain_id: ChainId,
     pub dump_on: Option<DumpOn>,
     pub dump_path: Option<String>,
+    pub dump_format: DumpFormat,
+    pub db_path: Option<String>,
     pub block_generation_on: BlockGenerationOn,
+    /// If set, transactions are held in a mempool until a block is produced
+    pub mempool: Option<MempoolOrdering>,
+    pub block_capacity_path: Option<String>,
+    /// The maximum weights of the transactions in a block. If not set, blocks are unlimited.
+    #[serde(skip_serializing)]
+    pub block_capacity: Option<BouncerWeights>,
+    pub full_block_policy: FullBlockPolicy,
+    /// If true, flushing the postman marks all blocks as accepted on L1
+    pub accept_on_l1_on_flush: bool,
     pub lite_mode: bool,
+    pub calculate_commitments: bool,
     pub state_archive: StateArchiveCapacity,
     pub fork_config: ForkConfig,
     pub eth_erc20_class_hash: Felt,
@@ -121,6 +329,15 @@ pub struct StarknetConfig {
     pub eth_erc20_contract_class: String,
     #[serde(skip_serializing)]
     pub strk_erc20_contract_class: String,
+    pub genesis_path: Option<String>,
+    #[serde(skip_serializing)]
+    pub genesis: Option<Genesis>,
+    /// Reported in block headers; also selects the versioned constants, unless they are loaded
+    /// from `versioned_constants_path`
+    pub starknet_version: StarknetVersion,
+    pub versioned_constants_path: Option<String>,
+    #[serde(skip_serializing)]
+    pub versioned_constants: VersionedConstants,
 }
 
 #[allow(clippy::unwrap_used)]
@@ -143,17 +360,105 @@ impl Default for StarknetConfig {
             gas_price_fri: DEVNET_DEFAULT_GAS_PRICE,
             data_gas_price_wei: DEVNET_DEFAULT_DATA_GAS_PRICE,
             data_gas_price_fri: DEVNET_DEFAULT_DATA_GAS_PRICE,
+            l1_da_mode: L1DaMode::default(),
+            gas_price_schedule_path: None,
+            gas_price_schedule: None,
             chain_id: DEVNET_DEFAULT_CHAIN_ID,
             dump_on: None,
             dump_path: None,
+            dump_format: DumpFormat::default(),
+            db_path: None,
             block_generation_on: BlockGenerationOn::Transaction,
+            mempool: None,
+            block_capacity_path: None,
+            block_capacity: None,
+            full_block_policy: FullBlockPolicy::default(),
+            accept_on_l1_on_flush: false,
             lite_mode: false,
+            calculate_commitments: false,
             state_archive: StateArchiveCapacity::default(),
             fork_config: ForkConfig::default(),
             eth_erc20_class_hash: CAIRO_1_ERC20_CONTRACT_CLASS_HASH,
             strk_erc20_class_hash: CAIRO_1_ERC20_CONTRACT_CLASS_HASH,
             eth_erc20_contract_class: CAIRO_1_ERC20_CONTRACT.to_string(),
             strk_erc20_contract_class: CAIRO_1_ERC20_CONTRACT.to_string(),
+            genesis_path: None,
+            genesis: None,
+            starknet_version: StarknetVersion::default(),
+            versioned_constants_path: None,
+            versioned_constants: StarknetVersion::default().versioned_constants(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blockifier::versioned_constants::{
+        ResourceCost, StarknetVersion as BlockifierStarknetVersion, VersionedConstants,
+    };
+    use clap::ValueEnum;
+
+    use super::{load_block_capacity, StarknetVersion};
+    use crate::utils::custom_bouncer_config;
+
+    #[test]
+    fn versioned_constants_equal_to_those_of_blockifier() {
+        for (version, blockifier_version, segment_arena_cells) in [
+            (StarknetVersion::V0_13_0, BlockifierStarknetVersion::V0_13_0, true),
+            (StarknetVersion::V0_13_1, BlockifierStarknetVersion::V0_13_1, true),
+            (StarknetVersion::V0_13_1_1, BlockifierStarknetVersion::V0_13_1_1, true),
+            (StarknetVersion::V0_13_2, BlockifierStarknetVersion::V0_13_2, false),
+            (StarknetVersion::V0_13_2_1, BlockifierStarknetVersion::Latest, false),
+        ] {
+            let constants = version.versioned_constants();
+            let blockifier_constants = VersionedConstants::get(blockifier_version);
+            // the constants don't implement PartialEq, but share the maps with the originals
+            assert_eq!(format!("{constants:?}"), format!("{blockifier_constants:?}"), "{version}");
+            assert_eq!(constants.segment_arena_cells, segment_arena_cells, "{version}");
         }
+        assert_eq!(StarknetVersion::value_variants().len(), 5);
+    }
+
+    #[test]
+    fn version_0_13_2_differs_from_the_latest() {
+        let v0_13_2 = StarknetVersion::V0_13_2.versioned_constants();
+        let latest = StarknetVersion::default().versioned_constants();
+        // in milligas
+        assert_eq!(v0_13_2.l2_resource_gas_costs.gas_per_code_byte, ResourceCost::new(875, 1000));
+        assert_eq!(latest.l2_resource_gas_costs.gas_per_code_byte, ResourceCost::new(32, 1000));
+    }
+
+    #[test]
+    fn older_versions_have_different_constants() {
+        let v0_13_0 = StarknetVersion::V0_13_0.versioned_constants();
+        let latest = StarknetVersion::default().versioned_constants();
+        assert_eq!(v0_13_0.invoke_tx_max_n_steps, 3_000_000);
+        assert_ne!(v0_13_0.invoke_tx_max_n_steps, latest.invoke_tx_max_n_steps);
+    }
+
+    #[test]
+    fn block_capacity_loaded_with_defaults_of_missing_weights() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(
+            &mut file,
+            br#"{ "n_steps": 1000000, "builtin_count": { "pedersen": 100 } }"#,
+        )
+        .unwrap();
+
+        let capacity = load_block_capacity(file.path()).unwrap();
+        let default_capacity = custom_bouncer_config().block_max_capacity;
+        assert_eq!(capacity.n_steps, 1_000_000);
+        assert_eq!(capacity.builtin_count.pedersen, 100);
+        assert_eq!(capacity.builtin_count.poseidon, default_capacity.builtin_count.poseidon);
+        assert_eq!(capacity.n_events, default_capacity.n_events);
+    }
+
+    #[test]
+    fn block_capacity_with_unknown_weight_rejected() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, br#"{ "n_stepz": 1000000 }"#).unwrap();
+
+        let err = load_block_capacity(file.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown weight n_stepz"), "{err}");
     }
 }
@@ -1,33 +1,53 @@
+use std::borrow::Cow;
+
 use starknet_rs_core::types::BlockId;
 
 use super::Starknet;
 use crate::error::DevnetResult;
 use crate::state::state_update::StateUpdate;
+use crate::traits::HashIdentified;
 
 pub fn state_update_by_block_id(
     starknet: &Starknet,
     block_id: &BlockId,
 ) -> DevnetResult<StateUpdate> {
-    let block = starknet.blocks.get_by_block_id(block_id).ok_or(crate::error::Error::NoBlock)?;
-    let state_diff =
-        starknet.blocks.hash_to_state_diff.get(&block.block_hash()).cloned().unwrap_or_default();
+    let block = starknet.blocks.get_by_block_id(block_id)?.ok_or(crate::error::Error::NoBlock)?;
+    let state_diff = starknet
+        .blocks
+        .get_state_diff(&block.block_hash())?
+        .map(Cow::into_owned)
+        .unwrap_or_default();
+
+    // the genesis block has no parent, so its old root is zero
+    let old_root = starknet
+        .blocks
+        .get_by_hash(block.parent_hash())?
+        .map(|parent| parent.new_root())
+        .unwrap_or_default();
 
-    Ok(StateUpdate::new(block.block_hash(), state_diff))
+    Ok(StateUpdate::new(block.block_hash(), block.new_root(), old_root, state_diff))
 }
 
 #[cfg(test)]
 mod tests {
 
     use starknet_api::transaction::Fee;
-    use starknet_rs_core::types::{Felt, TransactionExecutionStatus, TransactionFinalityStatus};
+    use starknet_rs_core::types::{
+        BlockId, BlockTag, Felt, TransactionExecutionStatus, TransactionFinalityStatus,
+    };
+    use starknet_types::contract_address::ContractAddress;
     use starknet_types::contract_class::ContractClass;
+    use starknet_types::patricia_key::PatriciaKey;
     use starknet_types::rpc::state::ThinStateDiff;
     use starknet_types::rpc::transactions::broadcasted_declare_transaction_v2::BroadcastedDeclareTransactionV2;
     use starknet_types::traits::HashProducer;
 
+    use super::state_update_by_block_id;
+    use crate::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use crate::starknet::starknet_config::StarknetConfig;
     use crate::starknet::tests::setup_starknet_with_no_signature_check_account;
+    use crate::starknet::Starknet;
     use crate::state::state_diff::StateDiff;
-    use crate::traits::HashIdentifiedMut;
     use crate::utils::calculate_casm_hash;
     use crate::utils::test_utils::dummy_cairo_1_contract_class;
 
@@ -63,7 +83,7 @@ mod tests {
                 )),
             )
             .unwrap();
-        let tx = starknet.transactions.get_by_hash_mut(&txn_hash).unwrap();
+        let tx = starknet.transactions.get(&txn_hash).unwrap().unwrap();
         assert_eq!(tx.finality_status, TransactionFinalityStatus::AcceptedOnL2);
         assert_eq!(tx.execution_result.status(), TransactionExecutionStatus::Succeeded);
 
@@ -88,4 +108,56 @@ mod tests {
             (expected_state_diff.deprecated_declared_classes, expected_state_diff.declared_classes);
         assert_eq!(class_diff, expected_class_diff);
     }
+
+    #[test]
+    fn old_root_is_new_root_of_parent_block() {
+        let config = StarknetConfig { calculate_commitments: true, ..Default::default() };
+        let mut starknet = Starknet::new(&config).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        let genesis_update = state_update_by_block_id(&starknet, &BlockId::Number(0)).unwrap();
+        assert_eq!(genesis_update.old_root, Felt::ZERO);
+        assert_ne!(genesis_update.new_root, Felt::ZERO);
+
+        let parent_root = starknet.get_latest_block().unwrap().new_root();
+        starknet.set_nonce(contract_address, Felt::ONE).unwrap();
+
+        let state_update =
+            state_update_by_block_id(&starknet, &BlockId::Tag(BlockTag::Latest)).unwrap();
+        assert_eq!(state_update.old_root, parent_root);
+        assert_eq!(state_update.new_root, starknet.get_latest_block().unwrap().new_root());
+        assert_ne!(state_update.new_root, state_update.old_root);
+    }
+
+    #[test]
+    fn roots_of_updated_tries_are_roots_of_whole_state() {
+        let config = StarknetConfig { calculate_commitments: true, ..Default::default() };
+        let mut starknet = Starknet::new(&config).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        let key = PatriciaKey::new(Felt::from(5)).unwrap();
+        let new_contract_address = ContractAddress::new(Felt::from(1234)).unwrap();
+
+        // adding, changing and removing values, and changing a contract without storage
+        starknet.set_storage_at(contract_address, key, Felt::ONE).unwrap();
+        starknet.set_storage_at(contract_address, key, Felt::TWO).unwrap();
+        starknet.set_nonce(new_contract_address, Felt::ONE).unwrap();
+        starknet.set_storage_at(contract_address, key, Felt::ZERO).unwrap();
+        for block_number in 1..=4 {
+            let block = starknet.get_block(&BlockId::Number(block_number)).unwrap();
+            assert_ne!(block.new_root(), Felt::ZERO);
+        }
+
+        let latest_root = starknet.get_latest_block().unwrap().new_root();
+        assert_eq!(latest_root, starknet.latest_state.build_state_tries().state_root());
+    }
+
+    #[test]
+    fn roots_are_zero_if_commitments_are_not_calculated() {
+        let mut starknet = Starknet::new(&StarknetConfig::default()).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        starknet.set_nonce(contract_address, Felt::ONE).unwrap();
+
+        let state_update =
+            state_update_by_block_id(&starknet, &BlockId::Tag(BlockTag::Latest)).unwrap();
+        assert_eq!((state_update.old_root, state_update.new_root), (Felt::ZERO, Felt::ZERO));
+    }
 }
@@ -0,0 +1,140 @@
+use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+use starknet_types::contract_address::ContractAddress;
+use starknet_types::rpc::storage_proof::{ContractStorageKeys, StorageProof};
+
+use super::starknet_config::StateArchiveCapacity;
+use super::Starknet;
+use crate::error::{DevnetResult, Error};
+
+/// Proves the requested classes, contracts and storage keys against the state of `block_id`. Only
+/// the states of accepted blocks kept with full state archive capacity can be proven, and only if
+/// state roots are calculated, so that the proven roots match the state root of the block.
+pub fn storage_proof_by_block_id(
+    starknet: &Starknet,
+    block_id: &BlockId,
+    class_hashes: &[Felt],
+    contract_addresses: &[ContractAddress],
+    contracts_storage_keys: &[ContractStorageKeys],
+) -> DevnetResult<StorageProof> {
+    let block = starknet.get_block(block_id)?;
+    if block_id == &BlockId::Tag(BlockTag::Pending)
+        || starknet.config.state_archive != StateArchiveCapacity::Full
+    {
+        return Err(Error::NoStateAtBlock { block_id: *block_id });
+    }
+    if !starknet.config.calculate_commitments || starknet.config.lite_mode {
+        let msg = "Storage proofs require calculating commitments, which is not done in lite mode";
+        return Err(Error::UnsupportedAction { msg: msg.into() });
+    }
+    // states read from the state history have no tries to prove against
+    if starknet.database.is_some() {
+        let msg = "Storage proofs are not supported when using a database";
+        return Err(Error::UnsupportedAction { msg: msg.into() });
+    }
+
+    let state = starknet
+        .blocks
+        .hash_to_state
+        .peek(&block.block_hash())
+        .ok_or(Error::NoStateAtBlock { block_id: *block_id })?;
+
+    Ok(state.get_storage_proof(
+        block.block_hash(),
+        class_hashes,
+        contract_addresses,
+        contracts_storage_keys,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::patricia_key::PatriciaKey;
+    use starknet_types::rpc::storage_proof::{ContractStorageKeys, GlobalRoots};
+    use starknet_types_core::hash::{Poseidon, StarkHash};
+
+    use crate::constants::{CAIRO_1_ERC20_CONTRACT_CLASS_HASH, ETH_ERC20_CONTRACT_ADDRESS};
+    use crate::error::Error;
+    use crate::starknet::starknet_config::{StarknetConfig, StateArchiveCapacity};
+    use crate::starknet::Starknet;
+
+    fn global_state_root(global_roots: &GlobalRoots) -> Felt {
+        Poseidon::hash_array(&[
+            Felt::from_bytes_be_slice(b"STARKNET_STATE_V0"),
+            global_roots.contracts_tree_root,
+            global_roots.classes_tree_root,
+        ])
+    }
+
+    #[test]
+    fn global_roots_match_state_root_of_block() {
+        let config = StarknetConfig {
+            state_archive: StateArchiveCapacity::Full,
+            calculate_commitments: true,
+            ..Default::default()
+        };
+        let mut starknet = Starknet::new(&config).unwrap();
+        let contract_address = ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap();
+        let key = Felt::from(5);
+        starknet
+            .set_storage_at(contract_address, PatriciaKey::new(key).unwrap(), Felt::ONE)
+            .unwrap();
+
+        let contracts_storage_keys =
+            [ContractStorageKeys { contract_address, storage_keys: vec![key] }];
+        for block_number in [0, 1] {
+            let block_id = BlockId::Number(block_number);
+            let storage_proof = starknet
+                .get_storage_proof(
+                    &block_id,
+                    &[CAIRO_1_ERC20_CONTRACT_CLASS_HASH],
+                    &[contract_address],
+                    &contracts_storage_keys,
+                )
+                .unwrap();
+
+            let block = starknet.get_block(&block_id).unwrap();
+            assert_eq!(storage_proof.global_roots.block_hash, block.block_hash());
+            assert_eq!(global_state_root(&storage_proof.global_roots), block.new_root());
+
+            assert!(!storage_proof.classes_proof.is_empty());
+            assert!(!storage_proof.contracts_proof.nodes.is_empty());
+            assert_eq!(
+                storage_proof.contracts_proof.contract_leaves_data[0].class_hash,
+                CAIRO_1_ERC20_CONTRACT_CLASS_HASH
+            );
+            assert_eq!(storage_proof.contracts_storage_proofs.len(), 1);
+        }
+    }
+
+    #[test]
+    fn storage_proof_requires_state_of_accepted_block() {
+        let config = StarknetConfig { calculate_commitments: true, ..Default::default() };
+        let mut starknet = Starknet::new(&config).unwrap();
+        let result = starknet.get_storage_proof(&BlockId::Tag(BlockTag::Latest), &[], &[], &[]);
+        assert!(matches!(result, Err(Error::NoStateAtBlock { .. })));
+
+        starknet.config.state_archive = StateArchiveCapacity::Full;
+        let result = starknet.get_storage_proof(&BlockId::Tag(BlockTag::Pending), &[], &[], &[]);
+        assert!(matches!(result, Err(Error::NoStateAtBlock { .. })));
+
+        let result = starknet.get_storage_proof(&BlockId::Number(1), &[], &[], &[]);
+        assert!(matches!(result, Err(Error::NoBlock)));
+    }
+
+    #[test]
+    fn storage_proof_requires_calculating_commitments() {
+        for (calculate_commitments, lite_mode) in [(false, false), (true, true)] {
+            let config = StarknetConfig {
+                state_archive: StateArchiveCapacity::Full,
+                calculate_commitments,
+                lite_mode,
+                ..Default::default()
+            };
+            let starknet = Starknet::new(&config).unwrap();
+            let result = starknet.get_storage_proof(&BlockId::Tag(BlockTag::Latest), &[], &[], &[]);
+            assert!(matches!(result, Err(Error::UnsupportedAction { .. })));
+        }
+    }
+}
@@ -1,22 +1,30 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use blockifier::state::cached_state::CachedState;
+use blockifier::state::cached_state::{CachedState, StateMaps};
 use blockifier::state::state_api::{State, StateReader};
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use parking_lot::RwLock;
-use starknet_api::core::CompiledClassHash;
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{CompiledClassHash, Nonce};
 use starknet_rs_core::types::Felt;
 use starknet_types::contract_address::ContractAddress;
-use starknet_types::contract_class::ContractClass;
+use starknet_types::contract_class::{compile_sierra_contract, ContractClass};
 use starknet_types::felt::ClassHash;
+use starknet_types::patricia_key::{PatriciaKey, StorageKey};
+use starknet_types::rpc::storage_proof::{ContractStorageKeys, StorageProof};
 
+use self::state_commitment::StateTries;
 use self::state_diff::StateDiff;
+use self::state_history::StateHistoryReader;
 use self::state_readers::DictState;
 use crate::error::{DevnetResult, Error};
 use crate::starknet::defaulter::StarknetDefaulter;
 use crate::utils::calculate_casm_hash;
 
+pub(crate) mod state_commitment;
 pub(crate) mod state_diff;
+pub(crate) mod state_history;
 pub(crate) mod state_readers;
 pub mod state_update;
 
@@ -70,6 +78,8 @@ pub struct CommittedClassStorage {
     committed: HashMap<ClassHash, (ContractClass, u64)>,
     /// Remembers all classes committed at a block
     block_number_to_classes: HashMap<u64, Vec<ClassHash>>,
+    /// CASM of Cairo 1 classes, compiled on declaration or on the first request
+    casm: HashMap<ClassHash, CasmContractClass>,
 }
 
 impl CommittedClassStorage {
@@ -78,6 +88,16 @@ impl CommittedClassStorage {
         self.staging.insert(class_hash, contract_class);
     }
 
+    /// Stores the CASM of the class with `class_hash`, so that it doesn't need to be recompiled.
+    pub fn insert_casm(&mut self, class_hash: ClassHash, casm: CasmContractClass) {
+        self.casm.insert(class_hash, casm);
+    }
+
+    /// Returns the stored CASM of the class, regardless of when it was declared.
+    pub fn get_casm(&self, class_hash: &ClassHash) -> Option<&CasmContractClass> {
+        self.casm.get(class_hash)
+    }
+
     /// Commits all of the staged classes and returns them, together with their hashes.
     pub fn commit(&mut self, block_number: u64) -> HashMap<ClassHash, ContractClass> {
         let mut newly_committed = HashMap::new();
@@ -135,14 +155,62 @@ impl CommittedClassStorage {
         if let Some(removable) = self.block_number_to_classes.remove(&block_number) {
             for class_hash in removable {
                 self.committed.remove(&class_hash);
+                self.casm.remove(&class_hash);
             }
         }
     }
 
     /// Removes all staged classes.
     pub fn empty_staging(&mut self) {
+        for class_hash in self.staging.keys() {
+            if !self.committed.contains_key(class_hash) {
+                self.casm.remove(class_hash);
+            }
+        }
         self.staging = Default::default();
     }
+
+    /// Returns all committed classes, together with the numbers of blocks they were committed at.
+    pub(crate) fn iter_committed(
+        &self,
+    ) -> impl Iterator<Item = (&ClassHash, &(ContractClass, u64))> {
+        self.committed.iter()
+    }
+}
+
+/// Serializable content of a committed state. Compiled classes are represented only by their
+/// hashes, since they can be recreated from the classes in `CommittedClassStorage`.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct SerializableState {
+    storage: HashMap<ContractAddress, HashMap<StorageKey, Felt>>,
+    nonces: HashMap<ContractAddress, Felt>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    compiled_class_hashes: HashMap<ClassHash, Felt>,
+    declared_classes: Vec<ClassHash>,
+}
+
+impl SerializableState {
+    pub(crate) fn declared_classes(&self) -> &[ClassHash] {
+        &self.declared_classes
+    }
+
+    /// Applies the changes of `state_diff`, turning this into the state the diff leads to.
+    pub(crate) fn apply_diff(&mut self, state_diff: &StateDiff) {
+        for (address, storage_updates) in &state_diff.storage_updates {
+            self.storage.entry(*address).or_default().extend(storage_updates);
+        }
+        self.nonces.extend(&state_diff.address_to_nonce);
+        self.class_hashes.extend(&state_diff.address_to_class_hash);
+        self.compiled_class_hashes.extend(&state_diff.class_hash_to_compiled_class_hash);
+
+        for class_hash in
+            state_diff.declared_contracts.iter().chain(&state_diff.cairo_0_declared_contracts)
+        {
+            if !self.declared_classes.contains(class_hash) {
+                self.declared_classes.push(*class_hash);
+            }
+        }
+    }
 }
 
 pub struct StarknetState {
@@ -192,6 +260,14 @@ impl StarknetState {
         Ok(diff)
     }
 
+    /// Discards the changes made since the last commit, e.g. by a transaction which is not stored.
+    /// The changes are only held in the cache, so the committed state underneath is kept as is.
+    pub(crate) fn discard_uncommitted(&mut self) {
+        let committed = std::mem::take(&mut self.state.state);
+        self.state = CachedState::new(committed);
+        self.rpc_contract_classes.write().empty_staging();
+    }
+
     pub fn assert_contract_deployed(
         &mut self,
         contract_address: ContractAddress,
@@ -218,9 +294,9 @@ impl StarknetState {
                 starknet_api::core::CompiledClassHash(casm_hash),
             )?;
         }
-        for (address, _nonce) in state_diff.address_to_nonce {
-            // assuming that historic_state.get_nonce(address) == _nonce - 1
-            historic_state.increment_nonce(address.try_into()?)?;
+        for (address, nonce) in state_diff.address_to_nonce {
+            // not incremented, since the nonce may have been set directly
+            historic_state.set_nonce(address.try_into()?, Nonce(nonce));
         }
         for (address, storage_updates) in state_diff.storage_updates {
             let core_address = address.try_into()?;
@@ -242,6 +318,148 @@ impl StarknetState {
         Ok(&self.historic_state)
     }
 
+    /// Unlike `State::increment_nonce`, allows setting the nonce to an arbitrary value.
+    pub(crate) fn set_nonce(
+        &mut self,
+        contract_address: starknet_api::core::ContractAddress,
+        nonce: Felt,
+    ) -> DevnetResult<()> {
+        // the initial value has to be cached for the write to be included in the state diff
+        self.state.get_nonce_at(contract_address)?;
+
+        let nonce_update = StateMaps {
+            nonces: HashMap::from([(contract_address, Nonce(nonce))]),
+            ..Default::default()
+        };
+        self.state.update_cache(&nonce_update, Default::default());
+        Ok(())
+    }
+
+    /// Returns the committed state, i.e. without the changes cached since the last commit.
+    pub(crate) fn committed_state(&self) -> &DictState {
+        &self.historic_state
+    }
+
+    /// Returns the serializable content of the committed state.
+    pub(crate) fn to_serializable(&self) -> SerializableState {
+        let dict_state = &self.historic_state;
+
+        let mut storage: HashMap<ContractAddress, HashMap<StorageKey, Felt>> = HashMap::new();
+        for ((address, key), value) in &dict_state.storage_view {
+            storage.entry((*address).into()).or_default().insert(PatriciaKey::from(key.0), *value);
+        }
+
+        SerializableState {
+            storage,
+            nonces: dict_state
+                .address_to_nonce
+                .iter()
+                .map(|(address, nonce)| ((*address).into(), nonce.0))
+                .collect(),
+            class_hashes: dict_state
+                .address_to_class_hash
+                .iter()
+                .map(|(address, class_hash)| ((*address).into(), class_hash.0))
+                .collect(),
+            compiled_class_hashes: dict_state
+                .class_hash_to_compiled_class_hash
+                .iter()
+                .map(|(class_hash, compiled_class_hash)| (class_hash.0, compiled_class_hash.0))
+                .collect(),
+            declared_classes: dict_state.class_hash_to_class.keys().map(|hash| hash.0).collect(),
+        }
+    }
+
+    /// Creates a committed state out of its serializable content. The compiled versions of the
+    /// declared classes are taken from `compiled_classes`.
+    pub(crate) fn from_serializable(
+        serializable: SerializableState,
+        defaulter: StarknetDefaulter,
+        rpc_contract_classes: Arc<RwLock<CommittedClassStorage>>,
+        compiled_classes: &HashMap<ClassHash, blockifier::execution::contract_class::ContractClass>,
+    ) -> DevnetResult<Self> {
+        let mut dict_state = DictState::new(defaulter);
+
+        for (address, storage_updates) in serializable.storage {
+            let core_address = address.try_into()?;
+            for (key, value) in storage_updates {
+                dict_state.set_storage_at(core_address, key.try_into()?, value)?;
+            }
+        }
+        for (address, nonce) in serializable.nonces {
+            dict_state.set_nonce(address.try_into()?, Nonce(nonce));
+        }
+        for (address, class_hash) in serializable.class_hashes {
+            dict_state.set_class_hash_at(
+                address.try_into()?,
+                starknet_api::core::ClassHash(class_hash),
+            )?;
+        }
+        for (class_hash, compiled_class_hash) in serializable.compiled_class_hashes {
+            dict_state.set_compiled_class_hash(
+                starknet_api::core::ClassHash(class_hash),
+                CompiledClassHash(compiled_class_hash),
+            )?;
+        }
+        for class_hash in serializable.declared_classes {
+            let compiled_class = compiled_classes
+                .get(&class_hash)
+                .ok_or(Error::StateError(crate::error::StateError::NoneCasmClass(class_hash)))?;
+            dict_state.set_contract_class(
+                starknet_api::core::ClassHash(class_hash),
+                compiled_class.clone(),
+            )?;
+        }
+
+        Ok(Self {
+            state: CachedState::new(dict_state.clone()),
+            rpc_contract_classes,
+            historic_state: dict_state,
+        })
+    }
+
+    /// Creates a committed state whose values are read from `history` unless set later. The
+    /// compiled versions of the classes declared by then are taken from `compiled_classes`.
+    pub(crate) fn with_history(
+        history: StateHistoryReader,
+        defaulter: StarknetDefaulter,
+        rpc_contract_classes: Arc<RwLock<CommittedClassStorage>>,
+        compiled_classes: HashMap<ClassHash, blockifier::execution::contract_class::ContractClass>,
+    ) -> DevnetResult<Self> {
+        let mut dict_state = DictState::with_history(defaulter, history);
+        for (class_hash, compiled_class) in compiled_classes {
+            dict_state
+                .set_contract_class(starknet_api::core::ClassHash(class_hash), compiled_class)?;
+        }
+
+        Ok(Self {
+            state: CachedState::new(dict_state.clone()),
+            rpc_contract_classes,
+            historic_state: dict_state,
+        })
+    }
+
+    /// Builds the tries of the committed state
+    pub(crate) fn build_state_tries(&self) -> StateTries {
+        StateTries::new(&self.historic_state)
+    }
+
+    /// Proves the requested classes, contracts and storage keys against the committed state
+    pub(crate) fn get_storage_proof(
+        &self,
+        block_hash: Felt,
+        class_hashes: &[Felt],
+        contract_addresses: &[ContractAddress],
+        contracts_storage_keys: &[ContractStorageKeys],
+    ) -> StorageProof {
+        self.historic_state.get_storage_proof(
+            block_hash,
+            class_hashes,
+            contract_addresses,
+            contracts_storage_keys,
+        )
+    }
+
     pub fn clone_historic(&self) -> Self {
         Self {
             state: CachedState::new(self.historic_state.clone()),
@@ -366,6 +584,15 @@ impl CustomStateReader for StarknetState {
     }
 }
 
+/// Converts the CASM of a Cairo 1 class to the class executed by blockifier
+fn compile_casm(
+    casm: CasmContractClass,
+) -> DevnetResult<blockifier::execution::contract_class::ContractClass> {
+    let contract_class: blockifier::execution::contract_class::ContractClassV1 =
+        casm.try_into().map_err(|_| starknet_types::error::Error::ProgramError)?;
+    Ok(blockifier::execution::contract_class::ContractClass::V1(contract_class))
+}
+
 impl CustomState for StarknetState {
     /// writes directly to the most underlying state, skipping cache
     fn predeclare_contract_class(
@@ -373,27 +600,36 @@ impl CustomState for StarknetState {
         class_hash: ClassHash,
         contract_class: ContractClass,
     ) -> DevnetResult<()> {
-        let compiled_class = contract_class.clone().try_into()?;
         let class_hash = starknet_api::core::ClassHash(class_hash);
 
-        if let ContractClass::Cairo1(cairo_lang_contract_class) = &contract_class {
-            let casm_json = usc::compile_contract(
-                serde_json::to_value(cairo_lang_contract_class)
-                    .map_err(|err| Error::SerializationError { origin: err.to_string() })?,
-            )
-            .map_err(|err| {
-                Error::TypesError(starknet_types::error::Error::SierraCompilationError {
-                    reason: err.to_string(),
-                })
-            })?;
-
-            let casm_hash = starknet_api::core::CompiledClassHash(calculate_casm_hash(casm_json)?);
-
-            self.state.state.set_compiled_class_hash(class_hash, casm_hash)?;
+        let (compiled_class, casm) = match &contract_class {
+            ContractClass::Cairo0(_) => (contract_class.clone().try_into()?, None),
+            ContractClass::Cairo1(cairo_lang_contract_class) => {
+                let casm_json = usc::compile_contract(
+                    serde_json::to_value(cairo_lang_contract_class)
+                        .map_err(|err| Error::SerializationError { origin: err.to_string() })?,
+                )
+                .map_err(|err| {
+                    Error::TypesError(starknet_types::error::Error::SierraCompilationError {
+                        reason: err.to_string(),
+                    })
+                })?;
+
+                let casm_hash =
+                    starknet_api::core::CompiledClassHash(calculate_casm_hash(casm_json.clone())?);
+                self.state.state.set_compiled_class_hash(class_hash, casm_hash)?;
+
+                let casm: CasmContractClass = serde_json::from_value(casm_json)
+                    .map_err(|err| Error::DeserializationError { origin: err.to_string() })?;
+                (compile_casm(casm.clone())?, Some(casm))
+            }
         };
 
         self.state.state.set_contract_class(class_hash, compiled_class)?;
         let mut class_storage = self.rpc_contract_classes.write();
+        if let Some(casm) = casm {
+            class_storage.insert_casm(*class_hash, casm);
+        }
         class_storage.insert(*class_hash, contract_class);
         Ok(())
     }
@@ -404,7 +640,13 @@ impl CustomState for StarknetState {
         casm_hash: Option<starknet_types::felt::CompiledClassHash>,
         contract_class: ContractClass,
     ) -> DevnetResult<()> {
-        let compiled_class = contract_class.clone().try_into()?;
+        let (compiled_class, casm) = match &contract_class {
+            ContractClass::Cairo0(_) => (contract_class.clone().try_into()?, None),
+            ContractClass::Cairo1(sierra_contract_class) => {
+                let casm = compile_sierra_contract(sierra_contract_class)?;
+                (compile_casm(casm.clone())?, Some(casm))
+            }
+        };
 
         let class_hash = starknet_api::core::ClassHash(class_hash);
         if let Some(casm_hash) = casm_hash {
@@ -416,6 +658,9 @@ impl CustomState for StarknetState {
 
         self.set_contract_class(class_hash, compiled_class)?;
         let mut class_storage = self.rpc_contract_classes.write();
+        if let Some(casm) = casm {
+            class_storage.insert_casm(*class_hash, casm);
+        }
         class_storage.insert(*class_hash, contract_class);
         Ok(())
     }
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use starknet_rs_core::types::Felt;
+use starknet_types::contract_address::ContractAddress;
+use starknet_types::rpc::storage_proof::{
+    ContractLeafData, ContractStorageKeys, ContractsProof, GlobalRoots, StorageProof,
+};
+use starknet_types_core::hash::{Pedersen, Poseidon, StarkHash};
+
+use super::state_diff::StateDiff;
+use super::state_readers::DictState;
+use crate::patricia_trie::{
+    calculate_root, calculate_root_with_proof, IncrementalTrie, STATE_TRIE_HEIGHT,
+};
+
+const CONTRACT_STATE_HASH_VERSION: Felt = Felt::ZERO;
+
+fn contract_class_leaf_version() -> Felt {
+    Felt::from_bytes_be_slice(b"CONTRACT_CLASS_LEAF_V0")
+}
+
+fn global_state_version() -> Felt {
+    Felt::from_bytes_be_slice(b"STARKNET_STATE_V0")
+}
+
+/// The class hash, nonce and storage of a contract, keyed by contract address in the contract trie
+#[derive(Default)]
+struct ContractState {
+    class_hash: Felt,
+    nonce: Felt,
+    storage: Vec<(Felt, Felt)>,
+}
+
+impl ContractState {
+    fn storage_root(&self) -> Felt {
+        calculate_root::<Pedersen>(self.storage.iter().copied(), STATE_TRIE_HEIGHT)
+    }
+
+    fn leaf_data(&self) -> ContractLeafData {
+        ContractLeafData {
+            nonce: self.nonce,
+            class_hash: self.class_hash,
+            storage_root: self.storage_root(),
+        }
+    }
+}
+
+/// Returns the leaf of a contract in the contract trie. An address without any state is not part
+/// of the trie, so its leaf is zero.
+fn contract_hash(ContractLeafData { nonce, class_hash, storage_root }: ContractLeafData) -> Felt {
+    if class_hash == Felt::ZERO && nonce == Felt::ZERO && storage_root == Felt::ZERO {
+        return Felt::ZERO;
+    }
+
+    Pedersen::hash(
+        &Pedersen::hash(&Pedersen::hash(&class_hash, &storage_root), &nonce),
+        &CONTRACT_STATE_HASH_VERSION,
+    )
+}
+
+/// Returns the leaves of the contract trie, keyed by contract address
+fn contract_leaves(contract_states: &HashMap<Felt, ContractState>) -> Vec<(Felt, Felt)> {
+    contract_states
+        .iter()
+        .map(|(address, contract_state)| (*address, contract_hash(contract_state.leaf_data())))
+        .collect()
+}
+
+fn class_leaf(compiled_class_hash: Felt) -> Felt {
+    Poseidon::hash(&contract_class_leaf_version(), &compiled_class_hash)
+}
+
+fn global_state_root(contracts_root: Felt, classes_root: Felt) -> Felt {
+    if classes_root == Felt::ZERO {
+        contracts_root
+    } else {
+        Poseidon::hash_array(&[global_state_version(), contracts_root, classes_root])
+    }
+}
+
+/// The class hash, nonce and storage trie of a contract, kept between blocks
+#[derive(Default)]
+struct ContractTries {
+    class_hash: Felt,
+    nonce: Felt,
+    storage: IncrementalTrie<Pedersen>,
+}
+
+/// The tries of a state, kept between blocks so that calculating the state root of a new block
+/// only hashes the paths to the values changed by the block.
+#[derive(Default)]
+pub(crate) struct StateTries {
+    contracts: HashMap<Felt, ContractTries>,
+    contract_trie: IncrementalTrie<Pedersen>,
+    class_trie: IncrementalTrie<Poseidon>,
+}
+
+impl StateTries {
+    /// Builds the tries of the whole state
+    pub(crate) fn new(state: &DictState) -> Self {
+        let mut tries = Self::default();
+        for ((address, key), value) in &state.storage_view {
+            let contract = tries.contracts.entry(*address.0.key()).or_default();
+            contract.storage.set(*key.0.key(), *value);
+        }
+        for (address, class_hash) in &state.address_to_class_hash {
+            tries.contracts.entry(*address.0.key()).or_default().class_hash = class_hash.0;
+        }
+        for (address, nonce) in &state.address_to_nonce {
+            tries.contracts.entry(*address.0.key()).or_default().nonce = nonce.0;
+        }
+        for (class_hash, compiled_class_hash) in &state.class_hash_to_compiled_class_hash {
+            tries.class_trie.set(class_hash.0, class_leaf(compiled_class_hash.0));
+        }
+
+        let addresses: Vec<Felt> = tries.contracts.keys().copied().collect();
+        tries.update_contract_leaves(addresses);
+        tries
+    }
+
+    /// Updates the tries with the changes of a block
+    pub(crate) fn apply_diff(&mut self, state_diff: &StateDiff) {
+        for (address, storage_updates) in &state_diff.storage_updates {
+            let contract = self.contracts.entry(Felt::from(*address)).or_default();
+            for (key, value) in storage_updates {
+                contract.storage.set(key.to_felt(), *value);
+            }
+        }
+        for (address, class_hash) in &state_diff.address_to_class_hash {
+            self.contracts.entry(Felt::from(*address)).or_default().class_hash = *class_hash;
+        }
+        for (address, nonce) in &state_diff.address_to_nonce {
+            self.contracts.entry(Felt::from(*address)).or_default().nonce = *nonce;
+        }
+        for (class_hash, compiled_class_hash) in &state_diff.class_hash_to_compiled_class_hash {
+            self.class_trie.set(*class_hash, class_leaf(*compiled_class_hash));
+        }
+
+        let addresses = state_diff
+            .storage_updates
+            .keys()
+            .chain(state_diff.address_to_class_hash.keys())
+            .chain(state_diff.address_to_nonce.keys())
+            .map(|address| Felt::from(*address));
+        self.update_contract_leaves(addresses);
+    }
+
+    /// Calculates the global state root, which commits to the class hash, nonce and storage of
+    /// every contract, and to the compiled class hash of every Cairo 1 class.
+    pub(crate) fn state_root(&mut self) -> Felt {
+        global_state_root(self.contract_trie.root(), self.class_trie.root())
+    }
+
+    fn update_contract_leaves(&mut self, addresses: impl IntoIterator<Item = Felt>) {
+        for address in addresses {
+            let Some(contract) = self.contracts.get_mut(&address) else { continue };
+            let leaf_data = ContractLeafData {
+                nonce: contract.nonce,
+                class_hash: contract.class_hash,
+                storage_root: contract.storage.root(),
+            };
+            self.contract_trie.set(address, contract_hash(leaf_data));
+        }
+    }
+}
+
+impl DictState {
+    /// Returns the proofs of the requested classes, contracts and storage keys, along with the
+    /// roots of the contract and the class trie, which the global state root commits to.
+    pub(crate) fn get_storage_proof(
+        &self,
+        block_hash: Felt,
+        class_hashes: &[Felt],
+        contract_addresses: &[ContractAddress],
+        contracts_storage_keys: &[ContractStorageKeys],
+    ) -> StorageProof {
+        let (classes_tree_root, classes_proof) = calculate_root_with_proof::<Poseidon>(
+            self.class_leaves(),
+            STATE_TRIE_HEIGHT,
+            class_hashes,
+        );
+
+        let contract_addresses: Vec<Felt> =
+            contract_addresses.iter().map(|address| Felt::from(*address)).collect();
+        let contract_states = self.contract_states();
+        let (contracts_tree_root, contract_nodes) = calculate_root_with_proof::<Pedersen>(
+            contract_leaves(&contract_states),
+            STATE_TRIE_HEIGHT,
+            &contract_addresses,
+        );
+        let contract_leaves_data = contract_addresses
+            .iter()
+            .map(|address| contract_states.get(address).map(ContractState::leaf_data))
+            .map(Option::unwrap_or_default)
+            .collect();
+
+        let contracts_storage_proofs = contracts_storage_keys
+            .iter()
+            .map(|ContractStorageKeys { contract_address, storage_keys }| {
+                let storage = contract_states
+                    .get(&Felt::from(*contract_address))
+                    .map(|contract_state| contract_state.storage.clone())
+                    .unwrap_or_default();
+                calculate_root_with_proof::<Pedersen>(storage, STATE_TRIE_HEIGHT, storage_keys).1
+            })
+            .collect();
+
+        StorageProof {
+            classes_proof,
+            contracts_proof: ContractsProof { nodes: contract_nodes, contract_leaves_data },
+            contracts_storage_proofs,
+            global_roots: GlobalRoots { contracts_tree_root, classes_tree_root, block_hash },
+        }
+    }
+
+    /// Returns the state of every contract with any state, keyed by contract address
+    fn contract_states(&self) -> HashMap<Felt, ContractState> {
+        let mut contract_states: HashMap<Felt, ContractState> = HashMap::new();
+        for ((address, key), value) in &self.storage_view {
+            let contract_state = contract_states.entry(*address.0.key()).or_default();
+            contract_state.storage.push((*key.0.key(), *value));
+        }
+        for (address, class_hash) in &self.address_to_class_hash {
+            contract_states.entry(*address.0.key()).or_default().class_hash = class_hash.0;
+        }
+        for (address, nonce) in &self.address_to_nonce {
+            contract_states.entry(*address.0.key()).or_default().nonce = nonce.0;
+        }
+
+        contract_states
+    }
+
+    /// Returns the leaves of the class trie, keyed by class hash
+    fn class_leaves(&self) -> impl Iterator<Item = (Felt, Felt)> + '_ {
+        self.class_hash_to_compiled_class_hash.iter().map(|(class_hash, compiled_class_hash)| {
+            (class_hash.0, class_leaf(compiled_class_hash.0))
+        })
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use blockifier::state::cached_state::CachedState;
 use blockifier::state::state_api::StateReader;
+use serde::{Deserialize, Serialize};
 use starknet_rs_core::types::Felt;
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::contract_class::ContractClass;
@@ -15,7 +16,7 @@ use starknet_types::rpc::state::{
 use crate::error::DevnetResult;
 
 /// This struct is used to store the difference between state modifications
-#[derive(PartialEq, Default, Debug, Clone)]
+#[derive(PartialEq, Default, Debug, Clone, Serialize, Deserialize)]
 pub struct StateDiff {
     pub(crate) storage_updates: HashMap<ContractAddress, HashMap<StorageKey, Felt>>,
     pub(crate) address_to_nonce: HashMap<ContractAddress, Felt>,
@@ -116,7 +117,9 @@ impl StateDiff {
     pub(crate) fn extend(&mut self, other: &StateDiff) {
         self.address_to_class_hash.extend(&other.address_to_class_hash);
         self.address_to_nonce.extend(&other.address_to_nonce);
-        self.storage_updates.extend(other.storage_updates.clone());
+        for (address, storage_updates) in &other.storage_updates {
+            self.storage_updates.entry(*address).or_default().extend(storage_updates);
+        }
         self.class_hash_to_compiled_class_hash.extend(&other.class_hash_to_compiled_class_hash);
         self.cairo_0_declared_contracts.extend(&other.cairo_0_declared_contracts);
         self.declared_contracts.extend(&other.declared_contracts);
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::StateResult;
+use starknet_rs_core::types::Felt;
+use starknet_types::contract_address::ContractAddress;
+use starknet_types::felt::ClassHash;
+use starknet_types::patricia_key::{PatriciaKey, StorageKey};
+
+use super::state_diff::StateDiff;
+use super::SerializableState;
+use crate::database::{Database, Table};
+use crate::error::DevnetResult;
+
+/// Entry of the state whose values are kept in the history. Its key in `Table::StateHistory` is
+/// followed by the number of the block which set the value, so that the value at a block is the
+/// one with the greatest key up to the key with that block number.
+enum HistoryEntry {
+    Storage(ContractAddress, StorageKey),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+    CompiledClassHash(ClassHash),
+}
+
+impl HistoryEntry {
+    fn key(&self, block_number: u64) -> Vec<u8> {
+        let (kind, first, second) = match self {
+            HistoryEntry::Storage(address, key) => (0, Felt::from(*address), key.to_felt()),
+            HistoryEntry::Nonce(address) => (1, Felt::from(*address), Felt::ZERO),
+            HistoryEntry::ClassHash(address) => (2, Felt::from(*address), Felt::ZERO),
+            HistoryEntry::CompiledClassHash(class_hash) => (3, *class_hash, Felt::ZERO),
+        };
+
+        let mut key = vec![kind];
+        key.extend(first.to_bytes_be());
+        key.extend(second.to_bytes_be());
+        key.extend(block_number.to_be_bytes());
+        key
+    }
+
+    /// All entries set in the given maps, together with their values
+    fn set_in(
+        storage: &HashMap<ContractAddress, HashMap<StorageKey, Felt>>,
+        nonces: &HashMap<ContractAddress, Felt>,
+        class_hashes: &HashMap<ContractAddress, ClassHash>,
+        compiled_class_hashes: &HashMap<ClassHash, Felt>,
+    ) -> Vec<(Self, Felt)> {
+        let mut entries = vec![];
+        for (address, storage_updates) in storage {
+            for (key, value) in storage_updates {
+                entries.push((HistoryEntry::Storage(*address, *key), *value));
+            }
+        }
+        for (address, nonce) in nonces {
+            entries.push((HistoryEntry::Nonce(*address), *nonce));
+        }
+        for (address, class_hash) in class_hashes {
+            entries.push((HistoryEntry::ClassHash(*address), *class_hash));
+        }
+        for (class_hash, compiled_class_hash) in compiled_class_hashes {
+            entries.push((HistoryEntry::CompiledClassHash(*class_hash), *compiled_class_hash));
+        }
+        entries
+    }
+
+    fn changed_by(state_diff: &StateDiff) -> Vec<(Self, Felt)> {
+        Self::set_in(
+            &state_diff.storage_updates,
+            &state_diff.address_to_nonce,
+            &state_diff.address_to_class_hash,
+            &state_diff.class_hash_to_compiled_class_hash,
+        )
+    }
+}
+
+/// Writes all values of `state` to the state history, as if they were set by the block with
+/// `block_number`.
+pub(crate) fn write_state(
+    database: &Database,
+    block_number: u64,
+    state: &SerializableState,
+) -> DevnetResult<()> {
+    let entries = HistoryEntry::set_in(
+        &state.storage,
+        &state.nonces,
+        &state.class_hashes,
+        &state.compiled_class_hashes,
+    );
+    for (entry, value) in entries {
+        database.put(Table::StateHistory, &entry.key(block_number), &value)?;
+    }
+    Ok(())
+}
+
+/// Writes the values changed by the block with `block_number` to the state history.
+pub(crate) fn write_block_changes(
+    database: &Database,
+    block_number: u64,
+    state_diff: &StateDiff,
+) -> DevnetResult<()> {
+    for (entry, value) in HistoryEntry::changed_by(state_diff) {
+        database.put(Table::StateHistory, &entry.key(block_number), &value)?;
+    }
+    Ok(())
+}
+
+/// Removes the values changed by the block with `block_number` from the state history.
+pub(crate) fn remove_block_changes(database: &Database, block_number: u64, state_diff: &StateDiff) {
+    for (entry, _) in HistoryEntry::changed_by(state_diff) {
+        database.delete(Table::StateHistory, &entry.key(block_number));
+    }
+}
+
+/// Reverts the values of `state` changed by `state_diff` to those at the block read by `history`.
+/// Values which were not set by then are removed, as are the classes declared in `state_diff`.
+pub(crate) fn revert_changes(
+    state: &mut SerializableState,
+    history: &StateHistoryReader,
+    state_diff: &StateDiff,
+) -> DevnetResult<()> {
+    for (entry, _) in HistoryEntry::changed_by(state_diff) {
+        let value = history.get(&entry)?;
+        match entry {
+            HistoryEntry::Storage(address, key) => {
+                set_or_remove(state.storage.entry(address).or_default(), key, value)
+            }
+            HistoryEntry::Nonce(address) => set_or_remove(&mut state.nonces, address, value),
+            HistoryEntry::ClassHash(address) => {
+                set_or_remove(&mut state.class_hashes, address, value)
+            }
+            HistoryEntry::CompiledClassHash(class_hash) => {
+                set_or_remove(&mut state.compiled_class_hashes, class_hash, value)
+            }
+        }
+    }
+
+    state.declared_classes.retain(|class_hash| {
+        !state_diff.declared_contracts.contains(class_hash)
+            && !state_diff.cairo_0_declared_contracts.contains(class_hash)
+    });
+    Ok(())
+}
+
+fn set_or_remove<K: Eq + Hash>(map: &mut HashMap<K, Felt>, key: K, value: Option<Felt>) {
+    match value {
+        Some(value) => map.insert(key, value),
+        None => map.remove(&key),
+    };
+}
+
+/// Reads the state at the end of a block from the state history.
+#[derive(Debug, Clone)]
+pub(crate) struct StateHistoryReader {
+    database: Database,
+    block_number: u64,
+}
+
+impl StateHistoryReader {
+    pub(crate) fn new(database: Database, block_number: u64) -> Self {
+        Self { database, block_number }
+    }
+
+    /// Returns the value of the entry at the block, if it was set up to the block.
+    fn get(&self, entry: &HistoryEntry) -> StateResult<Option<Felt>> {
+        let value = self
+            .database
+            .last_in_range(Table::StateHistory, &entry.key(0), &entry.key(self.block_number))
+            .map_err(|e| StateError::StateReadError(e.to_string()))?;
+        Ok(value.map(|(_, value)| value))
+    }
+
+    pub(crate) fn get_storage_at(
+        &self,
+        contract_address: starknet_api::core::ContractAddress,
+        key: starknet_api::state::StorageKey,
+    ) -> StateResult<Option<Felt>> {
+        self.get(&HistoryEntry::Storage(contract_address.into(), PatriciaKey::from(key.0)))
+    }
+
+    pub(crate) fn get_nonce_at(
+        &self,
+        contract_address: starknet_api::core::ContractAddress,
+    ) -> StateResult<Option<starknet_api::core::Nonce>> {
+        let nonce = self.get(&HistoryEntry::Nonce(contract_address.into()))?;
+        Ok(nonce.map(starknet_api::core::Nonce))
+    }
+
+    pub(crate) fn get_class_hash_at(
+        &self,
+        contract_address: starknet_api::core::ContractAddress,
+    ) -> StateResult<Option<starknet_api::core::ClassHash>> {
+        let class_hash = self.get(&HistoryEntry::ClassHash(contract_address.into()))?;
+        Ok(class_hash.map(starknet_api::core::ClassHash))
+    }
+
+    pub(crate) fn get_compiled_class_hash(
+        &self,
+        class_hash: starknet_api::core::ClassHash,
+    ) -> StateResult<Option<starknet_api::core::CompiledClassHash>> {
+        let compiled_class_hash = self.get(&HistoryEntry::CompiledClassHash(class_hash.0))?;
+        Ok(compiled_class_hash.map(starknet_api::core::CompiledClassHash))
+    }
+}
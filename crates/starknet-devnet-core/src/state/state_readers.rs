@@ -8,10 +8,12 @@ use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_rs_core::types::Felt;
 
+use super::state_history::StateHistoryReader;
 use crate::starknet::defaulter::StarknetDefaulter;
 
 /// A simple implementation of `StateReader` using `HashMap`s as storage.
-/// Copied from blockifier test_utils, added `impl State`
+/// Copied from blockifier test_utils, added `impl State`. Values which are not in the maps are read
+/// from the state history, if any, and then from the defaulter.
 #[derive(Debug, Default, Clone)]
 pub struct DictState {
     pub storage_view: HashMap<StorageEntry, Felt>,
@@ -20,12 +22,17 @@ pub struct DictState {
     pub class_hash_to_class: HashMap<ClassHash, ContractClass>,
     pub class_hash_to_compiled_class_hash: HashMap<ClassHash, CompiledClassHash>,
     defaulter: StarknetDefaulter,
+    history: Option<StateHistoryReader>,
 }
 
 impl DictState {
     pub fn new(defaulter: StarknetDefaulter) -> Self {
         Self { defaulter, ..Self::default() }
     }
+
+    pub(crate) fn with_history(defaulter: StarknetDefaulter, history: StateHistoryReader) -> Self {
+        Self { defaulter, history: Some(history), ..Self::default() }
+    }
 }
 
 impl StateReader for DictState {
@@ -35,17 +42,27 @@ impl StateReader for DictState {
         key: StorageKey,
     ) -> StateResult<Felt> {
         let contract_storage_key = (contract_address, key);
-        match self.storage_view.get(&contract_storage_key) {
-            Some(value) => Ok(*value),
-            None => self.defaulter.get_storage_at(contract_address, key),
+        if let Some(value) = self.storage_view.get(&contract_storage_key) {
+            return Ok(*value);
+        }
+        if let Some(history) = &self.history {
+            if let Some(value) = history.get_storage_at(contract_address, key)? {
+                return Ok(value);
+            }
         }
+        self.defaulter.get_storage_at(contract_address, key)
     }
 
     fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
-        match self.address_to_nonce.get(&contract_address) {
-            Some(value) => Ok(*value),
-            None => self.defaulter.get_nonce_at(contract_address),
+        if let Some(value) = self.address_to_nonce.get(&contract_address) {
+            return Ok(*value);
         }
+        if let Some(history) = &self.history {
+            if let Some(value) = history.get_nonce_at(contract_address)? {
+                return Ok(value);
+            }
+        }
+        self.defaulter.get_nonce_at(contract_address)
     }
 
     fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
@@ -56,20 +73,31 @@ impl StateReader for DictState {
     }
 
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
-        match self.address_to_class_hash.get(&contract_address) {
-            Some(class_hash) => Ok(*class_hash),
-            None => self.defaulter.get_class_hash_at(contract_address),
+        if let Some(class_hash) = self.address_to_class_hash.get(&contract_address) {
+            return Ok(*class_hash);
+        }
+        if let Some(history) = &self.history {
+            if let Some(class_hash) = history.get_class_hash_at(contract_address)? {
+                return Ok(class_hash);
+            }
         }
+        self.defaulter.get_class_hash_at(contract_address)
     }
 
     fn get_compiled_class_hash(
         &self,
         class_hash: ClassHash,
     ) -> StateResult<starknet_api::core::CompiledClassHash> {
+        if let Some(compiled_class_hash) = self.class_hash_to_compiled_class_hash.get(&class_hash) {
+            return Ok(*compiled_class_hash);
+        }
+        if let Some(history) = &self.history {
+            if let Some(compiled_class_hash) = history.get_compiled_class_hash(class_hash)? {
+                return Ok(compiled_class_hash);
+            }
+        }
         // can't ask origin for this - insufficient API - probably not important
-        let compiled_class_hash =
-            self.class_hash_to_compiled_class_hash.get(&class_hash).copied().unwrap_or_default();
-        Ok(compiled_class_hash)
+        Ok(CompiledClassHash::default())
     }
 }
 
@@ -86,12 +114,8 @@ impl DictState {
         Ok(())
     }
 
-    pub fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
-        let current_nonce = self.get_nonce_at(contract_address)?;
-        let next_nonce = Nonce(current_nonce.0 + 1);
-        self.address_to_nonce.insert(contract_address, next_nonce);
-
-        Ok(())
+    pub fn set_nonce(&mut self, contract_address: ContractAddress, nonce: Nonce) {
+        self.address_to_nonce.insert(contract_address, nonce);
     }
 
     pub fn set_class_hash_at(
@@ -10,8 +10,7 @@ pub struct StateUpdate {
 }
 
 impl StateUpdate {
-    pub fn new(block_hash: Felt, state_diff: StateDiff) -> Self {
-        // TODO new and old root are not computed, they are not part of the MVP
-        Self { block_hash, new_root: Felt::default(), old_root: Felt::default(), state_diff }
+    pub fn new(block_hash: Felt, new_root: Felt, old_root: Felt, state_diff: StateDiff) -> Self {
+        Self { block_hash, new_root, old_root, state_diff }
     }
 }
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use blockifier::state::state_api::StateReader;
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::contract_class::ContractClass;
@@ -12,17 +14,10 @@ use crate::state::{CustomState, CustomStateReader, StarknetState};
 /// This trait should be implemented by structures that internally have collections and each element
 /// could be found by a hash
 pub trait HashIdentified {
-    type Element;
-    type Hash;
-
-    fn get_by_hash(&self, hash: Self::Hash) -> Option<&Self::Element>;
-}
-
-pub trait HashIdentifiedMut {
-    type Element;
+    type Element: Clone;
     type Hash;
 
-    fn get_by_hash_mut(&mut self, hash: &Self::Hash) -> Option<&mut Self::Element>;
+    fn get_by_hash(&self, hash: Self::Hash) -> DevnetResult<Option<Cow<'_, Self::Element>>>;
 }
 
 pub(crate) trait Deployed {
@@ -1,9 +1,12 @@
+use std::borrow::Cow;
+
 use blockifier::execution::call_info::CallInfo;
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
-use starknet_rs_core::types::{ExecutionResult, Felt, TransactionFinalityStatus};
+use starknet_api::transaction::Fee;
+use starknet_rs_core::types::{ExecutionResult, TransactionFinalityStatus};
 use starknet_rs_core::utils::get_selector_from_name;
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::emitted_event::{Event, OrderedEvent};
@@ -11,7 +14,7 @@ use starknet_types::felt::{BlockHash, TransactionHash};
 use starknet_types::messaging::MessageToL2;
 use starknet_types::rpc::messaging::{MessageToL1, OrderedMessageToL1};
 use starknet_types::rpc::transaction_receipt::{
-    DeployTransactionReceipt, FeeAmount, FeeInUnits, TransactionReceipt,
+    DeployTransactionReceipt, ExecutionResources, FeeAmount, FeeInUnits, TransactionReceipt,
 };
 use starknet_types::rpc::transactions::{
     DeclareTransaction, DeployAccountTransaction, InvokeTransaction, Transaction, TransactionTrace,
@@ -19,53 +22,89 @@ use starknet_types::rpc::transactions::{
 };
 
 use crate::constants::UDC_CONTRACT_ADDRESS;
+use crate::database::{Database, Table};
 use crate::error::{DevnetResult, Error};
-use crate::traits::{HashIdentified, HashIdentifiedMut};
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct StarknetTransactions(IndexMap<TransactionHash, StarknetTransaction>);
+/// Transactions are kept in memory, unless a database is used, in which case they are only kept
+/// in the database and read from it on demand.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StarknetTransactions {
+    in_memory: IndexMap<TransactionHash, StarknetTransaction>,
+    #[serde(skip)]
+    database: Option<Database>,
+}
 
 impl StarknetTransactions {
-    pub fn insert(&mut self, transaction_hash: &TransactionHash, transaction: StarknetTransaction) {
-        self.0.insert(*transaction_hash, transaction);
+    pub(crate) fn with_database(database: Database) -> Self {
+        Self { in_memory: IndexMap::new(), database: Some(database) }
     }
 
-    pub fn get(&self, transaction_hash: &TransactionHash) -> Option<&StarknetTransaction> {
-        self.0.get(transaction_hash)
-    }
+    pub fn insert(
+        &mut self,
+        transaction_hash: &TransactionHash,
+        transaction: StarknetTransaction,
+    ) -> DevnetResult<()> {
+        match &self.database {
+            Some(database) => {
+                database.put(Table::Transactions, &transaction_hash.to_bytes_be(), &transaction)?
+            }
+            None => {
+                self.in_memory.insert(*transaction_hash, transaction);
+            }
+        }
 
-    pub fn iter(&self) -> indexmap::map::Iter<'_, Felt, StarknetTransaction> {
-        self.0.iter()
+        Ok(())
     }
-}
 
-impl HashIdentifiedMut for StarknetTransactions {
-    type Hash = TransactionHash;
-    type Element = StarknetTransaction;
-    fn get_by_hash_mut(&mut self, hash: &Self::Hash) -> Option<&mut StarknetTransaction> {
-        self.0.get_mut(hash)
+    pub fn get(
+        &self,
+        transaction_hash: &TransactionHash,
+    ) -> DevnetResult<Option<Cow<'_, StarknetTransaction>>> {
+        match &self.database {
+            Some(database) => Ok(database
+                .get(Table::Transactions, &transaction_hash.to_bytes_be())?
+                .map(Cow::Owned)),
+            None => Ok(self.in_memory.get(transaction_hash).map(Cow::Borrowed)),
+        }
     }
-}
 
-impl HashIdentified for StarknetTransactions {
-    type Hash = TransactionHash;
-    type Element = StarknetTransaction;
-    fn get_by_hash(&self, hash: Self::Hash) -> Option<&StarknetTransaction> {
-        self.0.get(&hash)
+    /// Applies `modify` to the transaction and stores the result.
+    pub(crate) fn update(
+        &mut self,
+        transaction_hash: &TransactionHash,
+        modify: impl FnOnce(&mut StarknetTransaction),
+    ) -> DevnetResult<()> {
+        match &self.database {
+            Some(database) => {
+                let key = transaction_hash.to_bytes_be();
+                let mut transaction: StarknetTransaction =
+                    database.get(Table::Transactions, &key)?.ok_or(Error::NoTransaction)?;
+                modify(&mut transaction);
+                database.put(Table::Transactions, &key, &transaction)
+            }
+            None => {
+                modify(self.in_memory.get_mut(transaction_hash).ok_or(Error::NoTransaction)?);
+                Ok(())
+            }
+        }
     }
 }
 
 #[allow(unused)]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarknetTransaction {
     pub inner: TransactionWithHash,
     pub(crate) finality_status: TransactionFinalityStatus,
     pub(crate) execution_result: ExecutionResult,
     pub(crate) block_hash: Option<BlockHash>,
     pub(crate) block_number: Option<BlockNumber>,
-    #[serde(skip)]
-    pub(crate) execution_info: TransactionExecutionInfo,
-    #[serde(skip)]
+    // Extracted from the execution info on creation, since the execution info itself cannot be
+    // serialized or cloned
+    pub(crate) events: Vec<Event>,
+    pub(crate) l2_to_l1_messages: Vec<MessageToL1>,
+    pub(crate) actual_fee: Fee,
+    pub(crate) execution_resources: ExecutionResources,
     pub(crate) trace: Option<TransactionTrace>,
 }
 
@@ -89,12 +128,15 @@ impl StarknetTransaction {
             inner: transaction.clone(),
             block_hash: None,
             block_number: None,
-            execution_info,
+            events: Self::extract_events(&execution_info),
+            l2_to_l1_messages: Self::extract_l2_to_l1_messages(&execution_info),
+            actual_fee: execution_info.transaction_receipt.fee,
+            execution_resources: ExecutionResources::from(&execution_info),
             trace: Some(trace),
         }
     }
 
-    pub fn get_events(&self) -> Vec<Event> {
+    fn extract_events(execution_info: &TransactionExecutionInfo) -> Vec<Event> {
         let mut events: Vec<Event> = vec![];
 
         fn get_blockifier_events_recursively(
@@ -118,9 +160,9 @@ impl StarknetTransaction {
         }
 
         let call_infos = vec![
-            self.execution_info.validate_call_info.as_ref(),
-            self.execution_info.execute_call_info.as_ref(),
-            self.execution_info.fee_transfer_call_info.as_ref(),
+            execution_info.validate_call_info.as_ref(),
+            execution_info.execute_call_info.as_ref(),
+            execution_info.fee_transfer_call_info.as_ref(),
         ];
 
         for inner_call_info in call_infos.into_iter().flatten() {
@@ -136,6 +178,10 @@ impl StarknetTransaction {
         events
     }
 
+    pub fn get_events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
     /// Scans through events and gets information from Event generated from UDC with specific
     /// ContractDeployed. Returns the contract address
     ///
@@ -172,7 +218,7 @@ impl StarknetTransaction {
         // L1 Handler transactions are in WEI
         // V3 transactions are in STRK(FRI)
         // Other transactions versions are in ETH(WEI)
-        let fee_amount = FeeAmount { amount: self.execution_info.transaction_receipt.fee };
+        let fee_amount = FeeAmount { amount: self.actual_fee };
         let actual_fee_in_units = match self.inner.transaction {
             Transaction::L1Handler(_) => FeeInUnits::WEI(fee_amount),
             Transaction::Declare(DeclareTransaction::V3(_))
@@ -189,7 +235,7 @@ impl StarknetTransaction {
             &self.execution_result,
             self.finality_status,
             actual_fee_in_units,
-            &self.execution_info,
+            self.execution_resources.clone(),
         );
 
         match &self.inner.transaction {
@@ -232,7 +278,7 @@ impl StarknetTransaction {
         self.trace.clone()
     }
 
-    pub fn get_l2_to_l1_messages(&self) -> Vec<MessageToL1> {
+    fn extract_l2_to_l1_messages(execution_info: &TransactionExecutionInfo) -> Vec<MessageToL1> {
         let mut messages = vec![];
 
         fn get_blockifier_messages_recursively(call_info: &CallInfo) -> Vec<OrderedMessageToL1> {
@@ -261,7 +307,7 @@ impl StarknetTransaction {
             messages
         }
 
-        let call_infos = self.execution_info.non_optional_call_infos();
+        let call_infos = execution_info.non_optional_call_infos();
 
         for inner_call_info in call_infos {
             let mut not_sorted_messages = get_blockifier_messages_recursively(inner_call_info);
@@ -271,6 +317,10 @@ impl StarknetTransaction {
 
         messages
     }
+
+    pub fn get_l2_to_l1_messages(&self) -> Vec<MessageToL1> {
+        self.l2_to_l1_messages.clone()
+    }
 }
 
 #[cfg(test)]
@@ -281,7 +331,6 @@ mod tests {
 
     use super::{StarknetTransaction, StarknetTransactions};
     use crate::starknet::transaction_trace::create_trace;
-    use crate::traits::HashIdentifiedMut;
     use crate::utils::test_utils::dummy_declare_transaction_v1;
 
     fn dummy_trace(tx: &TransactionWithHash) -> TransactionTrace {
@@ -305,18 +354,25 @@ mod tests {
             trace.clone(),
         );
         let mut sn_txs = StarknetTransactions::default();
-        sn_txs.insert(
-            tx.get_transaction_hash(),
-            StarknetTransaction::create_accepted(&tx, TransactionExecutionInfo::default(), trace),
-        );
-
-        let extracted_tran = sn_txs.get_by_hash_mut(tx.get_transaction_hash()).unwrap();
+        sn_txs
+            .insert(
+                tx.get_transaction_hash(),
+                StarknetTransaction::create_accepted(
+                    &tx,
+                    TransactionExecutionInfo::default(),
+                    trace,
+                ),
+            )
+            .unwrap();
+
+        let extracted_tran = sn_txs.get(tx.get_transaction_hash()).unwrap().unwrap();
 
         assert_eq!(sn_tx.block_hash, extracted_tran.block_hash);
         assert_eq!(sn_tx.block_number, extracted_tran.block_number);
         assert!(sn_tx.inner == extracted_tran.inner);
         assert_eq!(sn_tx.finality_status, extracted_tran.finality_status);
-        assert_eq!(sn_tx.execution_info, extracted_tran.execution_info);
+        assert_eq!(sn_tx.events, extracted_tran.events);
+        assert_eq!(sn_tx.execution_resources, extracted_tran.execution_resources);
     }
 
     #[test]
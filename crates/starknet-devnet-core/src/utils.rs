@@ -1,5 +1,4 @@
 use blockifier::bouncer::{BouncerConfig, BouncerWeights, BuiltinCount};
-use blockifier::versioned_constants::VersionedConstants;
 use serde_json::Value;
 use starknet_rs_core::types::contract::CompiledClass;
 use starknet_rs_core::types::Felt;
@@ -39,10 +38,6 @@ pub(crate) fn get_storage_var_address(
     Ok(PatriciaKey::new(storage_var_address)?)
 }
 
-pub(crate) fn get_versioned_constants() -> VersionedConstants {
-    VersionedConstants::create_for_testing()
-}
-
 /// Values not present here: https://docs.starknet.io/tools/limits-and-triggers/
 /// Asked the blockifier team about the values, they provided them here:
 /// https://spaceshard.slack.com/archives/C029F9AN8LX/p1721657837687799?thread_ts=1721400009.781699&cid=C029F9AN8LX
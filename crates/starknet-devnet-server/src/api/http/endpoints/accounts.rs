@@ -1,24 +1,19 @@
 use axum::extract::{Query, State};
 use axum::Json;
 use starknet_core::starknet::Starknet;
-use starknet_rs_core::types::{BlockTag, Felt};
+use starknet_rs_core::types::BlockTag;
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::rpc::transaction_receipt::FeeUnit;
 
 use super::mint_token::{get_balance, get_erc20_address};
 use crate::api::http::error::HttpApiError;
 use crate::api::http::models::{
-    AccountBalanceResponse, AccountBalancesResponse, SerializableAccount,
+    AccountBalanceResponse, AccountBalancesResponse, BalanceQuery, PredeployedAccountsQuery,
+    SerializableAccount,
 };
 use crate::api::http::{HttpApiHandler, HttpApiResult};
 use crate::api::Api;
 
-#[derive(serde::Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
-pub struct PredeployedAccountsQuery {
-    pub with_balance: Option<bool>,
-}
-
 pub async fn get_predeployed_accounts(
     State(state): State<HttpApiHandler>,
     Query(params): Query<PredeployedAccountsQuery>,
@@ -69,14 +64,6 @@ pub(crate) async fn get_predeployed_accounts_impl(
     Ok(predeployed_accounts)
 }
 
-#[derive(serde::Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
-pub struct BalanceQuery {
-    address: Felt,
-    unit: Option<FeeUnit>,
-    block_tag: Option<BlockTag>,
-}
-
 pub async fn get_account_balance(
     State(state): State<HttpApiHandler>,
     Query(params): Query<BalanceQuery>,
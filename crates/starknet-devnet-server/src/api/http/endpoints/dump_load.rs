@@ -1,12 +1,13 @@
 use axum::extract::State;
 use axum::Json;
+use starknet_core::starknet::starknet_config::DumpFormat;
 
 use super::extract_optional_json_from_request;
 use crate::api::http::error::HttpApiError;
 use crate::api::http::models::{DumpPath, DumpResponseBody};
 use crate::api::http::{HttpApiHandler, HttpApiResult};
 use crate::api::Api;
-use crate::dump_util::dump_events;
+use crate::dump_util::{dump_events, dump_state, DevnetDump};
 
 pub async fn dump(
     State(state): State<HttpApiHandler>,
@@ -33,14 +34,30 @@ pub(crate) async fn dump_impl(
         .or_else(|| starknet.config.dump_path.clone())
         .unwrap_or_default();
 
-    drop(starknet);
-    let dumpable_events = api.dumpable_events.lock().await;
+    match starknet.config.dump_format {
+        DumpFormat::Events => {
+            drop(starknet);
+            let dumpable_events = api.dumpable_events.lock().await;
 
-    if path.is_empty() {
-        Ok(Some(dumpable_events.clone()))
-    } else {
-        dump_events(&dumpable_events, &path)
-            .map_err(|err| HttpApiError::DumpError { msg: err.to_string() })?;
-        Ok(None)
+            if path.is_empty() {
+                Ok(Some(DevnetDump::Events(dumpable_events.clone())))
+            } else {
+                dump_events(&dumpable_events, &path)
+                    .map_err(|err| HttpApiError::DumpError { msg: err.to_string() })?;
+                Ok(None)
+            }
+        }
+        DumpFormat::State => {
+            let dump = starknet.dump_state();
+            drop(starknet);
+
+            if path.is_empty() {
+                Ok(Some(DevnetDump::State(Box::new(dump))))
+            } else {
+                dump_state(&dump, &path)
+                    .map_err(|err| HttpApiError::DumpError { msg: err.to_string() })?;
+                Ok(None)
+            }
+        }
     }
 }
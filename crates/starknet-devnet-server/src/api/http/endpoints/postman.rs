@@ -11,7 +11,7 @@ use crate::api::json_rpc::models::TransactionHashOutput;
 use crate::api::json_rpc::{DevnetResponse, JsonRpcHandler};
 use crate::api::Api;
 use crate::rpc_core::error::RpcError;
-use crate::rpc_core::request::RpcMethodCall;
+use crate::rpc_core::request::{Id, RequestParams, RpcMethodCall, Version};
 use crate::rpc_core::response::ResponseResult;
 use crate::rpc_handler::RpcHandler;
 
@@ -109,6 +109,21 @@ pub(crate) async fn postman_flush_impl(
         starknet.get_ethereum_url().unwrap_or("Not set".to_string())
     };
 
+    if !is_dry_run && starknet.config.accept_on_l1_on_flush {
+        drop(starknet); // drop to avoid deadlock
+
+        // executed as an RPC call so that it gets dumped
+        let rpc_call = RpcMethodCall {
+            jsonrpc: Version::V2,
+            method: "devnet_acceptOnL1".into(),
+            params: RequestParams::None,
+            id: Id::Number(0),
+        };
+        if let ResponseResult::Error(e) = rpc_handler.on_call(rpc_call).await.result {
+            return Err(ApiError::RpcError(e));
+        }
+    }
+
     let flushed_messages =
         FlushedMessages { messages_to_l1, messages_to_l2, generated_l2_transactions, l1_provider };
 
@@ -116,8 +131,14 @@ pub(crate) async fn postman_flush_impl(
 }
 
 pub async fn postman_send_message_to_l2_impl(api: &Api, message: MessageToL2) -> StrictRpcResult {
+    let l1_transaction_hash = message.l1_transaction_hash;
     let transaction = L1HandlerTransaction::try_from_message_to_l2(message)?;
-    let transaction_hash = api.starknet.lock().await.add_l1_handler_transaction(transaction)?;
+
+    let mut starknet = api.starknet.lock().await;
+    let transaction_hash = starknet.add_l1_handler_transaction(transaction)?;
+    if let Some(l1_transaction_hash) = l1_transaction_hash {
+        starknet.register_l1_handler_transaction(l1_transaction_hash, transaction_hash);
+    }
     Ok(DevnetResponse::TransactionHash(TransactionHashOutput { transaction_hash }).into())
 }
 
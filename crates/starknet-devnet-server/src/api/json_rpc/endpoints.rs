@@ -1,24 +1,35 @@
 use starknet_core::error::{Error, StateError};
-use starknet_rs_core::types::{BlockId as ImportedBlockId, MsgFromL1};
+use starknet_rs_core::types::{BlockId as ImportedBlockId, BlockTag, Hash256, MsgFromL1};
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::felt::{ClassHash, TransactionHash};
 use starknet_types::patricia_key::PatriciaKey;
 use starknet_types::rpc::block::{
     Block, BlockHeader, BlockId, BlockResult, PendingBlock, PendingBlockHeader,
 };
+use starknet_types::rpc::devnet::{
+    CoverageFormat, CoverageReport, CoverageRequest, FoldedProfile, LcovCoverage, ProfileFormat,
+    ProfileTransactionRequest,
+};
 use starknet_types::rpc::state::StateUpdateResult;
 use starknet_types::rpc::transactions::{
     BroadcastedTransaction, EventFilter, EventsChunk, FunctionCall, SimulationFlag,
 };
+use starknet_types::rpc::v0_8::MessageStatus;
 use starknet_types::starknet_api::block::BlockStatus;
 
 use super::error::{ApiError, StrictRpcResult};
-use super::models::{BlockHashAndNumberOutput, SyncingOutput, TransactionStatusOutput};
-use super::{DevnetResponse, JsonRpcHandler, JsonRpcResponse, StarknetResponse, RPC_SPEC_VERSION};
+use super::models::{
+    BlockHashAndNumberOutput, GetStorageProofInput, SyncingOutput, TransactionStatusOutput,
+};
+use super::{
+    DevnetResponse, JsonRpcHandler, JsonRpcResponse, StarknetResponse, StarknetResponseV0_8,
+};
 use crate::api::http::endpoints::accounts::{
-    get_account_balance_impl, get_predeployed_accounts_impl, BalanceQuery, PredeployedAccountsQuery,
+    get_account_balance_impl, get_predeployed_accounts_impl,
 };
 use crate::api::http::endpoints::DevnetConfig;
+use crate::api::http::models::{BalanceQuery, PredeployedAccountsQuery};
+use crate::rpc_core::error::RpcError;
 
 const DEFAULT_CONTINUATION_TOKEN: &str = "0";
 
@@ -26,7 +37,7 @@ const DEFAULT_CONTINUATION_TOKEN: &str = "0";
 impl JsonRpcHandler {
     /// starknet_specVersion
     pub fn spec_version(&self) -> StrictRpcResult {
-        Ok(StarknetResponse::String(RPC_SPEC_VERSION.to_string()).into())
+        Ok(StarknetResponse::String(self.rpc_version.spec_version().to_string()).into())
     }
 
     /// starknet_getBlockWithTxHashes
@@ -40,7 +51,7 @@ impl JsonRpcHandler {
 
         if block.status() == &BlockStatus::Pending {
             Ok(StarknetResponse::PendingBlock(PendingBlock {
-                header: PendingBlockHeader::from(block),
+                header: PendingBlockHeader::from(block.as_ref()),
                 transactions: starknet_types::rpc::transactions::Transactions::Hashes(
                     block.get_transactions().to_owned(),
                 ),
@@ -49,7 +60,7 @@ impl JsonRpcHandler {
         } else {
             Ok(StarknetResponse::Block(Block {
                 status: *block.status(),
-                header: BlockHeader::from(block),
+                header: BlockHeader::from(block.as_ref()),
                 transactions: starknet_types::rpc::transactions::Transactions::Hashes(
                     block.get_transactions().to_owned(),
                 ),
@@ -135,13 +146,36 @@ impl JsonRpcHandler {
         Ok(StarknetResponse::Felt(felt).into())
     }
 
+    /// starknet_getStorageProof
+    pub async fn get_storage_proof(&self, data: GetStorageProofInput) -> StrictRpcResult {
+        let storage_proof = self
+            .api
+            .starknet
+            .lock()
+            .await
+            .get_storage_proof(
+                data.block_id.as_ref(),
+                &data.class_hashes,
+                &data.contract_addresses,
+                &data.contracts_storage_keys,
+            )
+            .map_err(|err| match err {
+                Error::NoBlock => ApiError::BlockNotFound,
+                Error::NoStateAtBlock { .. } => ApiError::StorageProofNotSupported,
+                Error::UnsupportedAction { msg } => ApiError::UnsupportedAction { msg },
+                unknown_error => ApiError::StarknetDevnetError(unknown_error),
+            })?;
+
+        Ok(StarknetResponse::StorageProof(Box::new(storage_proof)).into())
+    }
+
     /// starknet_getTransactionByHash
     pub async fn get_transaction_by_hash(
         &self,
         transaction_hash: TransactionHash,
     ) -> StrictRpcResult {
         match self.api.starknet.lock().await.get_transaction_by_hash(transaction_hash) {
-            Ok(transaction) => Ok(StarknetResponse::Transaction(transaction.clone()).into()),
+            Ok(transaction) => Ok(StarknetResponse::Transaction(transaction).into()),
             Err(Error::NoTransaction) => Err(ApiError::TransactionNotFound),
             Err(err) => Err(err.into()),
         }
@@ -152,25 +186,57 @@ impl JsonRpcHandler {
         &self,
         transaction_hash: TransactionHash,
     ) -> StrictRpcResult {
-        match self
-            .api
-            .starknet
-            .lock()
-            .await
-            .get_transaction_execution_and_finality_status(transaction_hash)
-        {
+        let starknet = self.api.starknet.lock().await;
+        match starknet.get_transaction_execution_and_finality_status(transaction_hash) {
             Ok((execution_status, finality_status)) => {
-                Ok(StarknetResponse::TransactionStatusByHash(TransactionStatusOutput {
+                Ok(StarknetResponse::TransactionStatusByHash(TransactionStatusOutput::executed(
                     execution_status,
                     finality_status,
-                })
+                    starknet.get_transaction_revert_reason(transaction_hash)?,
+                ))
                 .into())
             }
-            Err(Error::NoTransaction) => Err(ApiError::TransactionNotFound),
+            Err(Error::NoTransaction) if starknet.is_transaction_in_mempool(&transaction_hash) => {
+                Ok(StarknetResponse::TransactionStatusByHash(TransactionStatusOutput::received())
+                    .into())
+            }
+            Err(Error::NoTransaction) => {
+                match starknet.get_transaction_rejection_reason(&transaction_hash) {
+                    Some(reason) => Ok(StarknetResponse::TransactionStatusByHash(
+                        TransactionStatusOutput::rejected(reason),
+                    )
+                    .into()),
+                    None => Err(ApiError::TransactionNotFound),
+                }
+            }
             Err(err) => Err(err.into()),
         }
     }
 
+    /// starknet_getMessagesStatus
+    pub async fn get_messages_status(&self, l1_transaction_hash: Hash256) -> StrictRpcResult {
+        let starknet = self.api.starknet.lock().await;
+        let transaction_hashes = starknet
+            .get_l1_handler_transactions(l1_transaction_hash)
+            .ok_or(ApiError::TransactionNotFound)?;
+
+        let mut statuses = vec![];
+        for transaction_hash in transaction_hashes {
+            match starknet.get_transaction_execution_and_finality_status(*transaction_hash) {
+                Ok((_, finality_status)) => statuses.push(MessageStatus {
+                    transaction_hash: *transaction_hash,
+                    finality_status,
+                    failure_reason: starknet.get_transaction_revert_reason(*transaction_hash)?,
+                }),
+                // the transaction was in an aborted block
+                Err(Error::NoTransaction) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(StarknetResponseV0_8::MessagesStatus(statuses).into())
+    }
+
     /// starknet_getTransactionByBlockIdAndIndex
     pub async fn get_transaction_by_block_id_and_index(
         &self,
@@ -184,7 +250,7 @@ impl JsonRpcHandler {
             .await
             .get_transaction_by_block_id_and_index(block_id.as_ref(), index)
         {
-            Ok(transaction) => Ok(StarknetResponse::Transaction(transaction.clone()).into()),
+            Ok(transaction) => Ok(StarknetResponse::Transaction(transaction).into()),
             Err(Error::InvalidTransactionIndexInBlock) => {
                 Err(ApiError::InvalidTransactionIndexInBlock)
             }
@@ -222,6 +288,27 @@ impl JsonRpcHandler {
         }
     }
 
+    /// starknet_getCompiledCasm
+    pub async fn get_compiled_casm(
+        &self,
+        block_id: Option<BlockId>,
+        class_hash: ClassHash,
+    ) -> StrictRpcResult {
+        let block_id = block_id.map_or(ImportedBlockId::Tag(BlockTag::Latest), Into::into);
+        match self.api.starknet.lock().await.get_compiled_casm(&block_id, class_hash) {
+            Ok(casm) => Ok(StarknetResponse::CompiledCasm(Box::new(casm)).into()),
+            Err(e) => Err(match e {
+                Error::NoBlock => ApiError::BlockNotFound,
+                Error::StateError(_) => ApiError::ClassHashNotFound,
+                e @ Error::NoStateAtBlock { .. } => ApiError::NoStateAtBlock { msg: e.to_string() },
+                Error::TypesError(starknet_types::error::Error::SierraCompilationError {
+                    reason,
+                }) => ApiError::CompilationError { reason },
+                unknown_error => ApiError::StarknetDevnetError(unknown_error),
+            }),
+        }
+    }
+
     /// starknet_getClassAt
     pub async fn get_class_at(
         &self,
@@ -494,4 +581,76 @@ impl JsonRpcHandler {
         })
         .into())
     }
+
+    /// devnet_getSandboxes
+    pub async fn get_sandboxes(&self) -> StrictRpcResult {
+        Ok(DevnetResponse::Sandboxes(self.hosted_sandboxes()?.list().await).into())
+    }
+
+    /// devnet_getMempool
+    pub async fn get_mempool(&self) -> StrictRpcResult {
+        let mempool = self.api.starknet.lock().await.get_mempool()?;
+        Ok(DevnetResponse::Mempool(mempool).into())
+    }
+
+    /// devnet_getCoverage
+    pub async fn get_coverage(&self, request: CoverageRequest) -> StrictRpcResult {
+        let mut starknet = self.api.starknet.lock().await;
+        Ok(match request.format {
+            CoverageFormat::Json => {
+                DevnetResponse::Coverage(CoverageReport { classes: starknet.get_coverage() })
+            }
+            CoverageFormat::Lcov => {
+                DevnetResponse::LcovCoverage(LcovCoverage { lcov: starknet.get_coverage_lcov()? })
+            }
+        }
+        .into())
+    }
+
+    /// devnet_profileTransaction
+    pub async fn profile_transaction(&self, request: ProfileTransactionRequest) -> StrictRpcResult {
+        // simulation requires mutable access, same as starknet_simulateTransactions
+        let mut starknet = self.api.starknet.lock().await;
+        let trace = match (request.transaction_hash, request.simulation) {
+            (Some(transaction_hash), None) => {
+                match starknet.get_transaction_trace_by_hash(transaction_hash) {
+                    Ok(trace) => trace,
+                    Err(Error::NoTransaction) => return Err(ApiError::TransactionNotFound),
+                    Err(Error::UnsupportedTransactionType) => {
+                        return Err(ApiError::NoTraceAvailable);
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            (None, Some(simulation)) => {
+                match starknet.simulate_transactions(
+                    simulation.block_id.as_ref(),
+                    &[simulation.transaction],
+                    simulation.simulation_flags,
+                ) {
+                    Ok(mut simulated) => simulated.remove(0).transaction_trace,
+                    Err(Error::ContractNotFound) => return Err(ApiError::ContractNotFound),
+                    Err(Error::NoBlock) => return Err(ApiError::BlockNotFound),
+                    Err(e @ Error::NoStateAtBlock { .. }) => {
+                        return Err(ApiError::NoStateAtBlock { msg: e.to_string() });
+                    }
+                    Err(err) => return Err(ApiError::ContractError { error: err }),
+                }
+            }
+            _ => {
+                return Err(ApiError::RpcError(RpcError::invalid_params(
+                    "Exactly one of transaction_hash and simulation has to be provided",
+                )));
+            }
+        };
+
+        let profile = starknet.profile_transaction_trace(&trace)?;
+        Ok(match request.format {
+            ProfileFormat::Json => DevnetResponse::TransactionProfile(profile),
+            ProfileFormat::Folded => DevnetResponse::FoldedProfile(FoldedProfile {
+                folded: starknet_core::starknet::profiler::folded_stacks(&profile, request.metric),
+            }),
+        }
+        .into())
+    }
 }
@@ -60,6 +60,12 @@ pub enum ApiError {
     HttpApiError(#[from] HttpApiError),
     #[error("the compiled class hash did not match the one supplied in the transaction")]
     CompiledClassHashMismatch,
+    #[error("the node doesn't support storage proofs for blocks that are too far in the past")]
+    StorageProofNotSupported,
+    #[error("Failed to compile the contract")]
+    CompilationError { reason: String },
+    #[error("Sandbox {sandbox_id} not found")]
+    SandboxNotFound { sandbox_id: u64 },
 }
 
 impl ApiError {
@@ -171,6 +177,16 @@ impl ApiError {
                 message: error_message.into(),
                 data: None,
             },
+            ApiError::StorageProofNotSupported => RpcError {
+                code: crate::rpc_core::error::ErrorCode::ServerError(42),
+                message: error_message.into(),
+                data: None,
+            },
+            ApiError::CompilationError { reason } => RpcError {
+                code: crate::rpc_core::error::ErrorCode::ServerError(100),
+                message: error_message.into(),
+                data: Some(json!({ "compilation_error": reason })),
+            },
             ApiError::StarknetDevnetError(
                 starknet_core::error::Error::TransactionValidationError(validation_error),
             ) => {
@@ -198,6 +214,11 @@ impl ApiError {
                 message: error_message.into(),
                 data: None,
             },
+            ApiError::SandboxNotFound { .. } => RpcError {
+                code: crate::rpc_core::error::ErrorCode::ServerError(WILDCARD_RPC_ERROR_CODE),
+                message: error_message.into(),
+                data: None,
+            },
             ApiError::HttpApiError(http_api_error) => http_api_error.http_api_error_to_rpc_error(),
         }
     }
@@ -276,6 +297,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn storage_proof_not_supported_error() {
+        error_expected_code_and_message(
+            ApiError::StorageProofNotSupported,
+            42,
+            "the node doesn't support storage proofs for blocks that are too far in the past",
+        );
+    }
+
+    #[test]
+    fn compilation_error() {
+        let error = ApiError::CompilationError { reason: "some reason".into() };
+        let rpc_error = error.api_error_to_rpc_error();
+        assert_eq!(rpc_error.code, crate::rpc_core::error::ErrorCode::ServerError(100));
+        assert_eq!(rpc_error.message, "Failed to compile the contract");
+        assert_eq!(rpc_error.data, Some(serde_json::json!({ "compilation_error": "some reason" })));
+    }
+
     #[test]
     fn contract_error() {
         fn test_error() -> starknet_core::error::Error {
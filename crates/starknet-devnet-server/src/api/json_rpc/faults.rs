@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use starknet_types::rpc::devnet::{FaultConfig, InjectedError, MethodFaults};
+
+use super::error::ApiError;
+use crate::rpc_core::error::RpcError;
+
+/// Key of the faults applying to methods without their own entry
+const ANY_METHOD: &str = "*";
+
+/// Faults are never injected into this method, so that they can always be changed
+const SET_FAULTS_METHOD: &str = "devnet_setFaults";
+
+const INJECTED_FAULT_MESSAGE: &str = "Injected fault";
+
+/// The faults set via `devnet_setFaults` and the generator deciding when they occur
+pub struct Faults {
+    config: FaultConfig,
+    rng: ChaCha8Rng,
+}
+
+impl Default for Faults {
+    fn default() -> Self {
+        Self { config: FaultConfig::default(), rng: ChaCha8Rng::seed_from_u64(0) }
+    }
+}
+
+impl Faults {
+    /// Replaces the current faults and returns the seed of the generator
+    pub fn set(&mut self, config: FaultConfig) -> Result<u64, ApiError> {
+        validate(&config)?;
+
+        let seed = config.seed.unwrap_or_else(rand::random);
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self.config = config;
+        Ok(seed)
+    }
+
+    /// Delay to apply before handling a request of `method`
+    pub fn latency(&self, method: &str) -> Duration {
+        method_faults(&self.config, method)
+            .map_or(Duration::ZERO, |faults| Duration::from_millis(faults.latency_ms))
+    }
+
+    /// Decides if a request of `method` fails without being handled and with which error
+    pub fn draw_error(&mut self, method: &str) -> Option<ApiError> {
+        let Self { config, rng } = self;
+        let errors = &method_faults(config, method)?.errors;
+        if errors.is_empty() {
+            return None;
+        }
+
+        let mut draw: f64 = rng.gen();
+        for fault in errors {
+            if draw < fault.probability {
+                return Some(to_api_error(fault.error));
+            }
+            draw -= fault.probability;
+        }
+        None
+    }
+
+    /// Decides if the response to a handled request of `method` is dropped
+    pub fn drops_response(&mut self, method: &str) -> bool {
+        let Self { config, rng } = self;
+        match method_faults(config, method) {
+            Some(faults) if faults.drop_probability > 0.0 => rng.gen_bool(faults.drop_probability),
+            _ => false,
+        }
+    }
+
+    /// Decides if a request forwarded to the forking origin fails
+    pub fn origin_fails(&mut self) -> bool {
+        let probability = self.config.origin_failure_probability;
+        probability > 0.0 && self.rng.gen_bool(probability)
+    }
+}
+
+fn method_faults<'a>(config: &'a FaultConfig, method: &str) -> Option<&'a MethodFaults> {
+    if method == SET_FAULTS_METHOD {
+        return None;
+    }
+
+    config.methods.get(method).or_else(|| config.methods.get(ANY_METHOD))
+}
+
+fn validate(config: &FaultConfig) -> Result<(), ApiError> {
+    let is_probability = |value: f64| (0.0..=1.0).contains(&value);
+    let invalid = |msg: String| ApiError::RpcError(RpcError::invalid_params(msg));
+
+    if !is_probability(config.origin_failure_probability) {
+        return Err(invalid("origin_failure_probability must be between 0 and 1".into()));
+    }
+
+    for (method, faults) in &config.methods {
+        if !is_probability(faults.drop_probability)
+            || faults.errors.iter().any(|fault| !is_probability(fault.probability))
+        {
+            return Err(invalid(format!(
+                "Probabilities of faults of {method} must be between 0 and 1"
+            )));
+        }
+
+        let error_probability: f64 = faults.errors.iter().map(|fault| fault.probability).sum();
+        if error_probability > 1.0 {
+            return Err(invalid(format!(
+                "Probabilities of errors of {method} must not exceed 1 in sum"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn to_api_error(error: InjectedError) -> ApiError {
+    match error {
+        InjectedError::BlockNotFound => ApiError::BlockNotFound,
+        InjectedError::TransactionNotFound => ApiError::TransactionNotFound,
+        InjectedError::ContractNotFound => ApiError::ContractNotFound,
+        InjectedError::ClassHashNotFound => ApiError::ClassHashNotFound,
+        InjectedError::InvalidTransactionNonce => ApiError::InvalidTransactionNonce,
+        InjectedError::InsufficientMaxFee => ApiError::InsufficientMaxFee,
+        InjectedError::InsufficientAccountBalance => ApiError::InsufficientAccountBalance,
+        InjectedError::ValidationFailure => {
+            ApiError::ValidationFailure { reason: INJECTED_FAULT_MESSAGE.into() }
+        }
+        InjectedError::InternalError => {
+            ApiError::RpcError(RpcError::internal_error_with(INJECTED_FAULT_MESSAGE))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_types::rpc::devnet::{ErrorFault, FaultConfig, InjectedError, MethodFaults};
+
+    use super::Faults;
+    use crate::api::json_rpc::error::ApiError;
+
+    fn config_with_method_faults(method: &str, faults: MethodFaults) -> FaultConfig {
+        FaultConfig {
+            seed: Some(42),
+            methods: [(method.to_string(), faults)].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn same_seed_produces_same_faults() {
+        let config = config_with_method_faults(
+            "*",
+            MethodFaults {
+                errors: vec![ErrorFault { error: InjectedError::BlockNotFound, probability: 0.5 }],
+                drop_probability: 0.5,
+                ..Default::default()
+            },
+        );
+
+        let mut draws = vec![];
+        for _ in 0..2 {
+            let mut faults = Faults::default();
+            faults.set(config.clone()).unwrap();
+            draws.push(
+                (0..20)
+                    .map(|_| {
+                        (
+                            faults.draw_error("starknet_blockNumber").is_some(),
+                            faults.drops_response("starknet_blockNumber"),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        assert_eq!(draws[0], draws[1]);
+        assert!(draws[0].iter().any(|(failed, _)| *failed));
+        assert!(draws[0].iter().any(|(failed, _)| !*failed));
+    }
+
+    #[test]
+    fn method_specific_faults_take_precedence() {
+        let mut config = config_with_method_faults(
+            "*",
+            MethodFaults {
+                errors: vec![ErrorFault { error: InjectedError::InternalError, probability: 1.0 }],
+                ..Default::default()
+            },
+        );
+        config.methods.insert("starknet_chainId".into(), MethodFaults::default());
+
+        let mut faults = Faults::default();
+        faults.set(config).unwrap();
+
+        assert!(faults.draw_error("starknet_chainId").is_none());
+        assert!(matches!(faults.draw_error("starknet_blockNumber"), Some(ApiError::RpcError(_))));
+        assert!(faults.draw_error("devnet_setFaults").is_none());
+    }
+
+    #[test]
+    fn invalid_probabilities_rejected() {
+        let too_likely_errors = config_with_method_faults(
+            "starknet_call",
+            MethodFaults {
+                errors: vec![
+                    ErrorFault { error: InjectedError::ContractNotFound, probability: 0.6 },
+                    ErrorFault { error: InjectedError::BlockNotFound, probability: 0.6 },
+                ],
+                ..Default::default()
+            },
+        );
+        let invalid_drop_probability = config_with_method_faults(
+            "starknet_call",
+            MethodFaults { drop_probability: 1.5, ..Default::default() },
+        );
+
+        let mut faults = Faults::default();
+        for config in [too_likely_errors, invalid_drop_probability] {
+            assert!(matches!(faults.set(config), Err(ApiError::RpcError(_))));
+        }
+    }
+}
@@ -1,52 +1,93 @@
 mod endpoints;
 pub mod error;
+pub mod faults;
 pub mod models;
 pub(crate) mod origin_forwarder;
 #[cfg(test)]
 mod spec_reader;
+mod websocket;
 mod write_endpoints;
 
 pub const RPC_SPEC_VERSION: &str = "0.7.1";
+pub const RPC_SPEC_VERSION_0_8: &str = "0.8.0";
+
+/// The version of the JSON-RPC specification according to which requests are answered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RpcVersion {
+    /// Served on `/`, `/rpc` and `/ws`
+    #[default]
+    V0_7,
+    /// Served on `/rpc/v0_8`
+    V0_8,
+}
+
+impl RpcVersion {
+    pub fn spec_version(&self) -> &'static str {
+        match self {
+            RpcVersion::V0_7 => RPC_SPEC_VERSION,
+            RpcVersion::V0_8 => RPC_SPEC_VERSION_0_8,
+        }
+    }
+}
+
+use std::path::Path;
 
+use axum::extract::ws::WebSocket;
 use enum_helper_macros::{AllVariantsSerdeRenames, VariantName};
 use models::{
     BlockAndClassHashInput, BlockAndContractAddressInput, BlockAndIndexInput, CallInput,
-    EstimateFeeInput, EventsInput, GetStorageInput, TransactionHashInput, TransactionHashOutput,
+    CompiledCasmInput, EstimateFeeInput, EventsInput, EventsSubscriptionInput, GetStorageInput,
+    GetStorageProofInput, L1TransactionHashInput, SubscriptionIdInput, TransactionHashInput,
+    TransactionHashOutput,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use starknet_core::starknet::starknet_config::{DumpOn, StarknetConfig};
+use starknet_core::starknet::fork_cache::ForkCache;
+use starknet_core::starknet::starknet_config::{DumpFormat, DumpOn, StarknetConfig};
 use starknet_rs_core::types::{ContractClass as CodegenContractClass, Felt};
+use starknet_types::cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use starknet_types::messaging::{MessageToL1, MessageToL2};
 use starknet_types::rpc::block::{Block, PendingBlock};
+use starknet_types::rpc::devnet::{
+    AcceptedOnL1, AcceptingOnL1, CoverageReport, CoverageRequest, DropTransactionsRequest,
+    DroppedTransactions, FaultConfig, FaultsSet, FoldedProfile, LcovCoverage, MempoolTransaction,
+    ProfileTransactionRequest, SandboxCreationRequest, SandboxIdInput, SandboxInfo,
+    TransactionProfile,
+};
 use starknet_types::rpc::estimate_message_fee::{
     EstimateMessageFeeRequestWrapper, FeeEstimateWrapper,
 };
-use starknet_types::rpc::gas_modification::{GasModification, GasModificationRequest};
+use starknet_types::rpc::gas_modification::{
+    DataAvailabilityModeModification, DataAvailabilityModeRequest, GasModification,
+    GasModificationRequest,
+};
 use starknet_types::rpc::state::{PendingStateUpdate, StateUpdate};
+use starknet_types::rpc::storage_proof::StorageProof;
 use starknet_types::rpc::transaction_receipt::TransactionReceipt;
 use starknet_types::rpc::transactions::{
-    BlockTransactionTrace, EventsChunk, SimulatedTransaction, TransactionTrace, TransactionWithHash,
+    BlockTransactionTrace, BroadcastedTransaction, EventsChunk, SimulatedTransaction,
+    TransactionTrace, TransactionWithHash,
 };
+use starknet_types::rpc::v0_8;
 use starknet_types::starknet_api::block::BlockNumber;
 use tracing::{error, info, trace};
 
-use self::error::StrictRpcResult;
+use self::error::{ApiError, StrictRpcResult};
 use self::models::{
     AccountAddressInput, BlockHashAndNumberOutput, BlockIdInput,
     BroadcastedDeclareTransactionInput, BroadcastedDeployAccountTransactionInput,
     BroadcastedInvokeTransactionInput, DeclareTransactionOutput, DeployAccountTransactionOutput,
-    SyncingOutput, TransactionStatusOutput,
+    SetClassHashAtInput, SetNonceInput, SetStorageAtInput, SyncingOutput, TransactionStatusOutput,
 };
 use self::origin_forwarder::OriginForwarder;
-use super::http::endpoints::accounts::{BalanceQuery, PredeployedAccountsQuery};
 use super::http::endpoints::DevnetConfig;
 use super::http::models::{
-    AbortedBlocks, AbortingBlocks, AccountBalanceResponse, CreatedBlock, DumpPath,
-    DumpResponseBody, FlushParameters, FlushedMessages, IncreaseTime, IncreaseTimeResponse,
-    LoadPath, MessageHash, MessagingLoadAddress, MintTokensRequest, MintTokensResponse,
-    PostmanLoadL1MessagingContract, RestartParameters, SerializableAccount, SetTime,
-    SetTimeResponse,
+    AbortedBlocks, AbortingBlocks, AccountBalanceResponse, BalanceQuery, CreatedBlock, DumpPath,
+    DumpResponseBody, ExportGenesisPath, ExportedGenesis, FlushParameters, FlushedMessages,
+    IncreaseTime, IncreaseTimeResponse, LoadPath, MessageHash, MessagingLoadAddress,
+    MintTokensRequest, MintTokensResponse, PostmanLoadL1MessagingContract,
+    PredeployedAccountsQuery, RestartParameters, RevertingToSnapshot, SerializableAccount, SetTime,
+    SetTimeResponse, TakenSnapshot,
 };
 use super::Api;
 use crate::api::json_rpc::models::{
@@ -54,12 +95,13 @@ use crate::api::json_rpc::models::{
     BroadcastedInvokeTransactionEnumWrapper, SimulateTransactionsInput,
 };
 use crate::api::serde_helpers::{empty_params, optional_params};
-use crate::dump_util::dump_event;
+use crate::dump_util::{dump_event, dump_state};
 use crate::restrictive_mode::is_json_rpc_method_restricted;
 use crate::rpc_core::error::RpcError;
 use crate::rpc_core::request::RpcMethodCall;
 use crate::rpc_core::response::{ResponseResult, RpcResponse};
 use crate::rpc_handler::RpcHandler;
+use crate::sandbox::Sandboxes;
 use crate::ServerConfig;
 
 /// Helper trait to easily convert results to rpc results
@@ -87,6 +129,7 @@ impl ToRpcResponseResult for StrictRpcResult {
             Ok(JsonRpcResponse::Empty) => to_rpc_result(json!({})),
             Ok(JsonRpcResponse::Devnet(data)) => to_rpc_result(data),
             Ok(JsonRpcResponse::Starknet(data)) => to_rpc_result(data),
+            Ok(JsonRpcResponse::StarknetV0_8(data)) => to_rpc_result(data),
             Err(err) => err.api_error_to_rpc_error().into(),
         }
     }
@@ -101,6 +144,9 @@ pub struct JsonRpcHandler {
     pub origin_caller: Option<OriginForwarder>,
     pub starknet_config: StarknetConfig,
     pub server_config: ServerConfig,
+    pub rpc_version: RpcVersion,
+    /// `None` if this is the handler of a sandbox
+    pub sandboxes: Option<Sandboxes>,
 }
 
 #[async_trait::async_trait]
@@ -116,6 +162,14 @@ impl RpcHandler for JsonRpcHandler {
         self.execute(request, original_call).await
     }
 
+    async fn on_websocket(&self, socket: WebSocket) {
+        self.handle_websocket(socket).await
+    }
+
+    async fn drops_response(&self, method: &str) -> bool {
+        self.api.faults.lock().await.drops_response(method)
+    }
+
     async fn on_call(&self, call: RpcMethodCall) -> RpcResponse {
         trace!(target: "rpc",  id = ?call.id , method = ?call.method, "received method call");
         let RpcMethodCall { method, params, id, .. } = call.clone();
@@ -165,7 +219,12 @@ impl JsonRpcHandler {
         let origin_caller = if let (Some(url), Some(block_number)) =
             (&starknet_config.fork_config.url, starknet_config.fork_config.block_number)
         {
-            Some(OriginForwarder::new(url.to_string(), block_number))
+            let forwarder = OriginForwarder::new(url.to_string(), block_number);
+            Some(match &starknet_config.fork_config.cache_dir {
+                Some(dir) => forwarder
+                    .with_cache(ForkCache::new(Path::new(dir)), starknet_config.chain_id.into()),
+                None => forwarder,
+            })
         } else {
             None
         };
@@ -175,9 +234,23 @@ impl JsonRpcHandler {
             origin_caller,
             starknet_config: starknet_config.clone(),
             server_config: server_config.clone(),
+            rpc_version: RpcVersion::default(),
+            sandboxes: Some(Sandboxes::default()),
         }
     }
 
+    /// Returns a handler sharing the state of this one, which answers according to `rpc_version`
+    pub fn with_rpc_version(&self, rpc_version: RpcVersion) -> JsonRpcHandler {
+        JsonRpcHandler { rpc_version, ..self.clone() }
+    }
+
+    /// The sandboxes hosted by this Devnet; sandboxes cannot host sandboxes themselves
+    pub(crate) fn hosted_sandboxes(&self) -> Result<&Sandboxes, ApiError> {
+        self.sandboxes.as_ref().ok_or(ApiError::UnsupportedAction {
+            msg: "Sandboxes can only be managed via the hosting Devnet".into(),
+        })
+    }
+
     /// The method matches the request to the corresponding enum variant and executes the request
     async fn execute(
         &self,
@@ -189,6 +262,21 @@ impl JsonRpcHandler {
         // true if origin should be tried after request fails; relevant in forking mode
         let mut forwardable = true;
 
+        let latency = self.api.faults.lock().await.latency(&original_call.method);
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        let injected_error = self.api.faults.lock().await.draw_error(&original_call.method);
+        if let Some(injected_error) = injected_error {
+            return self.to_versioned_rpc_result(Err(injected_error));
+        }
+
+        if self.rpc_version == RpcVersion::V0_7 && request.has_l1_data_gas_bounds() {
+            return ResponseResult::Error(RpcError::invalid_params(
+                "L1 data gas bounds are only supported in JSON-RPC 0.8",
+            ));
+        }
+
         let starknet_resp = match request {
             JsonRpcRequest::SpecVersion => self.spec_version(),
             JsonRpcRequest::BlockWithTransactionHashes(block) => {
@@ -204,9 +292,19 @@ impl JsonRpcHandler {
             JsonRpcRequest::StorageAt(GetStorageInput { contract_address, key, block_id }) => {
                 self.get_storage_at(contract_address, key, block_id).await
             }
+            JsonRpcRequest::StorageProof(data) => match self.rpc_version {
+                RpcVersion::V0_7 => Err(ApiError::RpcError(RpcError::method_not_found())),
+                RpcVersion::V0_8 => self.get_storage_proof(data).await,
+            },
             JsonRpcRequest::TransactionStatusByHash(TransactionHashInput { transaction_hash }) => {
                 self.get_transaction_status_by_hash(transaction_hash).await
             }
+            JsonRpcRequest::MessagesStatus(L1TransactionHashInput { transaction_hash }) => {
+                match self.rpc_version {
+                    RpcVersion::V0_7 => Err(ApiError::RpcError(RpcError::method_not_found())),
+                    RpcVersion::V0_8 => self.get_messages_status(transaction_hash).await,
+                }
+            }
             JsonRpcRequest::TransactionByHash(TransactionHashInput { transaction_hash }) => {
                 self.get_transaction_by_hash(transaction_hash).await
             }
@@ -219,6 +317,12 @@ impl JsonRpcHandler {
             JsonRpcRequest::ClassByHash(BlockAndClassHashInput { block_id, class_hash }) => {
                 self.get_class(block_id, class_hash).await
             }
+            JsonRpcRequest::CompiledCasm(CompiledCasmInput { class_hash, block_id }) => {
+                match self.rpc_version {
+                    RpcVersion::V0_7 => Err(ApiError::RpcError(RpcError::method_not_found())),
+                    RpcVersion::V0_8 => self.get_compiled_casm(block_id, class_hash).await,
+                }
+            }
             JsonRpcRequest::ClassHashAtContractAddress(BlockAndContractAddressInput {
                 block_id,
                 contract_address,
@@ -293,8 +397,12 @@ impl JsonRpcHandler {
             }
             JsonRpcRequest::AutoImpersonate => self.set_auto_impersonate(true).await,
             JsonRpcRequest::StopAutoImpersonate => self.set_auto_impersonate(false).await,
+            JsonRpcRequest::SetStorageAt(data) => self.set_storage_at(data).await,
+            JsonRpcRequest::SetNonce(data) => self.set_nonce(data).await,
+            JsonRpcRequest::SetClassHashAt(data) => self.set_class_hash_at(data).await,
             JsonRpcRequest::Dump(path) => self.dump(path).await,
             JsonRpcRequest::Load(LoadPath { path }) => self.load(path).await,
+            JsonRpcRequest::ExportGenesis(path) => self.export_genesis(path).await,
             JsonRpcRequest::PostmanLoadL1MessagingContract(data) => self.postman_load(data).await,
             JsonRpcRequest::PostmanFlush(data) => self.postman_flush(data).await,
             JsonRpcRequest::PostmanSendMessageToL2(message) => {
@@ -305,7 +413,13 @@ impl JsonRpcHandler {
             }
             JsonRpcRequest::CreateBlock => self.create_block().await,
             JsonRpcRequest::AbortBlocks(data) => self.abort_blocks(data).await,
+            JsonRpcRequest::AcceptOnL1(data) => self.accept_on_l1(data.unwrap_or_default()).await,
+            JsonRpcRequest::TakeSnapshot => self.take_snapshot().await,
+            JsonRpcRequest::RevertToSnapshot(data) => self.revert_to_snapshot(data).await,
             JsonRpcRequest::SetGasPrice(data) => self.set_gas_price(data).await,
+            JsonRpcRequest::SetDataAvailabilityMode(data) => {
+                self.set_data_availability_mode(data).await
+            }
             JsonRpcRequest::Restart(data) => self.restart(data).await,
             JsonRpcRequest::SetTime(data) => self.set_time(data).await,
             JsonRpcRequest::IncreaseTime(data) => self.increase_time(data).await,
@@ -313,6 +427,16 @@ impl JsonRpcHandler {
             JsonRpcRequest::AccountBalance(data) => self.get_account_balance(data).await,
             JsonRpcRequest::Mint(data) => self.mint(data).await,
             JsonRpcRequest::DevnetConfig => self.get_devnet_config().await,
+            JsonRpcRequest::CreateSandbox(data) => self.create_sandbox(data).await,
+            JsonRpcRequest::Sandboxes => self.get_sandboxes().await,
+            JsonRpcRequest::DestroySandbox(data) => self.destroy_sandbox(data).await,
+            JsonRpcRequest::Mempool => self.get_mempool().await,
+            JsonRpcRequest::DropMempoolTransactions(data) => {
+                self.drop_mempool_transactions(data).await
+            }
+            JsonRpcRequest::SetFaults(data) => self.set_faults(data.unwrap_or_default()).await,
+            JsonRpcRequest::Coverage(data) => self.get_coverage(data.unwrap_or_default()).await,
+            JsonRpcRequest::ProfileTransaction(data) => self.profile_transaction(data).await,
         };
 
         // If locally we got an error and forking is set up, forward the request to the origin
@@ -331,7 +455,18 @@ impl JsonRpcHandler {
                     // `forwardable` flag
 
                     if forwardable {
-                        return forwarder.call(&original_call).await;
+                        if self.api.faults.lock().await.origin_fails() {
+                            return ResponseResult::Error(RpcError::internal_error_with(
+                                "Error in interacting with origin: injected fault",
+                            ));
+                        }
+                        let origin_result = forwarder.call(&original_call).await;
+                        return match self.rpc_version {
+                            RpcVersion::V0_7 => origin_result,
+                            RpcVersion::V0_8 => {
+                                origin_forwarder::into_v0_8(&original_call.method, origin_result)
+                            }
+                        };
                     }
                 }
                 _other_error => (),
@@ -339,12 +474,20 @@ impl JsonRpcHandler {
         }
 
         if starknet_resp.is_ok() {
-            if let Err(e) = self.update_dump(&original_call).await {
+            if let Err(e) = self.handle_changes(&original_call).await {
                 return ResponseResult::Error(e);
             }
         }
 
-        starknet_resp.to_rpc_result()
+        self.to_versioned_rpc_result(starknet_resp)
+    }
+
+    /// Converts the result according to the RPC version of this handler
+    fn to_versioned_rpc_result(&self, result: StrictRpcResult) -> ResponseResult {
+        match self.rpc_version {
+            RpcVersion::V0_7 => result.to_rpc_result(),
+            RpcVersion::V0_8 => result.map(JsonRpcResponse::into_v0_8).to_rpc_result(),
+        }
     }
 
     const DUMPABLE_METHODS: &'static [&'static str] = &[
@@ -352,16 +495,24 @@ impl JsonRpcHandler {
         "devnet_stopImpersonateAccount",
         "devnet_autoImpersonate",
         "devnet_stopAutoImpersonate",
+        "devnet_setStorageAt",
+        "devnet_setNonce",
+        "devnet_setClassHashAt",
         // "devnet_postmanFlush", - not dumped because it creates new RPC calls which get dumped
         "devnet_postmanLoad",
         "devnet_postmanSendMessageToL2",
         "devnet_postmanConsumeMessageFromL2",
         "devnet_createBlock",
         "devnet_abortBlocks",
+        "devnet_acceptOnL1",
+        "devnet_snapshot",
+        "devnet_revert",
         "devnet_setGasPrice",
+        "devnet_setDataAvailabilityMode",
         "devnet_setTime",
         "devnet_increaseTime",
         "devnet_mint",
+        "devnet_dropMempoolTransactions",
         "starknet_addInvokeTransaction",
         "starknet_addDeclareTransaction",
         "starknet_addDeployAccountTransaction",
@@ -381,11 +532,35 @@ impl JsonRpcHandler {
                         .as_deref()
                         .ok_or(RpcError::internal_error_with("Undefined dump_path"))?;
 
-                    dump_event(event, dump_path).map_err(|e| {
+                    let dumping_result = match self.starknet_config.dump_format {
+                        DumpFormat::Events => dump_event(event, dump_path),
+                        // the whole state is rewritten, so only once per block
+                        DumpFormat::State => {
+                            let starknet = self.api.starknet.lock().await;
+                            let latest_block_hash =
+                                starknet.get_latest_block().ok().map(|block| block.block_hash());
+                            let mut last_dumped_block_hash =
+                                self.api.last_dumped_block_hash.lock().await;
+
+                            if *last_dumped_block_hash == latest_block_hash {
+                                Ok(())
+                            } else {
+                                let dumping_result = dump_state(&starknet.dump_state(), dump_path);
+                                if dumping_result.is_ok() {
+                                    *last_dumped_block_hash = latest_block_hash;
+                                }
+                                dumping_result
+                            }
+                        }
+                    };
+                    dumping_result.map_err(|e| {
                         let msg = format!("Failed dumping of {}: {e}", event.method);
                         RpcError::internal_error_with(msg)
                     })?;
                 }
+                // the state is only read when dumping, so there is nothing to collect
+                DumpOn::Request | DumpOn::Exit
+                    if self.starknet_config.dump_format == DumpFormat::State => {}
                 DumpOn::Request | DumpOn::Exit => {
                     self.api.dumpable_events.lock().await.push(event.clone())
                 }
@@ -395,6 +570,30 @@ impl JsonRpcHandler {
         Ok(())
     }
 
+    /// Changes made by the request are written to the database in a single transaction, which also
+    /// saves the parts of Devnet not kept in database tables.
+    async fn update_database(&self, event: &RpcMethodCall) -> Result<(), RpcError> {
+        if self.starknet_config.db_path.is_none()
+            || !Self::DUMPABLE_METHODS.contains(&event.method.as_str())
+        {
+            return Ok(());
+        }
+
+        self.api.starknet.lock().await.save_to_database().map_err(|e| {
+            let msg = format!("Failed saving to database after {}: {e}", event.method);
+            RpcError::internal_error_with(msg)
+        })
+    }
+
+    /// Dumps and saves the changes made by `event`, which has been handled successfully, and
+    /// notifies the subscribers of them. Changes Devnet makes on its own, such as creating blocks
+    /// on time interval, are handled as if made by the equivalent request.
+    pub(crate) async fn handle_changes(&self, event: &RpcMethodCall) -> Result<(), RpcError> {
+        self.update_dump(event).await?;
+        self.update_database(event).await?;
+        self.broadcast_changes().await.map_err(|e| e.api_error_to_rpc_error())
+    }
+
     pub async fn re_execute(&self, events: &[RpcMethodCall]) -> Result<(), RpcError> {
         for event in events {
             if let ResponseResult::Error(e) = self.on_call(event.clone()).await.result {
@@ -421,8 +620,12 @@ pub enum JsonRpcRequest {
     StateUpdate(BlockIdInput),
     #[serde(rename = "starknet_getStorageAt")]
     StorageAt(GetStorageInput),
+    #[serde(rename = "starknet_getStorageProof")]
+    StorageProof(GetStorageProofInput),
     #[serde(rename = "starknet_getTransactionByHash")]
     TransactionByHash(TransactionHashInput),
+    #[serde(rename = "starknet_getMessagesStatus")]
+    MessagesStatus(L1TransactionHashInput),
     #[serde(rename = "starknet_getTransactionByBlockIdAndIndex")]
     TransactionByBlockAndIndex(BlockAndIndexInput),
     #[serde(rename = "starknet_getTransactionReceipt")]
@@ -431,6 +634,8 @@ pub enum JsonRpcRequest {
     TransactionStatusByHash(TransactionHashInput),
     #[serde(rename = "starknet_getClass")]
     ClassByHash(BlockAndClassHashInput),
+    #[serde(rename = "starknet_getCompiledCasm")]
+    CompiledCasm(CompiledCasmInput),
     #[serde(rename = "starknet_getClassHashAt")]
     ClassHashAtContractAddress(BlockAndContractAddressInput),
     #[serde(rename = "starknet_getClassAt")]
@@ -475,10 +680,18 @@ pub enum JsonRpcRequest {
     AutoImpersonate,
     #[serde(rename = "devnet_stopAutoImpersonate", with = "empty_params")]
     StopAutoImpersonate,
+    #[serde(rename = "devnet_setStorageAt")]
+    SetStorageAt(SetStorageAtInput),
+    #[serde(rename = "devnet_setNonce")]
+    SetNonce(SetNonceInput),
+    #[serde(rename = "devnet_setClassHashAt")]
+    SetClassHashAt(SetClassHashAtInput),
     #[serde(rename = "devnet_dump", with = "optional_params")]
     Dump(Option<DumpPath>),
     #[serde(rename = "devnet_load")]
     Load(LoadPath),
+    #[serde(rename = "devnet_exportGenesis", with = "optional_params")]
+    ExportGenesis(Option<ExportGenesisPath>),
     #[serde(rename = "devnet_postmanLoad")]
     PostmanLoadL1MessagingContract(PostmanLoadL1MessagingContract),
     #[serde(rename = "devnet_postmanFlush", with = "optional_params")]
@@ -491,8 +704,16 @@ pub enum JsonRpcRequest {
     CreateBlock,
     #[serde(rename = "devnet_abortBlocks")]
     AbortBlocks(AbortingBlocks),
+    #[serde(rename = "devnet_acceptOnL1", with = "optional_params")]
+    AcceptOnL1(Option<AcceptingOnL1>),
+    #[serde(rename = "devnet_snapshot", with = "empty_params")]
+    TakeSnapshot,
+    #[serde(rename = "devnet_revert")]
+    RevertToSnapshot(RevertingToSnapshot),
     #[serde(rename = "devnet_setGasPrice")]
     SetGasPrice(GasModificationRequest),
+    #[serde(rename = "devnet_setDataAvailabilityMode")]
+    SetDataAvailabilityMode(DataAvailabilityModeRequest),
     #[serde(rename = "devnet_restart", with = "optional_params")]
     Restart(Option<RestartParameters>),
     #[serde(rename = "devnet_setTime")]
@@ -507,7 +728,66 @@ pub enum JsonRpcRequest {
     Mint(MintTokensRequest),
     #[serde(rename = "devnet_getConfig", with = "empty_params")]
     DevnetConfig,
+    #[serde(rename = "devnet_createSandbox", with = "optional_params")]
+    CreateSandbox(Option<SandboxCreationRequest>),
+    #[serde(rename = "devnet_getSandboxes", with = "empty_params")]
+    Sandboxes,
+    #[serde(rename = "devnet_destroySandbox")]
+    DestroySandbox(SandboxIdInput),
+    #[serde(rename = "devnet_getMempool", with = "empty_params")]
+    Mempool,
+    #[serde(rename = "devnet_dropMempoolTransactions", with = "optional_params")]
+    DropMempoolTransactions(Option<DropTransactionsRequest>),
+    #[serde(rename = "devnet_setFaults", with = "optional_params")]
+    SetFaults(Option<FaultConfig>),
+    #[serde(rename = "devnet_getCoverage", with = "optional_params")]
+    Coverage(Option<CoverageRequest>),
+    #[serde(rename = "devnet_profileTransaction")]
+    ProfileTransaction(ProfileTransactionRequest),
+}
+
+/// Requests which are only available via WebSocket, as they rely on pushing notifications to the
+/// client.
+#[derive(Deserialize, AllVariantsSerdeRenames, VariantName)]
+#[cfg_attr(test, derive(Debug))]
+#[serde(tag = "method", content = "params")]
+pub enum JsonRpcSubscriptionRequest {
+    #[serde(rename = "starknet_subscribeNewHeads", with = "optional_params")]
+    NewHeads(Option<BlockIdInput>),
+    #[serde(rename = "starknet_subscribeTransactionStatus")]
+    TransactionStatus(TransactionHashInput),
+    #[serde(rename = "starknet_subscribeEvents", with = "optional_params")]
+    Events(Option<EventsSubscriptionInput>),
+    #[serde(rename = "starknet_unsubscribe")]
+    Unsubscribe(SubscriptionIdInput),
+}
+
+impl JsonRpcRequest {
+    /// L1 data gas bounds of transactions were introduced in JSON-RPC 0.8
+    fn has_l1_data_gas_bounds(&self) -> bool {
+        match self {
+            JsonRpcRequest::AddDeclareTransaction(BroadcastedDeclareTransactionInput {
+                declare_transaction: BroadcastedDeclareTransactionEnumWrapper::Declare(tx),
+            }) => tx.has_l1_data_gas_bounds(),
+            JsonRpcRequest::AddDeployAccountTransaction(
+                BroadcastedDeployAccountTransactionInput {
+                    deploy_account_transaction:
+                        BroadcastedDeployAccountTransactionEnumWrapper::DeployAccount(tx),
+                },
+            ) => tx.has_l1_data_gas_bounds(),
+            JsonRpcRequest::AddInvokeTransaction(BroadcastedInvokeTransactionInput {
+                invoke_transaction: BroadcastedInvokeTransactionEnumWrapper::Invoke(tx),
+            }) => tx.has_l1_data_gas_bounds(),
+            JsonRpcRequest::EstimateFee(EstimateFeeInput { request: transactions, .. })
+            | JsonRpcRequest::SimulateTransactions(SimulateTransactionsInput {
+                transactions,
+                ..
+            }) => transactions.iter().any(BroadcastedTransaction::has_l1_data_gas_bounds),
+            _ => false,
+        }
+    }
 }
+
 impl std::fmt::Display for JsonRpcRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.variant_name())
@@ -518,16 +798,33 @@ impl std::fmt::Display for JsonRpcRequest {
 #[serde(untagged)]
 pub enum JsonRpcResponse {
     Starknet(StarknetResponse),
+    StarknetV0_8(StarknetResponseV0_8),
     Devnet(DevnetResponse),
     Empty,
 }
 
+impl JsonRpcResponse {
+    /// Converts the responses whose shape differs in JSON-RPC 0.8
+    fn into_v0_8(self) -> Self {
+        match self {
+            JsonRpcResponse::Starknet(resp) => JsonRpcResponse::StarknetV0_8(resp.into()),
+            other => other,
+        }
+    }
+}
+
 impl From<StarknetResponse> for JsonRpcResponse {
     fn from(resp: StarknetResponse) -> Self {
         JsonRpcResponse::Starknet(resp)
     }
 }
 
+impl From<StarknetResponseV0_8> for JsonRpcResponse {
+    fn from(resp: StarknetResponseV0_8) -> Self {
+        JsonRpcResponse::StarknetV0_8(resp)
+    }
+}
+
 impl From<DevnetResponse> for JsonRpcResponse {
     fn from(resp: DevnetResponse) -> Self {
         JsonRpcResponse::Devnet(resp)
@@ -562,6 +859,58 @@ pub enum StarknetResponse {
     SimulateTransactions(Vec<SimulatedTransaction>),
     TraceTransaction(TransactionTrace),
     BlockTransactionTraces(Vec<BlockTransactionTrace>),
+    StorageProof(Box<StorageProof>),
+    CompiledCasm(Box<CasmContractClass>),
+}
+
+/// The responses of JSON-RPC 0.8 whose shape differs from JSON-RPC 0.7
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum StarknetResponseV0_8 {
+    Block(v0_8::Block),
+    PendingBlock(v0_8::PendingBlock),
+    Transaction(v0_8::TransactionWithHash),
+    TransactionReceiptByTransactionHash(Box<v0_8::TransactionReceipt>),
+    TransactionStatusByHash(v0_8::TransactionStatus),
+    EstimateFee(Vec<v0_8::FeeEstimate>),
+    EstimateMessageFee(v0_8::FeeEstimate),
+    SimulateTransactions(Vec<v0_8::SimulatedTransaction>),
+    TraceTransaction(v0_8::TransactionTrace),
+    BlockTransactionTraces(Vec<v0_8::BlockTransactionTrace>),
+    MessagesStatus(Vec<v0_8::MessageStatus>),
+    Unchanged(StarknetResponse),
+}
+
+impl From<StarknetResponse> for StarknetResponseV0_8 {
+    fn from(resp: StarknetResponse) -> Self {
+        match resp {
+            StarknetResponse::Block(block) => Self::Block(block.into()),
+            StarknetResponse::PendingBlock(block) => Self::PendingBlock(block.into()),
+            StarknetResponse::Transaction(transaction) => {
+                Self::Transaction(v0_8::TransactionWithHash(transaction))
+            }
+            StarknetResponse::TransactionReceiptByTransactionHash(receipt) => {
+                Self::TransactionReceiptByTransactionHash(Box::new((*receipt).into()))
+            }
+            StarknetResponse::TransactionStatusByHash(status) => {
+                Self::TransactionStatusByHash(status.into())
+            }
+            StarknetResponse::EstimateFee(fee_estimates) => {
+                Self::EstimateFee(fee_estimates.into_iter().map(Into::into).collect())
+            }
+            StarknetResponse::EstimateMessageFee(fee_estimate) => {
+                Self::EstimateMessageFee(fee_estimate.into())
+            }
+            StarknetResponse::SimulateTransactions(simulations) => {
+                Self::SimulateTransactions(simulations.into_iter().map(Into::into).collect())
+            }
+            StarknetResponse::TraceTransaction(trace) => Self::TraceTransaction(trace.into()),
+            StarknetResponse::BlockTransactionTraces(traces) => {
+                Self::BlockTransactionTraces(traces.into_iter().map(Into::into).collect())
+            }
+            unchanged => Self::Unchanged(unchanged),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -572,6 +921,8 @@ pub enum DevnetResponse {
     MessageHash(MessageHash),
     CreatedBlock(CreatedBlock),
     AbortedBlocks(AbortedBlocks),
+    AcceptedOnL1(AcceptedOnL1),
+    TakenSnapshot(TakenSnapshot),
     GasModification(GasModification),
     SetTime(SetTimeResponse),
     IncreaseTime(IncreaseTimeResponse),
@@ -581,6 +932,17 @@ pub enum DevnetResponse {
     MintTokens(MintTokensResponse),
     DevnetConfig(DevnetConfig),
     DevnetDump(DumpResponseBody),
+    ExportedGenesis(ExportedGenesis),
+    Sandbox(SandboxInfo),
+    Sandboxes(Vec<SandboxInfo>),
+    Mempool(Vec<MempoolTransaction>),
+    DroppedTransactions(DroppedTransactions),
+    FaultsSet(FaultsSet),
+    Coverage(CoverageReport),
+    LcovCoverage(LcovCoverage),
+    TransactionProfile(TransactionProfile),
+    FoldedProfile(FoldedProfile),
+    DataAvailabilityModeModification(DataAvailabilityModeModification),
 }
 
 #[cfg(test)]
@@ -646,6 +1008,37 @@ mod requests_tests {
         );
     }
 
+    #[test]
+    fn deserialize_get_messages_status_request() {
+        let json_str =
+            r#"{"method":"starknet_getMessagesStatus","params":{"transaction_hash":"0xabc"}}"#;
+        assert_deserialization_succeeds(json_str);
+
+        assert_deserialization_fails(
+            &json_str.replace("0xabc", &format!("0x{}", "1".repeat(65))),
+            "unexpected length for 256-bit hash",
+        );
+    }
+
+    #[test]
+    fn deserialize_get_storage_proof_request() {
+        let json_str = r#"{"method":"starknet_getStorageProof","params":{"block_id":"latest","contracts_storage_keys":[{"contract_address":"0x1","storage_keys":["0x2"]}]}}"#;
+
+        match serde_json::from_str::<JsonRpcRequest>(json_str).unwrap() {
+            JsonRpcRequest::StorageProof(input) => {
+                assert!(input.class_hashes.is_empty());
+                assert!(input.contract_addresses.is_empty());
+                assert_eq!(input.contracts_storage_keys.len(), 1);
+            }
+            _ => panic!("Wrong request type"),
+        }
+
+        assert_deserialization_fails(
+            &json_str.replace(r#""storage_keys""#, r#""keys""#),
+            "unknown field `keys`",
+        );
+    }
+
     #[test]
     fn deserialize_get_transaction_by_hash_request() {
         let json_str = r#"{"method":"starknet_getTransactionByHash","params":{"transaction_hash":"0x134134"}}"#;
@@ -711,6 +1104,20 @@ mod requests_tests {
         );
     }
 
+    #[test]
+    fn deserialize_get_compiled_casm_request() {
+        let json_str = r#"{"method":"starknet_getCompiledCasm","params":{"class_hash":"0xAAABB"}}"#;
+        assert_deserialization_succeeds(json_str);
+        assert_deserialization_succeeds(
+            &json_str.replace(r#""class_hash""#, r#""block_id":{"block_number":1},"class_hash""#),
+        );
+
+        assert_deserialization_fails(
+            json_str.replace("0x", "").as_str(),
+            "Expected hex string to be prefixed by '0x'",
+        );
+    }
+
     #[test]
     fn deserialize_get_class_hash_at_request() {
         let json_str = r#"{"method":"starknet_getClassHashAt","params":{"block_id":"latest","contract_address":"0xAAABB"}}"#;
@@ -1,16 +1,32 @@
 use serde::{Deserialize, Serialize};
-use starknet_rs_core::types::{TransactionExecutionStatus, TransactionFinalityStatus};
+use starknet_rs_core::types::{
+    Felt, Hash256, SequencerTransactionStatus, TransactionExecutionStatus,
+    TransactionFinalityStatus,
+};
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::felt::{BlockHash, ClassHash, TransactionHash};
 use starknet_types::patricia_key::PatriciaKey;
 use starknet_types::rpc::block::BlockId;
+pub use starknet_types::rpc::devnet::{
+    AccountAddressInput, SetClassHashAtInput, SetNonceInput, SetStorageAtInput,
+};
+use starknet_types::rpc::storage_proof::ContractStorageKeys;
 use starknet_types::rpc::transactions::{
     BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
     BroadcastedInvokeTransaction, BroadcastedTransaction, EventFilter, FunctionCall,
     SimulationFlag,
 };
+use starknet_types::rpc::v0_8;
 use starknet_types::starknet_api::block::BlockNumber;
 
+use crate::subscribe::SubscriptionId;
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct L1TransactionHashInput {
+    pub transaction_hash: Hash256,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct BlockIdInput {
@@ -32,6 +48,18 @@ pub struct GetStorageInput {
     pub block_id: BlockId,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GetStorageProofInput {
+    pub block_id: BlockId,
+    #[serde(default)]
+    pub class_hashes: Vec<ClassHash>,
+    #[serde(default)]
+    pub contract_addresses: Vec<ContractAddress>,
+    #[serde(default)]
+    pub contracts_storage_keys: Vec<ContractStorageKeys>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct BlockAndIndexInput {
@@ -48,15 +76,18 @@ pub struct BlockAndClassHashInput {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
-pub struct BlockAndContractAddressInput {
-    pub block_id: BlockId,
-    pub contract_address: ContractAddress,
+pub struct CompiledCasmInput {
+    pub class_hash: ClassHash,
+    /// If omitted, the latest block is used
+    #[serde(default)]
+    pub block_id: Option<BlockId>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
-pub struct AccountAddressInput {
-    pub account_address: ContractAddress,
+pub struct BlockAndContractAddressInput {
+    pub block_id: BlockId,
+    pub contract_address: ContractAddress,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -164,12 +195,76 @@ pub struct SimulateTransactionsInput {
     pub simulation_flags: Vec<SimulationFlag>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[cfg_attr(test, derive(Deserialize))]
 #[serde(deny_unknown_fields)]
 pub struct TransactionStatusOutput {
-    pub finality_status: TransactionFinalityStatus,
-    pub execution_status: TransactionExecutionStatus,
+    pub finality_status: SequencerTransactionStatus,
+    /// Absent if the transaction hasn't been executed yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    /// Only reported by JSON-RPC 0.8
+    #[serde(skip)]
+    pub failure_reason: Option<String>,
+}
+
+impl TransactionStatusOutput {
+    pub fn executed(
+        execution_status: TransactionExecutionStatus,
+        finality_status: TransactionFinalityStatus,
+        failure_reason: Option<String>,
+    ) -> Self {
+        Self {
+            finality_status: match finality_status {
+                TransactionFinalityStatus::AcceptedOnL2 => SequencerTransactionStatus::AcceptedOnL2,
+                TransactionFinalityStatus::AcceptedOnL1 => SequencerTransactionStatus::AcceptedOnL1,
+            },
+            execution_status: Some(execution_status),
+            failure_reason,
+        }
+    }
+
+    /// The status of a transaction waiting in the mempool
+    pub fn received() -> Self {
+        Self {
+            finality_status: SequencerTransactionStatus::Received,
+            execution_status: None,
+            failure_reason: None,
+        }
+    }
+
+    /// The status of a transaction which failed validation
+    pub fn rejected(failure_reason: &str) -> Self {
+        Self {
+            finality_status: SequencerTransactionStatus::Rejected,
+            execution_status: None,
+            failure_reason: Some(failure_reason.into()),
+        }
+    }
+}
+
+impl From<TransactionStatusOutput> for v0_8::TransactionStatus {
+    fn from(status: TransactionStatusOutput) -> Self {
+        Self {
+            finality_status: status.finality_status,
+            execution_status: status.execution_status,
+            failure_reason: status.failure_reason,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventsSubscriptionInput {
+    pub from_address: Option<ContractAddress>,
+    pub keys: Option<Vec<Vec<Felt>>>,
+    pub block_id: Option<BlockId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionIdInput {
+    pub subscription_id: SubscriptionId,
 }
 
 #[cfg(test)]
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use serde_json::json;
+use serde_json::{json, Map, Value};
+use starknet_core::starknet::fork_cache::ForkCache;
+use starknet_rs_core::types::Felt;
+use tracing::warn;
 
 use crate::rpc_core::error::RpcError;
 use crate::rpc_core::request::RpcMethodCall;
@@ -12,12 +15,16 @@ use crate::rpc_core::response::{ResponseResult, RpcResponse};
 /// - NoStateAtBlock
 /// - ClassHashNotFound
 ///
-/// Basic contract-wise interaction is handled by `BlockingOriginReader`
+/// Basic contract-wise interaction is handled by `BlockingOriginReader`. If a cache is specified,
+/// results of requests about the forking block or an older one are read from it when possible and
+/// stored in it otherwise, just like the responses of `BlockingOriginReader`.
 #[derive(Clone)]
 pub struct OriginForwarder {
     reqwest_client: reqwest::Client,
     url: Arc<String>,
     block_number: u64,
+    /// The cache, together with the chain ID of the origin
+    cache: Option<(ForkCache, Felt)>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -25,7 +32,38 @@ enum OriginInteractionError {}
 
 impl OriginForwarder {
     pub fn new(url: String, block_number: u64) -> Self {
-        Self { reqwest_client: reqwest::Client::new(), url: Arc::new(url), block_number }
+        Self {
+            reqwest_client: reqwest::Client::new(),
+            url: Arc::new(url),
+            block_number,
+            cache: None,
+        }
+    }
+
+    pub fn with_cache(mut self, cache: ForkCache, chain_id: Felt) -> Self {
+        self.cache = Some((cache, chain_id));
+        self
+    }
+
+    /// Returns true if the block of the request is specified by a number not greater than the
+    /// forking block number. Only results of such requests are final and can be cached.
+    fn is_about_old_block(&self, rpc_call: &RpcMethodCall) -> bool {
+        let is_old_block_id = |block_id: &Value| {
+            block_id
+                .get("block_number")
+                .and_then(Value::as_u64)
+                .is_some_and(|block_number| block_number <= self.block_number)
+        };
+
+        match &rpc_call.params {
+            crate::rpc_core::request::RequestParams::None => false,
+            crate::rpc_core::request::RequestParams::Array(params) => {
+                params.iter().any(is_old_block_id)
+            }
+            crate::rpc_core::request::RequestParams::Object(params) => {
+                params.get("block_id").is_some_and(is_old_block_id)
+            }
+        }
     }
 
     /// In case block tag "pending" or "latest" is a part of the request, it is replaced with the
@@ -46,7 +84,9 @@ impl OriginForwarder {
             }
             crate::rpc_core::request::RequestParams::Object(ref mut params) => {
                 if let Some(block_id) = params.get_mut("block_id") {
-                    *block_id = origin_block_id;
+                    if let Some("latest" | "pending") = block_id.as_str() {
+                        *block_id = origin_block_id;
+                    }
                 }
             }
         }
@@ -58,6 +98,17 @@ impl OriginForwarder {
         rpc_call: &RpcMethodCall,
     ) -> Result<ResponseResult, anyhow::Error> {
         let rpc_call = self.clone_call_with_origin_block_id(rpc_call);
+        let cache = self.cache.as_ref().filter(|_| self.is_about_old_block(&rpc_call));
+        let params = serde_json::to_value(&rpc_call.params)?;
+
+        if let Some((cache, chain_id)) = cache {
+            match cache.get(*chain_id, self.block_number, &rpc_call.method, &params) {
+                Ok(Some(cached)) => return Ok(ResponseResult::Success(cached)),
+                Ok(None) => (),
+                Err(e) => warn!("Failed to read from fork cache: {e}"),
+            }
+        }
+
         let origin_rpc_resp: RpcResponse = self
             .reqwest_client
             .post(self.url.to_string())
@@ -67,6 +118,17 @@ impl OriginForwarder {
             .json()
             .await?;
 
+        // errors are not cached, since they may be temporary
+        if let (Some((cache, chain_id)), ResponseResult::Success(result)) =
+            (cache, &origin_rpc_resp.result)
+        {
+            if let Err(e) =
+                cache.put(*chain_id, self.block_number, &rpc_call.method, &params, result)
+            {
+                warn!("Failed to write to fork cache: {e}");
+            }
+        }
+
         Ok(origin_rpc_resp.result)
     }
 
@@ -80,12 +142,154 @@ impl OriginForwarder {
     }
 }
 
+/// Converts the result of a request forwarded to the origin, which is expected to answer according
+/// to JSON-RPC 0.7, to the shape of JSON-RPC 0.8. Parts already in the shape of JSON-RPC 0.8 are
+/// left as they are. Since origin's execution resources don't contain the total gas consumed, the
+/// converted execution resources only report the gas consumed for data availability.
+pub(crate) fn into_v0_8(method: &str, result: ResponseResult) -> ResponseResult {
+    let mut value = match result {
+        ResponseResult::Success(value) => value,
+        error => return error,
+    };
+
+    match method {
+        "starknet_getBlockWithTxHashes"
+        | "starknet_getBlockWithTxs"
+        | "starknet_getBlockWithReceipts" => {
+            if let Some(block) = value.as_object_mut() {
+                block
+                    .entry("l2_gas_price")
+                    .or_insert_with(|| json!({ "price_in_fri": "0x0", "price_in_wei": "0x0" }));
+            }
+            for transaction in value["transactions"].as_array_mut().into_iter().flatten() {
+                if transaction.get("receipt").is_some() {
+                    convert_transaction(&mut transaction["transaction"]);
+                    convert_receipt(&mut transaction["receipt"]);
+                } else {
+                    convert_transaction(transaction);
+                }
+            }
+        }
+        "starknet_getTransactionByHash" | "starknet_getTransactionByBlockIdAndIndex" => {
+            convert_transaction(&mut value)
+        }
+        "starknet_getTransactionReceipt" => convert_receipt(&mut value),
+        "starknet_estimateFee" => {
+            value.as_array_mut().into_iter().flatten().for_each(convert_fee_estimate)
+        }
+        "starknet_estimateMessageFee" => convert_fee_estimate(&mut value),
+        "starknet_simulateTransactions" => {
+            for simulation in value.as_array_mut().into_iter().flatten() {
+                convert_trace(&mut simulation["transaction_trace"]);
+                convert_fee_estimate(&mut simulation["fee_estimation"]);
+            }
+        }
+        "starknet_traceTransaction" => convert_trace(&mut value),
+        "starknet_traceBlockTransactions" => {
+            for trace in value.as_array_mut().into_iter().flatten() {
+                convert_trace(&mut trace["trace_root"]);
+            }
+        }
+        _ => (),
+    }
+
+    ResponseResult::Success(value)
+}
+
+fn convert_transaction(transaction: &mut Value) {
+    if let Some(resource_bounds) =
+        transaction.get_mut("resource_bounds").and_then(Value::as_object_mut)
+    {
+        resource_bounds
+            .entry("l1_data_gas")
+            .or_insert_with(|| json!({ "max_amount": "0x0", "max_price_per_unit": "0x0" }));
+    }
+}
+
+fn convert_receipt(receipt: &mut Value) {
+    if let Some(execution_resources) = receipt.get_mut("execution_resources") {
+        convert_execution_resources(execution_resources);
+    }
+}
+
+fn convert_execution_resources(execution_resources: &mut Value) {
+    if execution_resources.get("l2_gas").is_some() {
+        return;
+    }
+
+    let data_availability = &execution_resources["data_availability"];
+    *execution_resources = json!({
+        "l1_gas": data_availability.get("l1_gas").cloned().unwrap_or(json!(0)),
+        "l1_data_gas": data_availability.get("l1_data_gas").cloned().unwrap_or(json!(0)),
+        "l2_gas": 0,
+    });
+}
+
+fn convert_fee_estimate(fee_estimate: &mut Value) {
+    let Some(fee_estimate) = fee_estimate.as_object_mut() else { return };
+    if !fee_estimate.contains_key("gas_consumed") {
+        return;
+    }
+
+    let mut converted = Map::new();
+    for (old_key, new_key) in [
+        ("gas_consumed", "l1_gas_consumed"),
+        ("gas_price", "l1_gas_price"),
+        ("data_gas_consumed", "l1_data_gas_consumed"),
+        ("data_gas_price", "l1_data_gas_price"),
+    ] {
+        converted.insert(new_key.into(), fee_estimate.remove(old_key).unwrap_or(json!("0x0")));
+    }
+    converted.insert("l2_gas_consumed".into(), json!("0x0"));
+    converted.insert("l2_gas_price".into(), json!("0x0"));
+    fee_estimate.append(&mut converted);
+}
+
+fn convert_trace(trace: &mut Value) {
+    for invocation_key in [
+        "validate_invocation",
+        "execute_invocation",
+        "fee_transfer_invocation",
+        "constructor_invocation",
+        "function_invocation",
+    ] {
+        if let Some(invocation) = trace.get_mut(invocation_key) {
+            convert_invocation(invocation);
+        }
+    }
+    if let Some(execution_resources) = trace.get_mut("execution_resources") {
+        convert_execution_resources(execution_resources);
+    }
+}
+
+/// Call execution resources of JSON-RPC 0.8 are reported as zero, as they are by Devnet itself
+fn convert_invocation(invocation: &mut Value) {
+    // a reverted execution, a null invocation or an invocation already in the shape of 0.8
+    let Some(invocation_object) = invocation.as_object_mut() else { return };
+    if invocation_object.contains_key("revert_reason")
+        || invocation_object.contains_key("is_reverted")
+    {
+        return;
+    }
+
+    invocation_object.insert("execution_resources".into(), json!({ "l1_gas": 0, "l2_gas": 0 }));
+    invocation_object.insert("is_reverted".into(), json!(false));
+    for call in
+        invocation_object.get_mut("calls").and_then(Value::as_array_mut).into_iter().flatten()
+    {
+        convert_invocation(call);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
+    use starknet_core::starknet::fork_cache::ForkCache;
+    use starknet_rs_core::types::Felt;
 
     use super::OriginForwarder;
     use crate::rpc_core::request::RpcMethodCall;
+    use crate::rpc_core::response::ResponseResult;
 
     #[test]
     fn test_replacing_block_id() {
@@ -110,6 +314,11 @@ mod tests {
                 json!({ "param1": "a", "param2": 1, "block_id": "pending", "param3": 2 }),
                 json!({ "param1": "a", "param2": 1, "block_id": { "block_number": 10 }, "param3": 2 }),
             ),
+            (
+                "2.0",
+                json!({ "param1": "a", "block_id": { "block_number": 5 } }),
+                json!({ "param1": "a", "block_id": { "block_number": 5 } }),
+            ),
         ] {
             let mut orig_body = common_body.clone();
             orig_body["jsonrpc"] = serde_json::Value::String(jsonrpc_value.into());
@@ -126,4 +335,150 @@ mod tests {
             assert_eq!(replaced_request_json, expected_body);
         }
     }
+
+    #[tokio::test]
+    async fn results_about_old_blocks_read_from_cache() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = ForkCache::new(cache_dir.path());
+        let chain_id = Felt::from(1);
+        let block_number = 10;
+        // the origin is unreachable, so only cached results can be returned
+        let forwarder = OriginForwarder::new("http://localhost:1".to_string(), block_number)
+            .with_cache(cache.clone(), chain_id);
+
+        let method = "starknet_getBlockWithTxHashes";
+        let cached_result = json!({ "block_number": 5 });
+        for block_id in [json!({ "block_number": 5 }), json!({ "block_number": 11 })] {
+            let params = json!({ "block_id": block_id });
+            cache.put(chain_id, block_number, method, &params, &cached_result).unwrap();
+        }
+
+        let request = |block_id: serde_json::Value| -> RpcMethodCall {
+            serde_json::from_value(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": { "block_id": block_id },
+            }))
+            .unwrap()
+        };
+
+        let old_block_result = forwarder.call(&request(json!({ "block_number": 5 }))).await;
+        assert!(
+            matches!(old_block_result, ResponseResult::Success(value) if value == cached_result)
+        );
+
+        // the latest block resolves to the forking block, which is not cached yet
+        let latest_block_result = forwarder.call(&request(json!("latest"))).await;
+        assert!(matches!(latest_block_result, ResponseResult::Error(_)));
+
+        // blocks after the forking one may change, so they are always forwarded
+        let new_block_result = forwarder.call(&request(json!({ "block_number": 11 }))).await;
+        assert!(matches!(new_block_result, ResponseResult::Error(_)));
+    }
+
+    #[test]
+    fn forwarded_results_converted_to_v0_8() {
+        let block = json!({
+            "block_number": 1,
+            "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x2" },
+            "transactions": [{
+                "transaction": {
+                    "type": "INVOKE",
+                    "version": "0x3",
+                    "resource_bounds": {
+                        "l1_gas": { "max_amount": "0x1", "max_price_per_unit": "0x2" },
+                        "l2_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+                    },
+                },
+                "receipt": {
+                    "execution_resources": {
+                        "steps": 100,
+                        "data_availability": { "l1_gas": 0, "l1_data_gas": 128 },
+                    },
+                },
+            }],
+        });
+
+        let ResponseResult::Success(converted) =
+            super::into_v0_8("starknet_getBlockWithReceipts", ResponseResult::Success(block))
+        else {
+            panic!("Expected success");
+        };
+        assert_eq!(
+            converted["l2_gas_price"],
+            json!({ "price_in_fri": "0x0", "price_in_wei": "0x0" })
+        );
+        assert_eq!(
+            converted["transactions"][0]["transaction"]["resource_bounds"]["l1_data_gas"],
+            json!({ "max_amount": "0x0", "max_price_per_unit": "0x0" })
+        );
+        assert_eq!(
+            converted["transactions"][0]["receipt"]["execution_resources"],
+            json!({ "l1_gas": 0, "l1_data_gas": 128, "l2_gas": 0 })
+        );
+
+        // converting again changes nothing
+        let converted_again = super::into_v0_8(
+            "starknet_getBlockWithReceipts",
+            ResponseResult::Success(converted.clone()),
+        );
+        assert!(matches!(converted_again, ResponseResult::Success(value) if value == converted));
+    }
+
+    #[test]
+    fn forwarded_fee_estimate_and_trace_converted_to_v0_8() {
+        let simulation = json!([{
+            "transaction_trace": {
+                "type": "INVOKE",
+                "execute_invocation": {
+                    "execution_resources": { "steps": 10 },
+                    "calls": [{ "execution_resources": { "steps": 5 }, "calls": [] }],
+                },
+                "fee_transfer_invocation": null,
+                "execution_resources": {
+                    "steps": 20,
+                    "data_availability": { "l1_gas": 0, "l1_data_gas": 64 },
+                },
+            },
+            "fee_estimation": {
+                "gas_consumed": "0x1",
+                "gas_price": "0x2",
+                "data_gas_consumed": "0x3",
+                "data_gas_price": "0x4",
+                "overall_fee": "0xe",
+                "unit": "WEI",
+            },
+        }]);
+
+        let ResponseResult::Success(converted) =
+            super::into_v0_8("starknet_simulateTransactions", ResponseResult::Success(simulation))
+        else {
+            panic!("Expected success");
+        };
+        assert_eq!(
+            converted[0]["fee_estimation"],
+            json!({
+                "l1_gas_consumed": "0x1",
+                "l1_gas_price": "0x2",
+                "l2_gas_consumed": "0x0",
+                "l2_gas_price": "0x0",
+                "l1_data_gas_consumed": "0x3",
+                "l1_data_gas_price": "0x4",
+                "overall_fee": "0xe",
+                "unit": "WEI",
+            })
+        );
+
+        let trace = &converted[0]["transaction_trace"];
+        assert_eq!(
+            trace["execute_invocation"]["calls"][0],
+            json!({ "execution_resources": { "l1_gas": 0, "l2_gas": 0 }, "is_reverted": false, "calls": [] })
+        );
+        assert_eq!(trace["fee_transfer_invocation"], json!(null));
+        assert_eq!(
+            trace["execution_resources"],
+            json!({ "l1_gas": 0, "l1_data_gas": 64, "l2_gas": 0 })
+        );
+    }
 }
@@ -0,0 +1,466 @@
+use axum::extract::ws::{Message, WebSocket};
+use futures::{SinkExt, StreamExt};
+use starknet_core::error::Error;
+use starknet_core::starknet::Starknet;
+use starknet_rs_core::types::{BlockId as ImportedBlockId, Felt};
+use starknet_types::felt::TransactionHash;
+use starknet_types::rpc::block::{BlockHeader, BlockId};
+use starknet_types::starknet_api::block::BlockNumber;
+use tokio::sync::mpsc;
+use tracing::{error, trace};
+
+use super::error::ApiError;
+use super::models::{EventsSubscriptionInput, TransactionStatusOutput};
+use super::{JsonRpcHandler, JsonRpcSubscriptionRequest};
+use crate::rpc_core::error::RpcError;
+use crate::rpc_core::request::{Id, RpcMethodCall};
+use crate::rpc_core::response::{ResponseResult, RpcResponse};
+use crate::rpc_handler::RpcHandler;
+use crate::subscribe::{
+    ReorgData, SocketId, Subscription, SubscriptionId, SubscriptionNotification,
+    TransactionStatusNotification,
+};
+
+enum SubscriptionResult {
+    Subscribed { subscription_id: SubscriptionId, backlog_start: Option<BlockNumber> },
+    Unsubscribed(bool),
+}
+
+/// here are the definitions of the WebSocket connection handling and of all subscription methods
+impl JsonRpcHandler {
+    /// Reads JSON-RPC requests from the socket until it is closed. Subscription requests are
+    /// handled here, all others are passed to the regular JSON-RPC handling.
+    pub(crate) async fn handle_websocket(&self, socket: WebSocket) {
+        let (mut socket_writer, mut socket_reader) = socket.split();
+
+        // queued messages are written without holding the lock of the socket collection; the task
+        // ends when the socket is removed from the collection, which drops the sending half
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                if let Err(err) = socket_writer.send(message).await {
+                    error!(target: "rpc", ?err, "failed writing to websocket");
+                    break;
+                }
+            }
+        });
+
+        let socket_id = self.api.sockets.lock().await.insert(sender);
+
+        while let Some(message) = socket_reader.next().await {
+            match message {
+                Ok(Message::Text(text)) => self.on_websocket_text(socket_id, &text).await,
+                Ok(Message::Close(_)) => break,
+                Ok(other) => trace!(target: "rpc", ?other, "ignoring websocket message"),
+                Err(err) => {
+                    error!(target: "rpc", ?err, "failed reading from websocket");
+                    break;
+                }
+            }
+        }
+
+        self.api.sockets.lock().await.remove(&socket_id);
+    }
+
+    async fn on_websocket_text(&self, socket_id: SocketId, text: &str) {
+        let call = match serde_json::from_str::<RpcMethodCall>(text) {
+            Ok(call) => call,
+            Err(err) => {
+                error!(target: "rpc", ?err, "invalid websocket request");
+                self.send_websocket_response(socket_id, RpcResponse::invalid_request(Id::Null))
+                    .await;
+                return;
+            }
+        };
+
+        if !JsonRpcSubscriptionRequest::all_variants_serde_renames().contains(&call.method) {
+            let response = self.on_call(call).await;
+            self.send_websocket_response(socket_id, response).await;
+            return;
+        }
+
+        let id = call.id();
+        match self.on_subscription_call(socket_id, call).await {
+            Ok(SubscriptionResult::Subscribed { subscription_id, backlog_start }) => {
+                self.send_websocket_response(
+                    socket_id,
+                    RpcResponse::new(id, ResponseResult::Success(subscription_id.into())),
+                )
+                .await;
+
+                // the subscriber may only receive notifications after it knows the subscription id
+                if let Err(err) =
+                    self.notify_new_subscription(socket_id, subscription_id, backlog_start).await
+                {
+                    error!(target: "rpc", ?err, "failed notifying new subscription");
+                }
+            }
+            Ok(SubscriptionResult::Unsubscribed(success)) => {
+                self.send_websocket_response(
+                    socket_id,
+                    RpcResponse::new(id, ResponseResult::Success(success.into())),
+                )
+                .await;
+            }
+            Err(err) => self.send_websocket_response(socket_id, RpcResponse::new(id, err)).await,
+        }
+    }
+
+    async fn send_websocket_response(&self, socket_id: SocketId, response: RpcResponse) {
+        if let Some(socket_context) = self.api.sockets.lock().await.get_mut(&socket_id) {
+            socket_context.send_rpc_response(response);
+        }
+    }
+
+    async fn on_subscription_call(
+        &self,
+        socket_id: SocketId,
+        call: RpcMethodCall,
+    ) -> Result<SubscriptionResult, RpcError> {
+        let RpcMethodCall { method, params, .. } = call;
+
+        let params: serde_json::Value = params.into();
+        let deserializable_call = serde_json::json!({
+            "method": &method,
+            "params": params
+        });
+
+        let request = serde_json::from_value::<JsonRpcSubscriptionRequest>(deserializable_call)
+            .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+
+        let result = match request {
+            JsonRpcSubscriptionRequest::NewHeads(input) => {
+                self.subscribe_new_heads(socket_id, input.map(|input| input.block_id)).await
+            }
+            JsonRpcSubscriptionRequest::TransactionStatus(input) => {
+                self.subscribe_transaction_status(socket_id, input.transaction_hash).await
+            }
+            JsonRpcSubscriptionRequest::Events(input) => {
+                self.subscribe_events(socket_id, input.unwrap_or_default()).await
+            }
+            JsonRpcSubscriptionRequest::Unsubscribe(input) => {
+                let mut sockets = self.api.sockets.lock().await;
+                Ok(SubscriptionResult::Unsubscribed(
+                    sockets.unsubscribe(&socket_id, &input.subscription_id),
+                ))
+            }
+        };
+
+        result.map_err(ApiError::api_error_to_rpc_error)
+    }
+
+    /// starknet_subscribeNewHeads
+    async fn subscribe_new_heads(
+        &self,
+        socket_id: SocketId,
+        block_id: Option<BlockId>,
+    ) -> Result<SubscriptionResult, ApiError> {
+        let backlog_start = self.resolve_backlog_start(block_id).await?;
+        let subscription_id = self.subscribe(socket_id, Subscription::NewHeads).await?;
+        Ok(SubscriptionResult::Subscribed { subscription_id, backlog_start })
+    }
+
+    /// starknet_subscribeTransactionStatus
+    async fn subscribe_transaction_status(
+        &self,
+        socket_id: SocketId,
+        transaction_hash: TransactionHash,
+    ) -> Result<SubscriptionResult, ApiError> {
+        let subscription_id = self
+            .subscribe(
+                socket_id,
+                Subscription::TransactionStatus { transaction_hash, last_status: None },
+            )
+            .await?;
+        Ok(SubscriptionResult::Subscribed { subscription_id, backlog_start: None })
+    }
+
+    /// starknet_subscribeEvents
+    async fn subscribe_events(
+        &self,
+        socket_id: SocketId,
+        input: EventsSubscriptionInput,
+    ) -> Result<SubscriptionResult, ApiError> {
+        let backlog_start = self.resolve_backlog_start(input.block_id).await?;
+        let subscription_id = self
+            .subscribe(
+                socket_id,
+                Subscription::Events { address: input.from_address, keys: input.keys },
+            )
+            .await?;
+        Ok(SubscriptionResult::Subscribed { subscription_id, backlog_start })
+    }
+
+    async fn subscribe(
+        &self,
+        socket_id: SocketId,
+        subscription: Subscription,
+    ) -> Result<SubscriptionId, ApiError> {
+        self.api
+            .sockets
+            .lock()
+            .await
+            .subscribe(&socket_id, subscription)
+            .ok_or(ApiError::UnsupportedAction { msg: "Subscribing socket no longer open".into() })
+    }
+
+    /// If subscribing with a `block_id`, the subscriber is also notified of everything that
+    /// happened since that block. Returns the number of the block to start from.
+    async fn resolve_backlog_start(
+        &self,
+        block_id: Option<BlockId>,
+    ) -> Result<Option<BlockNumber>, ApiError> {
+        let Some(block_id) = block_id else {
+            return Ok(None);
+        };
+
+        let starknet = self.api.starknet.lock().await;
+        let block = starknet.get_block(block_id.as_ref()).map_err(|err| match err {
+            Error::NoBlock => ApiError::BlockNotFound,
+            unknown_error => ApiError::StarknetDevnetError(unknown_error),
+        })?;
+
+        Ok(Some(block.block_number()))
+    }
+
+    /// Sends what happened before the subscription was made: blocks and events starting from
+    /// `backlog_start` and the current status of the subscribed transaction.
+    async fn notify_new_subscription(
+        &self,
+        socket_id: SocketId,
+        subscription_id: SubscriptionId,
+        backlog_start: Option<BlockNumber>,
+    ) -> Result<(), ApiError> {
+        let mut sockets = self.api.sockets.lock().await;
+        let Some(socket_context) = sockets.get_mut(&socket_id) else {
+            return Ok(());
+        };
+
+        let notifications = {
+            let starknet = self.api.starknet.lock().await;
+            let latest_block_number = starknet.get_latest_block()?.block_number();
+            match socket_context.subscriptions_mut().get_mut(&subscription_id) {
+                Some(Subscription::TransactionStatus { transaction_hash, last_status }) => {
+                    transaction_status_notification(&starknet, *transaction_hash, last_status)
+                        .into_iter()
+                        .collect()
+                }
+                Some(subscription) => match backlog_start {
+                    Some(backlog_start) => block_range_notifications(
+                        &starknet,
+                        subscription,
+                        backlog_start,
+                        latest_block_number,
+                    )?,
+                    None => vec![],
+                },
+                None => vec![],
+            }
+        };
+
+        for notification in notifications {
+            socket_context.notify(subscription_id, &notification);
+        }
+
+        Ok(())
+    }
+
+    /// Notifies subscribers of blocks created since the last notification, of the events emitted
+    /// in them and of transaction status changes. The new blocks are determined while holding the
+    /// lock of the socket collection, so concurrent requests never notify of the same block twice.
+    pub async fn broadcast_changes(&self) -> Result<(), ApiError> {
+        let mut sockets = self.api.sockets.lock().await;
+        let starknet = self.api.starknet.lock().await;
+
+        let latest_block_number = starknet.get_latest_block()?.block_number();
+        let first_new_block_number = sockets
+            .last_notified_block_number()
+            .map_or(BlockNumber(0), |number| BlockNumber(number.0 + 1));
+        sockets.set_last_notified_block_number(Some(latest_block_number));
+
+        for socket_context in sockets.iter_mut() {
+            let mut notifications = vec![];
+            for (subscription_id, subscription) in socket_context.subscriptions_mut() {
+                let subscription_notifications = match subscription {
+                    Subscription::TransactionStatus { transaction_hash, last_status } => {
+                        transaction_status_notification(&starknet, *transaction_hash, last_status)
+                            .into_iter()
+                            .collect()
+                    }
+                    subscription => block_range_notifications(
+                        &starknet,
+                        subscription,
+                        first_new_block_number,
+                        latest_block_number,
+                    )?,
+                };
+
+                notifications.extend(
+                    subscription_notifications
+                        .into_iter()
+                        .map(|notification| (*subscription_id, notification)),
+                );
+            }
+
+            for (subscription_id, notification) in notifications {
+                socket_context.notify(subscription_id, &notification);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Notifies all subscribers that the blocks identified by `aborted` (ordered from the newest
+    /// to the oldest) are no longer part of the chain.
+    pub(crate) async fn broadcast_reorg(&self, aborted: &[Felt]) -> Result<(), ApiError> {
+        let (Some(ending_block_hash), Some(starting_block_hash)) =
+            (aborted.first(), aborted.last())
+        else {
+            return Ok(());
+        };
+
+        let reorg = {
+            let starknet = self.api.starknet.lock().await;
+            let block_number_of = |block_hash: &Felt| {
+                starknet
+                    .get_block(&ImportedBlockId::Hash(*block_hash))
+                    .map(|block| block.block_number())
+            };
+
+            ReorgData {
+                starting_block_hash: *starting_block_hash,
+                starting_block_number: block_number_of(starting_block_hash)?,
+                ending_block_hash: *ending_block_hash,
+                ending_block_number: block_number_of(ending_block_hash)?,
+            }
+        };
+
+        self.api.sockets.lock().await.notify_reorg(reorg);
+        Ok(())
+    }
+
+    /// Returns the numbers and hashes of the blocks of the chain, from the block numbered
+    /// `rewritten_from` to the latest. Taken before an operation which rewrites the chain from
+    /// that block on (restart, load, snapshot revert), so that the blocks it replaced can be
+    /// notified of afterwards. Empty if there is no one to notify or nothing is rewritten.
+    pub(crate) async fn chain_before_rewrite(
+        &self,
+        rewritten_from: impl FnOnce(&Starknet) -> Option<u64>,
+    ) -> Vec<(BlockNumber, Felt)> {
+        if self.api.sockets.lock().await.is_empty() {
+            return vec![];
+        }
+
+        let starknet = self.api.starknet.lock().await;
+        let (Some(first_block_number), Ok(latest_block)) =
+            (rewritten_from(&starknet), starknet.get_latest_block())
+        else {
+            return vec![];
+        };
+
+        (first_block_number..=latest_block.block_number().0)
+            .filter_map(|block_number| {
+                let block = starknet.get_block(&ImportedBlockId::Number(block_number)).ok()?;
+                Some((block.block_number(), block.block_hash()))
+            })
+            .collect()
+    }
+
+    /// Notifies all subscribers that the blocks of `old_chain`, as returned by
+    /// `chain_before_rewrite`, starting with the first one no longer part of the chain, were
+    /// orphaned.
+    pub(crate) async fn broadcast_rewrite(
+        &self,
+        old_chain: &[(BlockNumber, Felt)],
+    ) -> Result<(), ApiError> {
+        let reorg = {
+            let starknet = self.api.starknet.lock().await;
+            let mut orphaned = old_chain.iter().skip_while(|(block_number, block_hash)| {
+                starknet
+                    .get_block(&ImportedBlockId::Number(block_number.0))
+                    .is_ok_and(|block| block.block_hash() == *block_hash)
+            });
+
+            let Some(starting_block) = orphaned.next() else {
+                return Ok(());
+            };
+            let ending_block = orphaned.last().unwrap_or(starting_block);
+
+            ReorgData {
+                starting_block_hash: starting_block.1,
+                starting_block_number: starting_block.0,
+                ending_block_hash: ending_block.1,
+                ending_block_number: ending_block.0,
+            }
+        };
+
+        self.api.sockets.lock().await.notify_reorg(reorg);
+        Ok(())
+    }
+}
+
+/// Creates notifications of a block-related subscription for blocks in the provided range
+/// (inclusive). Transaction status subscriptions are not block-related, so nothing is returned.
+fn block_range_notifications(
+    starknet: &Starknet,
+    subscription: &Subscription,
+    from: BlockNumber,
+    to: BlockNumber,
+) -> Result<Vec<SubscriptionNotification>, ApiError> {
+    if from > to {
+        return Ok(vec![]);
+    }
+
+    match subscription {
+        Subscription::NewHeads => {
+            let mut notifications = vec![];
+            for block_number in from.0..=to.0 {
+                let block = starknet.get_block(&ImportedBlockId::Number(block_number))?;
+                let header = BlockHeader::from(block.as_ref());
+                notifications.push(SubscriptionNotification::NewHeads(Box::new(header)));
+            }
+            Ok(notifications)
+        }
+        Subscription::Events { address, keys } => {
+            let (events, _) = starknet.get_events(
+                Some(ImportedBlockId::Number(from.0)),
+                Some(ImportedBlockId::Number(to.0)),
+                *address,
+                keys.clone(),
+                0,
+                None,
+            )?;
+            Ok(events.into_iter().map(SubscriptionNotification::Event).collect())
+        }
+        Subscription::TransactionStatus { .. } => Ok(vec![]),
+    }
+}
+
+/// Returns a notification if the status of the transaction differs from the last notified one.
+fn transaction_status_notification(
+    starknet: &Starknet,
+    transaction_hash: TransactionHash,
+    last_status: &mut Option<TransactionStatusOutput>,
+) -> Option<SubscriptionNotification> {
+    let status = match starknet.get_transaction_execution_and_finality_status(transaction_hash) {
+        Ok((execution_status, finality_status)) => {
+            TransactionStatusOutput::executed(execution_status, finality_status, None)
+        }
+        Err(_) if starknet.is_transaction_in_mempool(&transaction_hash) => {
+            TransactionStatusOutput::received()
+        }
+        Err(_) => TransactionStatusOutput::rejected(
+            starknet.get_transaction_rejection_reason(&transaction_hash)?,
+        ),
+    };
+
+    if last_status.as_ref() == Some(&status) {
+        return None;
+    }
+
+    *last_status = Some(status.clone());
+    Some(SubscriptionNotification::TransactionStatus(TransactionStatusNotification {
+        transaction_hash,
+        status,
+    }))
+}
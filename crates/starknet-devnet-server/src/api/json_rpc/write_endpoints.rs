@@ -1,6 +1,16 @@
+use std::path::Path;
+
+use starknet_core::starknet::starknet_config::DumpFormat;
+use starknet_rs_core::types::{BlockId as ImportedBlockId, BlockTag};
 use starknet_types::contract_address::ContractAddress;
 use starknet_types::messaging::{MessageToL1, MessageToL2};
-use starknet_types::rpc::gas_modification::GasModificationRequest;
+use starknet_types::rpc::devnet::{
+    AcceptedOnL1, AcceptingOnL1, DropTransactionsRequest, DroppedTransactions, FaultConfig,
+    FaultsSet, SandboxCreationRequest, SandboxIdInput,
+};
+use starknet_types::rpc::gas_modification::{
+    DataAvailabilityModeModification, DataAvailabilityModeRequest, GasModificationRequest,
+};
 use starknet_types::rpc::transactions::{
     BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
     BroadcastedInvokeTransaction,
@@ -8,7 +18,8 @@ use starknet_types::rpc::transactions::{
 
 use super::error::{ApiError, StrictRpcResult};
 use super::models::{
-    DeclareTransactionOutput, DeployAccountTransactionOutput, TransactionHashOutput,
+    DeclareTransactionOutput, DeployAccountTransactionOutput, SetClassHashAtInput, SetNonceInput,
+    SetStorageAtInput, TransactionHashOutput,
 };
 use super::{DevnetResponse, StarknetResponse};
 use crate::api::http::endpoints::dump_load::dump_impl;
@@ -19,11 +30,13 @@ use crate::api::http::endpoints::postman::{
 };
 use crate::api::http::endpoints::time::{increase_time_impl, set_time_impl};
 use crate::api::http::models::{
-    AbortedBlocks, AbortingBlocks, CreatedBlock, DumpPath, FlushParameters, IncreaseTime,
-    MintTokensRequest, PostmanLoadL1MessagingContract, RestartParameters, SetTime,
+    AbortedBlocks, AbortingBlocks, CreatedBlock, DumpPath, ExportGenesisPath, FlushParameters,
+    IncreaseTime, MintTokensRequest, PostmanLoadL1MessagingContract, RestartParameters,
+    RevertingToSnapshot, SetTime, TakenSnapshot,
 };
 use crate::api::json_rpc::JsonRpcHandler;
-use crate::dump_util::load_events;
+use crate::dump_util::{load_events, load_state};
+use crate::sandbox::{create_sandbox_handler, sandbox_info};
 
 impl JsonRpcHandler {
     pub async fn add_declare_transaction(
@@ -98,18 +111,64 @@ impl JsonRpcHandler {
         Ok(super::JsonRpcResponse::Empty)
     }
 
+    /// devnet_setStorageAt
+    pub async fn set_storage_at(&self, data: SetStorageAtInput) -> StrictRpcResult {
+        let mut starknet = self.api.starknet.lock().await;
+        starknet.set_storage_at(data.contract_address, data.key, data.value)?;
+        Ok(super::JsonRpcResponse::Empty)
+    }
+
+    /// devnet_setNonce
+    pub async fn set_nonce(&self, data: SetNonceInput) -> StrictRpcResult {
+        let mut starknet = self.api.starknet.lock().await;
+        starknet.set_nonce(data.contract_address, data.nonce)?;
+        Ok(super::JsonRpcResponse::Empty)
+    }
+
+    /// devnet_setClassHashAt
+    pub async fn set_class_hash_at(&self, data: SetClassHashAtInput) -> StrictRpcResult {
+        let mut starknet = self.api.starknet.lock().await;
+        starknet.set_class_hash_at(data.contract_address, data.class_hash)?;
+        Ok(super::JsonRpcResponse::Empty)
+    }
+
     /// devnet_dump
     pub async fn dump(&self, path: Option<DumpPath>) -> StrictRpcResult {
         let dump = dump_impl(&self.api, path).await.map_err(ApiError::from)?;
         Ok(DevnetResponse::DevnetDump(dump).into())
     }
 
+    /// devnet_exportGenesis
+    pub async fn export_genesis(&self, path: Option<ExportGenesisPath>) -> StrictRpcResult {
+        let genesis = self.api.starknet.lock().await.export_genesis()?;
+
+        match path {
+            Some(ExportGenesisPath { path }) if !path.is_empty() => {
+                genesis.write(Path::new(&path))?;
+                Ok(DevnetResponse::ExportedGenesis(None).into())
+            }
+            _ => Ok(DevnetResponse::ExportedGenesis(Some(genesis)).into()),
+        }
+    }
+
     /// devnet_load
     pub async fn load(&self, path: String) -> StrictRpcResult {
-        let events = load_events(self.starknet_config.dump_on, &path)?;
-        // Necessary to restart before loading; restarting messaging to allow re-execution
-        self.restart(Some(RestartParameters { restart_l1_to_l2_messaging: true })).await?;
-        self.re_execute(&events).await.map_err(ApiError::RpcError)?;
+        match self.starknet_config.dump_format {
+            DumpFormat::Events => {
+                let events = load_events(self.starknet_config.dump_on, &path)?;
+                // Necessary to restart before loading; restarting messaging to allow re-execution
+                self.restart(Some(RestartParameters { restart_l1_to_l2_messaging: true })).await?;
+                self.re_execute(&events).await.map_err(ApiError::RpcError)?;
+            }
+            DumpFormat::State => {
+                let dump = load_state(&path)?;
+                let old_chain = self
+                    .chain_before_rewrite(|starknet| Some(starknet.genesis_block_number()))
+                    .await;
+                self.api.starknet.lock().await.load_state(dump)?;
+                self.broadcast_rewrite(&old_chain).await?;
+            }
+        }
 
         Ok(super::JsonRpcResponse::Empty)
     }
@@ -147,9 +206,33 @@ impl JsonRpcHandler {
     /// devnet_abortBlocks
     pub async fn abort_blocks(&self, data: AbortingBlocks) -> StrictRpcResult {
         let aborted = self.api.starknet.lock().await.abort_blocks(data.starting_block_id.into())?;
+        self.broadcast_reorg(&aborted).await?;
         Ok(DevnetResponse::AbortedBlocks(AbortedBlocks { aborted }).into())
     }
 
+    /// devnet_acceptOnL1
+    pub async fn accept_on_l1(&self, data: AcceptingOnL1) -> StrictRpcResult {
+        let block_id = data.block_id.map_or(ImportedBlockId::Tag(BlockTag::Latest), |id| id.into());
+        let accepted = self.api.starknet.lock().await.accept_on_l1(&block_id)?;
+        Ok(DevnetResponse::AcceptedOnL1(AcceptedOnL1 { accepted }).into())
+    }
+
+    /// devnet_snapshot
+    pub async fn take_snapshot(&self) -> StrictRpcResult {
+        let snapshot_id = self.api.starknet.lock().await.take_snapshot()?;
+        Ok(DevnetResponse::TakenSnapshot(TakenSnapshot { snapshot_id }).into())
+    }
+
+    /// devnet_revert
+    pub async fn revert_to_snapshot(&self, data: RevertingToSnapshot) -> StrictRpcResult {
+        let old_chain = self
+            .chain_before_rewrite(|starknet| starknet.snapshot_next_block_number(data.snapshot_id))
+            .await;
+        self.api.starknet.lock().await.revert_to_snapshot(data.snapshot_id)?;
+        self.broadcast_rewrite(&old_chain).await?;
+        Ok(super::JsonRpcResponse::Empty)
+    }
+
     /// devnet_setGasPrice
     pub async fn set_gas_price(&self, data: GasModificationRequest) -> StrictRpcResult {
         let modified_gas =
@@ -158,12 +241,34 @@ impl JsonRpcHandler {
         Ok(DevnetResponse::GasModification(modified_gas).into())
     }
 
+    /// devnet_setDataAvailabilityMode
+    pub async fn set_data_availability_mode(
+        &self,
+        data: DataAvailabilityModeRequest,
+    ) -> StrictRpcResult {
+        let l1_da_mode = self
+            .api
+            .starknet
+            .lock()
+            .await
+            .set_next_block_l1_da_mode(data)
+            .map_err(ApiError::from)?;
+
+        Ok(DevnetResponse::DataAvailabilityModeModification(DataAvailabilityModeModification {
+            l1_da_mode,
+        })
+        .into())
+    }
+
     /// devnet_restart
     pub async fn restart(&self, data: Option<RestartParameters>) -> StrictRpcResult {
         self.api.dumpable_events.lock().await.clear();
 
         let restart_params = data.unwrap_or_default();
+        let old_chain =
+            self.chain_before_rewrite(|starknet| Some(starknet.genesis_block_number())).await;
         self.api.starknet.lock().await.restart(restart_params.restart_l1_to_l2_messaging)?;
+        self.broadcast_rewrite(&old_chain).await?;
 
         Ok(super::JsonRpcResponse::Empty)
     }
@@ -182,6 +287,54 @@ impl JsonRpcHandler {
     pub async fn mint(&self, request: MintTokensRequest) -> StrictRpcResult {
         mint_impl(&self.api, request).await
     }
+
+    /// devnet_createSandbox
+    pub async fn create_sandbox(&self, request: Option<SandboxCreationRequest>) -> StrictRpcResult {
+        let sandboxes = self.hosted_sandboxes()?;
+        let SandboxCreationRequest { from_sandbox, config } = request.unwrap_or_default();
+
+        let source = match from_sandbox {
+            Some(sandbox_id) => Some(
+                sandboxes.get(sandbox_id).await.ok_or(ApiError::SandboxNotFound { sandbox_id })?,
+            ),
+            None => None,
+        };
+
+        let handler = create_sandbox_handler(self, source.as_ref(), config).await?;
+        let sandbox_id = sandboxes.insert(handler.clone()).await;
+        Ok(DevnetResponse::Sandbox(sandbox_info(sandbox_id, &handler)).into())
+    }
+
+    /// devnet_destroySandbox
+    pub async fn destroy_sandbox(&self, data: SandboxIdInput) -> StrictRpcResult {
+        self.hosted_sandboxes()?.destroy(data.sandbox_id).await?;
+        Ok(super::JsonRpcResponse::Empty)
+    }
+
+    /// devnet_dropMempoolTransactions
+    pub async fn drop_mempool_transactions(
+        &self,
+        data: Option<DropTransactionsRequest>,
+    ) -> StrictRpcResult {
+        let transaction_hashes = data.and_then(|data| data.transaction_hashes);
+        let dropped = self
+            .api
+            .starknet
+            .lock()
+            .await
+            .drop_mempool_transactions(transaction_hashes.as_deref())?;
+
+        Ok(DevnetResponse::DroppedTransactions(DroppedTransactions { dropped }).into())
+    }
+
+    /// devnet_setFaults
+    pub async fn set_faults(&self, config: FaultConfig) -> StrictRpcResult {
+        let revert_next_transactions = config.revert_next_transactions;
+        let seed = self.api.faults.lock().await.set(config)?;
+        self.api.starknet.lock().await.set_transactions_to_revert(revert_next_transactions);
+
+        Ok(DevnetResponse::FaultsSet(FaultsSet { seed }).into())
+    }
 }
 
 #[cfg(test)]
@@ -5,9 +5,12 @@ pub mod serde_helpers;
 use std::sync::Arc;
 
 use starknet_core::starknet::Starknet;
+use starknet_rs_core::types::Felt;
 use tokio::sync::Mutex;
 
+use self::json_rpc::faults::Faults;
 use crate::dump_util::DumpEvent;
+use crate::subscribe::SocketCollection;
 
 /// Data that can be shared between threads with read write lock access
 /// Whatever needs to be accessed as information outside of Starknet could be added to this struct
@@ -16,10 +19,24 @@ pub struct Api {
     // maybe the config should be added here next to the starknet instance
     pub starknet: Arc<Mutex<Starknet>>,
     pub dumpable_events: Arc<Mutex<Vec<DumpEvent>>>,
+    pub sockets: Arc<Mutex<SocketCollection>>,
+    pub faults: Arc<Mutex<Faults>>,
+    /// Hash of the latest block at the time of the last state dump on block creation
+    pub last_dumped_block_hash: Arc<Mutex<Option<Felt>>>,
 }
 
 impl Api {
     pub fn new(starknet: Starknet) -> Self {
-        Self { starknet: Arc::new(Mutex::new(starknet)), dumpable_events: Default::default() }
+        // blocks existing before any subscription was possible are not notified of
+        let latest_block_number =
+            starknet.get_latest_block().ok().map(|block| block.block_number());
+
+        Self {
+            starknet: Arc::new(Mutex::new(starknet)),
+            dumpable_events: Default::default(),
+            sockets: Arc::new(Mutex::new(SocketCollection::new(latest_block_number))),
+            faults: Default::default(),
+            last_dumped_block_hash: Default::default(),
+        }
     }
 }
@@ -1,6 +1,10 @@
 use std::net::IpAddr;
 
 use serde::Serialize;
+use starknet_core::constants::{
+    DEVNET_DEFAULT_HOST, DEVNET_DEFAULT_PORT, DEVNET_DEFAULT_REQUEST_BODY_SIZE_LIMIT,
+    DEVNET_DEFAULT_TIMEOUT,
+};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ServerConfig {
@@ -14,3 +18,17 @@ pub struct ServerConfig {
     pub log_response: bool,
     pub restricted_methods: Option<Vec<String>>,
 }
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: DEVNET_DEFAULT_HOST,
+            port: DEVNET_DEFAULT_PORT,
+            timeout: DEVNET_DEFAULT_TIMEOUT,
+            request_body_size_limit: DEVNET_DEFAULT_REQUEST_BODY_SIZE_LIMIT,
+            log_request: false,
+            log_response: false,
+            restricted_methods: None,
+        }
+    }
+}
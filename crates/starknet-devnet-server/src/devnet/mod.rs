@@ -0,0 +1,292 @@
+use std::future::{Future, IntoFuture};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use starknet_core::starknet::starknet_config::{
+    BlockGenerationOn, DumpFormat, DumpOn, StarknetConfig,
+};
+use starknet_core::starknet::Starknet;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep};
+use tracing::info;
+
+use crate::api::http::HttpApiHandler;
+use crate::api::json_rpc::JsonRpcHandler;
+use crate::api::Api;
+use crate::dump_util::{dump_events, dump_state, load_events, load_state, DumpEvent};
+use crate::rpc_core::request::{Id, RequestParams, Version};
+use crate::server::serve_http_api_json_rpc;
+use crate::ServerConfig;
+
+mod origin;
+
+/// Starts Devnet inside the current process. Performs what the `starknet-devnet` binary does on
+/// startup: sets up forking, loads the dump and starts generating blocks on interval, if
+/// configured so.
+///
+/// ```ignore
+/// let devnet = DevnetBuilder::new(starknet_config, server_config).ephemeral_port().build().await?;
+/// let url = devnet.url().unwrap();
+/// // ...
+/// devnet.shutdown().await?;
+/// ```
+pub struct DevnetBuilder {
+    starknet_config: StarknetConfig,
+    server_config: ServerConfig,
+    serve: bool,
+}
+
+impl DevnetBuilder {
+    pub fn new(starknet_config: StarknetConfig, server_config: ServerConfig) -> Self {
+        Self { starknet_config, server_config, serve: true }
+    }
+
+    /// Listens on a port chosen by the OS instead of the one in the server config
+    pub fn ephemeral_port(mut self) -> Self {
+        self.server_config.port = 0;
+        self
+    }
+
+    /// Doesn't listen on any port; Devnet is only accessible via the handle
+    pub fn without_server(mut self) -> Self {
+        self.serve = false;
+        self
+    }
+
+    pub async fn build(self) -> Result<RunningDevnet, anyhow::Error> {
+        let Self { mut starknet_config, mut server_config, serve } = self;
+
+        // If fork url is provided, then set fork config and chain_id from forked network
+        if let Some(url) = starknet_config.fork_config.url.clone() {
+            origin::set_fork_origin_config(&mut starknet_config, &url).await?;
+        }
+
+        let listener = if serve {
+            let listener = TcpListener::bind((server_config.host, server_config.port)).await?;
+            // reflect the actually bound port, relevant if it was chosen by the OS
+            server_config.port = listener.local_addr()?.port();
+            Some(listener)
+        } else {
+            None
+        };
+
+        let starknet = Starknet::new(&starknet_config)?;
+        let api = Api::new(starknet);
+
+        // set block timestamp shift during startup if start time is set
+        if let Some(start_time) = starknet_config.start_time {
+            api.starknet.lock().await.set_block_timestamp_shift(
+                start_time as i64 - Starknet::get_unix_timestamp_as_seconds() as i64,
+            );
+        };
+
+        let json_rpc_handler = JsonRpcHandler::new(api.clone(), &starknet_config, &server_config);
+        load_dump_if_present(&json_rpc_handler, &starknet_config).await?;
+
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+        let mut tasks = vec![];
+
+        let local_addr = match listener {
+            Some(listener) => {
+                let local_addr = listener.local_addr()?;
+                let http_api_handler =
+                    HttpApiHandler { api: api.clone(), server_config: server_config.clone() };
+                let server = serve_http_api_json_rpc(
+                    listener,
+                    &server_config,
+                    json_rpc_handler.clone(),
+                    http_api_handler,
+                )
+                .await;
+
+                let server_handle = tokio::spawn(
+                    server
+                        .with_graceful_shutdown(shutdown_requested(shutdown_receiver.clone()))
+                        .into_future(),
+                );
+                tasks.push(server_handle);
+                info!("Starknet Devnet listening on {}", local_addr);
+                Some(local_addr)
+            }
+            None => None,
+        };
+
+        if let BlockGenerationOn::Interval(seconds) = starknet_config.block_generation_on {
+            tasks.push(tokio::spawn(create_block_interval(
+                json_rpc_handler.clone(),
+                seconds,
+                shutdown_requested(shutdown_receiver),
+            )));
+        }
+
+        Ok(RunningDevnet {
+            api,
+            json_rpc_handler,
+            starknet_config,
+            server_config,
+            local_addr,
+            shutdown_sender,
+            tasks,
+        })
+    }
+}
+
+/// Handle of Devnet started with [`DevnetBuilder`]. Dropping it stops the server and the block
+/// generation too, but without waiting for them and without dumping on exit.
+pub struct RunningDevnet {
+    api: Api,
+    json_rpc_handler: JsonRpcHandler,
+    starknet_config: StarknetConfig,
+    server_config: ServerConfig,
+    local_addr: Option<SocketAddr>,
+    shutdown_sender: watch::Sender<bool>,
+    tasks: Vec<JoinHandle<Result<(), std::io::Error>>>,
+}
+
+impl RunningDevnet {
+    /// The address the server is listening on; `None` if started without a server
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:5050`; `None` if started without a server
+    pub fn url(&self) -> Option<String> {
+        self.local_addr.map(|local_addr| format!("http://{local_addr}"))
+    }
+
+    pub fn api(&self) -> &Api {
+        &self.api
+    }
+
+    /// Handles JSON-RPC requests the same way the server does, also when there is no server
+    pub fn json_rpc_handler(&self) -> &JsonRpcHandler {
+        &self.json_rpc_handler
+    }
+
+    /// The config Devnet was started with, including what was learned from the forking origin
+    pub fn starknet_config(&self) -> &StarknetConfig {
+        &self.starknet_config
+    }
+
+    /// The config of the server, with the port it is actually listening on
+    pub fn server_config(&self) -> &ServerConfig {
+        &self.server_config
+    }
+
+    /// Stops the server and the block generation, waiting for pending requests to be handled.
+    /// Dumps the state of Devnet and its sandboxes if they were configured to dump on exit.
+    pub async fn shutdown(self) -> Result<(), anyhow::Error> {
+        // the receivers may already be gone if the tasks have ended
+        let _ = self.shutdown_sender.send(true);
+
+        for result in futures::future::join_all(self.tasks).await {
+            result??;
+        }
+
+        if let Some(sandboxes) = &self.json_rpc_handler.sandboxes {
+            sandboxes.destroy_all().await?;
+        }
+
+        dump_on_exit(&self.api).await
+    }
+}
+
+pub(crate) async fn load_dump_if_present(
+    json_rpc_handler: &JsonRpcHandler,
+    starknet_config: &StarknetConfig,
+) -> Result<(), anyhow::Error> {
+    if let Some(dump_path) = &starknet_config.dump_path {
+        // Try to load events from the path. Since the same CLI parameter is used for dump and load
+        // path, it may be the case that there is no file at the path. This means that the file will
+        // be created during Devnet's lifetime via dumping, so its non-existence is here ignored.
+        match starknet_config.dump_format {
+            DumpFormat::Events => match load_events(starknet_config.dump_on, dump_path) {
+                Ok(loadable_events) => json_rpc_handler
+                    .re_execute(&loadable_events)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to re-execute dumped Devnet: {e}"))?,
+                Err(starknet_core::error::Error::FileNotFound) => (),
+                Err(err) => return Err(err.into()),
+            },
+            DumpFormat::State => match load_state(dump_path) {
+                Ok(dump) => json_rpc_handler.api.starknet.lock().await.load_state(dump)?,
+                Err(starknet_core::error::Error::FileNotFound) => (),
+                Err(err) => return Err(err.into()),
+            },
+        }
+    };
+
+    Ok(())
+}
+
+pub(crate) async fn dump_on_exit(api: &Api) -> Result<(), anyhow::Error> {
+    let starknet = api.starknet.lock().await;
+    if let (Some(DumpOn::Exit), Some(dump_path)) =
+        (starknet.config.dump_on, &starknet.config.dump_path)
+    {
+        match starknet.config.dump_format {
+            DumpFormat::Events => {
+                let events = api.dumpable_events.lock().await;
+                dump_events(&events, dump_path)?;
+            }
+            DumpFormat::State => dump_state(&starknet.dump_state(), dump_path)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves when shutdown is requested or the handle is dropped
+async fn shutdown_requested(mut shutdown_receiver: watch::Receiver<bool>) {
+    let _ = shutdown_receiver.wait_for(|requested| *requested).await;
+}
+
+/// Creates a block every `block_interval_seconds` until `shutdown` resolves
+pub(crate) async fn create_block_interval(
+    json_rpc_handler: JsonRpcHandler,
+    block_interval_seconds: u64,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), std::io::Error> {
+    tokio::pin!(shutdown);
+
+    let mut interval = interval(Duration::from_secs(block_interval_seconds));
+    loop {
+        // TODO does this need to be inside of the loop? or outside?
+        // avoid creating block instantly after startup
+        tokio::select! {
+            _ = sleep(Duration::from_secs(block_interval_seconds)) => (),
+            _ = &mut shutdown => return Ok(()),
+        }
+
+        tokio::select! {
+            _ = interval.tick() => {
+                info!("Generating block on time interval");
+                json_rpc_handler
+                    .api
+                    .starknet
+                    .lock()
+                    .await
+                    .create_block()
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+                // dumped and saved like the equivalent request; alternative: create a client and
+                // send the request
+                let event = DumpEvent {
+                    jsonrpc: Version::V2,
+                    method: "devnet_createBlock".into(),
+                    params: RequestParams::None,
+                    id: Id::Number(0),
+                };
+                json_rpc_handler
+                    .handle_changes(&event)
+                    .await
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+            }
+            _ = &mut shutdown => {
+                return Ok(())
+            }
+        }
+    }
+}
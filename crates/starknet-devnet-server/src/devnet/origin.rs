@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use serde::de::IntoDeserializer;
+use starknet_core::constants::{ETH_ERC20_CONTRACT_ADDRESS, STRK_ERC20_CONTRACT_ADDRESS};
+use starknet_core::starknet::fork_cache::{ForkCache, ForkOriginRecord};
+use starknet_core::starknet::starknet_config::{ForkConfig, StarknetConfig};
+use starknet_rs_core::types::ContractClass::{Legacy, Sierra};
+use starknet_rs_core::types::{
+    BlockId, BlockTag, Felt, MaybePendingBlockWithTxHashes, StarknetError,
+};
+use starknet_rs_providers::jsonrpc::HttpTransport;
+use starknet_rs_providers::{JsonRpcClient, Provider, ProviderError};
+use starknet_types::serde_helpers::rpc_sierra_contract_class_to_sierra_contract_class::deserialize_to_sierra_contract_class;
+use tracing::{info, warn};
+
+use crate::api::json_rpc::RPC_SPEC_VERSION;
+
+async fn check_forking_spec_version(
+    client: &JsonRpcClient<HttpTransport>,
+) -> Result<(), anyhow::Error> {
+    let origin_spec_version = client.spec_version().await?;
+    if origin_spec_version != RPC_SPEC_VERSION {
+        warn!(
+            "JSON-RPC API version of origin ({}) does not match this Devnet's version ({}).",
+            origin_spec_version, RPC_SPEC_VERSION
+        );
+    }
+    Ok(())
+}
+
+// Sets in the starknet_config the class hash and contract class of the ERC20 contracts if they are
+// different from the default ones.
+async fn set_erc20_contract_class_and_class_hash_if_different_than_default(
+    json_rpc_client: &JsonRpcClient<HttpTransport>,
+    starknet_config: &mut StarknetConfig,
+) -> Result<(), anyhow::Error> {
+    let block_id = BlockId::Number(
+        starknet_config
+            .fork_config
+            .block_number
+            .ok_or(anyhow::anyhow!("Forking block number is not set"))?,
+    );
+
+    async fn get_origin_class_hash_and_contract_class_if_different_from_default(
+        json_rpc_client: &JsonRpcClient<HttpTransport>,
+        block_id: BlockId,
+        contract_address: Felt,
+        default_class_hash: Felt,
+    ) -> Result<Option<(Felt, String)>, anyhow::Error> {
+        match json_rpc_client.get_class_hash_at(block_id, contract_address).await {
+            Ok(origin_class_hash) => {
+                if origin_class_hash != default_class_hash {
+                    let origin_contract_class =
+                        json_rpc_client.get_class(block_id, origin_class_hash).await?;
+                    let contract_class_json_str = match origin_contract_class {
+                        Sierra(_) => {
+                            let contract_class_json_value =
+                                serde_json::to_value(origin_contract_class)?;
+                            let sierra_contract_class = deserialize_to_sierra_contract_class(
+                                contract_class_json_value.into_deserializer(),
+                            )?;
+                            serde_json::to_string(&sierra_contract_class)?
+                        }
+                        Legacy(_) => serde_json::to_string(&origin_contract_class)?,
+                    };
+
+                    Ok(Some((origin_class_hash, contract_class_json_str)))
+                } else {
+                    Ok(None)
+                }
+            }
+            // if the contract is not found, then dont return an error. It means that the
+            // contract was not deployed at this state of the origin blockchain
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    if let Some((class_hash, contract_class)) =
+        get_origin_class_hash_and_contract_class_if_different_from_default(
+            json_rpc_client,
+            block_id,
+            ETH_ERC20_CONTRACT_ADDRESS,
+            starknet_config.eth_erc20_class_hash,
+        )
+        .await?
+    {
+        starknet_config.eth_erc20_class_hash = class_hash;
+        starknet_config.eth_erc20_contract_class = contract_class;
+    }
+
+    if let Some((class_hash, contract_class)) =
+        get_origin_class_hash_and_contract_class_if_different_from_default(
+            json_rpc_client,
+            block_id,
+            STRK_ERC20_CONTRACT_ADDRESS,
+            starknet_config.strk_erc20_class_hash,
+        )
+        .await?
+    {
+        starknet_config.strk_erc20_class_hash = class_hash;
+        starknet_config.strk_erc20_contract_class = contract_class;
+    }
+
+    Ok(())
+}
+
+/// Logs forking info if forking specified. If block_number is not specified, it is set to the
+/// latest block number. Returns the hash of the forking block.
+async fn set_and_log_fork_config(
+    fork_config: &mut ForkConfig,
+    json_rpc_client: &JsonRpcClient<HttpTransport>,
+) -> Result<Felt, anyhow::Error> {
+    let block_id = fork_config.block_number.map_or(BlockId::Tag(BlockTag::Latest), BlockId::Number);
+
+    let block = json_rpc_client.get_block_with_tx_hashes(block_id).await.map_err(|e| {
+        anyhow::Error::msg(match e {
+            starknet_rs_providers::ProviderError::StarknetError(
+                starknet_rs_core::types::StarknetError::BlockNotFound,
+            ) => format!("Forking from block {block_id:?}: block not found"),
+            _ => format!("Forking from block {block_id:?}: {e}; Check the URL"),
+        })
+    })?;
+
+    let block_hash = match block {
+        MaybePendingBlockWithTxHashes::Block(b) => {
+            fork_config.block_number = Some(b.block_number);
+            info!("Forking from block: number={}, hash={:#x}", b.block_number, b.block_hash);
+            b.block_hash
+        }
+        _ => panic!("Unreachable"),
+    };
+
+    check_forking_spec_version(json_rpc_client).await?;
+
+    Ok(block_hash)
+}
+
+/// Sets the fork config, the chain ID and the ERC20 classes according to the origin. If a record
+/// of the origin at the forking block is in the fork cache, the origin is not contacted;
+/// otherwise the record is stored in the cache, if there is one.
+pub(crate) async fn set_fork_origin_config(
+    starknet_config: &mut StarknetConfig,
+    url: &url::Url,
+) -> Result<(), anyhow::Error> {
+    let fork_cache =
+        starknet_config.fork_config.cache_dir.as_ref().map(|dir| ForkCache::new(Path::new(dir)));
+
+    if let (Some(cache), Some(block_number)) =
+        (&fork_cache, starknet_config.fork_config.block_number)
+    {
+        if let Some(record) = cache.get_origin_record(url, block_number)? {
+            info!(
+                "Forking from block: number={}, hash={:#x} (origin record read from fork cache)",
+                block_number, record.block_hash
+            );
+            starknet_config.chain_id = record.chain_id.into();
+            starknet_config.eth_erc20_class_hash = record.eth_erc20_class_hash;
+            starknet_config.eth_erc20_contract_class = record.eth_erc20_contract_class;
+            starknet_config.strk_erc20_class_hash = record.strk_erc20_class_hash;
+            starknet_config.strk_erc20_contract_class = record.strk_erc20_contract_class;
+            return Ok(());
+        }
+    }
+
+    let json_rpc_client = JsonRpcClient::new(HttpTransport::new(url.clone()));
+    let block_hash =
+        set_and_log_fork_config(&mut starknet_config.fork_config, &json_rpc_client).await?;
+    set_erc20_contract_class_and_class_hash_if_different_than_default(
+        &json_rpc_client,
+        starknet_config,
+    )
+    .await?;
+    // TODO: Add check for UDC contract
+    let chain_id = json_rpc_client.chain_id().await?;
+    starknet_config.chain_id = chain_id.into();
+
+    if let (Some(cache), Some(block_number)) =
+        (&fork_cache, starknet_config.fork_config.block_number)
+    {
+        let record = ForkOriginRecord {
+            chain_id,
+            block_hash,
+            eth_erc20_class_hash: starknet_config.eth_erc20_class_hash,
+            eth_erc20_contract_class: starknet_config.eth_erc20_contract_class.clone(),
+            strk_erc20_class_hash: starknet_config.strk_erc20_class_hash,
+            strk_erc20_contract_class: starknet_config.strk_erc20_contract_class.clone(),
+        };
+        cache.put_origin_record(url, block_number, &record)?;
+    }
+
+    Ok(())
+}
@@ -1,14 +1,24 @@
 use std::fs::{self, File, OpenOptions};
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
+use serde::Serialize;
 use starknet_core::error::{DevnetResult, Error};
+use starknet_core::starknet::dump::StarknetDump;
 use starknet_core::starknet::starknet_config::DumpOn;
 
 use crate::rpc_core::request::RpcMethodCall;
 
 pub type DumpEvent = RpcMethodCall;
 
+/// The content of a dump, depending on the configured dump format
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum DevnetDump {
+    Events(Vec<DumpEvent>),
+    State(Box<StarknetDump>),
+}
+
 /// Saves Devnet `events` to the file at `path`. If `events` is empty, does nothing.
 pub fn dump_events(events: &Vec<DumpEvent>, path: &str) -> DevnetResult<()> {
     if !events.is_empty() {
@@ -20,6 +30,17 @@ pub fn dump_events(events: &Vec<DumpEvent>, path: &str) -> DevnetResult<()> {
     Ok(())
 }
 
+/// Saves the Devnet state `dump` to the file at `path`, overwriting any previous content.
+pub fn dump_state(dump: &StarknetDump, path: &str) -> DevnetResult<()> {
+    let file = File::create(Path::new(path)).map_err(Error::IoError)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, dump)
+        .map_err(|e| Error::SerializationError { origin: e.to_string() })?;
+    writer.flush()?;
+
+    Ok(())
+}
+
 /// Attaches starknet event to the end of the file at `path`. If no file present, creates it.
 pub fn dump_event(event: &DumpEvent, path: &str) -> DevnetResult<()> {
     let file_path = Path::new(path);
@@ -73,3 +94,15 @@ pub fn load_events(dump_on: Option<DumpOn>, path: &str) -> DevnetResult<Vec<Dump
 
     Ok(events)
 }
+
+/// Returns the Devnet state dumped to the provided `path`
+pub fn load_state(path: &str) -> DevnetResult<StarknetDump> {
+    let file_path = Path::new(path);
+    if path.is_empty() || !file_path.exists() {
+        return Err(Error::FileNotFound);
+    }
+
+    let file = File::open(file_path).map_err(Error::IoError)?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| Error::DeserializationError { origin: e.to_string() })
+}
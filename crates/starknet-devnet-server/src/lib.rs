@@ -1,12 +1,15 @@
 pub mod api;
 mod config;
+pub mod devnet;
 pub mod dump_util;
 pub mod error;
 pub mod restrictive_mode;
 pub mod rpc_core;
 /// handlers for axum server
 pub mod rpc_handler;
+pub mod sandbox;
 pub mod server;
+pub mod subscribe;
 #[cfg(any(test, feature = "test_utils"))]
 pub mod test_utils;
 
@@ -24,6 +24,8 @@ const RPC_METHOD_SET_TIME: &str = "devnet_setTime";
 const RPC_METHOD_INCREASE_TIME: &str = "devnet_increaseTime";
 const RPC_METHOD_CREATE_BLOCK: &str = "devnet_createBlock";
 const RPC_METHOD_ABORT_BLOCKS: &str = "devnet_abortBlocks";
+const RPC_METHOD_SNAPSHOT: &str = "devnet_snapshot";
+const RPC_METHOD_REVERT: &str = "devnet_revert";
 const RPC_METHOD_RESTART: &str = "devnet_restart";
 const RPC_METHOD_MINT: &str = "devnet_mint";
 const RPC_METHOD_POSTMAN_LOAD: &str = "devnet_postmanLoad";
@@ -35,6 +37,13 @@ const RPC_METHOD_GET_ACCOUNT_BALANCE: &str = "devnet_getAccountBalance";
 const RPC_METHOD_GET_CONFIG: &str = "devnet_getConfig";
 const RPC_METHOD_IMPERSONATE_ACCOUNT: &str = "devnet_impersonateAccount";
 const RPC_METHOD_AUTO_IMPERSONATE: &str = "devnet_autoImpersonate";
+const RPC_METHOD_SET_STORAGE_AT: &str = "devnet_setStorageAt";
+const RPC_METHOD_SET_NONCE: &str = "devnet_setNonce";
+const RPC_METHOD_SET_CLASS_HASH_AT: &str = "devnet_setClassHashAt";
+const RPC_METHOD_CREATE_SANDBOX: &str = "devnet_createSandbox";
+const RPC_METHOD_DESTROY_SANDBOX: &str = "devnet_destroySandbox";
+const RPC_METHOD_DROP_MEMPOOL_TRANSACTIONS: &str = "devnet_dropMempoolTransactions";
+const RPC_METHOD_SET_FAULTS: &str = "devnet_setFaults";
 
 use lazy_static::lazy_static;
 lazy_static! {
@@ -63,9 +72,18 @@ lazy_static! {
         RPC_METHOD_RESTART.into(),
         RPC_METHOD_CREATE_BLOCK.into(),
         RPC_METHOD_ABORT_BLOCKS.into(),
+        RPC_METHOD_SNAPSHOT.into(),
+        RPC_METHOD_REVERT.into(),
         RPC_METHOD_IMPERSONATE_ACCOUNT.into(),
         RPC_METHOD_AUTO_IMPERSONATE.into(),
-        RPC_METHOD_GET_PREDEPLOYED_ACCOUNTS.into()
+        RPC_METHOD_SET_STORAGE_AT.into(),
+        RPC_METHOD_SET_NONCE.into(),
+        RPC_METHOD_SET_CLASS_HASH_AT.into(),
+        RPC_METHOD_GET_PREDEPLOYED_ACCOUNTS.into(),
+        RPC_METHOD_CREATE_SANDBOX.into(),
+        RPC_METHOD_DESTROY_SANDBOX.into(),
+        RPC_METHOD_DROP_MEMPOOL_TRANSACTIONS.into(),
+        RPC_METHOD_SET_FAULTS.into()
     ];
 }
 
@@ -1,7 +1,9 @@
 use std::fmt::{self};
 
 use axum::extract::rejection::JsonRejection;
+use axum::extract::ws::{WebSocket, WebSocketUpgrade};
 use axum::extract::State;
+use axum::response::IntoResponse;
 use axum::Json;
 use futures::{future, FutureExt};
 use serde::de::DeserializeOwned;
@@ -33,25 +35,76 @@ pub trait RpcHandler: Clone + Send + Sync + 'static {
     /// **Note**: override this function if the expected `Request` deviates from `{ "method" :
     /// "<name>", "params": "<params>" }`
     async fn on_call(&self, call: RpcMethodCall) -> RpcResponse;
+
+    /// Invoked when a WebSocket connection is established. Should serve the connection until it
+    /// is closed.
+    async fn on_websocket(&self, socket: WebSocket);
+
+    /// Invoked after a call of `method` was handled. If true, the connection is closed without
+    /// sending the response.
+    async fn drops_response(&self, _method: &str) -> bool {
+        false
+    }
 }
 
 /// Handles incoming JSON-RPC Request
 pub async fn handle<THandler: RpcHandler>(
     State(handler): State<THandler>,
     request: Result<Json<Request>, JsonRejection>,
-) -> Json<Response> {
+) -> axum::response::Response {
     match request {
-        Ok(req) => handle_request(req.0, handler)
-            .await
-            .unwrap_or_else(|| Response::error(RpcError::invalid_request()))
-            .into(),
+        Ok(req) => {
+            let methods = called_methods(&req.0);
+            let response = handle_request(req.0, handler.clone())
+                .await
+                .unwrap_or_else(|| Response::error(RpcError::invalid_request()));
+
+            for method in methods {
+                if handler.drops_response(&method).await {
+                    warn!(target: "rpc", ?method, "dropping response");
+                    return dropped_response();
+                }
+            }
+            Json(response).into_response()
+        }
         Err(err) => {
             warn!(target: "rpc", ?err, "invalid request");
-            Response::error(RpcError::invalid_request()).into()
+            Json(Response::error(RpcError::invalid_request())).into_response()
         }
     }
 }
 
+/// Names of the methods called in the request; notifications and invalid calls are omitted
+fn called_methods(request: &Request) -> Vec<String> {
+    let calls = match request {
+        Request::Single(call) => std::slice::from_ref(call),
+        Request::Batch(calls) => calls.as_slice(),
+    };
+    calls
+        .iter()
+        .filter_map(|call| match call {
+            RpcCall::MethodCall(call) => Some(call.method.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A response whose body fails to be produced, which makes the server close the connection
+fn dropped_response() -> axum::response::Response {
+    let failing_body = futures::stream::once(future::ready(Err::<axum::body::Bytes, _>(
+        std::io::Error::other("response dropped"),
+    )));
+    axum::body::Body::from_stream(failing_body).into_response()
+}
+
+/// Upgrades the HTTP connection to a WebSocket connection, which is then served by the handler
+pub async fn handle_socket<THandler: RpcHandler>(
+    ws_upgrade: WebSocketUpgrade,
+    State(handler): State<THandler>,
+) -> impl IntoResponse {
+    ws_upgrade.on_upgrade(move |socket| async move { handler.on_websocket(socket).await })
+}
+
 #[macro_export]
 /// Match a list of comma-separated pairs enclosed in square brackets. First pair member is the HTTP
 /// path which is mapped to an RPC request with the method that is the second pair member. Using the
@@ -0,0 +1,257 @@
+//! Sandboxes are independent Devnet instances hosted by the same server. Each has its own state
+//! and is served under `/sandbox/{id}/`. They are managed via the JSON-RPC API of the hosting
+//! Devnet.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::rejection::JsonRejection;
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response as HttpResponse};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::ValueEnum;
+use starknet_core::starknet::starknet_config::{BlockGenerationOn, DumpOn, StarknetConfig};
+use starknet_core::starknet::Starknet;
+use starknet_types::rpc::devnet::{SandboxConfig, SandboxId, SandboxInfo};
+use starknet_types::rpc::state::Balance;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::api::json_rpc::error::ApiError;
+use crate::api::json_rpc::{JsonRpcHandler, RpcVersion};
+use crate::api::Api;
+use crate::devnet::{create_block_interval, dump_on_exit, load_dump_if_present};
+use crate::rpc_core::error::RpcError;
+use crate::rpc_core::request::Request;
+use crate::rpc_handler;
+
+/// The sandboxes of a Devnet, shared by all clones of its handler
+#[derive(Clone, Default)]
+pub struct Sandboxes {
+    collection: Arc<Mutex<SandboxCollection>>,
+}
+
+#[derive(Default)]
+struct SandboxCollection {
+    handlers: BTreeMap<SandboxId, JsonRpcHandler>,
+    /// Tasks creating blocks of sandboxes which generate blocks on a time interval
+    block_intervals: BTreeMap<SandboxId, JoinHandle<Result<(), std::io::Error>>>,
+    next_id: SandboxId,
+}
+
+impl Sandboxes {
+    pub async fn get(&self, sandbox_id: SandboxId) -> Option<JsonRpcHandler> {
+        self.collection.lock().await.handlers.get(&sandbox_id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<SandboxInfo> {
+        let collection = self.collection.lock().await;
+        collection.handlers.iter().map(|(id, handler)| sandbox_info(*id, handler)).collect()
+    }
+
+    /// Adds the sandbox and starts generating its blocks if it generates them on a time interval
+    pub(crate) async fn insert(&self, handler: JsonRpcHandler) -> SandboxId {
+        let mut collection = self.collection.lock().await;
+        let sandbox_id = collection.next_id;
+        collection.next_id += 1;
+
+        if let BlockGenerationOn::Interval(seconds) = handler.starknet_config.block_generation_on {
+            // the generation is only stopped by aborting the task when the sandbox is destroyed
+            let block_interval = tokio::spawn(create_block_interval(
+                handler.clone(),
+                seconds,
+                std::future::pending(),
+            ));
+            collection.block_intervals.insert(sandbox_id, block_interval);
+        }
+
+        collection.handlers.insert(sandbox_id, handler);
+        sandbox_id
+    }
+
+    /// Removes the sandbox, stops generating its blocks and dumps it if it was configured to dump
+    /// on exit
+    pub(crate) async fn destroy(&self, sandbox_id: SandboxId) -> Result<(), ApiError> {
+        let handler = {
+            let mut collection = self.collection.lock().await;
+            if let Some(block_interval) = collection.block_intervals.remove(&sandbox_id) {
+                block_interval.abort();
+            }
+            collection
+                .handlers
+                .remove(&sandbox_id)
+                .ok_or(ApiError::SandboxNotFound { sandbox_id })?
+        };
+
+        dump_on_exit(&handler.api).await.map_err(|e| {
+            ApiError::RpcError(RpcError::internal_error_with(format!(
+                "Failed dumping of sandbox {sandbox_id}: {e}"
+            )))
+        })
+    }
+
+    pub(crate) async fn destroy_all(&self) -> Result<(), ApiError> {
+        let sandbox_ids: Vec<_> = self.collection.lock().await.handlers.keys().copied().collect();
+        for sandbox_id in sandbox_ids {
+            self.destroy(sandbox_id).await?;
+        }
+        Ok(())
+    }
+}
+
+pub fn sandbox_rpc_path(sandbox_id: SandboxId) -> String {
+    format!("/sandbox/{sandbox_id}/rpc")
+}
+
+pub(crate) fn sandbox_info(sandbox_id: SandboxId, handler: &JsonRpcHandler) -> SandboxInfo {
+    SandboxInfo {
+        sandbox_id,
+        rpc_path: sandbox_rpc_path(sandbox_id),
+        restricted_methods: handler.server_config.restricted_methods.clone(),
+        dump_on: handler
+            .starknet_config
+            .dump_on
+            .and_then(|dump_on| dump_on.to_possible_value())
+            .map(|value| value.get_name().to_string()),
+        dump_path: handler.starknet_config.dump_path.clone(),
+    }
+}
+
+/// Creates the handler of a new sandbox. Its config is derived from the config of `host`; if
+/// `source` is provided, its current state is copied into the sandbox.
+pub(crate) async fn create_sandbox_handler(
+    host: &JsonRpcHandler,
+    source: Option<&JsonRpcHandler>,
+    config: SandboxConfig,
+) -> Result<JsonRpcHandler, ApiError> {
+    let invalid = |msg: String| ApiError::UnsupportedAction { msg };
+
+    let mut starknet_config = match source {
+        Some(source) => {
+            if config.seed.is_some()
+                || config.total_accounts.is_some()
+                || config.initial_balance.is_some()
+                || config.start_time.is_some()
+            {
+                return Err(invalid(
+                    "The initial state of a sandbox cannot be configured if it is copied from \
+                     another sandbox"
+                        .into(),
+                ));
+            }
+            source.starknet_config.clone()
+        }
+        None => sandbox_starknet_config(&host.starknet_config, &config).map_err(invalid)?,
+    };
+
+    // sandboxes never share the files of the host
+    starknet_config.db_path = None;
+    starknet_config.dump_on = match config.dump_on {
+        Some(dump_on) => Some(
+            DumpOn::from_str(&dump_on, true)
+                .map_err(|e| invalid(format!("Invalid dump_on: {e}")))?,
+        ),
+        None => None,
+    };
+    starknet_config.dump_path = config.dump_path;
+    if starknet_config.dump_on.is_some() && starknet_config.dump_path.is_none() {
+        return Err(invalid("Dumping of a sandbox requires dump_path".into()));
+    }
+
+    let mut server_config = host.server_config.clone();
+    server_config.restricted_methods = config.restricted_methods;
+
+    let mut starknet = Starknet::new(&starknet_config)?;
+    if let Some(source) = source {
+        starknet.load_state(source.api.starknet.lock().await.dump_state())?;
+    } else if let Some(start_time) = starknet_config.start_time {
+        starknet.set_block_timestamp_shift(
+            start_time as i64 - Starknet::get_unix_timestamp_as_seconds() as i64,
+        );
+    }
+
+    let mut handler = JsonRpcHandler::new(Api::new(starknet), &starknet_config, &server_config);
+    // sandboxes are managed only by the host
+    handler.sandboxes = None;
+
+    if source.is_none() {
+        load_dump_if_present(&handler, &starknet_config).await.map_err(|e| {
+            ApiError::RpcError(RpcError::internal_error_with(format!(
+                "Failed loading dump of sandbox: {e}"
+            )))
+        })?;
+    }
+
+    Ok(handler)
+}
+
+fn sandbox_starknet_config(
+    host_config: &StarknetConfig,
+    config: &SandboxConfig,
+) -> Result<StarknetConfig, String> {
+    let mut starknet_config = host_config.clone();
+    if let Some(seed) = config.seed {
+        starknet_config.seed = seed;
+    }
+    if let Some(total_accounts) = config.total_accounts {
+        starknet_config.total_accounts = total_accounts;
+    }
+    if let Some(initial_balance) = &config.initial_balance {
+        starknet_config.predeployed_accounts_initial_balance =
+            Balance::from_str(initial_balance)
+                .map_err(|e| format!("Invalid initial_balance: {e}"))?;
+    }
+    if config.start_time.is_some() {
+        starknet_config.start_time = config.start_time;
+    }
+    Ok(starknet_config)
+}
+
+pub(crate) fn sandbox_routes(sandboxes: Sandboxes) -> Router {
+    Router::new()
+        .route("/sandbox/:sandbox_id/rpc", post(handle_sandbox_rpc))
+        .route("/sandbox/:sandbox_id/rpc/v0_8", post(handle_sandbox_rpc_v0_8))
+        .route("/sandbox/:sandbox_id/ws", get(handle_sandbox_socket))
+        .with_state(sandboxes)
+}
+
+async fn get_sandbox(
+    sandboxes: &Sandboxes,
+    sandbox_id: SandboxId,
+) -> Result<JsonRpcHandler, (StatusCode, String)> {
+    sandboxes
+        .get(sandbox_id)
+        .await
+        .ok_or((StatusCode::NOT_FOUND, format!("Sandbox {sandbox_id} not found")))
+}
+
+async fn handle_sandbox_rpc(
+    Path(sandbox_id): Path<SandboxId>,
+    State(sandboxes): State<Sandboxes>,
+    request: Result<Json<Request>, JsonRejection>,
+) -> Result<HttpResponse, (StatusCode, String)> {
+    let handler = get_sandbox(&sandboxes, sandbox_id).await?;
+    Ok(rpc_handler::handle(State(handler), request).await.into_response())
+}
+
+async fn handle_sandbox_rpc_v0_8(
+    Path(sandbox_id): Path<SandboxId>,
+    State(sandboxes): State<Sandboxes>,
+    request: Result<Json<Request>, JsonRejection>,
+) -> Result<HttpResponse, (StatusCode, String)> {
+    let handler = get_sandbox(&sandboxes, sandbox_id).await?.with_rpc_version(RpcVersion::V0_8);
+    Ok(rpc_handler::handle(State(handler), request).await.into_response())
+}
+
+async fn handle_sandbox_socket(
+    Path(sandbox_id): Path<SandboxId>,
+    State(sandboxes): State<Sandboxes>,
+    ws_upgrade: WebSocketUpgrade,
+) -> Result<HttpResponse, (StatusCode, String)> {
+    let handler = get_sandbox(&sandboxes, sandbox_id).await?;
+    Ok(rpc_handler::handle_socket(ws_upgrade, State(handler)).await.into_response())
+}
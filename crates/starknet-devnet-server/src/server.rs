@@ -16,9 +16,10 @@ use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
 
 use crate::api::http::{endpoints as http, HttpApiHandler};
-use crate::api::json_rpc::JsonRpcHandler;
+use crate::api::json_rpc::{JsonRpcHandler, RpcVersion};
 use crate::restrictive_mode::is_uri_path_restricted;
 use crate::rpc_handler::RpcHandler;
+use crate::sandbox::sandbox_routes;
 use crate::{http_rpc_router, rpc_handler, ServerConfig};
 pub type StarknetDevnetServer = axum::serve::Serve<IntoMakeService<Router>, Router>;
 
@@ -55,9 +56,17 @@ fn json_rpc_routes<TJsonRpcHandler: RpcHandler>(json_rpc_handler: TJsonRpcHandle
     Router::new()
         .route("/", post(rpc_handler::handle::<TJsonRpcHandler>))
         .route("/rpc", post(rpc_handler::handle::<TJsonRpcHandler>))
+        .route("/ws", get(rpc_handler::handle_socket::<TJsonRpcHandler>))
         .with_state(json_rpc_handler)
 }
 
+/// Serves JSON-RPC 0.8 with the state of the handler, which serves JSON-RPC 0.7 on other paths
+fn json_rpc_v0_8_routes(json_rpc_handler: &JsonRpcHandler) -> Router {
+    Router::new()
+        .route("/rpc/v0_8", post(rpc_handler::handle::<JsonRpcHandler>))
+        .with_state(json_rpc_handler.with_rpc_version(RpcVersion::V0_8))
+}
+
 fn http_api_routes(http_api_handler: HttpApiHandler) -> Router {
     let mut router = Router::new();
     for (path, method_router) in HTTP_API_ROUTES_WITH_HANDLERS.iter() {
@@ -94,9 +103,15 @@ pub async fn serve_http_api_json_rpc(
 ) -> StarknetDevnetServer {
     let mut routes = Router::new()
         .merge(json_rpc_routes(json_rpc_handler.clone()))
+        .merge(json_rpc_v0_8_routes(&json_rpc_handler))
         .merge(http_api_routes(http_handler))
-        .merge(converted_http_api_routes(json_rpc_handler))
-        .layer(TraceLayer::new_for_http());
+        .merge(converted_http_api_routes(json_rpc_handler.clone()));
+
+    if let Some(sandboxes) = json_rpc_handler.sandboxes {
+        routes = routes.merge(sandbox_routes(sandboxes));
+    }
+
+    routes = routes.layer(TraceLayer::new_for_http());
 
     if server_config.log_response {
         routes = routes.layer(axum::middleware::from_fn(response_logging_middleware));
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use axum::extract::ws::Message;
+use serde::Serialize;
+use serde_json::json;
+use starknet_rs_core::types::Felt;
+use starknet_types::contract_address::ContractAddress;
+use starknet_types::emitted_event::EmittedEvent;
+use starknet_types::felt::{BlockHash, TransactionHash};
+use starknet_types::rpc::block::BlockHeader;
+use starknet_types::starknet_api::block::BlockNumber;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::api::json_rpc::models::TransactionStatusOutput;
+use crate::rpc_core::response::RpcResponse;
+
+pub type SocketId = u64;
+pub type SubscriptionId = u64;
+
+/// What a client subscribed to. Only the transaction status subscription is stateful - it
+/// remembers the last status sent to the client, so that only changes are notified.
+#[derive(Debug)]
+pub enum Subscription {
+    NewHeads,
+    TransactionStatus {
+        transaction_hash: TransactionHash,
+        last_status: Option<TransactionStatusOutput>,
+    },
+    Events {
+        address: Option<ContractAddress>,
+        keys: Option<Vec<Vec<Felt>>>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionStatusNotification {
+    pub transaction_hash: TransactionHash,
+    pub status: TransactionStatusOutput,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReorgData {
+    pub starting_block_hash: BlockHash,
+    pub starting_block_number: BlockNumber,
+    pub ending_block_hash: BlockHash,
+    pub ending_block_number: BlockNumber,
+}
+
+#[derive(Debug, Clone)]
+pub enum SubscriptionNotification {
+    NewHeads(Box<BlockHeader>),
+    TransactionStatus(TransactionStatusNotification),
+    Event(EmittedEvent),
+    Reorg(ReorgData),
+}
+
+impl SubscriptionNotification {
+    fn method_name(&self) -> &'static str {
+        match self {
+            SubscriptionNotification::NewHeads(_) => "starknet_subscriptionNewHeads",
+            SubscriptionNotification::TransactionStatus(_) => {
+                "starknet_subscriptionTransactionStatus"
+            }
+            SubscriptionNotification::Event(_) => "starknet_subscriptionEvents",
+            SubscriptionNotification::Reorg(_) => "starknet_subscriptionReorg",
+        }
+    }
+
+    fn result(&self) -> serde_json::Result<serde_json::Value> {
+        match self {
+            SubscriptionNotification::NewHeads(header) => serde_json::to_value(header),
+            SubscriptionNotification::TransactionStatus(status) => serde_json::to_value(status),
+            SubscriptionNotification::Event(event) => serde_json::to_value(event),
+            SubscriptionNotification::Reorg(reorg) => serde_json::to_value(reorg),
+        }
+    }
+
+    /// Serializes into a JSON-RPC notification (a request without an id)
+    fn to_message(&self, subscription_id: SubscriptionId) -> serde_json::Result<Message> {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": self.method_name(),
+            "params": {
+                "subscription_id": subscription_id,
+                "result": self.result()?,
+            }
+        });
+
+        Ok(Message::Text(notification.to_string()))
+    }
+}
+
+/// The queue of messages to be written to a WebSocket connection along with the subscriptions
+/// made through it. Messages are written to the socket by a separate task, so queueing never waits
+/// for a slow client.
+pub struct SocketContext {
+    sender: UnboundedSender<Message>,
+    subscriptions: HashMap<SubscriptionId, Subscription>,
+}
+
+impl SocketContext {
+    pub fn send_rpc_response(&self, response: RpcResponse) {
+        match serde_json::to_string(&response) {
+            Ok(serialized) => self.send(Message::Text(serialized)),
+            Err(e) => tracing::error!("Failed serializing websocket response: {e}"),
+        }
+    }
+
+    pub fn notify(&self, subscription_id: SubscriptionId, notification: &SubscriptionNotification) {
+        match notification.to_message(subscription_id) {
+            Ok(message) => self.send(message),
+            Err(e) => tracing::error!("Failed serializing subscription notification: {e}"),
+        }
+    }
+
+    fn send(&self, message: Message) {
+        if let Err(e) = self.sender.send(message) {
+            tracing::error!("Failed queueing websocket message: {e}");
+        }
+    }
+
+    pub fn subscriptions(&self) -> &HashMap<SubscriptionId, Subscription> {
+        &self.subscriptions
+    }
+
+    pub fn subscriptions_mut(&mut self) -> &mut HashMap<SubscriptionId, Subscription> {
+        &mut self.subscriptions
+    }
+}
+
+/// All currently open WebSocket connections
+#[derive(Default)]
+pub struct SocketCollection {
+    sockets: HashMap<SocketId, SocketContext>,
+    last_socket_id: SocketId,
+    last_subscription_id: SubscriptionId,
+    /// The latest block subscribers have been notified of. `None` if not even the first block has
+    /// been notified of.
+    last_notified_block_number: Option<BlockNumber>,
+}
+
+impl SocketCollection {
+    pub fn new(last_notified_block_number: Option<BlockNumber>) -> Self {
+        Self { last_notified_block_number, ..Default::default() }
+    }
+
+    pub fn insert(&mut self, sender: UnboundedSender<Message>) -> SocketId {
+        self.last_socket_id += 1;
+        self.sockets
+            .insert(self.last_socket_id, SocketContext { sender, subscriptions: HashMap::new() });
+        self.last_socket_id
+    }
+
+    pub fn remove(&mut self, socket_id: &SocketId) {
+        self.sockets.remove(socket_id);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sockets.is_empty()
+    }
+
+    pub fn get_mut(&mut self, socket_id: &SocketId) -> Option<&mut SocketContext> {
+        self.sockets.get_mut(socket_id)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut SocketContext> {
+        self.sockets.values_mut()
+    }
+
+    pub fn last_notified_block_number(&self) -> Option<BlockNumber> {
+        self.last_notified_block_number
+    }
+
+    pub fn set_last_notified_block_number(&mut self, block_number: Option<BlockNumber>) {
+        self.last_notified_block_number = block_number;
+    }
+
+    /// Registers the subscription with the socket and returns its id. Ids are unique across
+    /// sockets.
+    pub fn subscribe(
+        &mut self,
+        socket_id: &SocketId,
+        subscription: Subscription,
+    ) -> Option<SubscriptionId> {
+        let socket_context = self.sockets.get_mut(socket_id)?;
+        self.last_subscription_id += 1;
+        socket_context.subscriptions.insert(self.last_subscription_id, subscription);
+        Some(self.last_subscription_id)
+    }
+
+    /// Returns true if the subscription existed in the socket identified by `socket_id`.
+    pub fn unsubscribe(&mut self, socket_id: &SocketId, subscription_id: &SubscriptionId) -> bool {
+        self.sockets.get_mut(socket_id).is_some_and(|socket_context| {
+            socket_context.subscriptions.remove(subscription_id).is_some()
+        })
+    }
+
+    /// Sends the reorg notification to every subscription. All subscription kinds are tied to
+    /// blocks, so all of them are concerned. Blocks replacing the orphaned ones are notified of as
+    /// new.
+    pub fn notify_reorg(&mut self, reorg: ReorgData) {
+        self.last_notified_block_number =
+            reorg.starting_block_number.0.checked_sub(1).map(BlockNumber);
+
+        let notification = SubscriptionNotification::Reorg(reorg);
+        for socket_context in self.sockets.values() {
+            for subscription_id in socket_context.subscriptions.keys() {
+                socket_context.notify(*subscription_id, &notification);
+            }
+        }
+    }
+}
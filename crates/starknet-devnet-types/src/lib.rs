@@ -10,4 +10,4 @@ mod utils;
 
 // Re export libraries
 pub use rpc::{contract_address, contract_class, emitted_event, felt, messaging};
-pub use {num_bigint, starknet_api};
+pub use {cairo_lang_starknet_classes, num_bigint, starknet_api};
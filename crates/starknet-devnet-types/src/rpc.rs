@@ -1,6 +1,7 @@
 pub mod block;
 pub mod contract_address;
 pub mod contract_class;
+pub mod devnet;
 pub mod emitted_event;
 pub mod estimate_message_fee;
 pub mod eth_address;
@@ -9,5 +10,7 @@ pub mod gas_modification;
 mod macro_utils;
 pub mod messaging;
 pub mod state;
+pub mod storage_proof;
 pub mod transaction_receipt;
 pub mod transactions;
+pub mod v0_8;
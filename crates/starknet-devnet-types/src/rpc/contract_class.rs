@@ -2,6 +2,8 @@ use core::fmt::Debug;
 use std::cmp::{Eq, PartialEq};
 
 use blockifier::execution::contract_class::ClassInfo;
+use cairo_lang_sierra_to_casm::compiler::SierraToCasmConfig;
+use cairo_lang_sierra_to_casm::metadata::calc_metadata;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use serde::de::IntoDeserializer;
@@ -113,14 +115,7 @@ impl TryFrom<ContractClass> for blockifier::execution::contract_class::ContractC
                 ))
             }
             ContractClass::Cairo1(sierra_contract_class) => {
-                let casm_json =
-                    usc::compile_contract(serde_json::to_value(sierra_contract_class).map_err(
-                        |err| Error::JsonError(JsonError::Custom { msg: err.to_string() }),
-                    )?)
-                    .map_err(|err| Error::SierraCompilationError { reason: err.to_string() })?;
-
-                let casm = serde_json::from_value::<CasmContractClass>(casm_json)
-                    .map_err(|err| Error::JsonError(JsonError::Custom { msg: err.to_string() }))?;
+                let casm = compile_sierra_contract(&sierra_contract_class)?;
 
                 let blockifier_contract_class: blockifier::execution::contract_class::ContractClassV1 =
                     casm.try_into().map_err(|_| Error::ProgramError)?;
@@ -249,6 +244,59 @@ fn convert_sierra_to_codegen(
     })
 }
 
+/// Compiles the Sierra contract class to CASM, the way it is done when the class is declared.
+pub fn compile_sierra_contract(
+    sierra_contract_class: &SierraContractClass,
+) -> DevnetResult<CasmContractClass> {
+    let casm_json = usc::compile_contract(
+        serde_json::to_value(sierra_contract_class)
+            .map_err(|err| Error::JsonError(JsonError::Custom { msg: err.to_string() }))?,
+    )
+    .map_err(|err| Error::SierraCompilationError { reason: err.to_string() })?;
+
+    serde_json::from_value::<CasmContractClass>(casm_json)
+        .map_err(|err| Error::JsonError(JsonError::Custom { msg: err.to_string() }))
+}
+
+/// Returns the offset of each Sierra statement in the bytecode of the CASM compiled from the
+/// class. Used for mapping the PCs visited during execution back to Sierra statements. Fails if the
+/// offsets can't be determined, e.g. if the class is executed as compiled by a different compiler
+/// version, whose bytecode differs.
+pub fn sierra_statement_offsets(
+    sierra_contract_class: &SierraContractClass,
+) -> DevnetResult<Vec<usize>> {
+    let offsets_error = |reason: String| Error::SierraCompilationError {
+        reason: format!("Failed to map Sierra statements to the bytecode: {reason}"),
+    };
+
+    let program = sierra_contract_class
+        .extract_sierra_program()
+        .map_err(|err| offsets_error(err.to_string()))?;
+    let metadata = calc_metadata(&program, Default::default())
+        .map_err(|err| offsets_error(err.to_string()))?;
+    // gas costs only affect the values of immediates, not the offsets of instructions
+    let config = SierraToCasmConfig { gas_usage_check: false, max_bytecode_size: usize::MAX };
+    let cairo_program = cairo_lang_sierra_to_casm::compiler::compile(&program, &metadata, config)
+        .map_err(|err| offsets_error(err.to_string()))?;
+
+    // the class is executed as compiled on declaration, possibly by a different compiler version
+    let casm = compile_sierra_contract(sierra_contract_class)?;
+    let bytecode_length = cairo_program.assemble().bytecode.len();
+    if bytecode_length != casm.bytecode.len() {
+        return Err(offsets_error(format!(
+            "the bytecode has {bytecode_length} instead of {} words",
+            casm.bytecode.len()
+        )));
+    }
+
+    Ok(cairo_program
+        .debug_info
+        .sierra_statement_info
+        .iter()
+        .map(|info| info.start_offset)
+        .collect())
+}
+
 pub fn convert_codegen_to_blockifier_compiled_class(
     class: CodegenContractClass,
 ) -> Result<blockifier::execution::contract_class::ContractClass, Error> {
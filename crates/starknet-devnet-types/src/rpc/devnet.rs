@@ -0,0 +1,496 @@
+//! Requests and responses of the `devnet_*` JSON-RPC methods and the equivalent HTTP endpoints.
+//! Shared by the server and the client, so that the two cannot diverge.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use starknet_rs_core::types::{BlockTag, Felt, Hash256};
+
+use crate::contract_address::ContractAddress;
+use crate::felt::{BlockHash, ClassHash, TransactionHash};
+use crate::num_bigint::BigUint;
+use crate::patricia_key::PatriciaKey;
+use crate::rpc::block::BlockId;
+use crate::rpc::messaging::{MessageToL1, MessageToL2};
+use crate::rpc::transaction_receipt::{DataAvailability, FeeUnit};
+use crate::rpc::transactions::{BroadcastedTransaction, SimulationFlag, TransactionType};
+use crate::serde_helpers::dec_string::{deserialize_biguint, serialize_biguint};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DumpPath {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportGenesisPath {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoadPath {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostmanLoadL1MessagingContract {
+    pub network_url: String,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageHash {
+    pub message_hash: Hash256,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedBlock {
+    pub block_hash: BlockHash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AbortingBlocks {
+    pub starting_block_id: BlockId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbortedBlocks {
+    pub aborted: Vec<BlockHash>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AcceptingOnL1 {
+    /// The last block to accept; defaults to the latest block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<BlockId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptedOnL1 {
+    pub accepted: Vec<BlockHash>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakenSnapshot {
+    pub snapshot_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RevertingToSnapshot {
+    pub snapshot_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IncreaseTime {
+    pub time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetTime {
+    pub time: u64,
+    pub generate_block: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetTimeResponse {
+    pub block_timestamp: u64,
+    pub block_hash: Option<BlockHash>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncreaseTimeResponse {
+    pub timestamp_increased_by: u64,
+    pub block_hash: BlockHash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableAccount {
+    pub initial_balance: String,
+    pub address: ContractAddress,
+    pub public_key: Felt,
+    pub private_key: Felt,
+    pub balance: Option<AccountBalancesResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBalancesResponse {
+    pub eth: AccountBalanceResponse,
+    pub strk: AccountBalanceResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBalanceResponse {
+    pub amount: String,
+    pub unit: FeeUnit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PredeployedAccountsQuery {
+    pub with_balance: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceQuery {
+    pub address: Felt,
+    pub unit: Option<FeeUnit>,
+    pub block_tag: Option<BlockTag>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MintTokensRequest {
+    pub address: ContractAddress,
+    #[serde(serialize_with = "serialize_biguint", deserialize_with = "deserialize_biguint")]
+    pub amount: BigUint,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<FeeUnit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintTokensResponse {
+    /// decimal repr
+    pub new_balance: String,
+    pub unit: FeeUnit,
+    pub tx_hash: TransactionHash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlushedMessages {
+    pub messages_to_l1: Vec<MessageToL1>,
+    pub messages_to_l2: Vec<MessageToL2>,
+    pub generated_l2_transactions: Vec<TransactionHash>,
+    pub l1_provider: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FlushParameters {
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagingLoadAddress {
+    pub messaging_contract_address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RestartParameters {
+    pub restart_l1_to_l2_messaging: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountAddressInput {
+    pub account_address: ContractAddress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetStorageAtInput {
+    pub contract_address: ContractAddress,
+    pub key: PatriciaKey,
+    pub value: Felt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetNonceInput {
+    pub contract_address: ContractAddress,
+    pub nonce: Felt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetClassHashAtInput {
+    pub contract_address: ContractAddress,
+    pub class_hash: ClassHash,
+}
+
+pub type SandboxId = u64;
+
+/// Settings of a sandbox which differ from those of the hosting Devnet. Settings affecting the
+/// initial state (seed, accounts, balance, start time) cannot be combined with copying a sandbox.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_accounts: Option<u8>,
+    /// decimal repr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_balance: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restricted_methods: Option<Vec<String>>,
+    /// One of `exit`, `block` or `request`, as in the CLI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dump_on: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dump_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxCreationRequest {
+    /// The sandbox whose current state is copied into the new one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_sandbox: Option<SandboxId>,
+    #[serde(default)]
+    pub config: SandboxConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxIdInput {
+    pub sandbox_id: SandboxId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxInfo {
+    pub sandbox_id: SandboxId,
+    /// Path of the JSON-RPC endpoint of the sandbox, e.g. `/sandbox/1/rpc`
+    pub rpc_path: String,
+    pub restricted_methods: Option<Vec<String>>,
+    pub dump_on: Option<String>,
+    pub dump_path: Option<String>,
+}
+
+/// A transaction received in mempool mode which hasn't been executed yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolTransaction {
+    pub transaction_hash: TransactionHash,
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    pub sender_address: ContractAddress,
+    pub nonce: Felt,
+    /// False if the transaction waits for a missing nonce of its sender
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DropTransactionsRequest {
+    /// If omitted, all transactions are dropped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hashes: Option<Vec<TransactionHash>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedTransactions {
+    pub dropped: Vec<TransactionHash>,
+}
+
+/// An error which can be injected into responses; named after the JSON-RPC error it produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InjectedError {
+    BlockNotFound,
+    #[serde(rename = "TXN_HASH_NOT_FOUND")]
+    TransactionNotFound,
+    ContractNotFound,
+    ClassHashNotFound,
+    InvalidTransactionNonce,
+    InsufficientMaxFee,
+    InsufficientAccountBalance,
+    ValidationFailure,
+    InternalError,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorFault {
+    pub error: InjectedError,
+    /// Between 0 and 1
+    pub probability: f64,
+}
+
+/// Faults of a single JSON-RPC method
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MethodFaults {
+    /// At most one of these errors is returned instead of handling the request; the sum of their
+    /// probabilities must not exceed 1
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ErrorFault>,
+    /// Delay before handling the request
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Probability of handling the request, but closing the connection instead of responding
+    #[serde(default)]
+    pub drop_probability: f64,
+}
+
+/// Faults injected into the handling of requests; replaces the previously set faults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultConfig {
+    /// Seed of the generator deciding which faults occur; random if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Keyed by method name; faults under `"*"` apply to methods without their own entry
+    #[serde(default)]
+    pub methods: BTreeMap<String, MethodFaults>,
+    /// Number of the next invoke transactions to be reverted
+    #[serde(default)]
+    pub revert_next_transactions: u64,
+    /// Probability of a request forwarded to the forking origin failing
+    #[serde(default)]
+    pub origin_failure_probability: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaultsSet {
+    /// The seed in use, which reproduces the faults if passed to a later `devnet_setFaults`
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageFormat {
+    #[default]
+    Json,
+    Lcov,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CoverageRequest {
+    #[serde(default)]
+    pub format: CoverageFormat,
+}
+
+/// Program counters visited during the execution of a class, as offsets in its compiled bytecode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassCoverage {
+    pub class_hash: ClassHash,
+    pub visited_pcs: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub classes: Vec<ClassCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LcovCoverage {
+    /// Coverage of Cairo source lines in the LCOV tracefile format
+    pub lcov: String,
+}
+
+/// A transaction to be simulated on top of the state at `block_id` and profiled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfiledSimulation {
+    pub block_id: BlockId,
+    pub transaction: BroadcastedTransaction,
+    #[serde(default)]
+    pub simulation_flags: Vec<SimulationFlag>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileFormat {
+    #[default]
+    Json,
+    /// Folded stacks, as consumed by flamegraph tools
+    Folded,
+}
+
+/// The resource by which folded stacks are weighted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileMetric {
+    #[default]
+    Steps,
+    L1Gas,
+    L1DataGas,
+}
+
+/// Exactly one of `transaction_hash` and `simulation` has to be provided
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileTransactionRequest {
+    /// Hash of a transaction executed by Devnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<TransactionHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<ProfiledSimulation>,
+    #[serde(default)]
+    pub format: ProfileFormat,
+    /// Only used with the folded format
+    #[serde(default)]
+    pub metric: ProfileMetric,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfiledResources {
+    pub steps: usize,
+    /// Applications of each used builtin, keyed by builtin name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub builtins: BTreeMap<String, usize>,
+    pub l1_gas: u128,
+    pub l1_data_gas: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallProfile {
+    pub contract_address: ContractAddress,
+    pub class_hash: ClassHash,
+    /// Resolved from the ABI of the class, if possible
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_name: Option<String>,
+    pub entry_point_selector: Felt,
+    /// Resolved from the ABI of the class, if possible
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_name: Option<String>,
+    /// Resources of the call, including its inner calls
+    pub resources: ProfiledResources,
+    /// Resources of the call, excluding its inner calls
+    pub self_resources: ProfiledResources,
+    pub calls: Vec<CallProfile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvocationPhase {
+    Validate,
+    Execute,
+    Constructor,
+    L1Handler,
+    FeeTransfer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvocationProfile {
+    pub phase: InvocationPhase,
+    pub call: CallProfile,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionProfile {
+    /// Top-level invocations, in the order of execution; reverted execution is omitted
+    pub invocations: Vec<InvocationProfile>,
+    /// Gas consumed by publishing the state diff of the transaction
+    pub data_availability: DataAvailability,
+    /// Resources of the whole transaction, including the overhead not attributed to any call
+    pub total: ProfiledResources,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoldedProfile {
+    /// One line per stack, consisting of `;`-separated frames and the weight of the stack
+    pub folded: String,
+}
@@ -1,6 +1,7 @@
 use std::num::NonZeroU128;
 
 use serde::{Deserialize, Serialize};
+use starknet_api::data_availability::L1DataAvailabilityMode;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -37,3 +38,15 @@ impl GasModification {
         }
     }
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataAvailabilityModeRequest {
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub generate_block: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DataAvailabilityModeModification {
+    pub l1_da_mode: L1DataAvailabilityMode,
+}
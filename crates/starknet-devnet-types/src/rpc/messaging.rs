@@ -16,6 +16,9 @@ pub struct MessageToL2 {
     pub payload: Calldata,
     pub paid_fee_on_l1: Felt,
     pub nonce: Nonce,
+    /// The hash of the L1 transaction which sent the message, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_transaction_hash: Option<Hash256>,
 }
 
 impl MessageToL2 {
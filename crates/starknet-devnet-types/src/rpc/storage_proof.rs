@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use starknet_rs_core::types::Felt;
+
+use crate::contract_address::ContractAddress;
+use crate::felt::{BlockHash, ClassHash, Nonce};
+
+/// A node of a Merkle-Patricia trie
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum MerkleNode {
+    Binary(BinaryNode),
+    Edge(EdgeNode),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BinaryNode {
+    pub left: Felt,
+    pub right: Felt,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EdgeNode {
+    pub path: Felt,
+    pub length: usize,
+    pub child: Felt,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeHashToNode {
+    pub node_hash: Felt,
+    pub node: MerkleNode,
+}
+
+/// The nodes needed to prove membership or non-membership of a set of keys in a trie
+pub type NodeHashToNodeMapping = Vec<NodeHashToNode>;
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractLeafData {
+    pub nonce: Nonce,
+    pub class_hash: ClassHash,
+    pub storage_root: Felt,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractsProof {
+    pub nodes: NodeHashToNodeMapping,
+    /// In the order of the requested contract addresses
+    pub contract_leaves_data: Vec<ContractLeafData>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalRoots {
+    pub contracts_tree_root: Felt,
+    pub classes_tree_root: Felt,
+    pub block_hash: BlockHash,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageProof {
+    pub classes_proof: NodeHashToNodeMapping,
+    pub contracts_proof: ContractsProof,
+    /// In the order of the requested contracts
+    pub contracts_storage_proofs: Vec<NodeHashToNodeMapping>,
+    pub global_roots: GlobalRoots,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractStorageKeys {
+    pub contract_address: ContractAddress,
+    pub storage_keys: Vec<Felt>,
+}
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use serde::{Deserialize, Deserializer, Serialize};
 use starknet_api::block::BlockNumber;
 use starknet_api::transaction::Fee;
@@ -62,9 +65,13 @@ pub struct ExecutionResources {
     #[serde(flatten)]
     pub computation_resources: ComputationResources,
     pub data_availability: DataAvailability,
+    /// The total gas consumed by the transaction, not part of the execution resources of
+    /// JSON-RPC 0.7, but reported by JSON-RPC 0.8
+    #[serde(skip)]
+    pub total_gas_consumed: DataAvailability,
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct DataAvailability {
     pub l1_gas: u128,
@@ -223,11 +230,52 @@ impl From<&blockifier::transaction::objects::TransactionExecutionInfo> for Execu
                 l1_gas: execution_info.transaction_receipt.da_gas.l1_gas,
                 l1_data_gas: execution_info.transaction_receipt.da_gas.l1_data_gas,
             },
+            total_gas_consumed: DataAvailability {
+                l1_gas: execution_info.transaction_receipt.gas.l1_gas,
+                l1_data_gas: execution_info.transaction_receipt.gas.l1_data_gas,
+            },
+        }
+    }
+}
+
+impl From<&ComputationResources> for VmExecutionResources {
+    fn from(resources: &ComputationResources) -> Self {
+        VmExecutionResources {
+            n_steps: resources.steps,
+            n_memory_holes: resources.memory_holes.unwrap_or_default(),
+            builtin_instance_counter: resources
+                .builtins()
+                .into_iter()
+                .filter_map(|(builtin, applications)| Some((builtin, applications?)))
+                .collect(),
         }
     }
 }
 
 impl ComputationResources {
+    /// Returns the number of applications of each used builtin, keyed by builtin name
+    pub fn builtin_applications(&self) -> BTreeMap<String, usize> {
+        self.builtins()
+            .into_iter()
+            .filter_map(|(builtin, applications)| {
+                Some((builtin.to_str().to_string(), applications?))
+            })
+            .collect()
+    }
+
+    fn builtins(&self) -> [(BuiltinName, Option<usize>); 8] {
+        [
+            (BuiltinName::range_check, self.range_check_builtin_applications),
+            (BuiltinName::pedersen, self.pedersen_builtin_applications),
+            (BuiltinName::poseidon, self.poseidon_builtin_applications),
+            (BuiltinName::ec_op, self.ec_op_builtin_applications),
+            (BuiltinName::ecdsa, self.ecdsa_builtin_applications),
+            (BuiltinName::bitwise, self.bitwise_builtin_applications),
+            (BuiltinName::keccak, self.keccak_builtin_applications),
+            (BuiltinName::segment_arena, self.segment_arena_builtin_applications),
+        ]
+    }
+
     fn get_memory_holes_from_call_info(
         call_info: &Option<blockifier::execution::call_info::CallInfo>,
     ) -> usize {
@@ -5,7 +5,6 @@ use std::sync::Arc;
 use blockifier::execution::contract_class::ClassInfo;
 use blockifier::state::state_api::StateReader;
 use blockifier::transaction::account_transaction::AccountTransaction;
-use blockifier::transaction::objects::TransactionExecutionInfo;
 use broadcasted_declare_transaction_v1::BroadcastedDeclareTransactionV1;
 use broadcasted_declare_transaction_v2::BroadcastedDeclareTransactionV2;
 use declare_transaction_v0v1::DeclareTransactionV0V1;
@@ -53,7 +52,6 @@ use crate::felt::{
     TransactionVersion,
 };
 use crate::rpc::transaction_receipt::{CommonTransactionReceipt, MaybePendingProperties};
-use crate::{impl_wrapper_deserialize, impl_wrapper_serialize};
 
 pub mod broadcasted_declare_transaction_v1;
 pub mod broadcasted_declare_transaction_v2;
@@ -129,6 +127,35 @@ pub struct TransactionWithHash {
     pub transaction: Transaction,
 }
 
+impl Transaction {
+    /// Returns the signature of the transaction, which is empty for unsigned transaction types
+    pub fn get_signature(&self) -> &[Felt] {
+        match self {
+            Transaction::Declare(DeclareTransaction::V1(tx)) => &tx.signature,
+            Transaction::Declare(DeclareTransaction::V2(tx)) => &tx.signature,
+            Transaction::Declare(DeclareTransaction::V3(tx)) => tx.get_signature(),
+            Transaction::DeployAccount(DeployAccountTransaction::V1(tx)) => &tx.signature,
+            Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => tx.get_signature(),
+            Transaction::Invoke(InvokeTransaction::V1(tx)) => &tx.signature,
+            Transaction::Invoke(InvokeTransaction::V3(tx)) => tx.get_signature(),
+            Transaction::Deploy(_) | Transaction::L1Handler(_) => &[],
+        }
+    }
+
+    /// Returns the L1 data gas bounds of a V3 transaction, if it was submitted with them
+    pub fn get_l1_data_gas_bounds(&self) -> Option<&ResourceBounds> {
+        let resource_bounds = match self {
+            Transaction::Declare(DeclareTransaction::V3(tx)) => tx.get_resource_bounds(),
+            Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => {
+                tx.get_resource_bounds()
+            }
+            Transaction::Invoke(InvokeTransaction::V3(tx)) => tx.get_resource_bounds(),
+            _ => return None,
+        };
+        resource_bounds.l1_data_gas()
+    }
+}
+
 impl TransactionWithHash {
     pub fn new(transaction_hash: TransactionHash, transaction: Transaction) -> Self {
         Self { transaction_hash, transaction }
@@ -158,10 +185,9 @@ impl TransactionWithHash {
         execution_result: &ExecutionResult,
         finality_status: TransactionFinalityStatus,
         actual_fee: FeeInUnits,
-        execution_info: &TransactionExecutionInfo,
+        execution_resources: ExecutionResources,
     ) -> CommonTransactionReceipt {
         let r#type = self.get_type();
-        let execution_resources = ExecutionResources::from(execution_info);
         let maybe_pending_properties =
             MaybePendingProperties { block_number, block_hash: block_hash.cloned() };
 
@@ -299,10 +325,44 @@ pub struct BroadcastedTransactionCommonV3 {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ResourceBoundsWrapper {
     inner: ResourceBoundsMapping,
+    /// Introduced in JSON-RPC 0.8. Unknown to blockifier, so Devnet checks it after execution. It
+    /// is part of the transaction hash if provided.
+    l1_data_gas: Option<ResourceBounds>,
 }
 
-impl_wrapper_serialize!(ResourceBoundsWrapper);
-impl_wrapper_deserialize!(ResourceBoundsWrapper, ResourceBoundsMapping);
+/// The JSON form of [`ResourceBoundsWrapper`], with `l1_data_gas` present only if it was provided
+#[derive(Deserialize, Serialize)]
+struct ResourceBoundsMappingJson {
+    l1_gas: ResourceBounds,
+    l2_gas: ResourceBounds,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    l1_data_gas: Option<ResourceBounds>,
+}
+
+impl Serialize for ResourceBoundsWrapper {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ResourceBoundsMappingJson {
+            l1_gas: self.inner.l1_gas.clone(),
+            l2_gas: self.inner.l2_gas.clone(),
+            l1_data_gas: self.l1_data_gas.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ResourceBoundsWrapper {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ResourceBoundsMappingJson { l1_gas, l2_gas, l1_data_gas } =
+            ResourceBoundsMappingJson::deserialize(deserializer)?;
+        Ok(ResourceBoundsWrapper { inner: ResourceBoundsMapping { l1_gas, l2_gas }, l1_data_gas })
+    }
+}
 
 impl ResourceBoundsWrapper {
     pub fn new(
@@ -322,8 +382,13 @@ impl ResourceBoundsWrapper {
                     max_price_per_unit: l2_gas_max_price_per_unit,
                 },
             },
+            l1_data_gas: None,
         }
     }
+
+    pub fn l1_data_gas(&self) -> Option<&ResourceBounds> {
+        self.l1_data_gas.as_ref()
+    }
 }
 
 impl From<&ResourceBoundsWrapper> for starknet_api::transaction::ResourceBoundsMapping {
@@ -365,6 +430,11 @@ impl BroadcastedTransactionCommonV3 {
         is_only_query_common(&self.version)
     }
 
+    /// L1 data gas bounds were introduced in JSON-RPC 0.8
+    pub fn has_l1_data_gas_bounds(&self) -> bool {
+        self.resource_bounds.l1_data_gas.is_some()
+    }
+
     /// Returns an array of Felts that reflects the `common_tx_fields` according to SNIP-8(https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-8.md/#protocol-changes).
     ///
     /// # Arguments
@@ -397,6 +467,24 @@ impl BroadcastedTransactionCommonV3 {
         let mut array = Vec::<Felt>::new();
         array.push(Felt::from(self.tip.0));
 
+        fn field_element_from_resource_name_and_bounds(
+            resource_name_bytes: &[u8],
+            resource_bounds: &ResourceBounds,
+        ) -> Felt {
+            // (resource||max_amount||max_price_per_unit) from SNIP-8 https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-8.md#protocol-changes
+            let bytes: Vec<u8> = [
+                resource_name_bytes,
+                resource_bounds.max_amount.to_be_bytes().as_slice(),
+                resource_bounds.max_price_per_unit.to_be_bytes().as_slice(),
+            ]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect();
+
+            Felt::from_bytes_be_slice(&bytes)
+        }
+
         fn field_element_from_resource_bounds(
             resource: Resource,
             resource_bounds: &ResourceBounds,
@@ -411,18 +499,7 @@ impl BroadcastedTransactionCommonV3 {
                 }))?
                 .as_bytes();
 
-            // (resource||max_amount||max_price_per_unit) from SNIP-8 https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-8.md#protocol-changes
-            let bytes: Vec<u8> = [
-                resource_name_bytes,
-                resource_bounds.max_amount.to_be_bytes().as_slice(),
-                resource_bounds.max_price_per_unit.to_be_bytes().as_slice(),
-            ]
-            .into_iter()
-            .flatten()
-            .copied()
-            .collect();
-
-            Ok(Felt::from_bytes_be_slice(&bytes))
+            Ok(field_element_from_resource_name_and_bounds(resource_name_bytes, resource_bounds))
         }
         array.push(field_element_from_resource_bounds(
             Resource::L1Gas,
@@ -432,6 +509,10 @@ impl BroadcastedTransactionCommonV3 {
             Resource::L2Gas,
             &self.resource_bounds.inner.l2_gas,
         )?);
+        // L1 data gas is not a resource of starknet_api at this version, so it is named directly
+        if let Some(l1_data_gas) = &self.resource_bounds.l1_data_gas {
+            array.push(field_element_from_resource_name_and_bounds(b"L1_DATA", l1_data_gas));
+        }
 
         Ok(array)
     }
@@ -456,7 +537,7 @@ impl BroadcastedTransactionCommonV3 {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum BroadcastedTransaction {
     Invoke(BroadcastedInvokeTransaction),
@@ -494,6 +575,14 @@ impl BroadcastedTransaction {
             BroadcastedTransaction::DeployAccount(_) => TransactionType::DeployAccount,
         }
     }
+
+    pub fn has_l1_data_gas_bounds(&self) -> bool {
+        match self {
+            BroadcastedTransaction::Invoke(tx) => tx.has_l1_data_gas_bounds(),
+            BroadcastedTransaction::Declare(tx) => tx.has_l1_data_gas_bounds(),
+            BroadcastedTransaction::DeployAccount(tx) => tx.has_l1_data_gas_bounds(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -532,6 +621,13 @@ impl BroadcastedDeclareTransaction {
         }
     }
 
+    pub fn has_l1_data_gas_bounds(&self) -> bool {
+        match self {
+            BroadcastedDeclareTransaction::V1(_) | BroadcastedDeclareTransaction::V2(_) => false,
+            BroadcastedDeclareTransaction::V3(tx) => tx.common.has_l1_data_gas_bounds(),
+        }
+    }
+
     /// Creates a blockifier declare transaction from the current transaction.
     /// The transaction hash is computed using the given chain id.
     ///
@@ -685,6 +781,13 @@ impl BroadcastedDeployAccountTransaction {
         }
     }
 
+    pub fn has_l1_data_gas_bounds(&self) -> bool {
+        match self {
+            BroadcastedDeployAccountTransaction::V1(_) => false,
+            BroadcastedDeployAccountTransaction::V3(tx) => tx.common.has_l1_data_gas_bounds(),
+        }
+    }
+
     /// Creates a blockifier deploy account transaction from the current transaction.
     /// The transaction hash is computed using the given chain id.
     ///
@@ -819,6 +922,13 @@ impl BroadcastedInvokeTransaction {
         }
     }
 
+    pub fn has_l1_data_gas_bounds(&self) -> bool {
+        match self {
+            BroadcastedInvokeTransaction::V1(_) => false,
+            BroadcastedInvokeTransaction::V3(tx) => tx.common.has_l1_data_gas_bounds(),
+        }
+    }
+
     /// Creates a blockifier invoke transaction from the current transaction.
     /// The transaction hash is computed using the given chain id.
     ///
@@ -979,7 +1089,7 @@ impl<'de> Deserialize<'de> for BroadcastedInvokeTransaction {
 /// By default, the sequencer behavior is replicated locally (enough funds are expected to be in the
 /// account, and fee will be deducted from the balance before the simulation of the next
 /// transaction). To skip the fee charge, use the SKIP_FEE_CHARGE flag.
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SimulationFlag {
     SkipValidate,
@@ -997,18 +1107,18 @@ pub enum CallType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct FunctionInvocation {
-    contract_address: ContractAddress,
-    entry_point_selector: EntryPointSelector,
-    calldata: Calldata,
-    caller_address: ContractAddress,
-    class_hash: Felt,
-    entry_point_type: EntryPointType,
-    call_type: CallType,
-    result: Vec<Felt>,
-    calls: Vec<FunctionInvocation>,
-    events: Vec<OrderedEvent>,
-    messages: Vec<OrderedMessageToL1>,
-    execution_resources: ComputationResources,
+    pub(crate) contract_address: ContractAddress,
+    pub(crate) entry_point_selector: EntryPointSelector,
+    pub(crate) calldata: Calldata,
+    pub(crate) caller_address: ContractAddress,
+    pub(crate) class_hash: Felt,
+    pub(crate) entry_point_type: EntryPointType,
+    pub(crate) call_type: CallType,
+    pub(crate) result: Vec<Felt>,
+    pub(crate) calls: Vec<FunctionInvocation>,
+    pub(crate) events: Vec<OrderedEvent>,
+    pub(crate) messages: Vec<OrderedMessageToL1>,
+    pub(crate) execution_resources: ComputationResources,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1083,6 +1193,26 @@ pub struct SimulatedTransaction {
 }
 
 impl FunctionInvocation {
+    pub fn contract_address(&self) -> ContractAddress {
+        self.contract_address
+    }
+
+    pub fn class_hash(&self) -> Felt {
+        self.class_hash
+    }
+
+    pub fn entry_point_selector(&self) -> EntryPointSelector {
+        self.entry_point_selector
+    }
+
+    pub fn calls(&self) -> &[FunctionInvocation] {
+        &self.calls
+    }
+
+    pub fn execution_resources(&self) -> &ComputationResources {
+        &self.execution_resources
+    }
+
     pub fn try_from_call_info(
         call_info: &blockifier::execution::call_info::CallInfo,
         state_reader: &mut impl StateReader,
@@ -1141,6 +1271,7 @@ impl FunctionInvocation {
 
 #[cfg(test)]
 mod tests {
+    use starknet_rs_core::types::Felt;
     use starknet_rs_crypto::poseidon_hash_many;
 
     use super::BroadcastedTransactionCommonV3;
@@ -1187,4 +1318,48 @@ mod tests {
 
         assert_eq!(common_fields_hash, expected_hash);
     }
+
+    #[test]
+    fn l1_data_gas_bounds_are_hashed_and_serialized_only_if_provided() {
+        let resource_bounds_json = serde_json::json!({
+            "l1_gas": { "max_amount": "0x7c9", "max_price_per_unit": "0x1" },
+            "l2_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+        });
+        let mut common_fields_json = serde_json::json!({
+            "signature": [],
+            "version": "0x3",
+            "nonce": "0x0",
+            "resource_bounds": resource_bounds_json,
+            "tip": "0x0",
+            "paymaster_data": [],
+            "nonce_data_availability_mode": "L1",
+            "fee_data_availability_mode": "L1",
+        });
+
+        let common_fields =
+            serde_json::from_value::<BroadcastedTransactionCommonV3>(common_fields_json.clone())
+                .unwrap();
+        assert_eq!(common_fields.get_resource_bounds_array().unwrap().len(), 3);
+        assert_eq!(
+            serde_json::to_value(&common_fields.resource_bounds).unwrap(),
+            resource_bounds_json
+        );
+
+        common_fields_json["resource_bounds"]["l1_data_gas"] =
+            serde_json::json!({ "max_amount": "0x1", "max_price_per_unit": "0x2" });
+        let common_fields =
+            serde_json::from_value::<BroadcastedTransactionCommonV3>(common_fields_json.clone())
+                .unwrap();
+        let resource_bounds_array = common_fields.get_resource_bounds_array().unwrap();
+        assert_eq!(
+            resource_bounds_array[3],
+            Felt::from_bytes_be_slice(
+                &[b"L1_DATA".as_slice(), &1_u64.to_be_bytes(), &2_u128.to_be_bytes()].concat()
+            )
+        );
+        assert_eq!(
+            serde_json::to_value(&common_fields.resource_bounds).unwrap(),
+            common_fields_json["resource_bounds"]
+        );
+    }
 }
@@ -46,4 +46,12 @@ impl DeclareTransactionV3 {
     pub fn get_class_hash(&self) -> &ClassHash {
         &self.class_hash
     }
+
+    pub fn get_signature(&self) -> &TransactionSignature {
+        &self.signature
+    }
+
+    pub fn get_resource_bounds(&self) -> &ResourceBoundsWrapper {
+        &self.resource_bounds
+    }
 }
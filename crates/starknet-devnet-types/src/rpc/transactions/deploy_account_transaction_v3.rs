@@ -52,6 +52,14 @@ impl DeployAccountTransactionV3 {
     pub fn get_contract_address(&self) -> &ContractAddress {
         &self.contract_address
     }
+
+    pub fn get_signature(&self) -> &TransactionSignature {
+        &self.signature
+    }
+
+    pub fn get_resource_bounds(&self) -> &ResourceBoundsWrapper {
+        &self.resource_bounds
+    }
 }
 
 impl From<DeployAccountTransactionV3> for BroadcastedDeployAccountTransactionV3 {
@@ -40,6 +40,14 @@ impl InvokeTransactionV3 {
             account_deployment_data: broadcasted_txn.account_deployment_data.clone(),
         }
     }
+
+    pub fn get_signature(&self) -> &TransactionSignature {
+        &self.signature
+    }
+
+    pub fn get_resource_bounds(&self) -> &ResourceBoundsWrapper {
+        &self.resource_bounds
+    }
 }
 
 impl From<InvokeTransactionV3> for BroadcastedInvokeTransactionV3 {
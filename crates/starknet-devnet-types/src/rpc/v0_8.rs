@@ -0,0 +1,546 @@
+//! Types of JSON-RPC 0.8, converted from the types of JSON-RPC 0.7 which Devnet produces. Only the
+//! types whose shape differs between the two versions are defined here.
+
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use starknet_api::block::BlockStatus;
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_rs_core::types::{
+    ExecutionResult, Felt, Hash256, PriceUnit, SequencerTransactionStatus,
+    TransactionExecutionStatus, TransactionFinalityStatus,
+};
+
+use super::block::{self, ResourcePrice};
+use super::estimate_message_fee::FeeEstimateWrapper;
+use super::messaging::{MessageToL1, OrderedMessageToL1};
+use super::state::ThinStateDiff;
+use super::transaction_receipt::{self, FeeInUnits, MaybePendingProperties};
+use super::transactions::{self, CallType, Reversion, TransactionType};
+use crate::contract_address::ContractAddress;
+use crate::emitted_event::{Event, OrderedEvent};
+use crate::felt::{Calldata, EntryPointSelector, TransactionHash};
+
+/// Blockifier of this version has no market for L2 gas, all gas is charged as L1 gas
+fn l2_gas_price() -> ResourcePrice {
+    ResourcePrice { price_in_fri: Felt::ZERO, price_in_wei: Felt::ZERO }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockHeader {
+    #[serde(flatten)]
+    pub header: block::BlockHeader,
+    pub l2_gas_price: ResourcePrice,
+}
+
+impl From<block::BlockHeader> for BlockHeader {
+    fn from(header: block::BlockHeader) -> Self {
+        Self { header, l2_gas_price: l2_gas_price() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingBlockHeader {
+    #[serde(flatten)]
+    pub header: block::PendingBlockHeader,
+    pub l2_gas_price: ResourcePrice,
+}
+
+impl From<block::PendingBlockHeader> for PendingBlockHeader {
+    fn from(header: block::PendingBlockHeader) -> Self {
+        Self { header, l2_gas_price: l2_gas_price() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Block {
+    pub status: BlockStatus,
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    pub transactions: Transactions,
+}
+
+impl From<block::Block> for Block {
+    fn from(block: block::Block) -> Self {
+        Self {
+            status: block.status,
+            header: block.header.into(),
+            transactions: block.transactions.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingBlock {
+    #[serde(flatten)]
+    pub header: PendingBlockHeader,
+    pub transactions: Transactions,
+}
+
+impl From<block::PendingBlock> for PendingBlock {
+    fn from(block: block::PendingBlock) -> Self {
+        Self { header: block.header.into(), transactions: block.transactions.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Transactions {
+    Hashes(Vec<TransactionHash>),
+    Full(Vec<TransactionWithHash>),
+    FullWithReceipts(Vec<TransactionWithReceipt>),
+}
+
+impl From<transactions::Transactions> for Transactions {
+    fn from(transactions: transactions::Transactions) -> Self {
+        match transactions {
+            transactions::Transactions::Hashes(hashes) => Self::Hashes(hashes),
+            transactions::Transactions::Full(transactions) => {
+                Self::Full(transactions.into_iter().map(TransactionWithHash).collect())
+            }
+            transactions::Transactions::FullWithReceipts(transactions) => Self::FullWithReceipts(
+                transactions.into_iter().map(TransactionWithReceipt::from).collect(),
+            ),
+        }
+    }
+}
+
+/// Serializes the transaction so that the resource bounds of a V3 transaction include L1 data gas,
+/// which are zero if the transaction was submitted without them
+fn serialize_with_l1_data_gas_bounds<T, S>(
+    transaction: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let mut transaction = serde_json::to_value(transaction).map_err(serde::ser::Error::custom)?;
+    if let Some(resource_bounds) =
+        transaction.get_mut("resource_bounds").and_then(Value::as_object_mut)
+    {
+        resource_bounds
+            .entry("l1_data_gas")
+            .or_insert_with(|| json!({ "max_amount": "0x0", "max_price_per_unit": "0x0" }));
+    }
+
+    transaction.serialize(serializer)
+}
+
+#[derive(Debug, Clone)]
+pub struct TransactionWithHash(pub transactions::TransactionWithHash);
+
+impl Serialize for TransactionWithHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_with_l1_data_gas_bounds(&self.0, serializer)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Transaction(pub transactions::Transaction);
+
+impl Serialize for Transaction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_with_l1_data_gas_bounds(&self.0, serializer)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionWithReceipt {
+    pub receipt: TransactionReceipt,
+    pub transaction: Transaction,
+}
+
+impl From<transactions::TransactionWithReceipt> for TransactionWithReceipt {
+    fn from(transaction: transactions::TransactionWithReceipt) -> Self {
+        Self {
+            receipt: transaction.receipt.into(),
+            transaction: Transaction(transaction.transaction),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum TransactionReceipt {
+    Deploy(DeployTransactionReceipt),
+    L1Handler(L1HandlerTransactionReceipt),
+    Common(CommonTransactionReceipt),
+}
+
+impl From<transaction_receipt::TransactionReceipt> for TransactionReceipt {
+    fn from(receipt: transaction_receipt::TransactionReceipt) -> Self {
+        match receipt {
+            transaction_receipt::TransactionReceipt::Deploy(receipt) => {
+                Self::Deploy(DeployTransactionReceipt {
+                    common: receipt.common.into(),
+                    contract_address: receipt.contract_address,
+                })
+            }
+            transaction_receipt::TransactionReceipt::L1Handler(receipt) => {
+                Self::L1Handler(L1HandlerTransactionReceipt {
+                    common: receipt.common.into(),
+                    message_hash: receipt.message_hash,
+                })
+            }
+            transaction_receipt::TransactionReceipt::Common(receipt) => {
+                Self::Common(receipt.into())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployTransactionReceipt {
+    #[serde(flatten)]
+    pub common: CommonTransactionReceipt,
+    pub contract_address: ContractAddress,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct L1HandlerTransactionReceipt {
+    #[serde(flatten)]
+    pub common: CommonTransactionReceipt,
+    pub message_hash: Hash256,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommonTransactionReceipt {
+    pub r#type: TransactionType,
+    pub transaction_hash: TransactionHash,
+    pub actual_fee: FeeInUnits,
+    pub messages_sent: Vec<MessageToL1>,
+    pub events: Vec<Event>,
+    #[serde(flatten)]
+    pub execution_status: ExecutionResult,
+    pub finality_status: TransactionFinalityStatus,
+    #[serde(flatten)]
+    pub maybe_pending_properties: MaybePendingProperties,
+    pub execution_resources: ExecutionResources,
+}
+
+impl From<transaction_receipt::CommonTransactionReceipt> for CommonTransactionReceipt {
+    fn from(receipt: transaction_receipt::CommonTransactionReceipt) -> Self {
+        Self {
+            r#type: receipt.r#type,
+            transaction_hash: receipt.transaction_hash,
+            actual_fee: receipt.actual_fee,
+            messages_sent: receipt.messages_sent,
+            events: receipt.events,
+            execution_status: receipt.execution_status,
+            finality_status: receipt.finality_status,
+            maybe_pending_properties: receipt.maybe_pending_properties,
+            execution_resources: (&receipt.execution_resources).into(),
+        }
+    }
+}
+
+/// The gas consumed by a transaction. Blockifier of this version charges all gas as L1 gas, so no
+/// L2 gas is reported.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ExecutionResources {
+    pub l1_gas: u128,
+    pub l1_data_gas: u128,
+    pub l2_gas: u128,
+}
+
+impl From<&transaction_receipt::ExecutionResources> for ExecutionResources {
+    fn from(execution_resources: &transaction_receipt::ExecutionResources) -> Self {
+        Self {
+            l1_gas: execution_resources.total_gas_consumed.l1_gas,
+            l1_data_gas: execution_resources.total_gas_consumed.l1_data_gas,
+            l2_gas: 0,
+        }
+    }
+}
+
+/// The gas consumed by a single call. Blockifier of this version doesn't attribute gas to
+/// individual calls, so it is reported as zero.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct InnerCallExecutionResources {
+    pub l1_gas: u128,
+    pub l2_gas: u128,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionInvocation {
+    pub contract_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Calldata,
+    pub caller_address: ContractAddress,
+    pub class_hash: Felt,
+    pub entry_point_type: EntryPointType,
+    pub call_type: CallType,
+    pub result: Vec<Felt>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<OrderedEvent>,
+    pub messages: Vec<OrderedMessageToL1>,
+    pub execution_resources: InnerCallExecutionResources,
+    /// A failed call reverts the whole transaction in this version of Blockifier, so an invocation
+    /// in a trace is never reverted on its own
+    pub is_reverted: bool,
+}
+
+impl From<transactions::FunctionInvocation> for FunctionInvocation {
+    fn from(invocation: transactions::FunctionInvocation) -> Self {
+        Self {
+            contract_address: invocation.contract_address,
+            entry_point_selector: invocation.entry_point_selector,
+            calldata: invocation.calldata,
+            caller_address: invocation.caller_address,
+            class_hash: invocation.class_hash,
+            entry_point_type: invocation.entry_point_type,
+            call_type: invocation.call_type,
+            result: invocation.result,
+            calls: invocation.calls.into_iter().map(FunctionInvocation::from).collect(),
+            events: invocation.events,
+            messages: invocation.messages,
+            execution_resources: InnerCallExecutionResources::default(),
+            is_reverted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ExecutionInvocation {
+    Succeeded(FunctionInvocation),
+    Reverted(Reversion),
+}
+
+impl From<transactions::ExecutionInvocation> for ExecutionInvocation {
+    fn from(invocation: transactions::ExecutionInvocation) -> Self {
+        match invocation {
+            transactions::ExecutionInvocation::Succeeded(invocation) => {
+                Self::Succeeded(invocation.into())
+            }
+            transactions::ExecutionInvocation::Reverted(reversion) => Self::Reverted(reversion),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionTrace {
+    Invoke(InvokeTransactionTrace),
+    Declare(DeclareTransactionTrace),
+    DeployAccount(DeployAccountTransactionTrace),
+    L1Handler(L1HandlerTransactionTrace),
+}
+
+impl From<transactions::TransactionTrace> for TransactionTrace {
+    fn from(trace: transactions::TransactionTrace) -> Self {
+        match trace {
+            transactions::TransactionTrace::Invoke(trace) => Self::Invoke(InvokeTransactionTrace {
+                validate_invocation: trace.validate_invocation.map(Into::into),
+                execute_invocation: trace.execute_invocation.into(),
+                fee_transfer_invocation: trace.fee_transfer_invocation.map(Into::into),
+                state_diff: trace.state_diff,
+                execution_resources: (&trace.execution_resources).into(),
+            }),
+            transactions::TransactionTrace::Declare(trace) => {
+                Self::Declare(DeclareTransactionTrace {
+                    validate_invocation: trace.validate_invocation.map(Into::into),
+                    fee_transfer_invocation: trace.fee_transfer_invocation.map(Into::into),
+                    state_diff: trace.state_diff,
+                    execution_resources: (&trace.execution_resources).into(),
+                })
+            }
+            transactions::TransactionTrace::DeployAccount(trace) => {
+                Self::DeployAccount(DeployAccountTransactionTrace {
+                    validate_invocation: trace.validate_invocation.map(Into::into),
+                    constructor_invocation: trace.constructor_invocation.map(Into::into),
+                    fee_transfer_invocation: trace.fee_transfer_invocation.map(Into::into),
+                    state_diff: trace.state_diff,
+                    execution_resources: (&trace.execution_resources).into(),
+                })
+            }
+            transactions::TransactionTrace::L1Handler(trace) => {
+                Self::L1Handler(L1HandlerTransactionTrace {
+                    function_invocation: trace.function_invocation.into(),
+                    state_diff: trace.state_diff,
+                    execution_resources: (&trace.execution_resources).into(),
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvokeTransactionTrace {
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub execute_invocation: ExecutionInvocation,
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub state_diff: Option<ThinStateDiff>,
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeclareTransactionTrace {
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub state_diff: Option<ThinStateDiff>,
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployAccountTransactionTrace {
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub constructor_invocation: Option<FunctionInvocation>,
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub state_diff: Option<ThinStateDiff>,
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct L1HandlerTransactionTrace {
+    pub function_invocation: FunctionInvocation,
+    pub state_diff: Option<ThinStateDiff>,
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockTransactionTrace {
+    pub transaction_hash: Felt,
+    pub trace_root: TransactionTrace,
+}
+
+impl From<transactions::BlockTransactionTrace> for BlockTransactionTrace {
+    fn from(trace: transactions::BlockTransactionTrace) -> Self {
+        Self { transaction_hash: trace.transaction_hash, trace_root: trace.trace_root.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedTransaction {
+    pub transaction_trace: TransactionTrace,
+    pub fee_estimation: FeeEstimate,
+}
+
+impl From<transactions::SimulatedTransaction> for SimulatedTransaction {
+    fn from(simulation: transactions::SimulatedTransaction) -> Self {
+        Self {
+            transaction_trace: simulation.transaction_trace.into(),
+            fee_estimation: simulation.fee_estimation.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub l1_gas_consumed: Felt,
+    pub l1_gas_price: Felt,
+    pub l2_gas_consumed: Felt,
+    pub l2_gas_price: Felt,
+    pub l1_data_gas_consumed: Felt,
+    pub l1_data_gas_price: Felt,
+    pub overall_fee: Felt,
+    pub unit: PriceUnit,
+}
+
+impl From<FeeEstimateWrapper> for FeeEstimate {
+    fn from(fee_estimate: FeeEstimateWrapper) -> Self {
+        Self {
+            l1_gas_consumed: fee_estimate.gas_consumed,
+            l1_gas_price: fee_estimate.gas_price,
+            l2_gas_consumed: Felt::ZERO,
+            l2_gas_price: Felt::ZERO,
+            l1_data_gas_consumed: fee_estimate.data_gas_consumed,
+            l1_data_gas_price: fee_estimate.data_gas_price,
+            overall_fee: fee_estimate.overall_fee,
+            unit: fee_estimate.unit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TransactionStatus {
+    pub finality_status: SequencerTransactionStatus,
+    /// Absent if the transaction hasn't been executed yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+/// The status of an L1 handler transaction created by a message sent from L1
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MessageStatus {
+    pub transaction_hash: TransactionHash,
+    pub finality_status: TransactionFinalityStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use starknet_rs_core::types::{Felt, PriceUnit};
+
+    use super::FeeEstimate;
+    use crate::rpc::estimate_message_fee::FeeEstimateWrapper;
+    use crate::rpc::transactions::TransactionWithHash;
+
+    #[test]
+    fn fee_estimate_reports_l1_gas_and_no_l2_gas() {
+        let fee_estimate = FeeEstimate::from(FeeEstimateWrapper {
+            gas_consumed: Felt::from(10),
+            data_gas_consumed: Felt::from(2),
+            gas_price: Felt::from(3),
+            data_gas_price: Felt::from(4),
+            overall_fee: Felt::from(38),
+            unit: PriceUnit::Wei,
+        });
+
+        assert_eq!(
+            serde_json::to_value(fee_estimate).unwrap(),
+            json!({
+                "l1_gas_consumed": "0xa",
+                "l1_gas_price": "0x3",
+                "l2_gas_consumed": "0x0",
+                "l2_gas_price": "0x0",
+                "l1_data_gas_consumed": "0x2",
+                "l1_data_gas_price": "0x4",
+                "overall_fee": "0x26",
+                "unit": "WEI",
+            })
+        );
+    }
+
+    #[test]
+    fn v3_transaction_gets_zero_l1_data_gas_bounds_if_submitted_without_them() {
+        let transaction_json = json!({
+            "transaction_hash": "0x1",
+            "type": "INVOKE",
+            "version": "0x3",
+            "signature": [],
+            "nonce": "0x0",
+            "resource_bounds": {
+                "l1_gas": { "max_amount": "0x7c9", "max_price_per_unit": "0x1" },
+                "l2_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+            },
+            "tip": "0x0",
+            "paymaster_data": [],
+            "nonce_data_availability_mode": "L1",
+            "fee_data_availability_mode": "L1",
+            "sender_address": "0x2",
+            "calldata": [],
+            "account_deployment_data": [],
+        });
+        let transaction: TransactionWithHash =
+            serde_json::from_value(transaction_json.clone()).unwrap();
+
+        let mut expected_json = transaction_json;
+        expected_json["resource_bounds"]["l1_data_gas"] =
+            json!({ "max_amount": "0x0", "max_price_per_unit": "0x0" });
+        assert_eq!(
+            serde_json::to_value(super::TransactionWithHash(transaction)).unwrap(),
+            expected_json
+        );
+    }
+}
@@ -198,7 +198,7 @@ pub mod dec_string {
 
     use bigdecimal::BigDecimal;
     use num_bigint::BigUint;
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
 
     pub fn deserialize_biguint<'de, D>(deserializer: D) -> Result<BigUint, D::Error>
     where
@@ -213,12 +213,22 @@ pub mod dec_string {
         BigUint::from_str(&big_decimal.with_scale(0).to_string()).map_err(serde::de::Error::custom)
     }
 
+    /// Serializes as a JSON number, regardless of size
+    pub fn serialize_biguint<S>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde_json::Number::from_str(&value.to_string())
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+
     #[cfg(test)]
     mod tests {
         use num_bigint::BigUint;
-        use serde::Deserialize;
+        use serde::{Deserialize, Serialize};
 
-        use crate::serde_helpers::dec_string::deserialize_biguint;
+        use crate::serde_helpers::dec_string::{deserialize_biguint, serialize_biguint};
 
         #[test]
         fn deserialization_biguint() {
@@ -249,6 +259,21 @@ pub mod dec_string {
                 }
             }
         }
+
+        #[test]
+        fn serialization_biguint() {
+            #[derive(Serialize)]
+            struct TestSerializationStruct {
+                #[serde(serialize_with = "serialize_biguint")]
+                value: BigUint,
+            }
+
+            let value = BigUint::from(10_u8).pow(30) + BigUint::from(1_u8);
+            assert_eq!(
+                serde_json::to_string(&TestSerializationStruct { value }).unwrap(),
+                r#"{"value":1000000000000000000000000000001}"#
+            );
+        }
     }
 }
 
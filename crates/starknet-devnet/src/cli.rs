@@ -1,5 +1,6 @@
 use std::collections::HashSet;
-use std::num::NonZeroU128;
+use std::num::{NonZeroU128, NonZeroU64};
+use std::path::Path;
 
 use clap::Parser;
 use server::api::json_rpc::JsonRpcRequest;
@@ -12,8 +13,11 @@ use starknet_core::constants::{
 };
 use starknet_core::contract_class_choice::{AccountClassWrapper, AccountContractClassChoice};
 use starknet_core::random_number_generator::generate_u32_random_number;
+use starknet_core::starknet::genesis::Genesis;
 use starknet_core::starknet::starknet_config::{
-    BlockGenerationOn, DumpOn, ForkConfig, StarknetConfig, StateArchiveCapacity,
+    load_block_capacity, load_gas_price_schedule, load_versioned_constants, BlockGenerationOn,
+    DumpFormat, DumpOn, ForkConfig, FullBlockPolicy, L1DaMode, MempoolOrdering, StarknetConfig,
+    StarknetVersion, StateArchiveCapacity,
 };
 use starknet_types::chain_id::ChainId;
 use tracing_subscriber::EnvFilter;
@@ -135,6 +139,24 @@ pub(crate) struct Args {
     #[arg(help = "Specify the gas price in fri per data gas unit;")]
     data_gas_price_fri: NonZeroU128,
 
+    #[arg(long = "l1-da-mode")]
+    #[arg(env = "L1_DA_MODE")]
+    #[arg(value_name = "MODE")]
+    #[arg(default_value = "blob")]
+    #[arg(help = "Specify where the state diffs of blocks are published on L1; data \
+                  availability is charged in L1 data gas with \"blob\" and in L1 gas with \
+                  \"calldata\";")]
+    l1_da_mode: L1DaMode,
+
+    #[arg(long = "gas-price-schedule-file")]
+    #[arg(env = "GAS_PRICE_SCHEDULE_FILE")]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Specify the path of a JSON file with a schedule of the gas prices of the \
+                  blocks after the genesis block: a series of prices, a random walk or an \
+                  oscillation;")]
+    #[arg(conflicts_with = "fork_replay_gas_prices")]
+    gas_price_schedule_path: Option<String>,
+
     #[arg(long = "chain-id")]
     #[arg(env = "CHAIN_ID")]
     #[arg(value_name = "CHAIN_ID")]
@@ -156,6 +178,13 @@ pub(crate) struct Args {
     #[arg(help = "Specify whether to run in lite mode and skip block hash calculation;")]
     lite_mode: bool,
 
+    #[arg(long = "calculate-commitments")]
+    #[arg(env = "CALCULATE_COMMITMENTS")]
+    #[arg(help = "Specify whether to calculate state roots and transaction and event \
+                  commitments of blocks; ignored in lite mode; not supported in forking mode;")]
+    #[arg(conflicts_with = "fork_network")]
+    calculate_commitments: bool,
+
     // Dump path as string
     #[arg(long = "dump-path")]
     #[arg(env = "DUMP_PATH")]
@@ -164,6 +193,21 @@ pub(crate) struct Args {
     #[arg(required_if_eq_any([("dump_on", "exit"), ("dump_on", "block")]))]
     dump_path: Option<String>,
 
+    #[arg(long = "dump-format")]
+    #[arg(env = "DUMP_FORMAT")]
+    #[arg(value_name = "FORMAT")]
+    #[arg(default_value = "events")]
+    #[arg(help = "Specify the format used for dumping and loading;")]
+    dump_format: DumpFormat,
+
+    #[arg(long = "db-path")]
+    #[arg(env = "DB_PATH")]
+    #[arg(value_name = "DIR")]
+    #[arg(help = "Specify the directory in which blocks, transactions and archived states are \
+                  stored; if it contains data of an earlier run, Devnet continues from it;")]
+    #[arg(conflicts_with_all = ["dump_on", "dump_path"])]
+    db_path: Option<String>,
+
     #[arg(long = "block-generation-on")]
     #[arg(env = "BLOCK_GENERATION_ON")]
     #[arg(default_value = "transaction")]
@@ -175,6 +219,39 @@ pub(crate) struct Args {
 Sending POST /create_block is also an option in modes other than \"demand\".")]
     block_generation_on: BlockGenerationOn,
 
+    #[arg(long = "mempool")]
+    #[arg(env = "MEMPOOL")]
+    #[arg(value_name = "ORDERING")]
+    #[arg(help = "Hold received transactions in a mempool until a block is generated, instead \
+                  of executing them immediately; transactions of a sender wait for missing \
+                  nonces and ready transactions are executed in the specified order;")]
+    mempool: Option<MempoolOrdering>,
+
+    #[arg(long = "block-capacity-file")]
+    #[arg(env = "BLOCK_CAPACITY_FILE")]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Specify the path of a JSON file with the maximum weights of the transactions \
+                  in a block, e.g. n_steps, n_events or state_diff_size; weights not specified \
+                  default to the limits of Starknet; if not provided, blocks are unlimited;")]
+    block_capacity_path: Option<String>,
+
+    #[arg(long = "full-block-policy")]
+    #[arg(env = "FULL_BLOCK_POLICY")]
+    #[arg(value_name = "POLICY")]
+    #[arg(default_value = "seal")]
+    #[arg(requires_if("queue", "mempool"))]
+    #[arg(help = "Specify what happens to a transaction which doesn't fit into the pending \
+                  block: the block is sealed and the transaction executed in the next one \
+                  (\"seal\"), the transaction is rejected (\"reject\") or it's left in the \
+                  mempool until the next block (\"queue\", requires --mempool);")]
+    full_block_policy: FullBlockPolicy,
+
+    #[arg(long = "accept-on-l1-on-flush")]
+    #[arg(env = "ACCEPT_ON_L1_ON_FLUSH")]
+    #[arg(help = "Specify whether flushing the postman marks all blocks, and the transactions \
+                  in them, as accepted on L1;")]
+    accept_on_l1_on_flush: bool,
+
     #[arg(long = "state-archive-capacity")]
     #[arg(env = "STATE_ARCHIVE_CAPACITY")]
     #[arg(value_name = "STATE_ARCHIVE_CAPACITY")]
@@ -195,6 +272,44 @@ Sending POST /create_block is also an option in modes other than \"demand\".")]
     #[arg(requires = "fork_network")]
     fork_block: Option<u64>,
 
+    #[arg(long = "fork-cache-dir")]
+    #[arg(env = "FORK_CACHE_DIR")]
+    #[arg(value_name = "DIR")]
+    #[arg(help = "Specify the directory in which responses of the forking origin are cached; if \
+                  the fork block is specified, a warmed-up cache allows forking offline;")]
+    #[arg(requires = "fork_network")]
+    fork_cache_dir: Option<String>,
+
+    #[arg(long = "fork-replay-gas-prices")]
+    #[arg(env = "FORK_REPLAY_GAS_PRICES")]
+    #[arg(value_name = "BLOCKS")]
+    #[arg(help = "Specify the number of origin blocks, up to and including the fork block, \
+                  whose gas prices are taken by new blocks, one block after another;")]
+    #[arg(requires = "fork_network")]
+    fork_replay_gas_prices: Option<NonZeroU64>,
+
+    #[arg(long = "genesis")]
+    #[arg(env = "GENESIS")]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Specify the path of a JSON or YAML file with classes, contracts and balances \
+                  to be present in the genesis block;")]
+    genesis_path: Option<String>,
+
+    #[arg(long = "starknet-version")]
+    #[arg(env = "STARKNET_VERSION")]
+    #[arg(value_name = "VERSION")]
+    #[arg(default_value_t = StarknetVersion::default())]
+    #[arg(help = "Specify the Starknet version reported in blocks; transactions are executed \
+                  with the versioned constants (e.g. fee costs and step limits) of this version;")]
+    starknet_version: StarknetVersion,
+
+    #[arg(long = "versioned-constants-file")]
+    #[arg(env = "VERSIONED_CONSTANTS_FILE")]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Specify the path of a JSON file with custom versioned constants, used instead \
+                  of the constants of the Starknet version;")]
+    versioned_constants_path: Option<String>,
+
     #[arg(long = "request-body-size-limit")]
     #[arg(env = "REQUEST_BODY_SIZE_LIMIT")]
     #[arg(value_name = "BYTES")]
@@ -219,7 +334,7 @@ impl Args {
             None => self.account_class_choice.get_class_wrapper()?,
         };
 
-        let starknet_config = StarknetConfig {
+        let mut starknet_config = StarknetConfig {
             seed: match self.seed {
                 Some(seed) => seed,
                 None => generate_u32_random_number(),
@@ -233,18 +348,49 @@ impl Args {
             gas_price_fri: self.gas_price_fri,
             data_gas_price_wei: self.data_gas_price_wei,
             data_gas_price_fri: self.data_gas_price_fri,
+            l1_da_mode: self.l1_da_mode,
+            gas_price_schedule_path: self.gas_price_schedule_path.clone(),
+            gas_price_schedule: self
+                .gas_price_schedule_path
+                .as_ref()
+                .map(|path| load_gas_price_schedule(Path::new(path)))
+                .transpose()?,
             chain_id: self.chain_id,
             dump_on: self.dump_on,
             dump_path: self.dump_path.clone(),
+            dump_format: self.dump_format,
+            db_path: self.db_path.clone(),
             block_generation_on: self.block_generation_on,
+            mempool: self.mempool,
+            block_capacity_path: self.block_capacity_path.clone(),
+            full_block_policy: self.full_block_policy,
+            accept_on_l1_on_flush: self.accept_on_l1_on_flush,
             lite_mode: self.lite_mode,
+            calculate_commitments: self.calculate_commitments,
             state_archive: self.state_archive,
             fork_config: ForkConfig {
                 url: self.fork_network.clone(),
                 block_number: self.fork_block,
+                cache_dir: self.fork_cache_dir.clone(),
+                replay_gas_prices: self.fork_replay_gas_prices,
+            },
+            genesis_path: self.genesis_path.clone(),
+            genesis: self
+                .genesis_path
+                .as_ref()
+                .map(|path| Genesis::load(Path::new(path)))
+                .transpose()?,
+            starknet_version: self.starknet_version,
+            versioned_constants_path: self.versioned_constants_path.clone(),
+            versioned_constants: match &self.versioned_constants_path {
+                Some(path) => load_versioned_constants(Path::new(path))?,
+                None => self.starknet_version.versioned_constants(),
             },
             ..Default::default()
         };
+        if let Some(path) = &self.block_capacity_path {
+            starknet_config.block_capacity = Some(load_block_capacity(Path::new(path))?);
+        }
 
         let RequestResponseLogging { log_request, log_response } =
             RequestResponseLogging::from_rust_log_environment_variable();
@@ -321,7 +467,7 @@ mod tests {
         CAIRO_0_ERC20_CONTRACT_PATH, CAIRO_1_ACCOUNT_CONTRACT_SIERRA_PATH,
     };
     use starknet_core::starknet::starknet_config::{
-        BlockGenerationOn, DumpOn, StateArchiveCapacity,
+        BlockGenerationOn, DumpOn, StarknetVersion, StateArchiveCapacity,
     };
     use tracing_subscriber::EnvFilter;
 
@@ -489,6 +635,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn not_allowing_queue_policy_without_mempool() {
+        match Args::try_parse_from(["--", "--full-block-policy", "queue"]) {
+            Err(_) => (),
+            Ok(parsed) => panic!("Should fail when just --full-block-policy queue got: {parsed:?}"),
+        }
+
+        Args::try_parse_from(["--", "--full-block-policy", "queue", "--mempool", "fifo"]).unwrap();
+    }
+
+    #[test]
+    fn not_allowing_calculating_commitments_in_forking_mode() {
+        match Args::try_parse_from([
+            "--",
+            "--calculate-commitments",
+            "--fork-network",
+            "http://localhost:5051",
+        ]) {
+            Err(_) => (),
+            Ok(parsed) => {
+                panic!("Should fail when forking with --calculate-commitments got: {parsed:?}")
+            }
+        }
+    }
+
+    #[test]
+    fn not_allowing_fork_cache_dir_without_fork_network() {
+        match Args::try_parse_from(["--", "--fork-cache-dir", "cache"]) {
+            Err(_) => (),
+            Ok(parsed) => panic!("Should fail when just --fork-cache-dir got: {parsed:?}"),
+        }
+    }
+
+    #[test]
+    fn not_allowing_fork_replay_gas_prices_without_fork_network() {
+        match Args::try_parse_from(["--", "--fork-replay-gas-prices", "3"]) {
+            Err(_) => (),
+            Ok(parsed) => panic!("Should fail when just --fork-replay-gas-prices got: {parsed:?}"),
+        }
+    }
+
+    #[test]
+    fn not_allowing_gas_price_schedule_with_fork_replay_gas_prices() {
+        match Args::try_parse_from([
+            "--",
+            "--fork-network",
+            "http://localhost:5051",
+            "--fork-replay-gas-prices",
+            "3",
+            "--gas-price-schedule-file",
+            "schedule.json",
+        ]) {
+            Err(_) => (),
+            Ok(parsed) => {
+                panic!("Should fail when both gas price sources are set; got: {parsed:?}")
+            }
+        }
+    }
+
     #[test]
     fn not_allowing_invalid_value_as_fork_block() {
         for number in ["", "abc", "-1"] {
@@ -577,11 +782,17 @@ mod tests {
             ("--data-gas-price-fri", "DATA_GAS_PRICE_FRI", "4"),
             ("--dump-on", "DUMP_ON", "exit"),
             ("--dump-path", "DUMP_PATH", "dummy-path"),
+            ("--dump-format", "DUMP_FORMAT", "state"),
             ("--state-archive-capacity", "STATE_ARCHIVE_CAPACITY", "full"),
             ("--fork-network", "FORK_NETWORK", "http://dummy.com"),
             ("--fork-block", "FORK_BLOCK", "42"),
+            ("--fork-cache-dir", "FORK_CACHE_DIR", "cache"),
             ("--request-body-size-limit", "REQUEST_BODY_SIZE_LIMIT", "100"),
             ("--block-generation-on", "BLOCK_GENERATION_ON", "demand"),
+            ("--mempool", "MEMPOOL", "fee_priority"),
+            ("--starknet-version", "STARKNET_VERSION", "0.13.1"),
+            ("--l1-da-mode", "L1_DA_MODE", "calldata"),
+            ("--full-block-policy", "FULL_BLOCK_POLICY", "reject"),
         ];
 
         let mut cli_args = vec!["--"];
@@ -611,7 +822,11 @@ mod tests {
     #[test]
     #[serial_test::serial]
     fn test_boolean_param_specification_via_env_vars() {
-        let config_source = [("--lite-mode", "LITE_MODE")];
+        let config_source = [
+            ("--lite-mode", "LITE_MODE"),
+            ("--calculate-commitments", "CALCULATE_COMMITMENTS"),
+            ("--accept-on-l1-on-flush", "ACCEPT_ON_L1_ON_FLUSH"),
+        ];
 
         let mut cli_args = vec!["--"];
         for (cli_param, _) in config_source {
@@ -635,7 +850,7 @@ mod tests {
         assert_eq!(config_via_cli, config_via_env);
 
         // remove var to avoid collision with other tests
-        for (var_name, _) in config_source {
+        for (_, var_name) in config_source {
             std::env::remove_var(var_name);
         }
     }
@@ -685,6 +900,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn not_allowing_db_path_and_dump_on() {
+        match Args::try_parse_from(["--", "--db-path", "devnet_db", "--dump-on", "request"]) {
+            Err(err) => assert_eq!(
+                get_first_line(&err.to_string()),
+                "error: the argument '--db-path <DIR>' cannot be used with '--dump-on <EVENT>'"
+            ),
+            Ok(parsed) => panic!("Should have failed; got: {parsed:?}"),
+        }
+    }
+
     #[test]
     fn invalid_dump_path_not_allowed() {
         match Args::try_parse_from(["--", "--dump-path", "dump_wrong_cli_mode", "--dump-on", "e"]) {
@@ -696,6 +922,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn not_allowing_unknown_starknet_version() {
+        match Args::try_parse_from(["--", "--starknet-version", "0.12.3"]) {
+            Ok(args) => panic!("Should have failed; got: {args:?}"),
+            Err(e) => assert_eq!(
+                get_first_line(&e.to_string()),
+                "error: invalid value '0.12.3' for '--starknet-version <VERSION>'"
+            ),
+        }
+    }
+
+    #[test]
+    fn versioned_constants_loaded_from_file() {
+        let args = Args::parse_from(["--", "--starknet-version", "0.13.0"]);
+        let (starknet_config, _) = args.to_config().unwrap();
+        assert_eq!(starknet_config.starknet_version, StarknetVersion::V0_13_0);
+
+        let args = Args::parse_from(["--", "--versioned-constants-file", "nonexistent.json"]);
+        match args.to_config() {
+            Err(e) => assert!(e.to_string().contains("Invalid versioned constants file")),
+            Ok(_) => panic!("Should have failed"),
+        }
+    }
+
+    #[test]
+    fn block_capacity_unlimited_by_default() {
+        let (starknet_config, _) = Args::parse_from(["--"]).to_config().unwrap();
+        assert!(starknet_config.block_capacity.is_none());
+    }
+
+    #[test]
+    fn block_capacity_loaded_from_file() {
+        let args = Args::parse_from(["--", "--block-capacity-file", "nonexistent.json"]);
+        match args.to_config() {
+            Err(e) => assert!(e.to_string().contains("nonexistent.json"), "{e}"),
+            Ok(_) => panic!("Should have failed"),
+        }
+    }
+
+    #[test]
+    fn gas_price_schedule_loaded_from_file() {
+        let args = Args::parse_from(["--", "--gas-price-schedule-file", "nonexistent.json"]);
+        match args.to_config() {
+            Err(e) => assert!(e.to_string().contains("nonexistent.json"), "{e}"),
+            Ok(_) => panic!("Should have failed"),
+        }
+    }
+
     #[test]
     fn check_if_method_with_incorrect_name_will_produce_an_error() {
         let args = Args::parse_from(["--", "--restrictive-mode", "devnet_dump", "devnet_loadd"]);
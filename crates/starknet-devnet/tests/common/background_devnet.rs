@@ -9,6 +9,7 @@ use reqwest::{Client, StatusCode};
 use serde_json::json;
 use server::rpc_core::error::{ErrorCode, RpcError};
 use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+use starknet_devnet_client::DevnetClient;
 use starknet_rs_core::types::{
     BlockId, BlockTag, BlockWithTxHashes, BlockWithTxs, Felt, FunctionCall,
     MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, PendingBlockWithTxHashes,
@@ -20,12 +21,14 @@ use starknet_rs_providers::{JsonRpcClient, Provider};
 use starknet_rs_signers::{LocalWallet, SigningKey};
 use starknet_types::felt::felt_from_prefixed_hex;
 use starknet_types::rpc::transaction_receipt::FeeUnit;
+use tokio::net::TcpStream;
 use tokio::sync::Mutex;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use url::Url;
 
 use super::constants::{
     ACCOUNTS, HEALTHCHECK_PATH, HOST, MAX_PORT, MIN_PORT, PREDEPLOYED_ACCOUNT_INITIAL_BALANCE,
-    RPC_PATH, SEED,
+    RPC_PATH, SEED, WS_PATH,
 };
 use super::errors::TestError;
 use super::reqwest_client::{PostReqwestSender, ReqwestClient};
@@ -40,10 +43,13 @@ lazy_static! {
     static ref BACKGROUND_DEVNET_MUTEX: Mutex<()> = Mutex::new(());
 }
 
+pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
 #[derive(Debug)]
 pub struct BackgroundDevnet {
     reqwest_client: ReqwestClient,
     pub json_rpc_client: JsonRpcClient<HttpTransport>,
+    pub devnet_client: DevnetClient,
     pub process: Child,
     pub port: u16,
     pub url: String,
@@ -145,6 +151,7 @@ impl BackgroundDevnet {
                 return Ok(BackgroundDevnet {
                     reqwest_client: ReqwestClient::new(devnet_url.clone(), reqwest_client),
                     json_rpc_client,
+                    devnet_client: DevnetClient::new(Url::parse(&devnet_url)?),
                     process,
                     port: free_port,
                     url: devnet_url,
@@ -164,6 +171,16 @@ impl BackgroundDevnet {
         &self,
         method: &str,
         params: serde_json::Value,
+    ) -> Result<serde_json::Value, RpcError> {
+        self.send_custom_rpc_at_path(RPC_PATH, method, params).await
+    }
+
+    /// Sends a JSON-RPC request to `path`, which determines the served version of the spec
+    pub async fn send_custom_rpc_at_path(
+        &self,
+        path: &str,
+        method: &str,
+        params: serde_json::Value,
     ) -> Result<serde_json::Value, RpcError> {
         let body_json = if params.is_null() {
             json!({
@@ -181,7 +198,7 @@ impl BackgroundDevnet {
         };
 
         let json_rpc_result: serde_json::Value =
-            self.reqwest_client().post_json_async(RPC_PATH, body_json).await.map_err(|err| {
+            self.reqwest_client().post_json_async(path, body_json).await.map_err(|err| {
                 RpcError {
                     code: ErrorCode::ServerError(err.status().as_u16().into()),
                     message: err.error_message().into(),
@@ -198,6 +215,13 @@ impl BackgroundDevnet {
         }
     }
 
+    /// Connects to the WebSocket endpoint of this devnet
+    pub async fn connect_ws(&self) -> Result<WsStream, anyhow::Error> {
+        let ws_url = format!("ws://{HOST}:{}{WS_PATH}", self.port);
+        let (ws_stream, _) = tokio_tungstenite::connect_async(ws_url).await?;
+        Ok(ws_stream)
+    }
+
     pub fn clone_provider(&self) -> JsonRpcClient<HttpTransport> {
         JsonRpcClient::new(HttpTransport::new(self.rpc_url.clone()))
     }
@@ -305,7 +329,7 @@ impl BackgroundDevnet {
     }
 
     pub async fn restart(&self) {
-        self.send_custom_rpc("devnet_restart", json!({})).await.unwrap();
+        self.devnet_client.restart(None).await.unwrap();
     }
 
     pub async fn fork(&self) -> Result<Self, TestError> {
@@ -327,11 +351,7 @@ impl BackgroundDevnet {
 
     /// Mines a new block and returns its hash
     pub async fn create_block(&self) -> Result<Felt, anyhow::Error> {
-        let block_creation_resp_body: serde_json::Value =
-            self.send_custom_rpc("devnet_createBlock", json!({})).await.unwrap();
-
-        let block_hash_str = block_creation_resp_body["block_hash"].as_str().unwrap();
-        Ok(felt_from_prefixed_hex(block_hash_str)?)
+        Ok(self.devnet_client.create_block().await?)
     }
 
     pub async fn get_latest_block_with_tx_hashes(
@@ -11,6 +11,8 @@ pub const CHAIN_ID_CLI_PARAM: &str = "TESTNET";
 
 // URL paths
 pub const RPC_PATH: &str = "/rpc";
+pub const RPC_V0_8_PATH: &str = "/rpc/v0_8";
+pub const WS_PATH: &str = "/ws";
 pub const HEALTHCHECK_PATH: &str = "/is_alive";
 
 // predeployed account info with seed=42
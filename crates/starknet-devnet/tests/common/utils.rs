@@ -335,26 +335,23 @@ pub fn felt_to_u256(f: Felt) -> U256 {
 
 #[cfg(test)]
 mod test_unique_auto_deletable_file {
-    use std::path::Path;
-
-    use crate::common::utils::UniqueAutoDeletableFile;
-
+    // not imported via `use` to avoid unused imports when included in benches
     #[test]
     fn test_deleted() {
-        let file = UniqueAutoDeletableFile::new("foo");
+        let file = super::UniqueAutoDeletableFile::new("foo");
         let saved_file_path = file.path.clone();
-        assert!(!Path::new(&file.path).exists());
+        assert!(!std::path::Path::new(&file.path).exists());
 
         std::fs::File::create(&file.path).unwrap();
-        assert!(Path::new(&file.path).exists());
+        assert!(std::path::Path::new(&file.path).exists());
 
         drop(file);
-        assert!(!Path::new(&saved_file_path).exists());
+        assert!(!std::path::Path::new(&saved_file_path).exists());
     }
 
     #[test]
     fn test_dropping_successful_if_file_not_created() {
-        let file = UniqueAutoDeletableFile::new("foo");
+        let file = super::UniqueAutoDeletableFile::new("foo");
         drop(file);
         // if everything ok, the test should just exit successfully
     }
@@ -364,8 +361,8 @@ mod test_unique_auto_deletable_file {
         let common_prefix = "foo";
         // run it many times to increase the probability of being secure
         for _ in 0..1_000_000 {
-            let file1 = UniqueAutoDeletableFile::new(common_prefix);
-            let file2 = UniqueAutoDeletableFile::new(common_prefix);
+            let file1 = super::UniqueAutoDeletableFile::new(common_prefix);
+            let file2 = super::UniqueAutoDeletableFile::new(common_prefix);
             assert_ne!(file1.path, file2.path);
         }
     }
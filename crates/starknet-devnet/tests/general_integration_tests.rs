@@ -9,7 +9,7 @@ mod general_integration_tests {
     use starknet_core::constants::{
         CAIRO_1_ERC20_CONTRACT_CLASS_HASH, ETH_ERC20_CONTRACT_ADDRESS, STRK_ERC20_CONTRACT_ADDRESS,
     };
-    use starknet_rs_core::types::{BlockId, BlockTag};
+    use starknet_rs_core::types::{BlockId, BlockTag, MaybePendingBlockWithTxHashes};
     use starknet_rs_core::utils::{get_storage_var_address, parse_cairo_short_string};
     use starknet_rs_providers::Provider;
 
@@ -102,13 +102,19 @@ mod general_integration_tests {
             "gas_price_fri": 7,
             "data_gas_price_wei": 6,
             "data_gas_price_fri": 8,
+            "l1_da_mode": "calldata",
+            "gas_price_schedule_path": null,
             "chain_id": "SN_MAIN",
             "dump_on": "exit",
             "dump_path": dump_file.path,
+            "dump_format": "state",
+            "db_path": null,
             "state_archive": "full",
             "fork_config": {
                 "url": null,
                 "block_number": null,
+                "cache_dir": null,
+                "replay_gas_prices": null,
             },
             "server_config": {
                 "host": "0.0.0.0",
@@ -118,9 +124,17 @@ mod general_integration_tests {
                 "restricted_methods": null,
             },
             "block_generation_on": "demand",
+            "mempool": "fifo",
+            "block_capacity_path": null,
+            "full_block_policy": "queue",
+            "accept_on_l1_on_flush": true,
             "lite_mode": false,
+            "calculate_commitments": true,
             "eth_erc20_class_hash": to_hex_felt(&CAIRO_1_ERC20_CONTRACT_CLASS_HASH),
             "strk_erc20_class_hash": to_hex_felt(&CAIRO_1_ERC20_CONTRACT_CLASS_HASH),
+            "genesis_path": null,
+            "starknet_version": "0.13.1",
+            "versioned_constants_path": null,
         });
 
         let devnet = BackgroundDevnet::spawn_with_additional_args(&[
@@ -140,14 +154,26 @@ mod general_integration_tests {
             &serde_json::to_string(&expected_config["data_gas_price_wei"]).unwrap(),
             "--data-gas-price-fri",
             &serde_json::to_string(&expected_config["data_gas_price_fri"]).unwrap(),
+            "--l1-da-mode",
+            &expected_config["l1_da_mode"].as_str().unwrap(),
             "--chain-id",
             "MAINNET",
             "--dump-on",
             &expected_config["dump_on"].as_str().unwrap(),
             "--dump-path",
             &expected_config["dump_path"].as_str().unwrap(),
+            "--dump-format",
+            &expected_config["dump_format"].as_str().unwrap(),
             "--block-generation-on",
             "demand",
+            "--mempool",
+            &expected_config["mempool"].as_str().unwrap(),
+            "--full-block-policy",
+            &expected_config["full_block_policy"].as_str().unwrap(),
+            "--accept-on-l1-on-flush",
+            "--calculate-commitments",
+            "--starknet-version",
+            &expected_config["starknet_version"].as_str().unwrap(),
             "--state-archive-capacity",
             &expected_config["state_archive"].as_str().unwrap(),
             "--host",
@@ -167,6 +193,27 @@ mod general_integration_tests {
         assert_eq!(fetched_config, expected_config);
     }
 
+    #[tokio::test]
+    async fn starknet_version_reported_in_blocks() {
+        let devnet =
+            BackgroundDevnet::spawn_with_additional_args(&["--starknet-version", "0.13.1"])
+                .await
+                .unwrap();
+
+        for block_tag in [BlockTag::Latest, BlockTag::Pending] {
+            let starknet_version = match devnet
+                .json_rpc_client
+                .get_block_with_tx_hashes(BlockId::Tag(block_tag))
+                .await
+                .unwrap()
+            {
+                MaybePendingBlockWithTxHashes::Block(block) => block.starknet_version,
+                MaybePendingBlockWithTxHashes::PendingBlock(block) => block.starknet_version,
+            };
+            assert_eq!(starknet_version, "0.13.1");
+        }
+    }
+
     #[tokio::test]
     async fn predeployed_erc20_tokens_have_expected_storage() {
         let devnet = BackgroundDevnet::spawn().await.unwrap();
@@ -0,0 +1,161 @@
+#![cfg(test)]
+pub mod common;
+
+mod block_capacity_tests {
+    use std::io::Write;
+
+    use server::test_utils::assert_contains;
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_rs_accounts::{Account, AccountError, ExecutionEncoding, SingleOwnerAccount};
+    use starknet_rs_core::types::{BlockId, BlockTag, Call, Felt};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_rs_providers::jsonrpc::HttpTransport;
+    use starknet_rs_providers::{JsonRpcClient, Provider, ProviderError};
+    use starknet_rs_signers::LocalWallet;
+    use tempfile::NamedTempFile;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants;
+    use crate::common::utils::assert_tx_successful;
+
+    type DevnetAccount = SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>;
+
+    /// Spawns Devnet whose blocks fit a single event, i.e. a single transfer.
+    async fn spawn_devnet_with_capacity_of_one_transfer(
+        additional_args: &[&str],
+    ) -> (BackgroundDevnet, NamedTempFile) {
+        let mut capacity_file = NamedTempFile::new().unwrap();
+        capacity_file.write_all(br#"{ "n_events": 1 }"#).unwrap();
+
+        let capacity_path = capacity_file.path().to_str().unwrap();
+        let mut args =
+            vec!["--block-generation-on", "demand", "--block-capacity-file", capacity_path];
+        args.extend_from_slice(additional_args);
+
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&args).await.unwrap();
+        (devnet, capacity_file)
+    }
+
+    async fn get_account(devnet: &BackgroundDevnet) -> DevnetAccount {
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        )
+    }
+
+    /// Sends a transfer of 1 WEI.
+    async fn send_transfer(
+        account: &DevnetAccount,
+        nonce: u32,
+    ) -> Result<Felt, AccountError<<DevnetAccount as Account>::SignError>> {
+        let transfer = Call {
+            to: ETH_ERC20_CONTRACT_ADDRESS,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+        };
+        account
+            .execute_v1(vec![transfer])
+            .max_fee(Felt::from(1e18 as u128))
+            .nonce(Felt::from(nonce))
+            .send()
+            .await
+            .map(|result| result.transaction_hash)
+    }
+
+    #[tokio::test]
+    async fn full_block_sealed_on_next_transaction() {
+        let (devnet, _capacity_file) = spawn_devnet_with_capacity_of_one_transfer(&[]).await;
+        let account = get_account(&devnet).await;
+
+        let first_hash = send_transfer(&account, 0).await.unwrap();
+        let second_hash = send_transfer(&account, 1).await.unwrap();
+        assert_tx_successful(&second_hash, &devnet.json_rpc_client).await;
+
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.block_number, 1);
+        assert_eq!(latest_block.transactions, vec![first_hash]);
+
+        let pending_block = devnet.get_pending_block_with_tx_hashes().await.unwrap();
+        assert_eq!(pending_block.transactions, vec![second_hash]);
+    }
+
+    #[tokio::test]
+    async fn transaction_not_fitting_into_full_block_rejected() {
+        let (devnet, _capacity_file) =
+            spawn_devnet_with_capacity_of_one_transfer(&["--full-block-policy", "reject"]).await;
+        let account = get_account(&devnet).await;
+
+        let first_hash = send_transfer(&account, 0).await.unwrap();
+        match send_transfer(&account, 1).await {
+            Err(AccountError::Provider(ProviderError::Other(e))) => {
+                assert_contains(&e.to_string(), "no room left")
+            }
+            unexpected => panic!("Got unexpected resp: {unexpected:?}"),
+        }
+
+        let pending_block = devnet.get_pending_block_with_tx_hashes().await.unwrap();
+        assert_eq!(pending_block.transactions, vec![first_hash]);
+        let nonce = devnet
+            .json_rpc_client
+            .get_nonce(BlockId::Tag(BlockTag::Pending), account.address())
+            .await
+            .unwrap();
+        assert_eq!(nonce, Felt::ONE);
+
+        // the transaction fits into the next block
+        devnet.create_block().await.unwrap();
+        let second_hash = send_transfer(&account, 1).await.unwrap();
+        assert_tx_successful(&second_hash, &devnet.json_rpc_client).await;
+    }
+
+    #[tokio::test]
+    async fn transactions_not_fitting_into_full_block_queued_in_mempool() {
+        let (devnet, _capacity_file) = spawn_devnet_with_capacity_of_one_transfer(&[
+            "--mempool",
+            "fifo",
+            "--full-block-policy",
+            "queue",
+        ])
+        .await;
+        let account = get_account(&devnet).await;
+
+        let first_hash = send_transfer(&account, 0).await.unwrap();
+        let second_hash = send_transfer(&account, 1).await.unwrap();
+
+        devnet.create_block().await.unwrap();
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.transactions, vec![first_hash]);
+        let mempool = devnet.devnet_client.get_mempool().await.unwrap();
+        assert_eq!(mempool.len(), 1);
+        assert_eq!(mempool[0].transaction_hash, second_hash);
+
+        devnet.create_block().await.unwrap();
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.transactions, vec![second_hash]);
+        assert!(devnet.devnet_client.get_mempool().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn transaction_exceeding_block_capacity_rejected() {
+        let mut capacity_file = NamedTempFile::new().unwrap();
+        capacity_file.write_all(br#"{ "n_steps": 1 }"#).unwrap();
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&[
+            "--block-capacity-file",
+            capacity_file.path().to_str().unwrap(),
+        ])
+        .await
+        .unwrap();
+        let account = get_account(&devnet).await;
+
+        match send_transfer(&account, 0).await {
+            Err(AccountError::Provider(ProviderError::Other(e))) => {
+                assert_contains(&e.to_string(), "exceeds the maximum block capacity")
+            }
+            unexpected => panic!("Got unexpected resp: {unexpected:?}"),
+        }
+    }
+}
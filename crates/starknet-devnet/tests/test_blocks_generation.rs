@@ -655,4 +655,37 @@ mod blocks_generation_tests {
         let last_block_after = devnet.get_latest_block_with_tx_hashes().await.unwrap();
         assert_eq!(last_block_after.block_number, last_block_before.block_number + 1);
     }
+
+    async fn get_state_update(devnet: &BackgroundDevnet, block_id: BlockId) -> StateUpdate {
+        match devnet.json_rpc_client.get_state_update(block_id).await.unwrap() {
+            MaybePendingStateUpdate::Update(state_update) => state_update,
+            other => panic!("Unexpected state update: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn state_roots_and_commitments_are_calculated() {
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&["--calculate-commitments"])
+            .await
+            .unwrap();
+        let genesis_update = get_state_update(&devnet, BlockId::Number(0)).await;
+        assert_eq!(genesis_update.old_root, Felt::ZERO);
+
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        let latest_update = get_state_update(&devnet, BlockId::Tag(BlockTag::Latest)).await;
+        assert_eq!(latest_update.old_root, genesis_update.new_root);
+        assert_eq!(latest_update.new_root, latest_block.new_root);
+        assert_ne!(latest_update.new_root, latest_update.old_root);
+    }
+
+    #[tokio::test]
+    async fn state_roots_are_zero_by_default() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+
+        let latest_update = get_state_update(&devnet, BlockId::Tag(BlockTag::Latest)).await;
+        assert_eq!((latest_update.old_root, latest_update.new_root), (Felt::ZERO, Felt::ZERO));
+    }
 }
@@ -0,0 +1,89 @@
+#![cfg(test)]
+pub mod common;
+
+mod coverage_tests {
+    use std::sync::Arc;
+
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_rs_accounts::{ExecutionEncoding, SingleOwnerAccount};
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt, FunctionCall};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_rs_providers::Provider;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants;
+    use crate::common::utils::{
+        declare_deploy_v1, get_simple_contract_in_sierra_and_compiled_class_hash,
+    };
+
+    async fn visited_pcs_of(devnet: &BackgroundDevnet, class_hash: Felt) -> Vec<usize> {
+        let coverage = devnet.devnet_client.get_coverage().await.unwrap();
+        coverage
+            .into_iter()
+            .find(|class| class.class_hash == class_hash)
+            .map(|class| class.visited_pcs)
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn transactions_and_calls_are_covered() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        let account = Arc::new(SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        ));
+
+        let (contract_class, casm_hash) = get_simple_contract_in_sierra_and_compiled_class_hash();
+        let (class_hash, contract_address) =
+            declare_deploy_v1(account, contract_class, casm_hash, &[Felt::from(10_u32)])
+                .await
+                .unwrap();
+
+        // the constructor was executed
+        let pcs_after_deployment = visited_pcs_of(&devnet, class_hash).await;
+        assert!(!pcs_after_deployment.is_empty());
+
+        devnet
+            .json_rpc_client
+            .call(
+                FunctionCall {
+                    contract_address,
+                    entry_point_selector: get_selector_from_name("get_balance").unwrap(),
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await
+            .unwrap();
+
+        let pcs_after_call = visited_pcs_of(&devnet, class_hash).await;
+        assert!(pcs_after_deployment.iter().all(|pc| pcs_after_call.contains(pc)));
+        assert!(pcs_after_call.len() > pcs_after_deployment.len());
+    }
+
+    #[tokio::test]
+    async fn lcov_without_source_locations_is_empty() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        // the predeployed ERC20 contract was not compiled with source locations
+        devnet
+            .json_rpc_client
+            .call(
+                FunctionCall {
+                    contract_address: ETH_ERC20_CONTRACT_ADDRESS,
+                    entry_point_selector: get_selector_from_name("balanceOf").unwrap(),
+                    calldata: vec![Felt::ONE],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await
+            .unwrap();
+        assert!(!devnet.devnet_client.get_coverage().await.unwrap().is_empty());
+
+        assert_eq!(devnet.devnet_client.get_coverage_lcov().await.unwrap(), "");
+    }
+}
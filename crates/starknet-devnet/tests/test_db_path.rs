@@ -0,0 +1,83 @@
+#![cfg(test)]
+pub mod common;
+
+mod db_path_tests {
+    use serde_json::json;
+    use starknet_rs_core::types::{BlockId, Felt, TransactionReceipt};
+    use starknet_rs_providers::Provider;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::utils::send_ctrl_c_signal_and_wait;
+
+    const DUMMY_ADDRESS: u128 = 1;
+    const DUMMY_AMOUNT: u128 = 1;
+
+    async fn spawn_with_db_path(db_path: &str) -> BackgroundDevnet {
+        BackgroundDevnet::spawn_with_additional_args(&[
+            "--db-path",
+            db_path,
+            "--state-archive-capacity",
+            "full",
+        ])
+        .await
+        .expect("Could not start Devnet")
+    }
+
+    #[tokio::test]
+    async fn history_survives_restart() {
+        let db_dir = tempfile::tempdir().unwrap();
+        let db_path = db_dir.path().to_str().unwrap();
+
+        let devnet = spawn_with_db_path(db_path).await;
+        let first_mint_hash = devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        send_ctrl_c_signal_and_wait(&devnet.process).await;
+
+        let devnet = spawn_with_db_path(db_path).await;
+        let restored_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(restored_block, latest_block);
+
+        let receipt =
+            devnet.json_rpc_client.get_transaction_receipt(first_mint_hash).await.unwrap();
+        assert_eq!(receipt.block.block_number(), Some(1));
+        assert!(matches!(receipt.receipt, TransactionReceipt::Invoke(_)));
+
+        let address = Felt::from(DUMMY_ADDRESS);
+        for (block_number, expected_balance) in [(1, DUMMY_AMOUNT), (2, 2 * DUMMY_AMOUNT)] {
+            let balance =
+                devnet.get_balance_at_block(&address, BlockId::Number(block_number)).await.unwrap();
+            assert_eq!(balance, Felt::from(expected_balance));
+        }
+
+        // the restored Devnet continues from the stored state
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let new_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(new_block.block_number, latest_block.block_number + 1);
+        assert_eq!(new_block.parent_hash, latest_block.block_hash);
+    }
+
+    #[tokio::test]
+    async fn restart_clears_stored_history() {
+        let db_dir = tempfile::tempdir().unwrap();
+        let db_path = db_dir.path().to_str().unwrap();
+
+        let devnet = spawn_with_db_path(db_path).await;
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        devnet.restart().await;
+        send_ctrl_c_signal_and_wait(&devnet.process).await;
+
+        let devnet = spawn_with_db_path(db_path).await;
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.block_number, 0);
+    }
+
+    #[tokio::test]
+    async fn snapshots_are_not_supported_with_db_path() {
+        let db_dir = tempfile::tempdir().unwrap();
+        let devnet = spawn_with_db_path(db_dir.path().to_str().unwrap()).await;
+
+        let error = devnet.send_custom_rpc("devnet_snapshot", json!({})).await.unwrap_err();
+        assert!(error.message.contains("Snapshots are not supported when using a database"));
+    }
+}
@@ -0,0 +1,83 @@
+#![cfg(test)]
+pub mod common;
+
+mod devnet_client_tests {
+    use starknet_devnet_client::Error;
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_rs_providers::Provider;
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::num_bigint::BigUint;
+    use starknet_types::rpc::devnet::{BalanceQuery, MintTokensRequest, SetTime};
+    use starknet_types::rpc::transaction_receipt::FeeUnit;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+
+    #[tokio::test]
+    async fn minted_amount_is_reflected_in_typed_balance() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let client = &devnet.devnet_client;
+
+        let address = Felt::from(0x1234);
+        let minted = client
+            .mint(&MintTokensRequest {
+                address: ContractAddress::new(address).unwrap(),
+                amount: BigUint::from(10_u32).pow(30),
+                unit: Some(FeeUnit::FRI),
+            })
+            .await
+            .unwrap();
+        assert_eq!(minted.new_balance, format!("1{}", "0".repeat(30)));
+        assert_eq!(minted.unit, FeeUnit::FRI);
+
+        let balance = client
+            .get_account_balance(&BalanceQuery {
+                address,
+                unit: Some(FeeUnit::FRI),
+                block_tag: Some(BlockTag::Latest),
+            })
+            .await
+            .unwrap();
+        assert_eq!(balance.amount, minted.new_balance);
+    }
+
+    #[tokio::test]
+    async fn blocks_created_and_aborted_via_client() {
+        let devnet = BackgroundDevnet::spawn_forkable_devnet().await.unwrap();
+        let client = &devnet.devnet_client;
+
+        let block_hash = client.create_block().await.unwrap();
+        assert_eq!(devnet.get_latest_block_with_tx_hashes().await.unwrap().block_hash, block_hash);
+
+        let aborted = client.abort_blocks(BlockId::Hash(block_hash).into()).await.unwrap();
+        assert_eq!(aborted, vec![block_hash]);
+        assert_eq!(devnet.json_rpc_client.block_number().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn time_is_set_and_reverted_via_client() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let client = &devnet.devnet_client;
+
+        let snapshot_id = client.take_snapshot().await.unwrap();
+
+        let time = 1_000_000_000;
+        let set_time =
+            client.set_time(&SetTime { time, generate_block: Some(true) }).await.unwrap();
+        assert_eq!(set_time.block_timestamp, time);
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(set_time.block_hash, Some(latest_block.block_hash));
+
+        client.revert_to_snapshot(snapshot_id).await.unwrap();
+        assert_eq!(devnet.json_rpc_client.block_number().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn rpc_error_is_returned_as_typed_error() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        match devnet.devnet_client.revert_to_snapshot(42).await {
+            Err(Error::RpcError { message, .. }) => assert!(message.contains("42")),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+}
@@ -22,7 +22,7 @@ mod dump_and_load_tests {
 
     use starknet_rs_accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
     use starknet_rs_contract::ContractFactory;
-    use starknet_rs_core::types::Felt;
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
 
     use crate::common::utils::get_events_contract_in_sierra_and_compiled_class_hash;
 
@@ -604,4 +604,169 @@ mod dump_and_load_tests {
             devnet.send_custom_rpc("devnet_dump", serde_json::Value::Null).await.unwrap();
         assert_eq!(dump_resp, json!([]));
     }
+
+    #[tokio::test]
+    async fn state_dump_on_exit_and_load_restores_blocks_and_transactions() {
+        let dump_file = UniqueAutoDeletableFile::new("state_dump_on_exit");
+        let args = [
+            "--dump-path",
+            &dump_file.path,
+            "--dump-on",
+            "exit",
+            "--dump-format",
+            "state",
+            "--state-archive-capacity",
+            "full",
+        ];
+        let devnet_dump = BackgroundDevnet::spawn_with_additional_args(&args).await.unwrap();
+        let first_mint_hash = devnet_dump.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        devnet_dump.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+
+        let dumped_block = devnet_dump.get_latest_block_with_tx_hashes().await.unwrap();
+        let dumped_receipt =
+            devnet_dump.json_rpc_client.get_transaction_receipt(first_mint_hash).await.unwrap();
+        let dumped_trace =
+            devnet_dump.json_rpc_client.trace_transaction(first_mint_hash).await.unwrap();
+
+        send_ctrl_c_signal_and_wait(&devnet_dump.process).await;
+
+        let devnet_load = BackgroundDevnet::spawn_with_additional_args(&args).await.unwrap();
+
+        // blocks are restored as they were, including timestamps
+        let loaded_block = devnet_load.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(loaded_block, dumped_block);
+        assert_eq!(
+            devnet_load.json_rpc_client.get_transaction_receipt(first_mint_hash).await.unwrap(),
+            dumped_receipt
+        );
+        assert_eq!(
+            devnet_load.json_rpc_client.trace_transaction(first_mint_hash).await.unwrap(),
+            dumped_trace
+        );
+
+        let dummy_address = Felt::from(DUMMY_ADDRESS);
+        let first_mint_block = BlockId::Number(1);
+        assert_eq!(
+            devnet_load.get_balance_at_block(&dummy_address, first_mint_block).await.unwrap(),
+            Felt::from(DUMMY_AMOUNT)
+        );
+
+        // the loaded Devnet continues from the restored state
+        devnet_load.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(
+            devnet_load.get_balance_at_block(&dummy_address, latest).await.unwrap(),
+            Felt::from(3 * DUMMY_AMOUNT)
+        );
+        assert_eq!(devnet_load.json_rpc_client.block_number().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn state_dump_on_block_preserves_declared_and_deployed_contract() {
+        let dump_file = UniqueAutoDeletableFile::new("state_dump_declare_deploy");
+        let args = ["--dump-path", &dump_file.path, "--dump-on", "block", "--dump-format", "state"];
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&args).await.unwrap();
+
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        let predeployed_account = Arc::new(SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        ));
+
+        let (cairo_1_contract, casm_class_hash) =
+            get_events_contract_in_sierra_and_compiled_class_hash();
+        let declaration_result = predeployed_account
+            .declare_v2(Arc::new(cairo_1_contract), casm_class_hash)
+            .max_fee(Felt::from(1e18 as u128))
+            .send()
+            .await
+            .unwrap();
+
+        let contract_factory =
+            ContractFactory::new(declaration_result.class_hash, predeployed_account.clone());
+        let deployment = contract_factory.deploy_v1(vec![], Felt::ZERO, false);
+        let contract_address = deployment.deployed_address();
+        deployment.max_fee(Felt::from(1e18 as u128)).send().await.unwrap();
+
+        let devnet_load = BackgroundDevnet::spawn_with_additional_args(&args).await.unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        let loaded_class_hash =
+            devnet_load.json_rpc_client.get_class_hash_at(latest, contract_address).await.unwrap();
+        assert_eq!(loaded_class_hash, declaration_result.class_hash);
+        assert_eq!(
+            devnet_load
+                .json_rpc_client
+                .get_class(latest, declaration_result.class_hash)
+                .await
+                .unwrap(),
+            devnet.json_rpc_client.get_class(latest, declaration_result.class_hash).await.unwrap()
+        );
+        assert_eq!(
+            devnet_load.json_rpc_client.get_nonce(latest, account_address).await.unwrap(),
+            Felt::TWO
+        );
+    }
+
+    #[tokio::test]
+    async fn state_dump_on_block_rewritten_only_on_new_block() {
+        let dump_file = UniqueAutoDeletableFile::new("state_dump_once_per_block");
+        let args = ["--dump-path", &dump_file.path, "--dump-on", "block", "--dump-format", "state"];
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&args).await.unwrap();
+
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        assert!(Path::new(&dump_file.path).exists());
+        std::fs::remove_file(&dump_file.path).unwrap();
+
+        // dumpable, but doesn't create a block
+        devnet.send_custom_rpc("devnet_setGasPrice", json!({ "gas_price_wei": 1 })).await.unwrap();
+        assert!(!Path::new(&dump_file.path).exists());
+
+        devnet.create_block().await.unwrap();
+        assert!(Path::new(&dump_file.path).exists());
+    }
+
+    #[tokio::test]
+    async fn state_dump_on_request_and_load_via_endpoint() {
+        let devnet_dump = BackgroundDevnet::spawn_with_additional_args(&[
+            "--dump-on",
+            "request",
+            "--dump-format",
+            "state",
+        ])
+        .await
+        .unwrap();
+        devnet_dump.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+
+        // without a path, the dump is returned in the response
+        let dump = devnet_dump.send_custom_rpc("devnet_dump", json!({})).await.unwrap();
+        assert!(dump["blocks"].is_object());
+
+        let dump_file = UniqueAutoDeletableFile::new("state_dump_on_request");
+        devnet_dump
+            .send_custom_rpc("devnet_dump", json!({ "path": dump_file.path }))
+            .await
+            .unwrap();
+
+        let devnet_load = BackgroundDevnet::spawn_with_additional_args(&["--dump-format", "state"])
+            .await
+            .unwrap();
+        devnet_load
+            .send_custom_rpc("devnet_load", json!({ "path": dump_file.path }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            devnet_load.get_latest_block_with_tx_hashes().await.unwrap(),
+            devnet_dump.get_latest_block_with_tx_hashes().await.unwrap()
+        );
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(
+            devnet_load.get_balance_at_block(&Felt::from(DUMMY_ADDRESS), latest).await.unwrap(),
+            Felt::from(DUMMY_AMOUNT)
+        );
+    }
 }
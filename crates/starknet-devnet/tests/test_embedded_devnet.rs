@@ -0,0 +1,59 @@
+#![cfg(test)]
+pub mod common;
+
+mod embedded_devnet_tests {
+    use serde_json::json;
+    use server::devnet::DevnetBuilder;
+    use server::rpc_core::request::RpcMethodCall;
+    use server::rpc_handler::RpcHandler;
+    use server::ServerConfig;
+    use starknet_core::starknet::starknet_config::StarknetConfig;
+    use starknet_rs_providers::jsonrpc::HttpTransport;
+    use starknet_rs_providers::{JsonRpcClient, Provider};
+    use url::Url;
+
+    #[tokio::test]
+    async fn embedded_devnet_is_served_on_ephemeral_port() {
+        let devnet = DevnetBuilder::new(StarknetConfig::default(), ServerConfig::default())
+            .ephemeral_port()
+            .build()
+            .await
+            .unwrap();
+
+        let local_addr = devnet.local_addr().unwrap();
+        assert_ne!(local_addr.port(), 0);
+        assert_eq!(devnet.server_config().port, local_addr.port());
+
+        let rpc_url = Url::parse(&format!("{}/rpc", devnet.url().unwrap())).unwrap();
+        let json_rpc_client = JsonRpcClient::new(HttpTransport::new(rpc_url));
+        assert_eq!(json_rpc_client.block_number().await.unwrap(), 0);
+
+        // state modified via the handle is visible to the server
+        devnet.api().starknet.lock().await.create_block().unwrap();
+        assert_eq!(json_rpc_client.block_number().await.unwrap(), 1);
+
+        devnet.shutdown().await.unwrap();
+        assert!(tokio::net::TcpStream::connect(local_addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn embedded_devnet_without_server_handles_json_rpc_calls() {
+        let devnet = DevnetBuilder::new(StarknetConfig::default(), ServerConfig::default())
+            .without_server()
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(devnet.url(), None);
+
+        let call: RpcMethodCall = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "starknet_blockNumber",
+        }))
+        .unwrap();
+        let response = devnet.json_rpc_handler().on_call(call).await;
+        assert_eq!(serde_json::to_value(response).unwrap()["result"], json!(0));
+
+        devnet.shutdown().await.unwrap();
+    }
+}
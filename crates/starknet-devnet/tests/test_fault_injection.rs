@@ -0,0 +1,141 @@
+#![cfg(test)]
+pub mod common;
+
+mod fault_injection_tests {
+    use std::time::{Duration, Instant};
+
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_devnet_client::Error;
+    use starknet_rs_accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
+    use starknet_rs_core::types::{BlockId, BlockTag, Call, Felt, StarknetError};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_rs_providers::{Provider, ProviderError};
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::num_bigint::BigUint;
+    use starknet_types::rpc::devnet::{
+        ErrorFault, FaultConfig, InjectedError, MethodFaults, MintTokensRequest,
+    };
+    use starknet_types::rpc::transaction_receipt::FeeUnit;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants;
+    use crate::common::utils::{assert_tx_reverted, assert_tx_successful};
+
+    fn method_faults_config(method: &str, faults: MethodFaults) -> FaultConfig {
+        FaultConfig {
+            methods: [(method.to_string(), faults)].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn injected_error_returned_until_faults_removed() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let faults = MethodFaults {
+            errors: vec![ErrorFault { error: InjectedError::BlockNotFound, probability: 1.0 }],
+            ..Default::default()
+        };
+        devnet
+            .devnet_client
+            .set_faults(method_faults_config("starknet_getBlockWithTxHashes", faults))
+            .await
+            .unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        match devnet.json_rpc_client.get_block_with_tx_hashes(latest).await {
+            Err(ProviderError::StarknetError(StarknetError::BlockNotFound)) => (),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+        // other methods are not affected
+        devnet.json_rpc_client.block_number().await.unwrap();
+
+        devnet.devnet_client.set_faults(FaultConfig::default()).await.unwrap();
+        devnet.json_rpc_client.get_block_with_tx_hashes(latest).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn latency_added_to_all_methods() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let latency_ms = 300;
+        let faults = MethodFaults { latency_ms, ..Default::default() };
+        devnet.devnet_client.set_faults(method_faults_config("*", faults)).await.unwrap();
+
+        let start = Instant::now();
+        devnet.json_rpc_client.chain_id().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(latency_ms));
+    }
+
+    #[tokio::test]
+    async fn request_with_dropped_response_is_handled() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let faults = MethodFaults { drop_probability: 1.0, ..Default::default() };
+        devnet.devnet_client.set_faults(method_faults_config("devnet_mint", faults)).await.unwrap();
+
+        let address = Felt::ONE;
+        let mint_amount = 1000_u32;
+        let mint_request = MintTokensRequest {
+            address: ContractAddress::new(address).unwrap(),
+            amount: BigUint::from(mint_amount),
+            unit: Some(FeeUnit::WEI),
+        };
+        match devnet.devnet_client.mint(&mint_request).await {
+            Err(Error::RequestError(_)) => (),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+
+        let balance = devnet.get_balance_latest(&address, FeeUnit::WEI).await.unwrap();
+        assert_eq!(balance, Felt::from(mint_amount));
+    }
+
+    #[tokio::test]
+    async fn next_transactions_reverted() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        let account = SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        );
+
+        let config = FaultConfig { revert_next_transactions: 1, ..Default::default() };
+        devnet.devnet_client.set_faults(config).await.unwrap();
+
+        let recipient = Felt::TWO;
+        let transfer = Call {
+            to: ETH_ERC20_CONTRACT_ADDRESS,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![recipient, Felt::ONE, Felt::ZERO],
+        };
+
+        let reverted = account.execute_v1(vec![transfer.clone()]).send().await.unwrap();
+        assert_tx_reverted(
+            &reverted.transaction_hash,
+            &devnet.json_rpc_client,
+            &["Reverted by fault injection"],
+        )
+        .await;
+        let balance = devnet.get_balance_latest(&recipient, FeeUnit::WEI).await.unwrap();
+        assert_eq!(balance, Felt::ZERO);
+
+        let succeeded = account.execute_v1(vec![transfer]).send().await.unwrap();
+        assert_tx_successful(&succeeded.transaction_hash, &devnet.json_rpc_client).await;
+        let balance = devnet.get_balance_latest(&recipient, FeeUnit::WEI).await.unwrap();
+        assert_eq!(balance, Felt::ONE);
+    }
+
+    #[tokio::test]
+    async fn invalid_probability_rejected() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let config = FaultConfig { origin_failure_probability: 2.0, ..Default::default() };
+        match devnet.devnet_client.set_faults(config).await {
+            Err(Error::RpcError { message, .. }) => assert!(message.contains("between 0 and 1")),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+}
@@ -23,6 +23,7 @@ mod fork_tests {
     use starknet_rs_providers::{Provider, ProviderError};
     use starknet_rs_signers::Signer;
     use starknet_types::felt::felt_from_prefixed_hex;
+    use starknet_types::rpc::gas_modification::GasModificationRequest;
     use starknet_types::rpc::transaction_receipt::FeeUnit;
 
     use crate::common::background_devnet::BackgroundDevnet;
@@ -43,7 +44,12 @@ mod fork_tests {
         let origin_devnet_config = origin_devnet.get_config().await;
         assert_eq!(
             origin_devnet_config["fork_config"],
-            serde_json::json!({ "url": null, "block_number": null })
+            serde_json::json!({
+                "url": null,
+                "block_number": null,
+                "cache_dir": null,
+                "replay_gas_prices": null,
+            })
         );
 
         let fork_devnet = origin_devnet.fork().await.unwrap();
@@ -530,6 +536,47 @@ mod fork_tests {
         };
     }
 
+    #[tokio::test]
+    async fn test_gas_prices_of_origin_replayed() {
+        let origin_devnet = BackgroundDevnet::spawn_forkable_devnet().await.unwrap();
+        let set_origin_gas_price = |gas_price_wei: u128| {
+            let request = GasModificationRequest {
+                gas_price_wei: Some(gas_price_wei.try_into().unwrap()),
+                data_gas_price_wei: None,
+                gas_price_fri: None,
+                data_gas_price_fri: None,
+                generate_block: Some(true),
+            };
+            let devnet_client = &origin_devnet.devnet_client;
+            async move { devnet_client.set_gas_price(&request).await.unwrap() }
+        };
+
+        // origin blocks 2 and 3 are priced at 1000 WEI, block 4 at 2000 WEI
+        set_origin_gas_price(1000).await;
+        origin_devnet.create_block().await.unwrap();
+        set_origin_gas_price(2000).await;
+        origin_devnet.create_block().await.unwrap();
+
+        // the last three blocks up to the forking block are replayed
+        let fork_devnet = BackgroundDevnet::spawn_with_additional_args(&[
+            "--fork-network",
+            origin_devnet.url.as_str(),
+            "--fork-block",
+            "4",
+            "--fork-replay-gas-prices",
+            "3",
+        ])
+        .await
+        .unwrap();
+
+        // the prices of the forking block are kept after the replay
+        for expected_gas_price_wei in [1000_u32, 1000, 2000, 2000] {
+            let pending_block = fork_devnet.get_pending_block_with_tx_hashes().await.unwrap();
+            assert_eq!(pending_block.l1_gas_price.price_in_wei, Felt::from(expected_gas_price_wei));
+            fork_devnet.create_block().await.unwrap();
+        }
+    }
+
     #[tokio::test]
     async fn test_block_count_increased_on_state() {
         let origin_devnet = BackgroundDevnet::spawn_forkable_devnet().await.unwrap();
@@ -573,6 +620,70 @@ mod fork_tests {
         assert_eq!(second_fork_block_number, [Felt::from(5_u8)]); // origin block + declare + deploy
     }
 
+    #[tokio::test]
+    async fn test_hashes_of_origin_blocks_stored_in_block_hash_contract() {
+        let origin_devnet = BackgroundDevnet::spawn_forkable_devnet().await.unwrap();
+        let mut origin_block_hashes = vec![];
+        for block_number in 0..=12 {
+            if block_number > 0 {
+                origin_devnet.create_block().await.unwrap();
+            }
+            match origin_devnet
+                .json_rpc_client
+                .get_block_with_tx_hashes(BlockId::Number(block_number))
+                .await
+            {
+                Ok(MaybePendingBlockWithTxHashes::Block(b)) => {
+                    origin_block_hashes.push(b.block_hash)
+                }
+                other => panic!("Unexpected resp: {other:?}"),
+            }
+        }
+
+        // the forked devnet mines its genesis block 13 on top of origin block 12
+        let fork_devnet = origin_devnet.fork().await.unwrap();
+
+        // hashes of origin blocks 0 to 2 were stored by the origin, the hash of block 3 was stored
+        // in block 13 and the hash of block 4 is stored in the pending block 14
+        for (block_number, block_tag) in [
+            (0, BlockTag::Latest),
+            (2, BlockTag::Latest),
+            (3, BlockTag::Latest),
+            (4, BlockTag::Pending),
+        ] {
+            let stored_hash = fork_devnet
+                .json_rpc_client
+                .get_storage_at(Felt::ONE, Felt::from(block_number), BlockId::Tag(block_tag))
+                .await
+                .unwrap();
+            assert_eq!(stored_hash, origin_block_hashes[block_number as usize]);
+        }
+
+        let not_yet_stored = fork_devnet
+            .json_rpc_client
+            .get_storage_at(Felt::ONE, Felt::from(5), BlockId::Tag(BlockTag::Pending))
+            .await
+            .unwrap();
+        assert_eq!(not_yet_stored, Felt::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_block_not_created_if_hash_of_origin_block_not_fetched() {
+        let origin_devnet = BackgroundDevnet::spawn_forkable_devnet().await.unwrap();
+        for _ in 0..12 {
+            origin_devnet.create_block().await.unwrap();
+        }
+
+        // the forked devnet mines its genesis block 13 on top of origin block 12
+        let fork_devnet = origin_devnet.fork().await.unwrap();
+        drop(origin_devnet);
+
+        // the pending block 15 of the new block would store the hash of origin block 5
+        fork_devnet.create_block().await.unwrap_err();
+        let latest_block = fork_devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.block_number, 13);
+    }
+
     #[tokio::test]
     async fn test_forking_https() {
         let origin_url = MAINNET_HTTPS_URL;
@@ -623,4 +734,37 @@ mod fork_tests {
             CAIRO_1_ERC20_CONTRACT_CLASS_HASH
         );
     }
+
+    #[tokio::test]
+    async fn test_forking_offline_with_warmed_up_cache() {
+        let origin_devnet = BackgroundDevnet::spawn_forkable_devnet().await.unwrap();
+        let dummy_address = Felt::ONE;
+        let mint_amount = 100;
+        origin_devnet.mint(dummy_address, mint_amount).await;
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let origin_url = origin_devnet.url.clone();
+        let fork_args = [
+            "--fork-network",
+            origin_url.as_str(),
+            "--fork-block",
+            "1",
+            "--fork-cache-dir",
+            cache_dir.path().to_str().unwrap(),
+            "--accounts",
+            "0",
+        ];
+
+        let fork_devnet = BackgroundDevnet::spawn_with_additional_args(&fork_args).await.unwrap();
+        let balance = fork_devnet.get_balance_latest(&dummy_address, FeeUnit::WEI).await.unwrap();
+        assert_eq!(balance, Felt::from(mint_amount));
+        send_ctrl_c_signal_and_wait(&fork_devnet.process).await;
+
+        // the origin is no longer reachable, so everything has to be read from the cache
+        send_ctrl_c_signal_and_wait(&origin_devnet.process).await;
+
+        let fork_devnet = BackgroundDevnet::spawn_with_additional_args(&fork_args).await.unwrap();
+        let balance = fork_devnet.get_balance_latest(&dummy_address, FeeUnit::WEI).await.unwrap();
+        assert_eq!(balance, Felt::from(mint_amount));
+    }
 }
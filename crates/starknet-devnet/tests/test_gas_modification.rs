@@ -5,13 +5,16 @@ mod gas_modification_tests {
     use std::sync::Arc;
 
     use serde_json::json;
-    use starknet_core::constants::DEVNET_DEFAULT_GAS_PRICE;
+    use starknet_core::constants::{DEVNET_DEFAULT_GAS_PRICE, ETH_ERC20_CONTRACT_ADDRESS};
     use starknet_rs_accounts::{Account, AccountError, ExecutionEncoding, SingleOwnerAccount};
-    use starknet_rs_core::types::{Felt, ResourcePrice, StarknetError};
+    use starknet_rs_core::types::{Call, Felt, ResourcePrice, StarknetError};
+    use starknet_rs_core::utils::get_selector_from_name;
     use starknet_rs_providers::ProviderError;
     use starknet_rs_signers::Signer;
     use starknet_types::chain_id::ChainId;
     use starknet_types::felt::felt_from_prefixed_hex;
+    use starknet_types::rpc::gas_modification::DataAvailabilityModeRequest;
+    use starknet_types::starknet_api::data_availability::L1DataAvailabilityMode;
 
     use crate::common::background_devnet::BackgroundDevnet;
     use crate::common::constants::{self, CAIRO_1_CONTRACT_PATH, INTEGRATION_SEPOLIA_HTTP_URL};
@@ -419,4 +422,114 @@ mod gas_modification_tests {
 
         assert_eq!(gas_response, &expected_gas_response);
     }
+
+    #[tokio::test]
+    async fn set_data_availability_mode() {
+        let devnet = BackgroundDevnet::spawn().await.expect("Could not start Devnet");
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        let account = SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        );
+        let transfer = account.execute_v1(vec![Call {
+            to: ETH_ERC20_CONTRACT_ADDRESS,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+        }]);
+
+        let blob_estimate = transfer.estimate_fee().await.unwrap();
+        assert!(blob_estimate.data_gas_consumed > Felt::ZERO);
+
+        let response = devnet
+            .devnet_client
+            .set_data_availability_mode(&DataAvailabilityModeRequest {
+                l1_da_mode: L1DataAvailabilityMode::Calldata,
+                generate_block: Some(true),
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.l1_da_mode, L1DataAvailabilityMode::Calldata);
+
+        // the generated block was pending before the mode was changed
+        for (block_id, expected_da_mode) in [("latest", "BLOB"), ("pending", "CALLDATA")] {
+            let block = devnet
+                .send_custom_rpc("starknet_getBlockWithTxHashes", json!({ "block_id": block_id }))
+                .await
+                .unwrap();
+            assert_eq!(block["l1_da_mode"], expected_da_mode);
+        }
+
+        let calldata_estimate = transfer.estimate_fee().await.unwrap();
+        assert_eq!(calldata_estimate.data_gas_consumed, Felt::ZERO);
+        assert!(calldata_estimate.gas_consumed > blob_estimate.gas_consumed);
+
+        let transaction_hash = transfer.send().await.unwrap().transaction_hash;
+        assert_tx_successful(&transaction_hash, &devnet.json_rpc_client).await;
+        let receipt = devnet
+            .send_custom_rpc(
+                "starknet_getTransactionReceipt",
+                json!({ "transaction_hash": transaction_hash }),
+            )
+            .await
+            .unwrap();
+        let data_availability = &receipt["execution_resources"]["data_availability"];
+        assert!(data_availability["l1_gas"].as_u64().unwrap() > 0);
+        assert_eq!(data_availability["l1_data_gas"], 0);
+    }
+
+    #[tokio::test]
+    async fn gas_prices_follow_schedule() {
+        let mut schedule_file = tempfile::NamedTempFile::new().unwrap();
+        let schedule = json!({
+            "oscillation": {
+                "period": 4,
+                "min": {
+                    "gas_price_wei": 100,
+                    "data_gas_price_wei": 10,
+                    "gas_price_fri": 1000,
+                    "data_gas_price_fri": 100,
+                },
+                "max": {
+                    "gas_price_wei": 300,
+                    "data_gas_price_wei": 30,
+                    "gas_price_fri": 3000,
+                    "data_gas_price_fri": 300,
+                },
+            }
+        });
+        std::io::Write::write_all(&mut schedule_file, schedule.to_string().as_bytes()).unwrap();
+
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&[
+            "--gas-price-schedule-file",
+            schedule_file.path().to_str().unwrap(),
+        ])
+        .await
+        .unwrap();
+
+        // the genesis block is priced by the CLI
+        let genesis_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(
+            genesis_block.l1_gas_price.price_in_wei,
+            Felt::from(u128::from(DEVNET_DEFAULT_GAS_PRICE))
+        );
+
+        for expected_gas_price_wei in [200_u32, 300, 200, 100, 200] {
+            let pending_block = devnet.get_pending_block_with_tx_hashes().await.unwrap();
+            assert_eq!(
+                pending_block.l1_gas_price,
+                ResourcePrice {
+                    price_in_wei: Felt::from(expected_gas_price_wei),
+                    price_in_fri: Felt::from(expected_gas_price_wei * 10),
+                }
+            );
+            assert_eq!(
+                pending_block.l1_data_gas_price.price_in_wei,
+                Felt::from(expected_gas_price_wei / 10)
+            );
+            devnet.create_block().await.unwrap();
+        }
+    }
 }
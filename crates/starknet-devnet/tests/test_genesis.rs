@@ -0,0 +1,193 @@
+#![cfg(test)]
+pub mod common;
+
+mod genesis_tests {
+    use serde_json::json;
+    use starknet_core::constants::CAIRO_1_ACCOUNT_CONTRACT_SIERRA_HASH;
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt, FunctionCall};
+    use starknet_rs_core::utils::{get_selector_from_name, get_storage_var_address};
+    use starknet_rs_providers::Provider;
+    use starknet_types::felt::felt_from_prefixed_hex;
+    use starknet_types::rpc::transaction_receipt::FeeUnit;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants::CAIRO_1_CONTRACT_PATH;
+    use crate::common::utils::get_simple_contract_in_sierra_and_compiled_class_hash;
+
+    async fn get_contract_balance(devnet: &BackgroundDevnet, contract_address: Felt) -> Felt {
+        let result = devnet
+            .json_rpc_client
+            .call(
+                FunctionCall {
+                    contract_address,
+                    entry_point_selector: get_selector_from_name("get_balance").unwrap(),
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await
+            .unwrap();
+        result[0]
+    }
+
+    async fn spawn_with_genesis(genesis: serde_json::Value) -> BackgroundDevnet {
+        let genesis_dir = tempfile::tempdir().unwrap();
+        let genesis_path = genesis_dir.path().join("genesis.json");
+        std::fs::write(&genesis_path, genesis.to_string()).unwrap();
+
+        BackgroundDevnet::spawn_with_additional_args(&["--genesis", genesis_path.to_str().unwrap()])
+            .await
+            .expect("Could not start Devnet")
+    }
+
+    #[tokio::test]
+    async fn contracts_and_balances_are_present_in_genesis_block() {
+        let (contract_class, _) = get_simple_contract_in_sierra_and_compiled_class_hash();
+        let class_hash = contract_class.class_hash();
+        let constructed_address = Felt::from(0x1234);
+        let stored_address = Felt::from(0x5678);
+        let balance_key = get_storage_var_address("balance", &[]).unwrap();
+        let storage = serde_json::Map::from_iter([(balance_key.to_hex_string(), json!("0x7"))]);
+        let erc20_amount = 1_000_000_u128;
+
+        let devnet = spawn_with_genesis(json!({
+            "classes": [
+                { "sierra": std::fs::canonicalize(CAIRO_1_CONTRACT_PATH).unwrap() }
+            ],
+            "contracts": [
+                {
+                    "address": constructed_address,
+                    "class_hash": class_hash,
+                    "constructor_calldata": ["0x64"],
+                },
+                {
+                    "address": stored_address,
+                    "class_hash": class_hash,
+                    "storage": storage,
+                },
+            ],
+            "balances": [
+                { "address": constructed_address, "amount": erc20_amount, "unit": "FRI" }
+            ],
+        }))
+        .await;
+
+        let genesis_block = BlockId::Number(0);
+        for address in [constructed_address, stored_address] {
+            let deployed_class_hash =
+                devnet.json_rpc_client.get_class_hash_at(genesis_block, address).await.unwrap();
+            assert_eq!(deployed_class_hash, class_hash);
+        }
+
+        assert_eq!(get_contract_balance(&devnet, constructed_address).await, Felt::from(100));
+        assert_eq!(get_contract_balance(&devnet, stored_address).await, Felt::from(7));
+
+        let fri_balance =
+            devnet.get_balance_latest(&constructed_address, FeeUnit::FRI).await.unwrap();
+        assert_eq!(fri_balance, Felt::from(erc20_amount));
+        let wei_balance =
+            devnet.get_balance_latest(&constructed_address, FeeUnit::WEI).await.unwrap();
+        assert_eq!(wei_balance, Felt::ZERO);
+    }
+
+    #[tokio::test]
+    async fn genesis_is_reapplied_on_restart() {
+        let address = Felt::from(0x1234);
+        let devnet = spawn_with_genesis(json!({
+            "balances": [{ "address": address, "amount": 10 }],
+        }))
+        .await;
+
+        devnet.mint(address, 5).await;
+        devnet.restart().await;
+
+        let balance = devnet.get_balance_latest(&address, FeeUnit::WEI).await.unwrap();
+        assert_eq!(balance, Felt::from(10));
+    }
+
+    #[tokio::test]
+    async fn contract_of_undeclared_class_is_rejected() {
+        let genesis_dir = tempfile::tempdir().unwrap();
+        let genesis_path = genesis_dir.path().join("genesis.json");
+        let genesis = json!({ "contracts": [{ "address": "0x1234", "class_hash": "0x1" }] });
+        std::fs::write(&genesis_path, genesis.to_string()).unwrap();
+
+        let result = BackgroundDevnet::spawn_with_additional_args(&[
+            "--genesis",
+            genesis_path.to_str().unwrap(),
+        ])
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn exported_genesis_boots_devnet_with_same_state() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (_, account_address) = devnet.get_first_predeployed_account().await;
+
+        let recipient = Felt::from(0x1234);
+        let mint_amount = 1000;
+        devnet.mint(recipient, mint_amount).await;
+
+        let nonce = Felt::from(5);
+        devnet
+            .send_custom_rpc(
+                "devnet_setNonce",
+                json!({ "contract_address": account_address, "nonce": nonce }),
+            )
+            .await
+            .unwrap();
+
+        let deployed_address = Felt::from(0x5678);
+        let class_hash = felt_from_prefixed_hex(CAIRO_1_ACCOUNT_CONTRACT_SIERRA_HASH).unwrap();
+        devnet
+            .send_custom_rpc(
+                "devnet_setClassHashAt",
+                json!({ "contract_address": deployed_address, "class_hash": class_hash }),
+            )
+            .await
+            .unwrap();
+
+        let returned_genesis =
+            devnet.send_custom_rpc("devnet_exportGenesis", json!({})).await.unwrap();
+        let classes = returned_genesis["classes"].as_array().unwrap();
+        assert!(!classes.is_empty());
+        assert!(classes.iter().all(|class| class.get("inline").is_some()));
+
+        let genesis_dir = tempfile::tempdir().unwrap();
+        let genesis_path = genesis_dir.path().join("exported.json");
+        devnet
+            .send_custom_rpc("devnet_exportGenesis", json!({ "path": genesis_path }))
+            .await
+            .unwrap();
+
+        let restored_devnet = BackgroundDevnet::spawn_with_additional_args(&[
+            "--genesis",
+            genesis_path.to_str().unwrap(),
+        ])
+        .await
+        .unwrap();
+
+        // no transaction history, only the genesis block
+        assert_eq!(restored_devnet.json_rpc_client.block_number().await.unwrap(), 0);
+
+        let genesis_block = BlockId::Number(0);
+        let restored_nonce = restored_devnet
+            .json_rpc_client
+            .get_nonce(genesis_block, account_address)
+            .await
+            .unwrap();
+        assert_eq!(restored_nonce, nonce);
+
+        let restored_class_hash = restored_devnet
+            .json_rpc_client
+            .get_class_hash_at(genesis_block, deployed_address)
+            .await
+            .unwrap();
+        assert_eq!(restored_class_hash, class_hash);
+
+        let restored_balance =
+            restored_devnet.get_balance_latest(&recipient, FeeUnit::WEI).await.unwrap();
+        assert_eq!(restored_balance, Felt::from(mint_amount));
+    }
+}
@@ -4,15 +4,18 @@ pub mod common;
 mod get_class_tests {
     use std::sync::Arc;
 
+    use serde_json::json;
+    use server::rpc_core::error::{ErrorCode, RpcError};
     use starknet_rs_accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
     use starknet_rs_core::chain_id;
     use starknet_rs_core::types::contract::legacy::LegacyContractClass;
+    use starknet_rs_core::types::contract::CompiledClass;
     use starknet_rs_core::types::{BlockId, BlockTag, ContractClass, Felt, StarknetError};
     use starknet_rs_providers::{Provider, ProviderError};
     use starknet_types::felt::felt_from_prefixed_hex;
 
     use crate::common::background_devnet::BackgroundDevnet;
-    use crate::common::constants::PREDEPLOYED_ACCOUNT_ADDRESS;
+    use crate::common::constants::{PREDEPLOYED_ACCOUNT_ADDRESS, RPC_V0_8_PATH};
     use crate::common::utils::{
         assert_cairo1_classes_equal, get_events_contract_in_sierra_and_compiled_class_hash,
     };
@@ -338,4 +341,64 @@ mod get_class_tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_getting_compiled_casm_of_declared_cairo1_contract() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        let predeployed_account = Arc::new(SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer.clone(),
+            account_address,
+            chain_id::SEPOLIA,
+            ExecutionEncoding::New,
+        ));
+
+        let (contract_class, casm_class_hash) =
+            get_events_contract_in_sierra_and_compiled_class_hash();
+
+        // declare the contract
+        let declaration_result = predeployed_account
+            .declare_v2(Arc::new(contract_class), casm_class_hash)
+            .max_fee(Felt::from(1e18 as u128))
+            .send()
+            .await
+            .unwrap();
+
+        for params in [
+            json!({ "class_hash": declaration_result.class_hash }),
+            json!({ "class_hash": declaration_result.class_hash, "block_id": "pending" }),
+        ] {
+            let casm = devnet
+                .send_custom_rpc_at_path(RPC_V0_8_PATH, "starknet_getCompiledCasm", params)
+                .await
+                .unwrap();
+            let casm: CompiledClass = serde_json::from_value(casm).unwrap();
+            assert_eq!(casm.class_hash().unwrap(), casm_class_hash);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_getting_compiled_casm_of_undeclared_class() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let err = devnet
+            .send_custom_rpc_at_path(
+                RPC_V0_8_PATH,
+                "starknet_getCompiledCasm",
+                json!({ "class_hash": "0x22" }),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            RpcError {
+                code: ErrorCode::ServerError(28),
+                message: "Class hash not found".into(),
+                data: None
+            }
+        );
+    }
 }
@@ -0,0 +1,128 @@
+#![cfg(test)]
+pub mod common;
+
+mod mempool_tests {
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_devnet_client::Error;
+    use starknet_rs_accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
+    use starknet_rs_core::types::{Call, Felt, TransactionStatus};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_rs_providers::jsonrpc::HttpTransport;
+    use starknet_rs_providers::{JsonRpcClient, Provider};
+    use starknet_rs_signers::LocalWallet;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants;
+    use crate::common::utils::assert_tx_successful;
+
+    async fn spawn_devnet_with_mempool() -> BackgroundDevnet {
+        BackgroundDevnet::spawn_with_additional_args(&[
+            "--mempool",
+            "fifo",
+            "--block-generation-on",
+            "demand",
+        ])
+        .await
+        .unwrap()
+    }
+
+    async fn get_account(
+        devnet: &BackgroundDevnet,
+    ) -> SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> {
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        )
+    }
+
+    /// Sends a transfer of 1 WEI without waiting for its execution.
+    async fn send_transfer(
+        account: &SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
+        nonce: u32,
+    ) -> Felt {
+        let transfer = Call {
+            to: ETH_ERC20_CONTRACT_ADDRESS,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+        };
+        account
+            .execute_v1(vec![transfer])
+            .max_fee(Felt::from(1e18 as u128))
+            .nonce(Felt::from(nonce))
+            .send()
+            .await
+            .unwrap()
+            .transaction_hash
+    }
+
+    #[tokio::test]
+    async fn transaction_with_nonce_gap_held_until_gap_filled() {
+        let devnet = spawn_devnet_with_mempool().await;
+        let account = get_account(&devnet).await;
+
+        let second_hash = send_transfer(&account, 1).await;
+        devnet.create_block().await.unwrap();
+
+        let mempool = devnet.devnet_client.get_mempool().await.unwrap();
+        assert_eq!(mempool.len(), 1);
+        assert_eq!(mempool[0].transaction_hash, second_hash);
+        assert!(!mempool[0].ready);
+        assert_eq!(
+            devnet.json_rpc_client.get_transaction_status(second_hash).await.unwrap(),
+            TransactionStatus::Received
+        );
+
+        let first_hash = send_transfer(&account, 0).await;
+        let mempool = devnet.devnet_client.get_mempool().await.unwrap();
+        assert_eq!(mempool.len(), 2);
+        assert!(mempool.iter().all(|tx| tx.ready));
+
+        devnet.create_block().await.unwrap();
+        assert!(devnet.devnet_client.get_mempool().await.unwrap().is_empty());
+
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.transactions, vec![first_hash, second_hash]);
+        for tx_hash in latest_block.transactions {
+            assert_tx_successful(&tx_hash, &devnet.json_rpc_client).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn dropped_transactions_not_executed() {
+        let devnet = spawn_devnet_with_mempool().await;
+        let account = get_account(&devnet).await;
+
+        let first_hash = send_transfer(&account, 0).await;
+        let second_hash = send_transfer(&account, 1).await;
+
+        let dropped =
+            devnet.devnet_client.drop_mempool_transactions(Some(vec![second_hash])).await.unwrap();
+        assert_eq!(dropped, vec![second_hash]);
+
+        devnet.create_block().await.unwrap();
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.transactions, vec![first_hash]);
+
+        // the nonce of the dropped transaction can be reused
+        let third_hash = send_transfer(&account, 1).await;
+        let dropped = devnet.devnet_client.drop_mempool_transactions(None).await.unwrap();
+        assert_eq!(dropped, vec![third_hash]);
+        assert!(devnet.devnet_client.get_mempool().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dropping_without_mempool_rejected() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        match devnet.devnet_client.drop_mempool_transactions(None).await {
+            Err(Error::RpcError { message, .. }) => {
+                assert!(message.contains("--mempool"))
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+}
@@ -24,7 +24,7 @@ mod test_messaging {
     };
     use starknet_rs_contract::ContractFactory;
     use starknet_rs_core::types::{
-        BlockId, BlockTag, Call, Felt, FunctionCall, InvokeTransactionResult,
+        BlockId, BlockStatus, BlockTag, Call, Felt, FunctionCall, InvokeTransactionResult,
         TransactionExecutionStatus, TransactionReceipt, TransactionReceiptWithBlockInfo,
     };
     use starknet_rs_core::utils::{
@@ -39,7 +39,7 @@ mod test_messaging {
     use crate::common::background_devnet::BackgroundDevnet;
     use crate::common::constants::{
         CHAIN_ID, L1_HANDLER_SELECTOR, MESSAGING_L1_CONTRACT_ADDRESS,
-        MESSAGING_L2_CONTRACT_ADDRESS, MESSAGING_WHITELISTED_L1_CONTRACT,
+        MESSAGING_L2_CONTRACT_ADDRESS, MESSAGING_WHITELISTED_L1_CONTRACT, RPC_V0_8_PATH,
     };
     use crate::common::utils::{
         assert_tx_successful, felt_to_u256,
@@ -354,6 +354,37 @@ mod test_messaging {
         }
     }
 
+    #[tokio::test]
+    async fn mock_message_to_l2_is_retrievable_by_l1_transaction_hash() {
+        let (devnet, _, l1l2_contract_address) = setup_devnet(&["--account-class", "cairo1"]).await;
+        let l1_transaction_hash = format!("0x{:064x}", 0xabc);
+
+        let body = devnet.send_custom_rpc("devnet_postmanSendMessageToL2", json!({
+            "l1_contract_address": MESSAGING_L1_ADDRESS,
+            "l2_contract_address": format!("0x{:64x}", l1l2_contract_address),
+            "entry_point_selector": format!("0x{:64x}", get_selector_from_name("deposit").unwrap()),
+            "payload": ["0x1", "0x1"],
+            "paid_fee_on_l1": "0x1234",
+            "nonce": "0x1",
+            "l1_transaction_hash": l1_transaction_hash,
+        }))
+        .await.unwrap();
+        let tx_hash = body.get("transaction_hash").unwrap();
+
+        let statuses = devnet
+            .send_custom_rpc_at_path(
+                RPC_V0_8_PATH,
+                "starknet_getMessagesStatus",
+                json!({ "transaction_hash": l1_transaction_hash }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            statuses,
+            json!([{ "transaction_hash": tx_hash, "finality_status": "ACCEPTED_ON_L2" }])
+        );
+    }
+
     #[tokio::test]
     async fn can_deploy_l1_messaging_contract() {
         let anvil = BackgroundAnvil::spawn().await.unwrap();
@@ -670,6 +701,35 @@ mod test_messaging {
         };
     }
 
+    #[tokio::test]
+    async fn flushing_accepts_blocks_on_l1_if_configured() {
+        let anvil = BackgroundAnvil::spawn().await.unwrap();
+        let (devnet, _, _) = setup_devnet(&["--accept-on-l1-on-flush"]).await;
+        devnet
+            .send_custom_rpc("devnet_postmanLoad", json!({ "network_url": anvil.url }))
+            .await
+            .unwrap();
+
+        // dry run doesn't accept
+        devnet.send_custom_rpc("devnet_postmanFlush", json!({ "dry_run": true })).await.unwrap();
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.status, BlockStatus::AcceptedOnL2);
+
+        devnet.send_custom_rpc("devnet_postmanFlush", json!({})).await.unwrap();
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(latest_block.status, BlockStatus::AcceptedOnL1);
+
+        let deployment_hash = latest_block.transactions[0];
+        let status = devnet
+            .send_custom_rpc(
+                "starknet_getTransactionStatus",
+                json!({ "transaction_hash": deployment_hash }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(status["finality_status"], "ACCEPTED_ON_L1");
+    }
+
     #[tokio::test]
     async fn flushing_only_new_messages_after_restart() {
         let anvil = BackgroundAnvil::spawn().await.unwrap();
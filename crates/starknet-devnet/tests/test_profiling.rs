@@ -0,0 +1,169 @@
+#![cfg(test)]
+pub mod common;
+
+mod profiling_tests {
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_devnet_client::Error;
+    use starknet_rs_accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
+    use starknet_rs_core::types::{BlockId as ImportedBlockId, BlockTag, Call, Felt};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_rs_providers::Provider;
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::rpc::block::BlockId;
+    use starknet_types::rpc::devnet::{
+        CallProfile, InvocationPhase, ProfileMetric, ProfileTransactionRequest, ProfiledSimulation,
+        TransactionProfile,
+    };
+    use starknet_types::rpc::transactions::SimulationFlag;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants;
+    use crate::common::utils::assert_tx_successful;
+
+    fn transfer_call() -> Call {
+        Call {
+            to: ETH_ERC20_CONTRACT_ADDRESS,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+        }
+    }
+
+    /// Returns the top-level call executed in the given phase
+    fn invocation_of(profile: &TransactionProfile, phase: InvocationPhase) -> &CallProfile {
+        profile
+            .invocations
+            .iter()
+            .find(|invocation| invocation.phase == phase)
+            .map(|invocation| &invocation.call)
+            .unwrap()
+    }
+
+    fn assert_transfer_profiled(profile: &TransactionProfile) {
+        let execute = invocation_of(profile, InvocationPhase::Execute);
+        assert_eq!(execute.function_name.as_deref(), Some("__execute__"));
+
+        let transfer = execute
+            .calls
+            .iter()
+            .find(|call| call.function_name.as_deref() == Some("transfer"))
+            .unwrap();
+        assert_eq!(
+            transfer.contract_address,
+            ContractAddress::new(ETH_ERC20_CONTRACT_ADDRESS).unwrap()
+        );
+        assert_eq!(transfer.contract_name.as_deref(), Some("ERC20"));
+        assert!(transfer.resources.steps > 0);
+        assert!(transfer.resources.l1_gas > 0);
+        assert!(execute.resources.steps > transfer.resources.steps);
+        assert!(profile.total.steps > execute.resources.steps);
+    }
+
+    #[tokio::test]
+    async fn executed_transaction_profiled() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        let account = SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        );
+
+        let transaction_hash =
+            account.execute_v1(vec![transfer_call()]).send().await.unwrap().transaction_hash;
+        assert_tx_successful(&transaction_hash, &devnet.json_rpc_client).await;
+
+        let request = ProfileTransactionRequest {
+            transaction_hash: Some(transaction_hash),
+            ..Default::default()
+        };
+        let profile = devnet.devnet_client.profile_transaction(&request).await.unwrap();
+        let phases: Vec<_> =
+            profile.invocations.iter().map(|invocation| invocation.phase).collect();
+        assert_eq!(
+            phases,
+            [InvocationPhase::Validate, InvocationPhase::Execute, InvocationPhase::FeeTransfer]
+        );
+        assert_transfer_profiled(&profile);
+
+        let folded = devnet.devnet_client.profile_transaction_folded(&request).await.unwrap();
+        assert!(folded
+            .lines()
+            .any(|line| line.starts_with("execute;") && line.contains(";ERC20::transfer ")));
+        assert!(folded.lines().any(|line| line.starts_with("fee_transfer;ERC20::transfer ")));
+        let total_steps: usize = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().1.parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(total_steps, profile.total.steps);
+
+        let folded_data_gas = devnet
+            .devnet_client
+            .profile_transaction_folded(&ProfileTransactionRequest {
+                metric: ProfileMetric::L1DataGas,
+                ..request
+            })
+            .await
+            .unwrap();
+        // calls are not charged for data availability
+        assert!(folded_data_gas.lines().all(|line| !line.contains("::")));
+    }
+
+    #[tokio::test]
+    async fn simulated_transaction_profiled() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (_, account_address) = devnet.get_first_predeployed_account().await;
+
+        let transfer = transfer_call();
+        // the account is called with the new encoding of calls
+        let transaction = serde_json::from_value(serde_json::json!({
+            "type": "INVOKE",
+            "version": "0x1",
+            "sender_address": account_address,
+            "calldata": [
+                Felt::ONE, // number of calls
+                transfer.to,
+                transfer.selector,
+                Felt::from(transfer.calldata.len()),
+                transfer.calldata[0],
+                transfer.calldata[1],
+                transfer.calldata[2],
+            ],
+            "max_fee": Felt::from(1e18 as u128),
+            "nonce": Felt::ZERO,
+            "signature": [],
+        }))
+        .unwrap();
+
+        let request = ProfileTransactionRequest {
+            simulation: Some(ProfiledSimulation {
+                block_id: BlockId::from(ImportedBlockId::Tag(BlockTag::Latest)),
+                transaction,
+                simulation_flags: vec![SimulationFlag::SkipValidate],
+            }),
+            ..Default::default()
+        };
+        let profile = devnet.devnet_client.profile_transaction(&request).await.unwrap();
+        assert_transfer_profiled(&profile);
+
+        // the simulated transaction was not executed
+        let nonce = devnet
+            .json_rpc_client
+            .get_nonce(ImportedBlockId::Tag(BlockTag::Latest), account_address)
+            .await
+            .unwrap();
+        assert_eq!(nonce, Felt::ZERO);
+    }
+
+    #[tokio::test]
+    async fn transaction_hash_and_simulation_are_exclusive() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        match devnet.devnet_client.profile_transaction(&ProfileTransactionRequest::default()).await
+        {
+            Err(Error::RpcError { message, .. }) => assert!(message.contains("Exactly one")),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+}
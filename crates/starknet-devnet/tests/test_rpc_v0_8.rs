@@ -0,0 +1,207 @@
+#![cfg(test)]
+pub mod common;
+
+mod rpc_v0_8_tests {
+    use serde_json::json;
+    use server::rpc_core::error::RpcError;
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_rs_core::types::Felt;
+    use starknet_rs_core::utils::get_selector_from_name;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants::{RPC_PATH, RPC_V0_8_PATH};
+    use crate::common::utils::to_hex_felt;
+
+    const DUMMY_ADDRESS: u128 = 1;
+    const DUMMY_AMOUNT: u128 = 1;
+
+    #[tokio::test]
+    async fn spec_version_depends_on_path() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        for (path, expected_version) in [(RPC_PATH, "0.7.1"), (RPC_V0_8_PATH, "0.8.0")] {
+            let version = devnet
+                .send_custom_rpc_at_path(path, "starknet_specVersion", json!([]))
+                .await
+                .unwrap();
+            assert_eq!(version, expected_version);
+        }
+    }
+
+    #[tokio::test]
+    async fn block_header_contains_l2_gas_price_only_in_v0_8() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let params = json!({ "block_id": "latest" });
+
+        let block = devnet
+            .send_custom_rpc_at_path(RPC_PATH, "starknet_getBlockWithTxHashes", params.clone())
+            .await
+            .unwrap();
+        assert!(block.get("l2_gas_price").is_none());
+
+        let block = devnet
+            .send_custom_rpc_at_path(RPC_V0_8_PATH, "starknet_getBlockWithTxHashes", params)
+            .await
+            .unwrap();
+        assert_eq!(block["l2_gas_price"], json!({ "price_in_fri": "0x0", "price_in_wei": "0x0" }));
+    }
+
+    #[tokio::test]
+    async fn receipt_and_status_in_v0_8() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mint_hash = devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let params = json!({ "transaction_hash": mint_hash });
+
+        let receipt = devnet
+            .send_custom_rpc_at_path(
+                RPC_V0_8_PATH,
+                "starknet_getTransactionReceipt",
+                params.clone(),
+            )
+            .await
+            .unwrap();
+        let execution_resources = receipt["execution_resources"].as_object().unwrap();
+        let mut resource_names: Vec<_> = execution_resources.keys().collect();
+        resource_names.sort();
+        assert_eq!(resource_names, ["l1_data_gas", "l1_gas", "l2_gas"]);
+        assert!(execution_resources["l1_gas"].as_u64().unwrap() > 0);
+        assert_eq!(execution_resources["l2_gas"], 0);
+
+        let status = devnet
+            .send_custom_rpc_at_path(RPC_V0_8_PATH, "starknet_getTransactionStatus", params)
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            json!({ "finality_status": "ACCEPTED_ON_L2", "execution_status": "SUCCEEDED" })
+        );
+    }
+
+    #[tokio::test]
+    async fn simulation_in_v0_8() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (_, account_address) = devnet.get_first_predeployed_account().await;
+
+        let transfer_call = [
+            Felt::ONE, // number of calls
+            ETH_ERC20_CONTRACT_ADDRESS,
+            get_selector_from_name("transfer").unwrap(),
+            Felt::THREE, // length of calldata
+            Felt::from(DUMMY_ADDRESS),
+            Felt::from(DUMMY_AMOUNT), // low
+            Felt::ZERO,               // high
+        ];
+        let params = json!({
+            "block_id": "latest",
+            "transactions": [{
+                "type": "INVOKE",
+                "version": "0x1",
+                "max_fee": "0xde0b6b3a7640000",
+                "signature": [],
+                "nonce": "0x0",
+                "sender_address": to_hex_felt(&account_address),
+                "calldata": transfer_call.iter().map(to_hex_felt).collect::<Vec<_>>(),
+            }],
+            "simulation_flags": ["SKIP_VALIDATE"],
+        });
+
+        let simulation = &devnet
+            .send_custom_rpc_at_path(RPC_V0_8_PATH, "starknet_simulateTransactions", params)
+            .await
+            .unwrap()[0];
+
+        let fee_estimation = &simulation["fee_estimation"];
+        for property in ["l1_gas_consumed", "l1_data_gas_price", "overall_fee"] {
+            assert!(fee_estimation[property].is_string(), "{property} missing");
+        }
+        assert_eq!(fee_estimation["l2_gas_consumed"], "0x0");
+        assert!(fee_estimation.get("gas_consumed").is_none());
+
+        let trace = &simulation["transaction_trace"];
+        assert_eq!(trace["execution_resources"]["l2_gas"], 0);
+        let execute_invocation = &trace["execute_invocation"];
+        assert_eq!(execute_invocation["is_reverted"], false);
+        assert_eq!(execute_invocation["execution_resources"], json!({ "l1_gas": 0, "l2_gas": 0 }));
+    }
+
+    #[tokio::test]
+    async fn messages_status_is_only_in_v0_8() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let params = json!({ "transaction_hash": format!("0x{:064x}", 1) });
+
+        let error = devnet
+            .send_custom_rpc_at_path(RPC_PATH, "starknet_getMessagesStatus", params.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(error, RpcError::method_not_found());
+
+        let error = devnet
+            .send_custom_rpc_at_path(RPC_V0_8_PATH, "starknet_getMessagesStatus", params)
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, "Transaction hash not found");
+    }
+
+    #[tokio::test]
+    async fn compiled_casm_is_only_in_v0_8() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let params = json!({ "class_hash": "0x22" });
+
+        let error = devnet
+            .send_custom_rpc_at_path(RPC_PATH, "starknet_getCompiledCasm", params.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(error, RpcError::method_not_found());
+
+        let error = devnet
+            .send_custom_rpc_at_path(RPC_V0_8_PATH, "starknet_getCompiledCasm", params)
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, "Class hash not found");
+    }
+
+    #[tokio::test]
+    async fn l1_data_gas_bounds_are_only_accepted_in_v0_8() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (_, account_address) = devnet.get_first_predeployed_account().await;
+
+        let params = json!({
+            "block_id": "latest",
+            "request": [{
+                "type": "INVOKE",
+                "version": "0x3",
+                "signature": [],
+                "nonce": "0x0",
+                "resource_bounds": {
+                    "l1_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+                    "l2_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+                    "l1_data_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+                },
+                "tip": "0x0",
+                "paymaster_data": [],
+                "nonce_data_availability_mode": "L1",
+                "fee_data_availability_mode": "L1",
+                "sender_address": to_hex_felt(&account_address),
+                "calldata": [],
+                "account_deployment_data": [],
+            }],
+            "simulation_flags": ["SKIP_VALIDATE"],
+        });
+
+        let error = devnet
+            .send_custom_rpc_at_path(RPC_PATH, "starknet_estimateFee", params.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RpcError::invalid_params("L1 data gas bounds are only supported in JSON-RPC 0.8")
+        );
+
+        // on the 0.8 path, the request is executed and fails due to the empty calldata
+        let error = devnet
+            .send_custom_rpc_at_path(RPC_V0_8_PATH, "starknet_estimateFee", params)
+            .await
+            .unwrap_err();
+        assert_ne!(error.message, "L1 data gas bounds are only supported in JSON-RPC 0.8");
+    }
+}
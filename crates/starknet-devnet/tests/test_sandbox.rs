@@ -0,0 +1,217 @@
+#![cfg(test)]
+pub mod common;
+
+mod sandbox_tests {
+    use std::time;
+
+    use starknet_devnet_client::Error;
+    use starknet_rs_core::types::{BlockTag, Felt};
+    use starknet_rs_providers::jsonrpc::HttpTransport;
+    use starknet_rs_providers::{JsonRpcClient, Provider};
+    use starknet_types::contract_address::ContractAddress;
+    use starknet_types::num_bigint::BigUint;
+    use starknet_types::rpc::devnet::{
+        BalanceQuery, MintTokensRequest, SandboxConfig, SandboxCreationRequest,
+    };
+    use starknet_types::rpc::transaction_receipt::FeeUnit;
+    use url::Url;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+
+    fn mint_request(address: Felt, amount: u32) -> MintTokensRequest {
+        MintTokensRequest {
+            address: ContractAddress::new(address).unwrap(),
+            amount: BigUint::from(amount),
+            unit: Some(FeeUnit::WEI),
+        }
+    }
+
+    fn balance_query(address: Felt) -> BalanceQuery {
+        BalanceQuery { address, unit: Some(FeeUnit::WEI), block_tag: Some(BlockTag::Latest) }
+    }
+
+    #[tokio::test]
+    async fn sandbox_state_is_isolated_from_host() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let info = devnet.devnet_client.create_sandbox(&Default::default()).await.unwrap();
+        assert_eq!(info.rpc_path, format!("/sandbox/{}/rpc", info.sandbox_id));
+        let sandbox = devnet.devnet_client.sandbox(info.sandbox_id);
+
+        let address = Felt::from(0x1234);
+        let minted = sandbox.mint(&mint_request(address, 1000)).await.unwrap();
+        assert_eq!(minted.new_balance, "1000");
+        sandbox.create_block().await.unwrap();
+
+        let host_balance =
+            devnet.devnet_client.get_account_balance(&balance_query(address)).await.unwrap();
+        assert_eq!(host_balance.amount, "0");
+        // host only has its genesis block
+        assert_eq!(devnet.json_rpc_client.block_number().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn sandbox_copied_from_another_sandbox_has_its_state() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let client = &devnet.devnet_client;
+
+        let source_info = client.create_sandbox(&Default::default()).await.unwrap();
+        let source = client.sandbox(source_info.sandbox_id);
+        let address = Felt::from(0x1234);
+        source.mint(&mint_request(address, 1000)).await.unwrap();
+
+        let copy_info = client
+            .create_sandbox(&SandboxCreationRequest {
+                from_sandbox: Some(source_info.sandbox_id),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_ne!(copy_info.sandbox_id, source_info.sandbox_id);
+        let copy = client.sandbox(copy_info.sandbox_id);
+        assert_eq!(copy.get_account_balance(&balance_query(address)).await.unwrap().amount, "1000");
+
+        // further changes of the copy don't affect the source
+        copy.mint(&mint_request(address, 1000)).await.unwrap();
+        assert_eq!(copy.get_account_balance(&balance_query(address)).await.unwrap().amount, "2000");
+        assert_eq!(
+            source.get_account_balance(&balance_query(address)).await.unwrap().amount,
+            "1000"
+        );
+    }
+
+    #[tokio::test]
+    async fn copying_from_sandbox_rejects_initial_state_config() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let client = &devnet.devnet_client;
+
+        let source_info = client.create_sandbox(&Default::default()).await.unwrap();
+        let result = client
+            .create_sandbox(&SandboxCreationRequest {
+                from_sandbox: Some(source_info.sandbox_id),
+                config: SandboxConfig { seed: Some(42), ..Default::default() },
+            })
+            .await;
+
+        match result {
+            Err(Error::RpcError { message, .. }) => assert!(message.contains("copied")),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn sandbox_config_overrides_predeployed_accounts() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let info = devnet
+            .devnet_client
+            .create_sandbox(&SandboxCreationRequest {
+                from_sandbox: None,
+                config: SandboxConfig {
+                    total_accounts: Some(2),
+                    initial_balance: Some("123".into()),
+                    ..Default::default()
+                },
+            })
+            .await
+            .unwrap();
+
+        let accounts = devnet
+            .devnet_client
+            .sandbox(info.sandbox_id)
+            .get_predeployed_accounts(false)
+            .await
+            .unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].initial_balance, "123");
+    }
+
+    #[tokio::test]
+    async fn sandbox_has_its_own_restricted_methods() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let info = devnet
+            .devnet_client
+            .create_sandbox(&SandboxCreationRequest {
+                from_sandbox: None,
+                config: SandboxConfig {
+                    restricted_methods: Some(vec!["devnet_mint".into()]),
+                    ..Default::default()
+                },
+            })
+            .await
+            .unwrap();
+        assert_eq!(info.restricted_methods, Some(vec!["devnet_mint".to_string()]));
+
+        let address = Felt::from(0x1234);
+        match devnet.devnet_client.sandbox(info.sandbox_id).mint(&mint_request(address, 1)).await {
+            Err(Error::RpcError { code, .. }) => assert_eq!(code, -32604),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+
+        // the host is not restricted
+        devnet.devnet_client.mint(&mint_request(address, 1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sandbox_generates_blocks_on_interval_of_host() {
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&["--block-generation-on", "2"])
+            .await
+            .unwrap();
+
+        let info = devnet.devnet_client.create_sandbox(&Default::default()).await.unwrap();
+        let sandbox_rpc_url = Url::parse(&format!("{}{}", devnet.url, info.rpc_path)).unwrap();
+        let sandbox_rpc_client = JsonRpcClient::new(HttpTransport::new(sandbox_rpc_url));
+
+        // wait for one and a half interval
+        tokio::time::sleep(time::Duration::from_secs(3)).await;
+
+        // first is genesis block, second block is generated after the interval
+        assert_eq!(sandbox_rpc_client.block_number().await.unwrap(), 1);
+
+        devnet.devnet_client.destroy_sandbox(info.sandbox_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sandboxes_listed_and_destroyed() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let client = &devnet.devnet_client;
+
+        let first = client.create_sandbox(&Default::default()).await.unwrap();
+        let second = client.create_sandbox(&Default::default()).await.unwrap();
+        let listed: Vec<_> =
+            client.get_sandboxes().await.unwrap().into_iter().map(|s| s.sandbox_id).collect();
+        assert_eq!(listed, vec![first.sandbox_id, second.sandbox_id]);
+
+        client.destroy_sandbox(first.sandbox_id).await.unwrap();
+        let listed: Vec<_> =
+            client.get_sandboxes().await.unwrap().into_iter().map(|s| s.sandbox_id).collect();
+        assert_eq!(listed, vec![second.sandbox_id]);
+
+        let resp = reqwest::Client::new()
+            .post(format!("{}/sandbox/{}/rpc", devnet.url, first.sandbox_id))
+            .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 0, "method": "devnet_createBlock" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+
+        match client.destroy_sandbox(first.sandbox_id).await {
+            Err(Error::RpcError { message, .. }) => assert!(message.contains("not found")),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn sandboxes_cannot_be_managed_from_sandbox() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let info = devnet.devnet_client.create_sandbox(&Default::default()).await.unwrap();
+        let sandbox = devnet.devnet_client.sandbox(info.sandbox_id);
+
+        match sandbox.create_sandbox(&Default::default()).await {
+            Err(Error::RpcError { message, .. }) => assert!(message.contains("hosting Devnet")),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+#![cfg(test)]
+pub mod common;
+
+mod snapshot_tests {
+    use serde_json::json;
+    use server::test_utils::assert_contains;
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt, StarknetError};
+    use starknet_rs_providers::{Provider, ProviderError};
+
+    use crate::common::background_devnet::BackgroundDevnet;
+
+    const DUMMY_ADDRESS: u128 = 1;
+    const DUMMY_AMOUNT: u128 = 100;
+
+    async fn take_snapshot(devnet: &BackgroundDevnet) -> u64 {
+        let snapshot = devnet.send_custom_rpc("devnet_snapshot", json!({})).await.unwrap();
+        snapshot["snapshot_id"].as_u64().unwrap()
+    }
+
+    async fn revert(devnet: &BackgroundDevnet, snapshot_id: u64) {
+        devnet
+            .send_custom_rpc("devnet_revert", json!({ "snapshot_id": snapshot_id }))
+            .await
+            .unwrap();
+    }
+
+    async fn get_dummy_balance(devnet: &BackgroundDevnet, block_id: BlockId) -> Felt {
+        devnet.get_balance_at_block(&Felt::from(DUMMY_ADDRESS), block_id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn revert_restores_state_and_removes_transactions() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let snapshot_id = take_snapshot(&devnet).await;
+
+        let mint_hash = devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(get_dummy_balance(&devnet, latest).await, Felt::from(2 * DUMMY_AMOUNT));
+
+        revert(&devnet, snapshot_id).await;
+        assert_eq!(get_dummy_balance(&devnet, latest).await, Felt::from(DUMMY_AMOUNT));
+        assert_eq!(devnet.json_rpc_client.block_number().await.unwrap(), 1);
+
+        match devnet.json_rpc_client.get_transaction_by_hash(mint_hash).await {
+            Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => (),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+
+        // Devnet continues working normally from the restored state
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        assert_eq!(get_dummy_balance(&devnet, latest).await, Felt::from(2 * DUMMY_AMOUNT));
+        assert_eq!(devnet.json_rpc_client.block_number().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn revert_restores_pending_block() {
+        let devnet =
+            BackgroundDevnet::spawn_with_additional_args(&["--block-generation-on", "demand"])
+                .await
+                .unwrap();
+
+        let mint_hash = devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let snapshot_id = take_snapshot(&devnet).await;
+
+        devnet.create_block().await.unwrap();
+        revert(&devnet, snapshot_id).await;
+
+        let pending_block = devnet.get_pending_block_with_tx_hashes().await.unwrap();
+        assert_eq!(pending_block.transactions, vec![mint_hash]);
+
+        let pending = BlockId::Tag(BlockTag::Pending);
+        assert_eq!(get_dummy_balance(&devnet, pending).await, Felt::from(DUMMY_AMOUNT));
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(get_dummy_balance(&devnet, latest).await, Felt::ZERO);
+    }
+
+    #[tokio::test]
+    async fn reverting_discards_later_snapshots() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let first_snapshot_id = take_snapshot(&devnet).await;
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        let second_snapshot_id = take_snapshot(&devnet).await;
+
+        revert(&devnet, first_snapshot_id).await;
+
+        for snapshot_id in [first_snapshot_id, second_snapshot_id] {
+            let error = devnet
+                .send_custom_rpc("devnet_revert", json!({ "snapshot_id": snapshot_id }))
+                .await
+                .unwrap_err();
+            assert_contains(&error.message, &format!("No snapshot with id {snapshot_id}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn restart_discards_snapshots() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let snapshot_id = take_snapshot(&devnet).await;
+        devnet.restart().await;
+
+        let error = devnet
+            .send_custom_rpc("devnet_revert", json!({ "snapshot_id": snapshot_id }))
+            .await
+            .unwrap_err();
+        assert_contains(&error.message, "No snapshot with id");
+    }
+}
@@ -0,0 +1,129 @@
+#![cfg(test)]
+pub mod common;
+
+mod state_manipulation_tests {
+    use serde_json::json;
+    use server::test_utils::assert_contains;
+    use starknet_core::constants::{
+        CAIRO_1_ACCOUNT_CONTRACT_SIERRA_HASH, ETH_ERC20_CONTRACT_ADDRESS,
+    };
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt, MaybePendingStateUpdate};
+    use starknet_rs_core::utils::get_storage_var_address;
+    use starknet_rs_providers::Provider;
+    use starknet_types::felt::felt_from_prefixed_hex;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::utils::UniqueAutoDeletableFile;
+
+    const DUMMY_ADDRESS: u128 = 1;
+
+    #[tokio::test]
+    async fn set_storage_at_modifies_balance() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let dummy_address = Felt::from(DUMMY_ADDRESS);
+        let storage_key = get_storage_var_address("ERC20_balances", &[dummy_address]).unwrap();
+        let new_balance = Felt::from(1234);
+        devnet
+            .send_custom_rpc(
+                "devnet_setStorageAt",
+                json!({
+                    "contract_address": ETH_ERC20_CONTRACT_ADDRESS,
+                    "key": storage_key,
+                    "value": new_balance,
+                }),
+            )
+            .await
+            .unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(devnet.get_balance_at_block(&dummy_address, latest).await.unwrap(), new_balance);
+
+        // the modification is included in a new block, like a transaction would be
+        assert_eq!(devnet.json_rpc_client.block_number().await.unwrap(), 1);
+        match devnet.json_rpc_client.get_state_update(latest).await.unwrap() {
+            MaybePendingStateUpdate::Update(state_update) => {
+                let storage_diffs = state_update.state_diff.storage_diffs;
+                assert_eq!(storage_diffs.len(), 1);
+                assert_eq!(storage_diffs[0].address, ETH_ERC20_CONTRACT_ADDRESS);
+                assert_eq!(storage_diffs[0].storage_entries[0].key, storage_key);
+                assert_eq!(storage_diffs[0].storage_entries[0].value, new_balance);
+            }
+            other => panic!("Unexpected state update: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn set_nonce_of_predeployed_account() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let (_, account_address) = devnet.get_first_predeployed_account().await;
+
+        let nonce = Felt::from(42);
+        devnet
+            .send_custom_rpc(
+                "devnet_setNonce",
+                json!({ "contract_address": account_address, "nonce": nonce }),
+            )
+            .await
+            .unwrap();
+
+        let retrieved_nonce = devnet
+            .json_rpc_client
+            .get_nonce(BlockId::Tag(BlockTag::Latest), account_address)
+            .await
+            .unwrap();
+        assert_eq!(retrieved_nonce, nonce);
+    }
+
+    #[tokio::test]
+    async fn set_class_hash_at_deploys_contract_and_is_preserved_by_dumping() {
+        let dump_file = UniqueAutoDeletableFile::new("set_class_hash_at_dump");
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&[
+            "--dump-path",
+            &dump_file.path,
+            "--dump-on",
+            "request",
+        ])
+        .await
+        .unwrap();
+
+        let contract_address = Felt::from(DUMMY_ADDRESS);
+        let class_hash = felt_from_prefixed_hex(CAIRO_1_ACCOUNT_CONTRACT_SIERRA_HASH).unwrap();
+        devnet
+            .send_custom_rpc(
+                "devnet_setClassHashAt",
+                json!({ "contract_address": contract_address, "class_hash": class_hash }),
+            )
+            .await
+            .unwrap();
+        devnet.send_custom_rpc("devnet_dump", json!({})).await.unwrap();
+
+        let loaded_devnet =
+            BackgroundDevnet::spawn_with_additional_args(&["--dump-path", &dump_file.path])
+                .await
+                .unwrap();
+
+        for devnet in [&devnet, &loaded_devnet] {
+            let retrieved_class_hash = devnet
+                .json_rpc_client
+                .get_class_hash_at(BlockId::Tag(BlockTag::Latest), contract_address)
+                .await
+                .unwrap();
+            assert_eq!(retrieved_class_hash, class_hash);
+        }
+    }
+
+    #[tokio::test]
+    async fn set_class_hash_at_fails_for_undeclared_class() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let error = devnet
+            .send_custom_rpc(
+                "devnet_setClassHashAt",
+                json!({ "contract_address": Felt::from(DUMMY_ADDRESS), "class_hash": "0x123" }),
+            )
+            .await
+            .unwrap_err();
+        assert_contains(&error.message, "No class hash 123 found");
+    }
+}
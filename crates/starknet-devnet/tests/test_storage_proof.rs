@@ -0,0 +1,173 @@
+#![cfg(test)]
+pub mod common;
+
+mod storage_proof_tests {
+    use serde_json::json;
+    use server::rpc_core::error::{ErrorCode, RpcError};
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_rs_core::crypto::pedersen_hash;
+    use starknet_rs_core::types::{BlockId, BlockTag, Felt};
+    use starknet_rs_core::utils::get_storage_var_address;
+    use starknet_rs_crypto::poseidon_hash_many;
+    use starknet_rs_providers::Provider;
+    use starknet_types::rpc::storage_proof::{MerkleNode, NodeHashToNodeMapping, StorageProof};
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants::{RPC_PATH, RPC_V0_8_PATH};
+
+    const DUMMY_ADDRESS: u128 = 1;
+    const DUMMY_AMOUNT: u128 = 1234;
+
+    /// Follows the path to `key` from `root` through the nodes of a storage trie proof and returns
+    /// the proven value, which is zero if the key is proven not to be in the trie
+    fn get_proven_value(root: Felt, key: Felt, proof: &NodeHashToNodeMapping) -> Felt {
+        let key_bits = key.to_bits_be();
+        let mut node_hash = root;
+        let mut depth = 256 - 251;
+        while depth < 256 {
+            let node = &proof.iter().find(|item| item.node_hash == node_hash).unwrap().node;
+            match node {
+                MerkleNode::Binary(binary) => {
+                    assert_eq!(pedersen_hash(&binary.left, &binary.right), node_hash);
+                    node_hash = if key_bits[depth] { binary.right } else { binary.left };
+                    depth += 1;
+                }
+                MerkleNode::Edge(edge) => {
+                    let length = Felt::from(edge.length);
+                    assert_eq!(pedersen_hash(&edge.child, &edge.path) + length, node_hash);
+
+                    let path_bits = edge.path.to_bits_be();
+                    if key_bits[depth..depth + edge.length] != path_bits[256 - edge.length..] {
+                        return Felt::ZERO;
+                    }
+                    node_hash = edge.child;
+                    depth += edge.length;
+                }
+            }
+        }
+
+        node_hash
+    }
+
+    #[tokio::test]
+    async fn storage_proof_of_minted_balance() {
+        let devnet = BackgroundDevnet::spawn_with_additional_args(&[
+            "--state-archive-capacity",
+            "full",
+            "--calculate-commitments",
+        ])
+        .await
+        .unwrap();
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+
+        let balance_key =
+            get_storage_var_address("ERC20_balances", &[Felt::from(DUMMY_ADDRESS)]).unwrap();
+        let absent_key = Felt::from(DUMMY_ADDRESS);
+        let storage_proof = devnet
+            .send_custom_rpc_at_path(
+                RPC_V0_8_PATH,
+                "starknet_getStorageProof",
+                json!({
+                    "block_id": "latest",
+                    "contract_addresses": [ETH_ERC20_CONTRACT_ADDRESS],
+                    "contracts_storage_keys": [{
+                        "contract_address": ETH_ERC20_CONTRACT_ADDRESS,
+                        "storage_keys": [balance_key, absent_key],
+                    }],
+                }),
+            )
+            .await
+            .unwrap();
+        let storage_proof: StorageProof = serde_json::from_value(storage_proof).unwrap();
+
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        let global_roots = &storage_proof.global_roots;
+        assert_eq!(global_roots.block_hash, latest_block.block_hash);
+        assert_eq!(
+            poseidon_hash_many(&[
+                Felt::from_bytes_be_slice(b"STARKNET_STATE_V0"),
+                global_roots.contracts_tree_root,
+                global_roots.classes_tree_root,
+            ]),
+            latest_block.new_root
+        );
+
+        let contract_leaf = &storage_proof.contracts_proof.contract_leaves_data[0];
+        let class_hash = devnet
+            .json_rpc_client
+            .get_class_hash_at(BlockId::Tag(BlockTag::Latest), ETH_ERC20_CONTRACT_ADDRESS)
+            .await
+            .unwrap();
+        assert_eq!(contract_leaf.class_hash, class_hash);
+
+        let storage_trie_proof = &storage_proof.contracts_storage_proofs[0];
+        assert_eq!(
+            get_proven_value(contract_leaf.storage_root, balance_key, storage_trie_proof),
+            Felt::from(DUMMY_AMOUNT)
+        );
+        assert_eq!(
+            get_proven_value(contract_leaf.storage_root, absent_key, storage_trie_proof),
+            Felt::ZERO
+        );
+    }
+
+    #[tokio::test]
+    async fn storage_proof_not_supported_without_full_state_archive() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let error = devnet
+            .send_custom_rpc_at_path(
+                RPC_V0_8_PATH,
+                "starknet_getStorageProof",
+                json!({ "block_id": "latest" }),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RpcError {
+                code: ErrorCode::ServerError(42),
+                message: "the node doesn't support storage proofs for blocks that are too far in \
+                          the past"
+                    .into(),
+                data: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn storage_proof_not_supported_without_calculating_commitments() {
+        let devnet =
+            BackgroundDevnet::spawn_with_additional_args(&["--state-archive-capacity", "full"])
+                .await
+                .unwrap();
+
+        let error = devnet
+            .send_custom_rpc_at_path(
+                RPC_V0_8_PATH,
+                "starknet_getStorageProof",
+                json!({ "block_id": "latest" }),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+    }
+
+    #[tokio::test]
+    async fn storage_proof_is_only_in_v0_8() {
+        let devnet =
+            BackgroundDevnet::spawn_with_additional_args(&["--state-archive-capacity", "full"])
+                .await
+                .unwrap();
+
+        let error = devnet
+            .send_custom_rpc_at_path(
+                RPC_PATH,
+                "starknet_getStorageProof",
+                json!({ "block_id": "latest" }),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error, RpcError::method_not_found());
+    }
+}
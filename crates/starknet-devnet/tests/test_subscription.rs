@@ -0,0 +1,297 @@
+#![cfg(test)]
+pub mod common;
+
+mod subscription_tests {
+    use std::time::Duration;
+
+    use futures::{SinkExt, StreamExt};
+    use serde_json::json;
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_rs_core::types::{BlockId, Felt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::common::background_devnet::{BackgroundDevnet, WsStream};
+    use crate::common::utils::to_hex_felt;
+
+    const DUMMY_ADDRESS: u128 = 1;
+    const DUMMY_AMOUNT: u128 = 1;
+
+    async fn send_ws_request(
+        ws: &mut WsStream,
+        method: &str,
+        params: serde_json::Value,
+    ) -> serde_json::Value {
+        let request_id = 42;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "method": method,
+            "params": params,
+        });
+        ws.send(Message::Text(request.to_string())).await.unwrap();
+
+        let response = receive_ws_message(ws).await;
+        assert_eq!(response["id"], request_id);
+        response
+    }
+
+    async fn receive_ws_message(ws: &mut WsStream) -> serde_json::Value {
+        let message = tokio::time::timeout(Duration::from_secs(5), ws.next())
+            .await
+            .expect("No websocket message received")
+            .unwrap()
+            .unwrap();
+
+        serde_json::from_str(message.to_text().unwrap()).unwrap()
+    }
+
+    async fn subscribe(ws: &mut WsStream, method: &str, params: serde_json::Value) -> u64 {
+        let response = send_ws_request(ws, method, params).await;
+        response["result"].as_u64().unwrap()
+    }
+
+    /// Returns the result of the notification
+    async fn receive_notification(
+        ws: &mut WsStream,
+        method: &str,
+        subscription_id: u64,
+    ) -> serde_json::Value {
+        let mut notification = receive_ws_message(ws).await;
+        assert_eq!(notification["method"], method);
+        assert_eq!(notification["params"]["subscription_id"], subscription_id);
+        notification["params"]["result"].take()
+    }
+
+    async fn assert_no_notifications(ws: &mut WsStream) {
+        let received = tokio::time::timeout(Duration::from_millis(500), ws.next()).await;
+        if let Ok(message) = received {
+            panic!("Unexpected websocket message: {message:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn regular_json_rpc_request_via_websocket() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        devnet.create_block().await.unwrap();
+        let response = send_ws_request(&mut ws, "starknet_blockNumber", json!({})).await;
+        assert_eq!(response["result"], 1);
+    }
+
+    #[tokio::test]
+    async fn new_heads_notification_on_block_creation() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        let subscription_id = subscribe(&mut ws, "starknet_subscribeNewHeads", json!({})).await;
+        assert_no_notifications(&mut ws).await;
+
+        let created_block_hash = devnet.create_block().await.unwrap();
+        let header =
+            receive_notification(&mut ws, "starknet_subscriptionNewHeads", subscription_id).await;
+        assert_eq!(header["block_hash"], to_hex_felt(&created_block_hash));
+        assert_eq!(header["block_number"], 1);
+
+        assert_no_notifications(&mut ws).await;
+    }
+
+    #[tokio::test]
+    async fn new_heads_subscription_with_block_id_notifies_of_older_blocks() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        devnet.create_block().await.unwrap();
+        devnet.create_block().await.unwrap();
+
+        let subscription_id = subscribe(
+            &mut ws,
+            "starknet_subscribeNewHeads",
+            json!({ "block_id": BlockId::Number(0) }),
+        )
+        .await;
+
+        for expected_block_number in 0..=2 {
+            let header =
+                receive_notification(&mut ws, "starknet_subscriptionNewHeads", subscription_id)
+                    .await;
+            assert_eq!(header["block_number"], expected_block_number);
+        }
+
+        assert_no_notifications(&mut ws).await;
+    }
+
+    #[tokio::test]
+    async fn no_notifications_after_unsubscribing() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        let subscription_id = subscribe(&mut ws, "starknet_subscribeNewHeads", json!({})).await;
+
+        let response = send_ws_request(
+            &mut ws,
+            "starknet_unsubscribe",
+            json!({ "subscription_id": subscription_id }),
+        )
+        .await;
+        assert_eq!(response["result"], true);
+
+        devnet.create_block().await.unwrap();
+        assert_no_notifications(&mut ws).await;
+
+        // unsubscribing again is not successful
+        let response = send_ws_request(
+            &mut ws,
+            "starknet_unsubscribe",
+            json!({ "subscription_id": subscription_id }),
+        )
+        .await;
+        assert_eq!(response["result"], false);
+    }
+
+    #[tokio::test]
+    async fn transaction_status_notification() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        let mint_hash = devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+
+        let subscription_id = subscribe(
+            &mut ws,
+            "starknet_subscribeTransactionStatus",
+            json!({ "transaction_hash": mint_hash }),
+        )
+        .await;
+
+        let notification = receive_notification(
+            &mut ws,
+            "starknet_subscriptionTransactionStatus",
+            subscription_id,
+        )
+        .await;
+        assert_eq!(
+            notification,
+            json!({
+                "transaction_hash": mint_hash,
+                "status": {
+                    "finality_status": "ACCEPTED_ON_L2",
+                    "execution_status": "SUCCEEDED",
+                }
+            })
+        );
+
+        // status unchanged, so no new notification
+        devnet.create_block().await.unwrap();
+        assert_no_notifications(&mut ws).await;
+    }
+
+    #[tokio::test]
+    async fn events_notification_on_mint() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        let subscription_id = subscribe(
+            &mut ws,
+            "starknet_subscribeEvents",
+            json!({ "from_address": ETH_ERC20_CONTRACT_ADDRESS }),
+        )
+        .await;
+
+        let mint_hash = devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+
+        let event =
+            receive_notification(&mut ws, "starknet_subscriptionEvents", subscription_id).await;
+        assert_eq!(event["transaction_hash"], to_hex_felt(&mint_hash));
+        assert_eq!(event["from_address"], to_hex_felt(&ETH_ERC20_CONTRACT_ADDRESS));
+        assert_eq!(event["block_number"], 1);
+    }
+
+    #[tokio::test]
+    async fn events_subscription_filters_by_address() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        subscribe(&mut ws, "starknet_subscribeEvents", json!({ "from_address": Felt::ONE })).await;
+
+        devnet.mint(DUMMY_ADDRESS, DUMMY_AMOUNT).await;
+        assert_no_notifications(&mut ws).await;
+    }
+
+    #[tokio::test]
+    async fn reorg_notification_on_aborted_blocks() {
+        let devnet =
+            BackgroundDevnet::spawn_with_additional_args(&["--state-archive-capacity", "full"])
+                .await
+                .unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        let first_block_hash = devnet.create_block().await.unwrap();
+        let second_block_hash = devnet.create_block().await.unwrap();
+
+        let subscription_id = subscribe(&mut ws, "starknet_subscribeNewHeads", json!({})).await;
+
+        devnet
+            .send_custom_rpc(
+                "devnet_abortBlocks",
+                json!({ "starting_block_id": BlockId::Hash(first_block_hash) }),
+            )
+            .await
+            .unwrap();
+
+        let reorg =
+            receive_notification(&mut ws, "starknet_subscriptionReorg", subscription_id).await;
+        assert_eq!(
+            reorg,
+            json!({
+                "starting_block_hash": first_block_hash,
+                "starting_block_number": 1,
+                "ending_block_hash": second_block_hash,
+                "ending_block_number": 2,
+            })
+        );
+
+        // the replacement block reuses the number of the first aborted block
+        devnet.create_block().await.unwrap();
+        let header =
+            receive_notification(&mut ws, "starknet_subscriptionNewHeads", subscription_id).await;
+        assert_eq!(header["block_number"], 1);
+    }
+
+    #[tokio::test]
+    async fn reorg_notification_on_restart() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        let created_block_hash = devnet.create_block().await.unwrap();
+        let subscription_id = subscribe(&mut ws, "starknet_subscribeNewHeads", json!({})).await;
+
+        devnet.restart().await;
+
+        // the genesis block is replaced as well, unless recreated within the same second
+        let reorg =
+            receive_notification(&mut ws, "starknet_subscriptionReorg", subscription_id).await;
+        assert!([json!(0), json!(1)].contains(&reorg["starting_block_number"]));
+        assert_eq!(reorg["ending_block_hash"], to_hex_felt(&created_block_hash));
+        assert_eq!(reorg["ending_block_number"], 1);
+    }
+
+    #[tokio::test]
+    async fn blocks_of_concurrent_requests_notified_once() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let mut ws = devnet.connect_ws().await.unwrap();
+
+        let subscription_id = subscribe(&mut ws, "starknet_subscribeNewHeads", json!({})).await;
+
+        let n_blocks = 5;
+        futures::future::join_all((0..n_blocks).map(|_| devnet.create_block())).await;
+
+        for expected_block_number in 1..=n_blocks {
+            let header =
+                receive_notification(&mut ws, "starknet_subscriptionNewHeads", subscription_id)
+                    .await;
+            assert_eq!(header["block_number"], expected_block_number);
+        }
+
+        assert_no_notifications(&mut ws).await;
+    }
+}
@@ -0,0 +1,120 @@
+#![cfg(test)]
+pub mod common;
+
+mod transaction_status_tests {
+    use serde_json::json;
+    use starknet_core::constants::ETH_ERC20_CONTRACT_ADDRESS;
+    use starknet_rs_accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
+    use starknet_rs_core::types::{BlockId, BlockStatus, Call, Felt};
+    use starknet_rs_core::utils::get_selector_from_name;
+    use starknet_rs_providers::jsonrpc::HttpTransport;
+    use starknet_rs_providers::JsonRpcClient;
+    use starknet_rs_signers::LocalWallet;
+
+    use crate::common::background_devnet::BackgroundDevnet;
+    use crate::common::constants::{self, RPC_V0_8_PATH};
+
+    async fn get_account(
+        devnet: &BackgroundDevnet,
+    ) -> SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> {
+        let (signer, account_address) = devnet.get_first_predeployed_account().await;
+        SingleOwnerAccount::new(
+            devnet.clone_provider(),
+            signer,
+            account_address,
+            constants::CHAIN_ID,
+            ExecutionEncoding::New,
+        )
+    }
+
+    fn transfer_call() -> Call {
+        Call {
+            to: ETH_ERC20_CONTRACT_ADDRESS,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+        }
+    }
+
+    async fn get_finality_status(devnet: &BackgroundDevnet, transaction_hash: Felt) -> String {
+        let status = devnet
+            .send_custom_rpc(
+                "starknet_getTransactionStatus",
+                json!({ "transaction_hash": transaction_hash }),
+            )
+            .await
+            .unwrap();
+        status["finality_status"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn transaction_failing_validation_is_rejected() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let account = get_account(&devnet).await;
+
+        // the nonce of the account is still 0
+        let execution = account
+            .execute_v1(vec![transfer_call()])
+            .max_fee(Felt::from(1e18 as u128))
+            .nonce(Felt::from(5));
+        let prepared = execution.prepared().unwrap();
+        let transaction_hash = prepared.transaction_hash(false);
+        prepared.send().await.unwrap_err();
+
+        let status = devnet
+            .send_custom_rpc_at_path(
+                RPC_V0_8_PATH,
+                "starknet_getTransactionStatus",
+                json!({ "transaction_hash": transaction_hash }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(status["finality_status"], "REJECTED");
+        assert!(status.get("execution_status").is_none());
+        assert!(status["failure_reason"].as_str().unwrap().contains("nonce"));
+
+        // not part of any block
+        assert!(devnet.get_latest_block_with_tx_hashes().await.unwrap().transactions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn blocks_accepted_on_l1_up_to_specified_block() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+        let account = get_account(&devnet).await;
+
+        let mut transaction_hashes = vec![];
+        for nonce in 0..2_u32 {
+            let result = account
+                .execute_v1(vec![transfer_call()])
+                .max_fee(Felt::from(1e18 as u128))
+                .nonce(Felt::from(nonce))
+                .send()
+                .await
+                .unwrap();
+            transaction_hashes.push(result.transaction_hash);
+        }
+
+        // blocks 0 and 1 are accepted, block 2 isn't
+        let accepted =
+            devnet.devnet_client.accept_on_l1(Some(BlockId::Number(1).into())).await.unwrap();
+        assert_eq!(accepted.len(), 2);
+        assert_eq!(get_finality_status(&devnet, transaction_hashes[0]).await, "ACCEPTED_ON_L1");
+        assert_eq!(get_finality_status(&devnet, transaction_hashes[1]).await, "ACCEPTED_ON_L2");
+
+        let accepted = devnet.devnet_client.accept_on_l1(None).await.unwrap();
+        let latest_block = devnet.get_latest_block_with_tx_hashes().await.unwrap();
+        assert_eq!(accepted, vec![latest_block.block_hash]);
+        assert_eq!(latest_block.status, BlockStatus::AcceptedOnL1);
+        assert_eq!(get_finality_status(&devnet, transaction_hashes[1]).await, "ACCEPTED_ON_L1");
+    }
+
+    #[tokio::test]
+    async fn pending_block_cannot_be_accepted_on_l1() {
+        let devnet = BackgroundDevnet::spawn().await.unwrap();
+
+        let result = devnet
+            .send_custom_rpc("devnet_acceptOnL1", json!({ "block_id": "pending" }))
+            .await
+            .unwrap_err();
+        assert!(result.message.contains("Pending block"));
+    }
+}